@@ -58,12 +58,12 @@ impl Producer {
             messages.push(message);
         }
 
-        let mut send_messages = SendMessages {
-            stream_id: Identifier::numeric(self.stream_id)?,
-            topic_id: Identifier::numeric(topic_id)?,
-            partitioning: Partitioning::partition_id(partition_id),
+        let mut send_messages = SendMessages::new(
+            Identifier::numeric(self.stream_id)?.into(),
+            Identifier::numeric(topic_id)?.into(),
+            Partitioning::partition_id(partition_id),
             messages,
-        };
+        );
 
         info!(
             "Producer #{} → sending {} messages in {} batches of {} messages...",
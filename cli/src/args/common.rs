@@ -2,7 +2,9 @@ use clap::ValueEnum;
 use iggy::cli::client::get_clients::GetClientsOutput;
 use iggy::cli::consumer_group::get_consumer_groups::GetConsumerGroupsOutput;
 use iggy::cli::context::get_contexts::GetContextsOutput;
+use iggy::cli::partitions::hash_partitions::HashPartitionsOutput;
 use iggy::cli::personal_access_tokens::get_personal_access_tokens::GetPersonalAccessTokensOutput;
+use iggy::cli::streams::get_stream::GetStreamOutput;
 use iggy::cli::streams::get_streams::GetStreamsOutput;
 use iggy::cli::topics::get_topics::GetTopicsOutput;
 use iggy::cli::users::get_users::GetUsersOutput;
@@ -13,6 +15,32 @@ pub(crate) enum ListMode {
     List,
 }
 
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub(crate) enum OutputMode {
+    Table,
+    List,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub(crate) enum TopicListMode {
+    Auto,
+    Table,
+    List,
+    Json,
+    Csv,
+}
+
+impl From<OutputMode> for GetStreamOutput {
+    fn from(mode: OutputMode) -> Self {
+        match mode {
+            OutputMode::Table => GetStreamOutput::Table,
+            OutputMode::List => GetStreamOutput::List,
+            OutputMode::Json => GetStreamOutput::Json,
+        }
+    }
+}
+
 impl From<ListMode> for GetStreamsOutput {
     fn from(mode: ListMode) -> Self {
         match mode {
@@ -22,11 +50,14 @@ impl From<ListMode> for GetStreamsOutput {
     }
 }
 
-impl From<ListMode> for GetTopicsOutput {
-    fn from(mode: ListMode) -> Self {
+impl From<TopicListMode> for GetTopicsOutput {
+    fn from(mode: TopicListMode) -> Self {
         match mode {
-            ListMode::Table => GetTopicsOutput::Table,
-            ListMode::List => GetTopicsOutput::List,
+            TopicListMode::Auto => GetTopicsOutput::Auto,
+            TopicListMode::Table => GetTopicsOutput::Table,
+            TopicListMode::List => GetTopicsOutput::List,
+            TopicListMode::Json => GetTopicsOutput::Json,
+            TopicListMode::Csv => GetTopicsOutput::Csv,
         }
     }
 }
@@ -75,3 +106,12 @@ impl From<ListMode> for GetContextsOutput {
         }
     }
 }
+
+impl From<ListMode> for HashPartitionsOutput {
+    fn from(mode: ListMode) -> Self {
+        match mode {
+            ListMode::Table => HashPartitionsOutput::Table,
+            ListMode::List => HashPartitionsOutput::List,
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use crate::args::common::ListMode;
 use clap::{Args, Subcommand};
+use iggy::consumer_groups::reset_consumer_group_offset::ResetOffsetTarget;
 use iggy::identifier::Identifier;
 
 #[derive(Debug, Clone, Subcommand)]
@@ -31,6 +32,7 @@ pub(crate) enum ConsumerGroupAction {
     ///  iggy consumer-group delete 1 topic group
     ///  iggy consumer-group delete stream 2 group
     ///  iggy consumer-group delete stream topic group
+    ///  iggy consumer-group delete --force 1 2 3
     #[clap(verbatim_doc_comment, visible_alias = "d")]
     Delete(ConsumerGroupDeleteArgs),
     /// Get details of a single consumer group with given ID for given stream ID and topic ID
@@ -62,6 +64,20 @@ pub(crate) enum ConsumerGroupAction {
     ///  iggy consumer-group list production sensor -l table
     #[clap(verbatim_doc_comment, visible_alias = "l")]
     List(ConsumerGroupListArgs),
+    /// Reset offsets of consumer group with given ID for given stream ID and topic ID
+    ///
+    /// Stream ID can be specified as a stream name or ID
+    /// Topic ID can be specified as a topic name or ID
+    /// Consumer group ID can be specified as a consumer group name or ID
+    /// Offsets are reset in all partitions of the topic and the consumer group
+    /// continues polling from the first message after the reset target.
+    ///
+    /// Examples:
+    ///  iggy consumer-group reset 1 2 3 --to earliest
+    ///  iggy consumer-group reset stream 2 group --to latest
+    ///  iggy consumer-group reset stream topic group --to 2024-01-31T12:00:00Z
+    #[clap(verbatim_doc_comment, visible_alias = "r")]
+    Reset(ConsumerGroupResetArgs),
 }
 
 #[derive(Debug, Clone, Args)]
@@ -99,6 +115,11 @@ pub(crate) struct ConsumerGroupDeleteArgs {
     /// Consumer group ID can be specified as a consumer group name or ID
     #[arg(value_parser = clap::value_parser!(Identifier))]
     pub(crate) consumer_group_id: Identifier,
+    /// Delete without asking for confirmation
+    ///
+    /// Required when the command is not run interactively (e.g. in scripts)
+    #[clap(short, long, default_value_t = false)]
+    pub(crate) force: bool,
 }
 
 #[derive(Debug, Clone, Args)]
@@ -136,3 +157,29 @@ pub(crate) struct ConsumerGroupListArgs {
     #[clap(short, long, value_enum, default_value_t = ListMode::Table)]
     pub(crate) list_mode: ListMode,
 }
+
+#[derive(Debug, Clone, Args)]
+pub(crate) struct ConsumerGroupResetArgs {
+    /// Stream ID to reset consumer group offsets
+    ///
+    /// Stream ID can be specified as a stream name or ID
+    #[arg(value_parser = clap::value_parser!(Identifier))]
+    pub(crate) stream_id: Identifier,
+    /// Topic ID to reset consumer group offsets
+    ///
+    /// Topic ID can be specified as a topic name or ID
+    #[arg(value_parser = clap::value_parser!(Identifier))]
+    pub(crate) topic_id: Identifier,
+    /// Consumer group ID to reset offsets
+    ///
+    /// Consumer group ID can be specified as a consumer group name or ID
+    #[arg(value_parser = clap::value_parser!(Identifier))]
+    pub(crate) consumer_group_id: Identifier,
+    /// Target to which offsets are reset
+    ///
+    /// Target can be specified as earliest (first message),
+    /// latest (messages sent after the reset) or timestamp
+    /// in RFC 3339 format (first message sent at or after it)
+    #[arg(long, value_parser = clap::value_parser!(ResetOffsetTarget), verbatim_doc_comment)]
+    pub(crate) to: ResetOffsetTarget,
+}
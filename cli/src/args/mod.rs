@@ -120,6 +120,12 @@ pub(crate) enum Command {
     /// Collect basic Iggy server statistics like number of streams, topics, partitions, etc.
     /// Server OS name, version, etc. are also collected.
     Stats,
+    /// diagnose connectivity and credentials
+    ///
+    /// Connect to Iggy server, log in and send ping and get stats commands,
+    /// printing the checklist with the result of each step, to find out whether
+    /// the problem is with the network or the credentials.
+    Diagnose,
     /// personal access token operations
     #[command(subcommand)]
     Pat(PersonalAccessTokenAction),
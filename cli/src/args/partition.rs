@@ -1,5 +1,8 @@
-use clap::{Args, Subcommand};
+use crate::args::common::ListMode;
+use clap::{Args, Subcommand, ValueEnum};
+use iggy::cli::partitions::hash_partitions::HashPartitionsKind;
 use iggy::identifier::Identifier;
+use iggy::utils::key_hash::HashAlgo;
 use std::convert::From;
 
 #[derive(Debug, Clone, Subcommand)]
@@ -30,6 +33,20 @@ pub(crate) enum PartitionAction {
     ///  iggy partition delete 1 sensor 16
     #[clap(verbatim_doc_comment, visible_alias = "d")]
     Delete(PartitionDeleteArgs),
+    /// Resolve the partitions to which the messages with
+    /// the given keys are sent, using the same hashing as the server.
+    ///
+    /// Stream ID can be specified as a stream name or ID
+    /// Topic ID can be specified as a topic name or ID
+    /// The partitions count of the topic is used, unless it's provided
+    ///
+    /// Examples
+    ///  iggy partition hash 1 1 order-1 order-2
+    ///  iggy partition hash prod sensor device-7 --kind consistent-key
+    ///  iggy partition hash prod sensor device-7 --kind hashed-key --hash-algo xxh64
+    ///  iggy partition hash test sensor device-7 --partitions-count 16
+    #[clap(verbatim_doc_comment, visible_alias = "h")]
+    Hash(PartitionHashArgs),
 }
 
 #[derive(Debug, Clone, Args)]
@@ -65,3 +82,73 @@ pub(crate) struct PartitionDeleteArgs {
     #[arg(value_parser = clap::value_parser!(u32).range(1..100_001))]
     pub(crate) partitions_count: u32,
 }
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub(crate) enum PartitionHashKind {
+    /// Hash of the key, the same as for the messages key partitioning
+    #[value(name = "messages-key")]
+    Messages,
+    /// Jump consistent hash of the key
+    #[value(name = "consistent-key")]
+    Consistent,
+    /// Hash of the key using the selected hash algorithm
+    #[value(name = "hashed-key")]
+    Hashed,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub(crate) enum PartitionHashAlgo {
+    Xxh32,
+    #[value(name = "fnv1a64")]
+    Fnv1a64,
+    Xxh64,
+}
+
+impl From<PartitionHashAlgo> for HashAlgo {
+    fn from(algo: PartitionHashAlgo) -> Self {
+        match algo {
+            PartitionHashAlgo::Xxh32 => HashAlgo::Xxh32,
+            PartitionHashAlgo::Fnv1a64 => HashAlgo::Fnv1a64,
+            PartitionHashAlgo::Xxh64 => HashAlgo::Xxh64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Args)]
+pub(crate) struct PartitionHashArgs {
+    /// Stream ID of the topic
+    ///
+    /// Stream ID can be specified as a stream name or ID
+    #[arg(value_parser = clap::value_parser!(Identifier))]
+    pub(crate) stream_id: Identifier,
+    /// Topic ID to resolve the partitions for
+    ///
+    /// Topic ID can be specified as a topic name or ID
+    #[arg(value_parser = clap::value_parser!(Identifier))]
+    pub(crate) topic_id: Identifier,
+    /// Keys to resolve the partitions for
+    #[arg(required = true)]
+    pub(crate) keys: Vec<String>,
+    /// Partitioning kind used by the producer
+    #[arg(short, long, value_enum, default_value_t = PartitionHashKind::Messages)]
+    pub(crate) kind: PartitionHashKind,
+    /// Hash algorithm used with the hashed key partitioning kind
+    #[arg(short = 'a', long, value_enum, default_value_t = PartitionHashAlgo::Xxh32)]
+    pub(crate) hash_algo: PartitionHashAlgo,
+    /// Partitions count to resolve the partitions for, instead of the partitions count of the topic
+    #[arg(short, long, value_parser = clap::value_parser!(u32).range(1..100_001))]
+    pub(crate) partitions_count: Option<u32>,
+    /// List mode (table or list)
+    #[clap(short, long, value_enum, default_value_t = ListMode::Table)]
+    pub(crate) list_mode: ListMode,
+}
+
+impl PartitionHashArgs {
+    pub(crate) fn hash_kind(&self) -> HashPartitionsKind {
+        match self.kind {
+            PartitionHashKind::Messages => HashPartitionsKind::MessagesKey,
+            PartitionHashKind::Consistent => HashPartitionsKind::ConsistentKey,
+            PartitionHashKind::Hashed => HashPartitionsKind::HashedKey(self.hash_algo.into()),
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use crate::args::common::ListMode;
+use crate::args::common::{ListMode, OutputMode};
 use clap::{Args, Subcommand};
 use iggy::identifier::Identifier;
 
@@ -93,6 +93,10 @@ pub(crate) struct StreamGetArgs {
     ///
     /// Stream ID can be specified as a stream name or ID
     pub(crate) stream_id: Identifier,
+
+    /// Output mode (table, list or json)
+    #[clap(short, long, value_enum, default_value_t = OutputMode::Table)]
+    pub(crate) output: OutputMode,
 }
 
 #[derive(Debug, Clone, Args)]
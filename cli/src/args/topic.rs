@@ -1,8 +1,10 @@
-use crate::args::common::ListMode;
-use clap::{Args, Subcommand};
+use crate::args::common::TopicListMode;
+use clap::{Args, Subcommand, ValueEnum};
+use iggy::cli::topics::get_topics::TopicColumn;
 use iggy::cli::utils::message_expiry::MessageExpiry;
 use iggy::identifier::Identifier;
 use iggy::utils::byte_size::IggyByteSize;
+use iggy::utils::duration::IggyDuration;
 use std::convert::From;
 
 #[derive(Debug, Clone, Subcommand)]
@@ -78,6 +80,18 @@ pub(crate) enum TopicAction {
     ///  iggy topic purge 2 debugs
     #[clap(verbatim_doc_comment, visible_alias = "p")]
     Purge(TopicPurgeArgs),
+    /// Watch topic details for given topic ID and stream ID, refreshed every interval
+    ///
+    /// Stream ID can be specified as a stream name or ID
+    /// Topic ID can be specified as a topic name or ID
+    /// Press Ctrl-C to stop watching
+    ///
+    /// Examples
+    ///  iggy topic watch 1 1
+    ///  iggy topic watch prod sensor1 --interval 5s
+    ///  iggy topic watch test debugs -i 1min
+    #[clap(verbatim_doc_comment, visible_alias = "w")]
+    Watch(TopicWatchArgs),
 }
 
 #[derive(Debug, Clone, Args)]
@@ -176,9 +190,51 @@ pub(crate) struct TopicListArgs {
     #[arg(value_parser = clap::value_parser!(Identifier))]
     pub(crate) stream_id: Identifier,
 
-    /// List mode (table or list)
-    #[clap(short, long, value_enum, default_value_t = ListMode::Table)]
-    pub(crate) list_mode: ListMode,
+    /// List mode (auto, table, list, json or csv)
+    ///
+    /// Auto mode displays a table in the terminal and a list when the output is piped
+    #[clap(short, long, value_enum, default_value_t = TopicListMode::Auto)]
+    pub(crate) list_mode: TopicListMode,
+
+    /// Comma separated columns to display in table, list or csv mode (all by default)
+    #[clap(short, long, value_enum, value_delimiter = ',')]
+    pub(crate) columns: Option<Vec<TopicListColumn>>,
+
+    /// Field separator used in list mode, fields containing it are quoted
+    #[clap(
+        short,
+        long,
+        default_value = "|",
+        value_parser = clap::builder::NonEmptyStringValueParser::new()
+    )]
+    pub(crate) separator: String,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub(crate) enum TopicListColumn {
+    Id,
+    Created,
+    Name,
+    Size,
+    MaxTopicSize,
+    MessageExpiry,
+    MessagesCount,
+    PartitionsCount,
+}
+
+impl From<TopicListColumn> for TopicColumn {
+    fn from(column: TopicListColumn) -> Self {
+        match column {
+            TopicListColumn::Id => TopicColumn::Id,
+            TopicListColumn::Created => TopicColumn::Created,
+            TopicListColumn::Name => TopicColumn::Name,
+            TopicListColumn::Size => TopicColumn::Size,
+            TopicListColumn::MaxTopicSize => TopicColumn::MaxTopicSize,
+            TopicListColumn::MessageExpiry => TopicColumn::MessageExpiry,
+            TopicListColumn::MessagesCount => TopicColumn::MessagesCount,
+            TopicListColumn::PartitionsCount => TopicColumn::PartitionsCount,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Args)]
@@ -194,3 +250,20 @@ pub(crate) struct TopicPurgeArgs {
     #[arg(value_parser = clap::value_parser!(Identifier))]
     pub(crate) topic_id: Identifier,
 }
+
+#[derive(Debug, Clone, Args)]
+pub(crate) struct TopicWatchArgs {
+    /// Stream ID to watch topic
+    ///
+    /// Stream ID can be specified as a stream name or ID
+    #[arg(value_parser = clap::value_parser!(Identifier))]
+    pub(crate) stream_id: Identifier,
+    /// Topic ID to watch
+    ///
+    /// Topic ID can be specified as a topic name or ID
+    #[arg(value_parser = clap::value_parser!(Identifier))]
+    pub(crate) topic_id: Identifier,
+    /// Interval between refreshes in human readable format like 5s 1min
+    #[arg(short, long, default_value = "1s")]
+    pub(crate) interval: IggyDuration,
+}
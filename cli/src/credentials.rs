@@ -2,6 +2,7 @@ use crate::args::CliOptions;
 use crate::error::{CmdToolError, IggyCmdError};
 use anyhow::Context;
 use iggy::args::Args;
+use iggy::cli::system::diagnose::DiagnoseLogin;
 use iggy::cli_command::PRINT_TARGET;
 use iggy::client::{PersonalAccessTokenClient, UserClient};
 use iggy::clients::client::IggyClient;
@@ -102,6 +103,19 @@ impl<'a> IggyCredentials<'a> {
         }
     }
 
+    /// Converts the credentials into the login used by the diagnose command, which logs in on its own to report the result.
+    pub(crate) fn into_diagnose_login(self) -> Option<DiagnoseLogin> {
+        match self.credentials? {
+            Credentials::UserNameAndPassword(user) => Some(DiagnoseLogin::User(LoginUser {
+                username: user.username,
+                password: user.password,
+            })),
+            Credentials::PersonalAccessToken(token) => Some(DiagnoseLogin::PersonalAccessToken(
+                LoginWithPersonalAccessToken { token },
+            )),
+        }
+    }
+
     pub(crate) fn set_iggy_client(&mut self, iggy_client: &'a IggyClient) {
         self.iggy_client = Some(iggy_client);
     }
@@ -21,19 +21,24 @@ use clap::Parser;
 use iggy::args::Args;
 use iggy::cli::context::common::ContextManager;
 use iggy::cli::context::use_context::UseContextCmd;
+use iggy::cli::error::CliError;
 use iggy::cli::{
     client::{get_client::GetClientCmd, get_clients::GetClientsCmd},
     consumer_group::{
         create_consumer_group::CreateConsumerGroupCmd,
         delete_consumer_group::DeleteConsumerGroupCmd, get_consumer_group::GetConsumerGroupCmd,
         get_consumer_groups::GetConsumerGroupsCmd,
+        reset_consumer_group_offset::ResetConsumerGroupOffsetCmd,
     },
     consumer_offset::{
         get_consumer_offset::GetConsumerOffsetCmd, set_consumer_offset::SetConsumerOffsetCmd,
     },
     context::get_contexts::GetContextsCmd,
     message::{poll_messages::PollMessagesCmd, send_messages::SendMessagesCmd},
-    partitions::{create_partitions::CreatePartitionsCmd, delete_partitions::DeletePartitionsCmd},
+    partitions::{
+        create_partitions::CreatePartitionsCmd, delete_partitions::DeletePartitionsCmd,
+        hash_partitions::HashPartitionsCmd,
+    },
     personal_access_tokens::{
         create_personal_access_token::CreatePersonalAccessTokenCmd,
         delete_personal_access_tokens::DeletePersonalAccessTokenCmd,
@@ -43,10 +48,15 @@ use iggy::cli::{
         create_stream::CreateStreamCmd, delete_stream::DeleteStreamCmd, get_stream::GetStreamCmd,
         get_streams::GetStreamsCmd, purge_stream::PurgeStreamCmd, update_stream::UpdateStreamCmd,
     },
-    system::{me::GetMeCmd, ping::PingCmd, stats::GetStatsCmd},
+    system::{diagnose::DiagnoseCmd, me::GetMeCmd, ping::PingCmd, stats::GetStatsCmd},
     topics::{
-        create_topic::CreateTopicCmd, delete_topic::DeleteTopicCmd, get_topic::GetTopicCmd,
-        get_topics::GetTopicsCmd, purge_topic::PurgeTopicCmd, update_topic::UpdateTopicCmd,
+        create_topic::CreateTopicCmd,
+        delete_topic::DeleteTopicCmd,
+        get_topic::GetTopicCmd,
+        get_topics::{GetTopicsCmd, TopicColumn},
+        purge_topic::PurgeTopicCmd,
+        update_topic::UpdateTopicCmd,
+        watch_topic::WatchTopicCmd,
     },
     users::{
         change_password::ChangePasswordCmd,
@@ -63,6 +73,7 @@ use iggy::cli_command::{CliCommand, PRINT_TARGET};
 use iggy::client_provider::{self, ClientProviderConfig};
 use iggy::clients::client::{IggyClient, IggyClientConfig};
 use iggy::utils::crypto::{Aes256GcmEncryptor, Encryptor};
+use std::process::ExitCode;
 use std::sync::Arc;
 use tracing::{event, Level};
 
@@ -82,7 +93,10 @@ fn get_command(
                 args.stream_id.clone(),
                 args.name.clone(),
             )),
-            StreamAction::Get(args) => Box::new(GetStreamCmd::new(args.stream_id.clone())),
+            StreamAction::Get(args) => Box::new(GetStreamCmd::new(
+                args.stream_id.clone(),
+                args.output.into(),
+            )),
             StreamAction::List(args) => Box::new(GetStreamsCmd::new(args.list_mode.into())),
             StreamAction::Purge(args) => Box::new(PurgeStreamCmd::new(args.stream_id.clone())),
         },
@@ -115,11 +129,21 @@ fn get_command(
             TopicAction::List(args) => Box::new(GetTopicsCmd::new(
                 args.stream_id.clone(),
                 args.list_mode.into(),
+                match &args.columns {
+                    Some(columns) => columns.iter().map(|column| (*column).into()).collect(),
+                    None => TopicColumn::all(),
+                },
+                args.separator.clone(),
             )),
             TopicAction::Purge(args) => Box::new(PurgeTopicCmd::new(
                 args.stream_id.clone(),
                 args.topic_id.clone(),
             )),
+            TopicAction::Watch(args) => Box::new(WatchTopicCmd::new(
+                args.stream_id.clone(),
+                args.topic_id.clone(),
+                args.interval,
+            )),
         },
         Command::Partition(command) => match command {
             PartitionAction::Create(args) => Box::new(CreatePartitionsCmd::new(
@@ -132,10 +156,23 @@ fn get_command(
                 args.topic_id.clone(),
                 args.partitions_count,
             )),
+            PartitionAction::Hash(args) => Box::new(HashPartitionsCmd::new(
+                args.stream_id.clone(),
+                args.topic_id.clone(),
+                args.keys.clone(),
+                args.hash_kind(),
+                args.partitions_count,
+                args.list_mode.into(),
+            )),
         },
         Command::Ping(args) => Box::new(PingCmd::new(args.count)),
         Command::Me => Box::new(GetMeCmd::new()),
         Command::Stats => Box::new(GetStatsCmd::new()),
+        Command::Diagnose => Box::new(DiagnoseCmd::new(
+            IggyCredentials::new(cli_options, iggy_args, true)
+                .ok()
+                .and_then(IggyCredentials::into_diagnose_login),
+        )),
         Command::Pat(command) => match command {
             PersonalAccessTokenAction::Create(pat_create_args) => {
                 Box::new(CreatePersonalAccessTokenCmd::new(
@@ -211,6 +248,7 @@ fn get_command(
                 delete_args.stream_id.clone(),
                 delete_args.topic_id.clone(),
                 delete_args.consumer_group_id.clone(),
+                delete_args.force,
             )),
             ConsumerGroupAction::Get(get_args) => Box::new(GetConsumerGroupCmd::new(
                 get_args.stream_id.clone(),
@@ -222,6 +260,12 @@ fn get_command(
                 list_args.topic_id.clone(),
                 list_args.list_mode.into(),
             )),
+            ConsumerGroupAction::Reset(reset_args) => Box::new(ResetConsumerGroupOffsetCmd::new(
+                reset_args.stream_id.clone(),
+                reset_args.topic_id.clone(),
+                reset_args.consumer_group_id.clone(),
+                reset_args.to,
+            )),
         },
         Command::Message(command) => match command {
             MessageAction::Send(send_args) => Box::new(SendMessagesCmd::new(
@@ -271,17 +315,17 @@ fn get_command(
 }
 
 #[tokio::main]
-async fn main() -> Result<(), IggyCmdError> {
+async fn main() -> Result<ExitCode, IggyCmdError> {
     let args = IggyConsoleArgs::parse();
 
     if let Some(generator) = args.cli.generator {
         args.generate_completion(generator);
-        return Ok(());
+        return Ok(ExitCode::SUCCESS);
     }
 
     if args.command.is_none() {
         IggyConsoleArgs::print_overview();
-        return Ok(());
+        return Ok(ExitCode::SUCCESS);
     }
 
     let mut logging = Logging::new();
@@ -323,9 +367,19 @@ async fn main() -> Result<(), IggyCmdError> {
     } else {
         println!("Executing {}", command.explain());
     }
-    command.execute_cmd(&client).await?;
+    let result = command.execute_cmd(&client).await;
 
     credentials.logout_user().await?;
 
-    Ok(())
+    if let Err(error) = result {
+        let exit_code = CliError::exit_code_of(&error);
+        let error = IggyCmdError::from(error);
+        if let Some(exit_code) = exit_code {
+            eprintln!("Error: {error:?}");
+            return Ok(ExitCode::from(exit_code));
+        }
+        return Err(error);
+    }
+
+    Ok(ExitCode::SUCCESS)
 }
@@ -103,12 +103,12 @@ async fn produce_messages(client: &dyn Client) -> Result<(), Box<dyn Error>> {
             messages.push(message);
         }
         client
-            .send_messages(&mut SendMessages {
-                stream_id: Identifier::numeric(STREAM_ID)?,
-                topic_id: Identifier::numeric(TOPIC_ID)?,
-                partitioning: Partitioning::partition_id(PARTITION_ID),
+            .send_messages(&mut SendMessages::new(
+                Identifier::numeric(STREAM_ID)?.into(),
+                Identifier::numeric(TOPIC_ID)?.into(),
+                Partitioning::partition_id(PARTITION_ID),
                 messages,
-            })
+            ))
             .await?;
         sent_batches += 1;
         info!("Sent {messages_per_batch} message(s).");
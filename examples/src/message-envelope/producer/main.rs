@@ -56,12 +56,12 @@ async fn produce_messages(args: &Args, client: &IggyClient) -> Result<(), Box<dy
             serializable_messages.push(serializable_message);
         }
         client
-            .send_messages(&mut SendMessages {
-                stream_id: Identifier::numeric(args.stream_id)?,
-                topic_id: Identifier::numeric(args.topic_id)?,
-                partitioning: Partitioning::partition_id(args.partition_id),
+            .send_messages(&mut SendMessages::new(
+                Identifier::numeric(args.stream_id)?.into(),
+                Identifier::numeric(args.topic_id)?.into(),
+                Partitioning::partition_id(args.partition_id),
                 messages,
-            })
+            ))
             .await?;
         sent_batches += 1;
         info!("Sent messages: {:#?}", serializable_messages);
@@ -38,6 +38,8 @@ pub(crate) enum OutputFormat {
     Default,
     List,
     Table,
+    Json,
+    Csv,
 }
 
 impl Display for OutputFormat {
@@ -46,6 +48,8 @@ impl Display for OutputFormat {
             Self::Default => write!(f, "table"),
             Self::List => write!(f, "list"),
             Self::Table => write!(f, "table"),
+            Self::Json => write!(f, "json"),
+            Self::Csv => write!(f, "csv"),
         }
     }
 }
@@ -56,6 +60,8 @@ impl OutputFormat {
             Self::Default => vec![],
             Self::List => vec!["--list-mode", "list"],
             Self::Table => vec!["--list-mode", "table"],
+            Self::Json => vec!["--list-mode", "json"],
+            Self::Csv => vec!["--list-mode", "csv"],
         }
     }
 }
@@ -170,6 +176,7 @@ impl IggyCmdTest {
             let mut child = command
                 .stdin(Stdio::piped())
                 .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
                 .spawn()
                 .expect("Failed to spawn child process");
 
@@ -11,7 +11,8 @@ use iggy::streams::delete_stream::DeleteStream;
 use iggy::topics::create_topic::CreateTopic;
 use iggy::topics::delete_topic::DeleteTopic;
 use iggy::{client::Client, identifier::Identifier};
-use predicates::str::diff;
+use predicates::prelude::PredicateBooleanExt;
+use predicates::str::{contains, diff, starts_with};
 use serial_test::parallel;
 
 struct TestConsumerGroupDeleteCmd {
@@ -24,6 +25,7 @@ struct TestConsumerGroupDeleteCmd {
     using_stream_id: TestStreamId,
     using_topic_id: TestTopicId,
     using_consumer_group_id: TestConsumerGroupId,
+    force: Option<&'static str>,
 }
 
 impl TestConsumerGroupDeleteCmd {
@@ -38,6 +40,7 @@ impl TestConsumerGroupDeleteCmd {
         using_stream_id: TestStreamId,
         using_topic_id: TestTopicId,
         using_consumer_group_id: TestConsumerGroupId,
+        force: Option<&'static str>,
     ) -> Self {
         Self {
             stream_id,
@@ -49,14 +52,20 @@ impl TestConsumerGroupDeleteCmd {
             using_stream_id,
             using_topic_id,
             using_consumer_group_id,
+            force,
         }
     }
 
     fn to_args(&self) -> Vec<String> {
-        let mut command = match self.using_stream_id {
-            TestStreamId::Numeric => vec![format!("{}", self.stream_id)],
-            TestStreamId::Named => vec![self.stream_name.clone()],
-        };
+        let mut command = self
+            .force
+            .map(|force| vec![force.to_string()])
+            .unwrap_or_default();
+
+        command.push(match self.using_stream_id {
+            TestStreamId::Numeric => format!("{}", self.stream_id),
+            TestStreamId::Named => self.stream_name.clone(),
+        });
 
         command.push(match self.using_topic_id {
             TestTopicId::Numeric => format!("{}", self.topic_id),
@@ -214,7 +223,10 @@ pub async fn should_be_successful() {
     ];
 
     iggy_cmd_test.setup().await;
-    for (using_stream_id, using_topic_id, using_consumer_group_id) in test_parameters {
+    for ((using_stream_id, using_topic_id, using_consumer_group_id), force) in test_parameters
+        .into_iter()
+        .zip(["--force", "-f"].into_iter().cycle())
+    {
         iggy_cmd_test
             .execute_test(TestConsumerGroupDeleteCmd::new(
                 1,
@@ -226,11 +238,98 @@ pub async fn should_be_successful() {
                 using_stream_id,
                 using_topic_id,
                 using_consumer_group_id,
+                Some(force),
             ))
             .await;
     }
 }
 
+struct TestConsumerGroupDeleteAbortCmd {
+    delete: TestConsumerGroupDeleteCmd,
+    stdin_input: Option<Vec<String>>,
+}
+
+#[async_trait]
+impl IggyCmdTestCase for TestConsumerGroupDeleteAbortCmd {
+    async fn prepare_server_state(&mut self, client: &dyn Client) {
+        self.delete.prepare_server_state(client).await;
+    }
+
+    fn get_command(&self) -> IggyCmdCommand {
+        self.delete.get_command()
+    }
+
+    fn provide_stdin_input(&self) -> Option<Vec<String>> {
+        self.stdin_input.clone()
+    }
+
+    fn verify_command(&self, command_state: Assert) {
+        // Stdin is not a terminal here, so the confirmation isn't asked for, and even the confirming answer must not be taken into account.
+        command_state
+            .failure()
+            .stdout(contains("Are you sure").not())
+            .stderr(starts_with(format!(
+                "Error: CommandError(Cannot confirm deletion of consumer group with ID: {} in non-interactive mode, use --force to delete it",
+                self.delete.consumer_group_id
+            )));
+    }
+
+    async fn verify_server_state(&self, client: &dyn Client) {
+        let consumer_groups = client
+            .get_consumer_groups(&GetConsumerGroups {
+                topic_id: Identifier::numeric(self.delete.topic_id).unwrap(),
+                stream_id: Identifier::numeric(self.delete.stream_id).unwrap(),
+            })
+            .await;
+        assert!(consumer_groups.is_ok());
+        let consumer_groups_details = consumer_groups.unwrap();
+        assert_eq!(consumer_groups_details.len(), 1);
+        assert_eq!(consumer_groups_details[0].id, self.delete.consumer_group_id);
+
+        let topic = client
+            .delete_topic(&DeleteTopic {
+                stream_id: Identifier::numeric(self.delete.stream_id).unwrap(),
+                topic_id: Identifier::numeric(self.delete.topic_id).unwrap(),
+            })
+            .await;
+        assert!(topic.is_ok());
+
+        let stream = client
+            .delete_stream(&DeleteStream {
+                stream_id: Identifier::numeric(self.delete.stream_id).unwrap(),
+            })
+            .await;
+        assert!(stream.is_ok());
+    }
+}
+
+#[tokio::test]
+#[parallel]
+pub async fn should_abort_without_force_when_not_interactive() {
+    let mut iggy_cmd_test = IggyCmdTest::default();
+
+    iggy_cmd_test.setup().await;
+    for stdin_input in [Some(vec![String::from("y")]), None] {
+        iggy_cmd_test
+            .execute_test(TestConsumerGroupDeleteAbortCmd {
+                delete: TestConsumerGroupDeleteCmd::new(
+                    1,
+                    String::from("stream"),
+                    2,
+                    String::from("topic"),
+                    3,
+                    String::from("consumer-group"),
+                    TestStreamId::Numeric,
+                    TestTopicId::Numeric,
+                    TestConsumerGroupId::Numeric,
+                    None,
+                ),
+                stdin_input,
+            })
+            .await;
+    }
+}
+
 #[tokio::test]
 #[parallel]
 pub async fn should_help_match() {
@@ -255,8 +354,9 @@ Examples:
  iggy consumer-group delete 1 topic group
  iggy consumer-group delete stream 2 group
  iggy consumer-group delete stream topic group
+ iggy consumer-group delete --force 1 2 3
 
-{USAGE_PREFIX} consumer-group delete <STREAM_ID> <TOPIC_ID> <CONSUMER_GROUP_ID>
+{USAGE_PREFIX} consumer-group delete [OPTIONS] <STREAM_ID> <TOPIC_ID> <CONSUMER_GROUP_ID>
 
 Arguments:
   <STREAM_ID>
@@ -275,6 +375,11 @@ Arguments:
           Consumer group ID can be specified as a consumer group name or ID
 
 Options:
+  -f, --force
+          Delete without asking for confirmation
+{CLAP_INDENT}
+          Required when the command is not run interactively (e.g. in scripts)
+
   -h, --help
           Print help (see a summary with '-h')
 "#,
@@ -294,7 +399,7 @@ pub async fn should_short_help_match() {
             format!(
                 r#"Delete consumer group with given ID for given stream ID and topic ID
 
-{USAGE_PREFIX} consumer-group delete <STREAM_ID> <TOPIC_ID> <CONSUMER_GROUP_ID>
+{USAGE_PREFIX} consumer-group delete [OPTIONS] <STREAM_ID> <TOPIC_ID> <CONSUMER_GROUP_ID>
 
 Arguments:
   <STREAM_ID>          Stream ID to delete consumer group
@@ -302,7 +407,8 @@ Arguments:
   <CONSUMER_GROUP_ID>  Consumer group ID to delete
 
 Options:
-  -h, --help  Print help (see more with '--help')
+  -f, --force  Delete without asking for confirmation
+  -h, --help   Print help (see more with '--help')
 "#,
             ),
         ))
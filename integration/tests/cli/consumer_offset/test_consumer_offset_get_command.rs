@@ -113,12 +113,12 @@ impl IggyCmdTestCase for TestConsumerOffsetGetCmd {
             .collect::<Vec<_>>();
 
         let send_status = client
-            .send_messages(&mut SendMessages {
-                stream_id: Identifier::numeric(self.stream_id).unwrap(),
-                topic_id: Identifier::numeric(self.topic_id).unwrap(),
-                partitioning: Partitioning::partition_id(self.partition_id),
+            .send_messages(&mut SendMessages::new(
+                Identifier::numeric(self.stream_id).unwrap().into(),
+                Identifier::numeric(self.topic_id).unwrap().into(),
+                Partitioning::partition_id(self.partition_id),
                 messages,
-            })
+            ))
             .await;
         assert!(send_status.is_ok());
 
@@ -32,6 +32,14 @@ impl PartitionSelection {
             PartitionSelection::Key(key) => vec!["-m".into(), key.clone()],
         }
     }
+
+    fn describe(&self) -> String {
+        match self {
+            PartitionSelection::Balanced => "round-robin balanced across partitions".into(),
+            PartitionSelection::Id(id) => format!("routed to partition {id}"),
+            PartitionSelection::Key(key) => format!("hashed by key '{key}'"),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -174,8 +182,8 @@ impl IggyCmdTestCase for TestMessageSendCmd {
             TestTopicId::Named => self.topic_name.clone(),
         };
 
-        let message = format!("Executing send messages to topic with ID: {} and stream with ID: {}\nSent messages to topic with ID: {} and stream with ID: {}\n",
-            topic_id, stream_id, topic_id, stream_id);
+        let message = format!("Executing send messages to topic with ID: {} and stream with ID: {}, {}\nSent messages to topic with ID: {} and stream with ID: {}\n",
+            topic_id, stream_id, self.partitioning.describe(), topic_id, stream_id);
 
         command_state.success().stdout(diff(message));
     }
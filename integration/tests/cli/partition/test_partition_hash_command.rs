@@ -0,0 +1,160 @@
+use crate::cli::common::{IggyCmdCommand, IggyCmdTest, IggyCmdTestCase};
+use assert_cmd::assert::Assert;
+use async_trait::async_trait;
+use iggy::streams::create_stream::CreateStream;
+use iggy::streams::delete_stream::DeleteStream;
+use iggy::topics::create_topic::CreateTopic;
+use iggy::topics::delete_topic::DeleteTopic;
+use iggy::{client::Client, identifier::Identifier};
+use predicates::str::diff;
+use serial_test::parallel;
+
+struct TestPartitionHashCmd {
+    stream_id: u32,
+    topic_id: u32,
+    partitions_count: u32,
+    options: Vec<String>,
+    expected_kind: String,
+    expected_partitions: Vec<(String, u32)>,
+}
+
+impl TestPartitionHashCmd {
+    fn new(
+        stream_id: u32,
+        topic_id: u32,
+        partitions_count: u32,
+        options: Vec<&str>,
+        expected_kind: &str,
+        expected_partitions: Vec<(&str, u32)>,
+    ) -> Self {
+        Self {
+            stream_id,
+            topic_id,
+            partitions_count,
+            options: options.into_iter().map(String::from).collect(),
+            expected_kind: expected_kind.to_string(),
+            expected_partitions: expected_partitions
+                .into_iter()
+                .map(|(key, partition_id)| (key.to_string(), partition_id))
+                .collect(),
+        }
+    }
+}
+
+#[async_trait]
+impl IggyCmdTestCase for TestPartitionHashCmd {
+    async fn prepare_server_state(&mut self, client: &dyn Client) {
+        let stream = client
+            .create_stream(&CreateStream {
+                stream_id: Some(self.stream_id),
+                name: format!("stream-{}", self.stream_id),
+            })
+            .await;
+        assert!(stream.is_ok());
+
+        let topic = client
+            .create_topic(&CreateTopic {
+                stream_id: Identifier::numeric(self.stream_id).unwrap(),
+                topic_id: Some(self.topic_id),
+                partitions_count: self.partitions_count,
+                name: format!("topic-{}", self.topic_id),
+                message_expiry: None,
+                max_topic_size: None,
+                replication_factor: 1,
+            })
+            .await;
+        assert!(topic.is_ok());
+    }
+
+    fn get_command(&self) -> IggyCmdCommand {
+        IggyCmdCommand::new()
+            .arg("partition")
+            .arg("hash")
+            .arg(format!("{}", self.stream_id))
+            .arg(format!("{}", self.topic_id))
+            .args(
+                self.expected_partitions
+                    .iter()
+                    .map(|(key, _)| key.clone())
+                    .collect(),
+            )
+            .args(self.options.clone())
+            .arg("--list-mode")
+            .arg("list")
+            .with_env_credentials()
+    }
+
+    fn verify_command(&self, command_state: Assert) {
+        let mut message = format!(
+            "Executing resolve partitions by {} for topic with ID: {} and stream with ID: {} in list mode\n",
+            self.expected_kind, self.topic_id, self.stream_id
+        );
+        for (key, partition_id) in &self.expected_partitions {
+            message.push_str(&format!("{key}|{partition_id}\n"));
+        }
+
+        command_state.success().stdout(diff(message));
+    }
+
+    async fn verify_server_state(&self, client: &dyn Client) {
+        let topic = client
+            .delete_topic(&DeleteTopic {
+                stream_id: Identifier::numeric(self.stream_id).unwrap(),
+                topic_id: Identifier::numeric(self.topic_id).unwrap(),
+            })
+            .await;
+        assert!(topic.is_ok());
+
+        let stream = client
+            .delete_stream(&DeleteStream {
+                stream_id: Identifier::numeric(self.stream_id).unwrap(),
+            })
+            .await;
+        assert!(stream.is_ok());
+    }
+}
+
+#[tokio::test]
+#[parallel]
+pub async fn should_be_successful() {
+    let mut iggy_cmd_test = IggyCmdTest::default();
+
+    iggy_cmd_test.setup().await;
+    iggy_cmd_test
+        .execute_test(TestPartitionHashCmd::new(
+            1,
+            1,
+            10,
+            vec![],
+            "messages key",
+            vec![("orders", 7), ("customer-42", 2)],
+        ))
+        .await;
+    iggy_cmd_test
+        .execute_test(TestPartitionHashCmd::new(
+            2,
+            1,
+            10,
+            vec!["--kind", "hashed-key", "--hash-algo", "fnv1a64"],
+            "fnv1a64 hashed key",
+            vec![("orders", 2), ("customer-42", 6)],
+        ))
+        .await;
+    iggy_cmd_test
+        .execute_test(TestPartitionHashCmd::new(
+            3,
+            1,
+            1,
+            vec![
+                "--kind",
+                "hashed-key",
+                "--hash-algo",
+                "xxh64",
+                "--partitions-count",
+                "7",
+            ],
+            "xxh64 hashed key",
+            vec![("orders", 2), ("customer-42", 1)],
+        ))
+        .await;
+}
@@ -19,6 +19,8 @@ Commands:
               and stream ID based on the given count. [aliases: c]
   delete  Delete partitions for the specified topic ID
               and stream ID based on the given count. [aliases: d]
+  hash    Resolve the partitions to which the messages with
+              the given keys are sent, using the same hashing as the server. [aliases: h]
   help    Print this message or the help of the given subcommand(s)
 
 Options:
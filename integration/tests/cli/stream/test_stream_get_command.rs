@@ -96,6 +96,44 @@ pub async fn should_be_successful() {
         .await;
 }
 
+struct TestStreamGetMissingCmd {
+    stream_id: u32,
+}
+
+#[async_trait]
+impl IggyCmdTestCase for TestStreamGetMissingCmd {
+    async fn prepare_server_state(&mut self, _client: &dyn Client) {}
+
+    fn get_command(&self) -> IggyCmdCommand {
+        IggyCmdCommand::new()
+            .arg("stream")
+            .arg("get")
+            .arg(format!("{}", self.stream_id))
+            .with_env_credentials()
+    }
+
+    fn verify_command(&self, command_state: Assert) {
+        // The missing resource is reported with the dedicated exit code, see `CliError::exit_code`.
+        command_state.code(3).stderr(starts_with(format!(
+            "Error: CommandError(Problem getting stream with ID: {}",
+            self.stream_id
+        )));
+    }
+
+    async fn verify_server_state(&self, _client: &dyn Client) {}
+}
+
+#[tokio::test]
+#[parallel]
+pub async fn should_fail_with_not_found_exit_code() {
+    let mut iggy_cmd_test = IggyCmdTest::default();
+
+    iggy_cmd_test.setup().await;
+    iggy_cmd_test
+        .execute_test(TestStreamGetMissingCmd { stream_id: 42 })
+        .await;
+}
+
 #[tokio::test]
 #[parallel]
 pub async fn should_help_match() {
@@ -113,7 +151,7 @@ Examples:
  iggy stream get 1
  iggy stream get test
 
-{USAGE_PREFIX} stream get <STREAM_ID>
+{USAGE_PREFIX} stream get [OPTIONS] <STREAM_ID>
 
 Arguments:
   <STREAM_ID>
@@ -122,6 +160,12 @@ Arguments:
           Stream ID can be specified as a stream name or ID
 
 Options:
+  -o, --output <OUTPUT>
+          Output mode (table, list or json)
+{CLAP_INDENT}
+          [default: table]
+          [possible values: table, list, json]
+
   -h, --help
           Print help (see a summary with '-h')
 "#,
@@ -141,13 +185,14 @@ pub async fn should_short_help_match() {
             format!(
                 r#"Get details of a single stream with given ID
 
-{USAGE_PREFIX} stream get <STREAM_ID>
+{USAGE_PREFIX} stream get [OPTIONS] <STREAM_ID>
 
 Arguments:
   <STREAM_ID>  Stream ID to get
 
 Options:
-  -h, --help  Print help (see more with '--help')
+  -o, --output <OUTPUT>  Output mode (table, list or json) [default: table] [possible values: table, list, json]
+  -h, --help             Print help (see more with '--help')
 "#,
             ),
         ))
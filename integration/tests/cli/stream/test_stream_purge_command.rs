@@ -72,12 +72,12 @@ impl IggyCmdTestCase for TestStreamPurgeCmd {
             .collect::<Vec<_>>();
 
         let send_status = client
-            .send_messages(&mut SendMessages {
-                stream_id: Identifier::numeric(self.stream_id).unwrap(),
-                topic_id: Identifier::numeric(self.topic_id).unwrap(),
-                partitioning: Partitioning::default(),
+            .send_messages(&mut SendMessages::new(
+                Identifier::numeric(self.stream_id).unwrap().into(),
+                Identifier::numeric(self.topic_id).unwrap().into(),
+                Partitioning::default(),
                 messages,
-            })
+            ))
             .await;
         assert!(send_status.is_ok());
 
@@ -1,3 +1,4 @@
+mod test_diagnose_command;
 mod test_me_command;
 mod test_ping_command;
 mod test_stats_command;
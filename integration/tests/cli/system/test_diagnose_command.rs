@@ -0,0 +1,132 @@
+use crate::cli::common::{IggyCmdCommand, IggyCmdTest, IggyCmdTestCase, TestHelpCmd, USAGE_PREFIX};
+use assert_cmd::assert::Assert;
+use async_trait::async_trait;
+use iggy::client::Client;
+use iggy::users::defaults::DEFAULT_ROOT_USERNAME;
+use predicates::str::{contains, starts_with};
+use serial_test::parallel;
+
+enum TestCredentials {
+    Valid,
+    WrongPassword,
+}
+
+struct TestDiagnoseCmd {
+    credentials: TestCredentials,
+}
+
+impl TestDiagnoseCmd {
+    fn new(credentials: TestCredentials) -> Self {
+        Self { credentials }
+    }
+}
+
+#[async_trait]
+impl IggyCmdTestCase for TestDiagnoseCmd {
+    async fn prepare_server_state(&mut self, _client: &dyn Client) {}
+
+    fn get_command(&self) -> IggyCmdCommand {
+        let command = IggyCmdCommand::new().arg("diagnose");
+        match self.credentials {
+            TestCredentials::Valid => command.with_env_credentials(),
+            TestCredentials::WrongPassword => command
+                .opt("--username")
+                .opt(DEFAULT_ROOT_USERNAME)
+                .opt("--password")
+                .opt("wrong-password"),
+        }
+    }
+
+    fn verify_command(&self, command_state: Assert) {
+        match self.credentials {
+            TestCredentials::Valid => {
+                command_state
+                    .success()
+                    .stdout(starts_with("Executing diagnose command\n"))
+                    .stdout(contains("[PASS] connect to server"))
+                    .stdout(contains("[PASS] login"))
+                    .stdout(contains("[PASS] ping"))
+                    .stdout(contains("[PASS] get stats"));
+            }
+            TestCredentials::WrongPassword => {
+                command_state
+                    .failure()
+                    .stdout(starts_with("Executing diagnose command\n"))
+                    .stdout(contains("[PASS] connect to server"))
+                    .stdout(contains("[FAIL] login: "))
+                    .stdout(contains("[PASS] ping"))
+                    .stdout(contains("[SKIP] get stats (previous step failed)"))
+                    .stderr(contains("Diagnosis failed, 2 of 4 steps didn't pass"));
+            }
+        }
+    }
+
+    async fn verify_server_state(&self, _client: &dyn Client) {}
+}
+
+#[tokio::test]
+#[parallel]
+pub async fn should_be_successful() {
+    let mut iggy_cmd_test = IggyCmdTest::default();
+
+    iggy_cmd_test.setup().await;
+    iggy_cmd_test
+        .execute_test(TestDiagnoseCmd::new(TestCredentials::Valid))
+        .await;
+}
+
+#[tokio::test]
+#[parallel]
+pub async fn should_report_failed_login() {
+    let mut iggy_cmd_test = IggyCmdTest::default();
+
+    iggy_cmd_test.setup().await;
+    iggy_cmd_test
+        .execute_test(TestDiagnoseCmd::new(TestCredentials::WrongPassword))
+        .await;
+}
+
+#[tokio::test]
+#[parallel]
+pub async fn should_help_match() {
+    let mut iggy_cmd_test = IggyCmdTest::help_message();
+
+    iggy_cmd_test
+        .execute_test_for_help_command(TestHelpCmd::new(
+            vec!["diagnose", "--help"],
+            format!(
+                r#"diagnose connectivity and credentials
+
+Connect to Iggy server, log in and send ping and get stats commands, printing the checklist with the result of each step, to find out whether the problem is with the network or the credentials.
+
+{USAGE_PREFIX} diagnose
+
+Options:
+  -h, --help
+          Print help (see a summary with '-h')
+"#,
+            ),
+        ))
+        .await;
+}
+
+#[tokio::test]
+#[parallel]
+pub async fn should_short_help_match() {
+    let mut iggy_cmd_test = IggyCmdTest::default();
+
+    iggy_cmd_test
+        .execute_test_for_help_command(TestHelpCmd::new(
+            vec!["diagnose", "-h"],
+            format!(
+                r#"diagnose connectivity and credentials
+
+{USAGE_PREFIX} diagnose
+
+Options:
+  -h, --help  Print help (see more with '--help')
+"#,
+            ),
+        ))
+        .await;
+}
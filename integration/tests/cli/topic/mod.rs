@@ -5,3 +5,4 @@ mod test_topic_help_command;
 mod test_topic_list_command;
 mod test_topic_purge_command;
 mod test_topic_update_command;
+mod test_topic_watch_command;
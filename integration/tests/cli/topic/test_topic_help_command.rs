@@ -21,6 +21,7 @@ Commands:
   get     Get topic detail for given topic ID and stream ID [aliases: g]
   list    List all topics in given stream ID [aliases: l]
   purge   Purge topic with given ID in given stream ID [aliases: p]
+  watch   Watch topic details for given topic ID and stream ID, refreshed every interval [aliases: w]
   help    Print this message or the help of the given subcommand(s)
 
 Options:
@@ -19,6 +19,7 @@ struct TestTopicListCmd {
     topic_name: String,
     using_stream_id: TestStreamId,
     output: OutputFormat,
+    columns: Option<(String, String)>,
 }
 
 impl TestTopicListCmd {
@@ -37,9 +38,15 @@ impl TestTopicListCmd {
             topic_name,
             using_stream_id,
             output,
+            columns: None,
         }
     }
 
+    fn with_columns(mut self, columns: &str, expected_header: &str) -> Self {
+        self.columns = Some((columns.to_string(), expected_header.to_string()));
+        self
+    }
+
     fn to_args(&self) -> Vec<String> {
         let mut args = match self.using_stream_id {
             TestStreamId::Numeric => vec![format!("{}", self.stream_id)],
@@ -48,6 +55,11 @@ impl TestTopicListCmd {
 
         args.extend(self.output.to_args().into_iter().map(String::from));
 
+        if let Some((columns, _)) = &self.columns {
+            args.push(String::from("--columns"));
+            args.push(columns.clone());
+        }
+
         args
     }
 }
@@ -91,13 +103,22 @@ impl IggyCmdTestCase for TestTopicListCmd {
             TestStreamId::Named => self.stream_name.clone(),
         };
 
-        command_state
+        let mode = match self.output {
+            OutputFormat::Default => String::from("auto"),
+            _ => self.output.to_string(),
+        };
+
+        let command_state = command_state
             .success()
             .stdout(starts_with(format!(
                 "Executing list topics from stream with ID: {} in {} mode",
-                stream_id, self.output
+                stream_id, mode
             )))
             .stdout(contains(self.topic_name.clone()));
+
+        if let Some((_, expected_header)) = &self.columns {
+            command_state.stdout(contains(expected_header.clone()));
+        }
     }
 
     async fn verify_server_state(&self, client: &dyn Client) {
@@ -154,6 +175,42 @@ pub async fn should_be_successful() {
             OutputFormat::Table,
         ))
         .await;
+    iggy_cmd_test
+        .execute_test(TestTopicListCmd::new(
+            4,
+            String::from("analytics"),
+            2,
+            String::from("events"),
+            TestStreamId::Named,
+            OutputFormat::Json,
+        ))
+        .await;
+    iggy_cmd_test
+        .execute_test(TestTopicListCmd::new(
+            5,
+            String::from("billing"),
+            1,
+            String::from("invoices"),
+            TestStreamId::Numeric,
+            OutputFormat::Csv,
+        ))
+        .await;
+    iggy_cmd_test
+        .execute_test(
+            TestTopicListCmd::new(
+                6,
+                String::from("shipping"),
+                4,
+                String::from("parcels"),
+                TestStreamId::Named,
+                OutputFormat::Csv,
+            )
+            .with_columns(
+                "name,id,partitions-count",
+                "Name,ID,Partitions Count\nparcels,4,1",
+            ),
+        )
+        .await;
 }
 
 #[tokio::test]
@@ -183,10 +240,22 @@ Arguments:
 
 Options:
   -l, --list-mode <LIST_MODE>
-          List mode (table or list)
+          List mode (auto, table, list, json or csv)
+{CLAP_INDENT}
+          Auto mode displays a table in the terminal and a list when the output is piped
+{CLAP_INDENT}
+          [default: auto]
+          [possible values: auto, table, list, json, csv]
+
+  -c, --columns <COLUMNS>
+          Comma separated columns to display in table, list or csv mode (all by default)
+{CLAP_INDENT}
+          [possible values: id, created, name, size, max-topic-size, message-expiry, messages-count, partitions-count]
+
+  -s, --separator <SEPARATOR>
+          Field separator used in list mode, fields containing it are quoted
 {CLAP_INDENT}
-          [default: table]
-          [possible values: table, list]
+          [default: |]
 
   -h, --help
           Print help (see a summary with '-h')
@@ -213,7 +282,9 @@ Arguments:
   <STREAM_ID>  Stream ID to list topics
 
 Options:
-  -l, --list-mode <LIST_MODE>  List mode (table or list) [default: table] [possible values: table, list]
+  -l, --list-mode <LIST_MODE>  List mode (auto, table, list, json or csv) [default: auto] [possible values: auto, table, list, json, csv]
+  -c, --columns <COLUMNS>      Comma separated columns to display in table, list or csv mode (all by default) [possible values: id, created, name, size, max-topic-size, message-expiry, messages-count, partitions-count]
+  -s, --separator <SEPARATOR>  Field separator used in list mode, fields containing it are quoted [default: |]
   -h, --help                   Print help (see more with '--help')
 "#,
             ),
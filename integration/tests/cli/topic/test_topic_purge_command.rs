@@ -88,12 +88,12 @@ impl IggyCmdTestCase for TestTopicPurgeCmd {
             .collect::<Vec<_>>();
 
         let send_status = client
-            .send_messages(&mut SendMessages {
-                stream_id: Identifier::numeric(self.stream_id).unwrap(),
-                topic_id: Identifier::numeric(self.topic_id).unwrap(),
-                partitioning: Partitioning::default(),
+            .send_messages(&mut SendMessages::new(
+                Identifier::numeric(self.stream_id).unwrap().into(),
+                Identifier::numeric(self.topic_id).unwrap().into(),
+                Partitioning::default(),
                 messages,
-            })
+            ))
             .await;
         assert!(send_status.is_ok());
 
@@ -0,0 +1,75 @@
+use crate::cli::common::{IggyCmdTest, TestHelpCmd, CLAP_INDENT, USAGE_PREFIX};
+use serial_test::parallel;
+
+#[tokio::test]
+#[parallel]
+pub async fn should_help_match() {
+    let mut iggy_cmd_test = IggyCmdTest::help_message();
+
+    iggy_cmd_test
+        .execute_test_for_help_command(TestHelpCmd::new(
+            vec!["topic", "watch", "--help"],
+            format!(
+                r#"Watch topic details for given topic ID and stream ID, refreshed every interval
+
+Stream ID can be specified as a stream name or ID
+Topic ID can be specified as a topic name or ID
+Press Ctrl-C to stop watching
+
+Examples
+ iggy topic watch 1 1
+ iggy topic watch prod sensor1 --interval 5s
+ iggy topic watch test debugs -i 1min
+
+{USAGE_PREFIX} topic watch [OPTIONS] <STREAM_ID> <TOPIC_ID>
+
+Arguments:
+  <STREAM_ID>
+          Stream ID to watch topic
+{CLAP_INDENT}
+          Stream ID can be specified as a stream name or ID
+
+  <TOPIC_ID>
+          Topic ID to watch
+{CLAP_INDENT}
+          Topic ID can be specified as a topic name or ID
+
+Options:
+  -i, --interval <INTERVAL>
+          Interval between refreshes in human readable format like 5s 1min
+{CLAP_INDENT}
+          [default: 1s]
+
+  -h, --help
+          Print help (see a summary with '-h')
+"#,
+            ),
+        ))
+        .await;
+}
+
+#[tokio::test]
+#[parallel]
+pub async fn should_short_help_match() {
+    let mut iggy_cmd_test = IggyCmdTest::default();
+
+    iggy_cmd_test
+        .execute_test_for_help_command(TestHelpCmd::new(
+            vec!["topic", "watch", "-h"],
+            format!(
+                r#"Watch topic details for given topic ID and stream ID, refreshed every interval
+
+{USAGE_PREFIX} topic watch [OPTIONS] <STREAM_ID> <TOPIC_ID>
+
+Arguments:
+  <STREAM_ID>  Stream ID to watch topic
+  <TOPIC_ID>   Topic ID to watch
+
+Options:
+  -i, --interval <INTERVAL>  Interval between refreshes in human readable format like 5s 1min [default: 1s]
+  -h, --help                 Print help (see more with '--help')
+"#,
+            ),
+        ))
+        .await;
+}
@@ -0,0 +1,132 @@
+use bytes::Bytes;
+use iggy::client::{MessageClient, StreamClient, TopicClient};
+use iggy::clients::client::{IggyClient, IggyClientConfig, SendMessagesConfig};
+use iggy::consumer::Consumer;
+use iggy::identifier::Identifier;
+use iggy::messages::poll_messages::{PollMessages, PollingStrategy};
+use iggy::messages::send_messages::{Message, Partitioning, SendMessages};
+use iggy::streams::create_stream::CreateStream;
+use iggy::streams::delete_stream::DeleteStream;
+use iggy::topics::create_topic::CreateTopic;
+use integration::test_server::{assert_clean_system, login_root, ClientFactory};
+use std::time::Duration;
+use tokio::time::sleep;
+
+const STREAM_ID: u32 = 1;
+const TOPIC_ID: u32 = 1;
+const STREAM_NAME: &str = "test-stream";
+const TOPIC_NAME: &str = "test-topic";
+const PARTITIONS_COUNT: u32 = 1;
+const PARTITION_ID: u32 = 1;
+const MESSAGES_COUNT: u32 = 100;
+// The interval is long enough to make sure that the messages are never sent by the background task during the test.
+const SEND_INTERVAL_MS: u64 = 600_000;
+
+pub async fn run(client_factory: &dyn ClientFactory) {
+    let client = client_factory.create_client().await;
+    let client = IggyClient::create(client, IggyClientConfig::default(), None, None, None);
+    login_root(&client).await;
+    init_system(&client).await;
+
+    // 1. Send the messages in background and drop the producer without flushing
+    let producer = create_producer(client_factory).await;
+    send_messages(&producer, 0).await;
+    drop(producer);
+    wait_for_messages(&client, MESSAGES_COUNT).await;
+
+    // 2. Send the messages in background and close the producer explicitly
+    let mut producer = create_producer(client_factory).await;
+    send_messages(&producer, MESSAGES_COUNT).await;
+    producer.close().await.unwrap();
+    let polled_messages_count = poll_messages_count(&client).await;
+    assert_eq!(polled_messages_count, 2 * MESSAGES_COUNT);
+
+    cleanup_system(&client).await;
+    assert_clean_system(&client).await;
+}
+
+async fn create_producer(client_factory: &dyn ClientFactory) -> IggyClient {
+    let client = client_factory.create_client().await;
+    let config = IggyClientConfig {
+        send_messages: SendMessagesConfig {
+            enabled: true,
+            interval: SEND_INTERVAL_MS,
+            max_messages: MESSAGES_COUNT,
+            ..SendMessagesConfig::default()
+        },
+        ..IggyClientConfig::default()
+    };
+    let client = IggyClient::create(client, config, None, None, None);
+    login_root(&client).await;
+    client
+}
+
+async fn send_messages(client: &IggyClient, start_offset: u32) {
+    for offset in start_offset..start_offset + MESSAGES_COUNT {
+        let mut send_messages = SendMessages::new(
+            Identifier::numeric(STREAM_ID).unwrap().into(),
+            Identifier::numeric(TOPIC_ID).unwrap().into(),
+            Partitioning::partition_id(PARTITION_ID),
+            vec![Message::new(
+                None,
+                Bytes::from(format!("message {}", offset)),
+                None,
+            )],
+        );
+        client.send_messages(&mut send_messages).await.unwrap();
+    }
+}
+
+async fn wait_for_messages(client: &IggyClient, expected_count: u32) {
+    for _ in 0..50 {
+        if poll_messages_count(client).await == expected_count {
+            return;
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+
+    panic!("The buffered messages were not flushed when the producer was dropped.");
+}
+
+async fn poll_messages_count(client: &IggyClient) -> u32 {
+    let poll_messages = PollMessages {
+        consumer: Consumer::default(),
+        stream_id: Identifier::numeric(STREAM_ID).unwrap(),
+        topic_id: Identifier::numeric(TOPIC_ID).unwrap(),
+        partition_id: Some(PARTITION_ID),
+        strategy: PollingStrategy::offset(0),
+        count: 10 * MESSAGES_COUNT,
+        auto_commit: false,
+    };
+
+    let polled_messages = client.poll_messages(&poll_messages).await.unwrap();
+    polled_messages.messages.len() as u32
+}
+
+async fn init_system(client: &IggyClient) {
+    // 1. Create the stream
+    let create_stream = CreateStream {
+        stream_id: Some(STREAM_ID),
+        name: STREAM_NAME.to_string(),
+    };
+    client.create_stream(&create_stream).await.unwrap();
+
+    // 2. Create the topic
+    let create_topic = CreateTopic {
+        stream_id: Identifier::numeric(STREAM_ID).unwrap(),
+        topic_id: Some(TOPIC_ID),
+        partitions_count: PARTITIONS_COUNT,
+        name: TOPIC_NAME.to_string(),
+        message_expiry: None,
+        max_topic_size: None,
+        replication_factor: 1,
+    };
+    client.create_topic(&create_topic).await.unwrap();
+}
+
+async fn cleanup_system(client: &IggyClient) {
+    let delete_stream = DeleteStream {
+        stream_id: Identifier::numeric(STREAM_ID).unwrap(),
+    };
+    client.delete_stream(&delete_stream).await.unwrap();
+}
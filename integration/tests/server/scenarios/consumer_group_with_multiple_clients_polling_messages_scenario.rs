@@ -135,12 +135,12 @@ async fn execute_using_messages_key_key(
     for entity_id in 1..=MESSAGES_COUNT {
         let message = Message::from_str(&get_message_payload(entity_id)).unwrap();
         let messages = vec![message];
-        let mut send_messages = SendMessages {
-            stream_id: Identifier::numeric(STREAM_ID).unwrap(),
-            topic_id: Identifier::numeric(TOPIC_ID).unwrap(),
-            partitioning: Partitioning::messages_key_u32(entity_id),
+        let mut send_messages = SendMessages::new(
+            Identifier::numeric(STREAM_ID).unwrap().into(),
+            Identifier::numeric(TOPIC_ID).unwrap().into(),
+            Partitioning::messages_key_u32(entity_id),
             messages,
-        };
+        );
         system_client
             .send_messages(&mut send_messages)
             .await
@@ -196,12 +196,12 @@ async fn execute_using_none_key(
         let message =
             Message::from_str(&get_extended_message_payload(partition_id, entity_id)).unwrap();
         let messages = vec![message];
-        let mut send_messages = SendMessages {
-            stream_id: Identifier::numeric(STREAM_ID).unwrap(),
-            topic_id: Identifier::numeric(TOPIC_ID).unwrap(),
-            partitioning: Partitioning::balanced(),
+        let mut send_messages = SendMessages::new(
+            Identifier::numeric(STREAM_ID).unwrap().into(),
+            Identifier::numeric(TOPIC_ID).unwrap().into(),
+            Partitioning::balanced(),
             messages,
-        };
+        );
         system_client
             .send_messages(&mut send_messages)
             .await
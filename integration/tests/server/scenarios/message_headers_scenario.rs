@@ -34,20 +34,15 @@ pub async fn run(client_factory: &dyn ClientFactory) {
         let id = (offset + 1) as u128;
         let payload = get_message_payload(offset as u64);
         let headers = get_message_headers();
-        messages.push(Message {
-            id,
-            length: payload.len() as u32,
-            payload,
-            headers: Some(headers),
-        });
+        messages.push(Message::new(Some(id), payload, Some(headers)));
     }
 
-    let mut send_messages = SendMessages {
-        stream_id: Identifier::numeric(STREAM_ID).unwrap(),
-        topic_id: Identifier::numeric(TOPIC_ID).unwrap(),
-        partitioning: Partitioning::partition_id(PARTITION_ID),
+    let mut send_messages = SendMessages::new(
+        Identifier::numeric(STREAM_ID).unwrap().into(),
+        Identifier::numeric(TOPIC_ID).unwrap().into(),
+        Partitioning::partition_id(PARTITION_ID),
         messages,
-    };
+    );
     client.send_messages(&mut send_messages).await.unwrap();
 
     // 2. Poll messages and validate the headers
@@ -173,12 +173,12 @@ async fn validate_operations_on_topic_twice(
 ) {
     // 1. Append messages to the topic
     let messages = create_messages();
-    let mut send_messages = SendMessages {
-        stream_id: Identifier::from_str(stream_name).unwrap(),
-        topic_id: Identifier::from_str(topic_name).unwrap(),
-        partitioning: Partitioning::partition_id(partition_id),
+    let mut send_messages = SendMessages::new(
+        Identifier::from_str(stream_name).unwrap().into(),
+        Identifier::from_str(topic_name).unwrap().into(),
+        Partitioning::partition_id(partition_id),
         messages,
-    };
+    );
     client.send_messages(&mut send_messages).await.unwrap();
 
     // 2. Validate topic size and number of messages
@@ -186,12 +186,12 @@ async fn validate_operations_on_topic_twice(
 
     // 3. Again append same number of messages to the topic
     let messages = create_messages();
-    let mut send_messages = SendMessages {
-        stream_id: Identifier::from_str(stream_name).unwrap(),
-        topic_id: Identifier::from_str(topic_name).unwrap(),
-        partitioning: Partitioning::partition_id(partition_id),
+    let mut send_messages = SendMessages::new(
+        Identifier::from_str(stream_name).unwrap().into(),
+        Identifier::from_str(topic_name).unwrap().into(),
+        Partitioning::partition_id(partition_id),
         messages,
-    };
+    );
     client.send_messages(&mut send_messages).await.unwrap();
 
     // 4. Validate topic size and number of messages
@@ -281,12 +281,7 @@ fn create_messages() -> Vec<Message> {
         let id = (offset + 1) as u128;
         let payload = Bytes::from(vec![0xD; MESSAGE_PAYLOAD_SIZE_BYTES as usize]);
 
-        let message = Message {
-            id,
-            length: payload.len() as u32,
-            payload,
-            headers: None,
-        };
+        let message = Message::new(Some(id), payload, None);
         messages.push(message);
     }
     messages
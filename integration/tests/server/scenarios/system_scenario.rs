@@ -222,12 +222,12 @@ pub async fn run(client_factory: &dyn ClientFactory) {
 
     // 17. Send messages to the specific topic and partition
     let messages = create_messages();
-    let mut send_messages = SendMessages {
-        stream_id: Identifier::numeric(STREAM_ID).unwrap(),
-        topic_id: Identifier::numeric(TOPIC_ID).unwrap(),
-        partitioning: Partitioning::partition_id(PARTITION_ID),
+    let mut send_messages = SendMessages::new(
+        Identifier::numeric(STREAM_ID).unwrap().into(),
+        Identifier::numeric(TOPIC_ID).unwrap().into(),
+        Partitioning::partition_id(PARTITION_ID),
         messages,
-    };
+    );
     client.send_messages(&mut send_messages).await.unwrap();
 
     // 18. Poll messages from the specific partition in topic
@@ -649,12 +649,12 @@ pub async fn run(client_factory: &dyn ClientFactory) {
 
     // 39. Purge the existing stream and ensure it has no messages
     let messages = create_messages();
-    let mut send_messages = SendMessages {
-        stream_id: Identifier::numeric(STREAM_ID).unwrap(),
-        topic_id: Identifier::numeric(TOPIC_ID).unwrap(),
-        partitioning: Partitioning::partition_id(PARTITION_ID),
+    let mut send_messages = SendMessages::new(
+        Identifier::numeric(STREAM_ID).unwrap().into(),
+        Identifier::numeric(TOPIC_ID).unwrap().into(),
+        Partitioning::partition_id(PARTITION_ID),
         messages,
-    };
+    );
     client.send_messages(&mut send_messages).await.unwrap();
 
     client
@@ -765,12 +765,7 @@ fn create_messages() -> Vec<Message> {
     for offset in 0..MESSAGES_COUNT {
         let id = (offset + 1) as u128;
         let payload = get_message_payload(offset as u64);
-        messages.push(Message {
-            id,
-            length: payload.len() as u32,
-            payload,
-            headers: None,
-        });
+        messages.push(Message::new(Some(id), payload, None));
     }
     messages
 }
@@ -1,5 +1,6 @@
-use iggy::client::{PersonalAccessTokenClient, SystemClient, UserClient};
+use iggy::client::{Client, PersonalAccessTokenClient, SystemClient, UserClient};
 use iggy::clients::client::{IggyClient, IggyClientConfig};
+use iggy::error::IggyError;
 use iggy::identifier::Identifier;
 use iggy::models::permissions::{GlobalPermissions, Permissions};
 use iggy::models::user_status::UserStatus;
@@ -313,6 +314,7 @@ pub async fn run(client_factory: &dyn ClientFactory) {
     client.logout_user(&LogoutUser {}).await.unwrap();
 
     // 26. Trying to perform any secured operation after logout should fail
+    assert!(!client.is_authenticated().await);
     let get_users = client.get_users(&GetUsers {}).await;
-    assert!(get_users.is_err());
+    assert!(matches!(get_users, Err(IggyError::Unauthenticated)));
 }
@@ -1,5 +1,6 @@
 use crate::server::scenarios::{
-    consumer_group_join_scenario, consumer_group_with_multiple_clients_polling_messages_scenario,
+    background_send_messages_scenario, consumer_group_join_scenario,
+    consumer_group_with_multiple_clients_polling_messages_scenario,
     consumer_group_with_single_client_polling_messages_scenario, message_headers_scenario,
     stream_size_validation_scenario, system_scenario, user_scenario,
 };
@@ -75,3 +76,13 @@ async fn stream_size_validation_scenario_should_be_valid() {
     let client_factory = TcpClientFactory { server_addr };
     stream_size_validation_scenario::run(&client_factory).await;
 }
+
+#[tokio::test]
+#[parallel]
+async fn background_send_messages_scenario_should_be_valid() {
+    let mut test_server = TestServer::default();
+    test_server.start();
+    let server_addr = test_server.get_raw_tcp_addr().unwrap();
+    let client_factory = TcpClientFactory { server_addr };
+    background_send_messages_scenario::run(&client_factory).await;
+}
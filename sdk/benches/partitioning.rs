@@ -0,0 +1,27 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use iggy::bytes_serializable::BytesSerializable;
+use iggy::messages::send_messages::Partitioning;
+
+// Compares the owned deserialization, which allocates the value on every call, with the borrowed zero-copy view.
+fn partitioning_deserialization(c: &mut Criterion) {
+    let bytes = Partitioning::messages_key_str("customer-1234567890")
+        .unwrap()
+        .as_bytes();
+
+    let mut group = c.benchmark_group("partitioning_deserialization");
+    group.bench_function("from_bytes", |b| {
+        b.iter(|| Partitioning::from_bytes(black_box(bytes.clone())).unwrap())
+    });
+    group.bench_function("from_bytes_ref", |b| {
+        b.iter(|| {
+            Partitioning::from_bytes_ref(black_box(&bytes))
+                .unwrap()
+                .value()
+                .len()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, partitioning_deserialization);
+criterion_main!(benches);
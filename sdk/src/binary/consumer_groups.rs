@@ -5,6 +5,7 @@ use crate::client::ConsumerGroupClient;
 use crate::command::{
     CREATE_CONSUMER_GROUP_CODE, DELETE_CONSUMER_GROUP_CODE, GET_CONSUMER_GROUPS_CODE,
     GET_CONSUMER_GROUP_CODE, JOIN_CONSUMER_GROUP_CODE, LEAVE_CONSUMER_GROUP_CODE,
+    RESET_CONSUMER_GROUP_OFFSET_CODE,
 };
 use crate::consumer_groups::create_consumer_group::CreateConsumerGroup;
 use crate::consumer_groups::delete_consumer_group::DeleteConsumerGroup;
@@ -12,6 +13,7 @@ use crate::consumer_groups::get_consumer_group::GetConsumerGroup;
 use crate::consumer_groups::get_consumer_groups::GetConsumerGroups;
 use crate::consumer_groups::join_consumer_group::JoinConsumerGroup;
 use crate::consumer_groups::leave_consumer_group::LeaveConsumerGroup;
+use crate::consumer_groups::reset_consumer_group_offset::ResetConsumerGroupOffset;
 use crate::error::IggyError;
 use crate::models::consumer_group::{ConsumerGroup, ConsumerGroupDetails};
 
@@ -66,4 +68,14 @@ impl<B: BinaryClient> ConsumerGroupClient for B {
             .await?;
         Ok(())
     }
+
+    async fn reset_consumer_group_offset(
+        &self,
+        command: &ResetConsumerGroupOffset,
+    ) -> Result<(), IggyError> {
+        fail_if_not_authenticated(self).await?;
+        self.send_with_response(RESET_CONSUMER_GROUP_OFFSET_CODE, command.as_bytes())
+            .await?;
+        Ok(())
+    }
 }
@@ -2,10 +2,11 @@ use crate::binary::binary_client::BinaryClient;
 use crate::binary::{fail_if_not_authenticated, mapper};
 use crate::bytes_serializable::BytesSerializable;
 use crate::client::MessageClient;
-use crate::command::{POLL_MESSAGES_CODE, SEND_MESSAGES_CODE};
+use crate::command::{POLL_MESSAGES_CODE, SEND_MESSAGES_CODE, SEND_MESSAGES_CONDITIONAL_CODE};
 use crate::error::IggyError;
 use crate::messages::poll_messages::PollMessages;
 use crate::messages::send_messages::SendMessages;
+use crate::messages::send_messages_conditional::SendMessagesConditional;
 use crate::models::messages::PolledMessages;
 
 #[async_trait::async_trait]
@@ -24,4 +25,14 @@ impl<B: BinaryClient> MessageClient for B {
             .await?;
         Ok(())
     }
+
+    async fn send_messages_conditional(
+        &self,
+        command: &mut SendMessagesConditional,
+    ) -> Result<(), IggyError> {
+        fail_if_not_authenticated(self).await?;
+        self.send_with_response(SEND_MESSAGES_CONDITIONAL_CODE, command.as_bytes())
+            .await?;
+        Ok(())
+    }
 }
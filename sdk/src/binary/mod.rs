@@ -1,4 +1,4 @@
-use crate::binary::binary_client::{BinaryClient, ClientState};
+use crate::binary::binary_client::BinaryClient;
 use crate::error::IggyError;
 
 pub mod binary_client;
@@ -14,8 +14,100 @@ pub mod topics;
 pub mod users;
 
 async fn fail_if_not_authenticated(client: &dyn BinaryClient) -> Result<(), IggyError> {
-    if client.get_state().await != ClientState::Authenticated {
+    if !client.is_authenticated().await {
         return Err(IggyError::Unauthenticated);
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binary::binary_client::ClientState;
+    use crate::client::{Client, StreamClient, UserClient};
+    use crate::command::LOGIN_USER_CODE;
+    use crate::streams::get_streams::GetStreams;
+    use crate::users::login_user::LoginUser;
+    use crate::users::logout_user::LogoutUser;
+    use async_trait::async_trait;
+    use bytes::Bytes;
+    use tokio::sync::Mutex;
+
+    #[derive(Debug)]
+    struct TestClient {
+        state: Mutex<ClientState>,
+    }
+
+    #[async_trait]
+    impl Client for TestClient {
+        async fn connect(&self) -> Result<(), IggyError> {
+            self.set_state(ClientState::Connected).await;
+            Ok(())
+        }
+
+        async fn disconnect(&self) -> Result<(), IggyError> {
+            self.set_state(ClientState::Disconnected).await;
+            Ok(())
+        }
+
+        async fn is_authenticated(&self) -> bool {
+            self.get_state().await == ClientState::Authenticated
+        }
+    }
+
+    #[async_trait]
+    impl BinaryClient for TestClient {
+        async fn get_state(&self) -> ClientState {
+            *self.state.lock().await
+        }
+
+        async fn set_state(&self, state: ClientState) {
+            *self.state.lock().await = state;
+        }
+
+        async fn send_with_response(
+            &self,
+            command: u32,
+            _payload: Bytes,
+        ) -> Result<Bytes, IggyError> {
+            match command {
+                // The identity info response containing only the user ID.
+                LOGIN_USER_CODE => Ok(Bytes::copy_from_slice(&1u32.to_le_bytes())),
+                // The empty list of streams.
+                _ => Ok(Bytes::new()),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn command_after_logout_should_fail_with_unauthenticated_error_until_login() {
+        let client = TestClient {
+            state: Mutex::new(ClientState::Disconnected),
+        };
+        client.connect().await.unwrap();
+        assert!(!client.is_authenticated().await);
+
+        login(&client).await;
+        assert!(client.is_authenticated().await);
+        assert!(client.get_streams(&GetStreams {}).await.is_ok());
+
+        client.logout_user(&LogoutUser {}).await.unwrap();
+        assert!(!client.is_authenticated().await);
+        let result = client.get_streams(&GetStreams {}).await;
+        assert!(matches!(result, Err(IggyError::Unauthenticated)));
+
+        login(&client).await;
+        assert!(client.is_authenticated().await);
+        assert!(client.get_streams(&GetStreams {}).await.is_ok());
+    }
+
+    async fn login(client: &TestClient) {
+        client
+            .login_user(&LoginUser {
+                username: "user".to_string(),
+                password: "secret".to_string(),
+            })
+            .await
+            .unwrap();
+    }
+}
@@ -11,4 +11,21 @@ pub trait BytesSerializable {
     fn from_bytes(bytes: Bytes) -> Result<Self, IggyError>
     where
         Self: Sized;
+
+    /// Deserializes the struct from bytes, failing with `InvalidCommand` unless the bytes are consumed exactly,
+    /// so the trailing or truncated bytes of the malformed frame are reported instead of being ignored.
+    /// By default, the deserialized struct is serialized back and its length is compared with the input,
+    /// the types whose serialization isn't canonical (e.g. due to the compression) override it.
+    fn from_bytes_checked(bytes: Bytes) -> Result<Self, IggyError>
+    where
+        Self: Sized,
+    {
+        let length = bytes.len();
+        let value = Self::from_bytes(bytes)?;
+        if value.as_bytes().len() != length {
+            return Err(IggyError::InvalidCommand);
+        }
+
+        Ok(value)
+    }
 }
@@ -4,6 +4,7 @@ use crate::consumer_groups::create_consumer_group::CreateConsumerGroup;
 use crate::identifier::Identifier;
 use anyhow::Context;
 use async_trait::async_trait;
+use serde_json::{json, Value};
 use tracing::{event, Level};
 
 pub struct CreateConsumerGroupCmd {
@@ -40,6 +41,16 @@ impl CliCommand for CreateConsumerGroupCmd {
         )
     }
 
+    fn explain_structured(&self) -> Value {
+        json!({
+            "operation": "create_consumer_group",
+            "stream_id": self.create_consumer_group.stream_id.to_string(),
+            "topic_id": self.create_consumer_group.topic_id.to_string(),
+            "consumer_group_id": self.create_consumer_group.consumer_group_id.to_string(),
+            "name": self.create_consumer_group.name,
+        })
+    }
+
     async fn execute_cmd(&mut self, client: &dyn Client) -> anyhow::Result<(), anyhow::Error> {
         client
             .create_consumer_group(&self.create_consumer_group)
@@ -2,38 +2,88 @@ use crate::cli_command::{CliCommand, PRINT_TARGET};
 use crate::client::Client;
 use crate::consumer_groups::delete_consumer_group::DeleteConsumerGroup;
 use crate::identifier::Identifier;
-use anyhow::Context;
+use anyhow::{bail, Context};
 use async_trait::async_trait;
+use passterm::{isatty, Stream};
+use serde_json::{json, Value};
+use std::io;
 use tracing::{event, Level};
 
 pub struct DeleteConsumerGroupCmd {
     delete_consumer_group: DeleteConsumerGroup,
+    force: bool,
 }
 
 impl DeleteConsumerGroupCmd {
-    pub fn new(stream_id: Identifier, topic_id: Identifier, consumer_group_id: Identifier) -> Self {
+    pub fn new(
+        stream_id: Identifier,
+        topic_id: Identifier,
+        consumer_group_id: Identifier,
+        force: bool,
+    ) -> Self {
         Self {
             delete_consumer_group: DeleteConsumerGroup {
                 stream_id,
                 topic_id,
                 consumer_group_id,
             },
+            force,
         }
     }
+
+    fn confirm(&self) -> anyhow::Result<bool, anyhow::Error> {
+        if !isatty(Stream::Stdin) {
+            bail!(
+                "Cannot confirm deletion of consumer group with ID: {} in non-interactive mode, use --force to delete it",
+                self.delete_consumer_group.consumer_group_id
+            );
+        }
+
+        event!(target: PRINT_TARGET, Level::INFO,
+            "Are you sure you want to delete consumer group {}? [y/N]",
+            self.delete_consumer_group.consumer_group_id
+        );
+        let mut answer = String::new();
+        io::stdin().read_line(&mut answer)?;
+
+        Ok(is_confirmed(&answer))
+    }
+}
+
+// Only the explicit "y" or "yes" answer confirms the deletion, anything else (including empty input) declines it.
+fn is_confirmed(answer: &str) -> bool {
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
 }
 
 #[async_trait]
 impl CliCommand for DeleteConsumerGroupCmd {
     fn explain(&self) -> String {
+        let fields = self.explain_structured();
         format!(
             "delete consumer group with ID: {} for topic with ID: {} and stream with ID: {}",
-            self.delete_consumer_group.consumer_group_id,
-            self.delete_consumer_group.topic_id,
-            self.delete_consumer_group.stream_id,
+            fields["consumer_group_id"].as_str().unwrap_or_default(),
+            fields["topic_id"].as_str().unwrap_or_default(),
+            fields["stream_id"].as_str().unwrap_or_default(),
         )
     }
 
+    fn explain_structured(&self) -> Value {
+        json!({
+            "operation": "delete_consumer_group",
+            "stream_id": self.delete_consumer_group.stream_id.to_string(),
+            "topic_id": self.delete_consumer_group.topic_id.to_string(),
+            "consumer_group_id": self.delete_consumer_group.consumer_group_id.to_string(),
+        })
+    }
+
     async fn execute_cmd(&mut self, client: &dyn Client) -> anyhow::Result<(), anyhow::Error> {
+        if !self.force && !self.confirm()? {
+            bail!(
+                "Deletion of consumer group with ID: {} aborted",
+                self.delete_consumer_group.consumer_group_id
+            );
+        }
+
         client
             .delete_consumer_group(&self.delete_consumer_group)
             .await
@@ -54,3 +104,50 @@ impl CliCommand for DeleteConsumerGroupCmd {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_explicit_yes_should_confirm_deletion() {
+        assert!(is_confirmed("y\n"));
+        assert!(is_confirmed("Yes\n"));
+        assert!(is_confirmed(" YES "));
+        assert!(!is_confirmed("\n"));
+        assert!(!is_confirmed("n\n"));
+        assert!(!is_confirmed("no"));
+        assert!(!is_confirmed("yep"));
+    }
+
+    #[test]
+    fn should_explain_command_as_discrete_fields() {
+        let command = DeleteConsumerGroupCmd::new(
+            Identifier::named("prod").unwrap(),
+            Identifier::numeric(2).unwrap(),
+            Identifier::named("billing").unwrap(),
+            true,
+        );
+
+        let explanation = command.explain_structured();
+
+        assert_eq!(
+            explanation,
+            json!({
+                "operation": "delete_consumer_group",
+                "stream_id": "prod",
+                "topic_id": "2",
+                "consumer_group_id": "billing",
+            })
+        );
+        assert_eq!(
+            command.explain(),
+            format!(
+                "delete consumer group with ID: {} for topic with ID: {} and stream with ID: {}",
+                explanation["consumer_group_id"].as_str().unwrap(),
+                explanation["topic_id"].as_str().unwrap(),
+                explanation["stream_id"].as_str().unwrap(),
+            )
+        );
+    }
+}
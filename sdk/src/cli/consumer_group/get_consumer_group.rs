@@ -5,6 +5,7 @@ use crate::identifier::Identifier;
 use anyhow::Context;
 use async_trait::async_trait;
 use comfy_table::{presets::ASCII_NO_BORDERS, Table};
+use serde_json::{json, Value};
 use tracing::{event, Level};
 
 pub struct GetConsumerGroupCmd {
@@ -34,6 +35,15 @@ impl CliCommand for GetConsumerGroupCmd {
         )
     }
 
+    fn explain_structured(&self) -> Value {
+        json!({
+            "operation": "get_consumer_group",
+            "stream_id": self.get_consumer_group.stream_id.to_string(),
+            "topic_id": self.get_consumer_group.topic_id.to_string(),
+            "consumer_group_id": self.get_consumer_group.consumer_group_id.to_string(),
+        })
+    }
+
     async fn execute_cmd(&mut self, client: &dyn Client) -> anyhow::Result<(), anyhow::Error> {
         let consumer_group = client
             .get_consumer_group(&self.get_consumer_group)
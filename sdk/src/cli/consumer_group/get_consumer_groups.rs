@@ -5,6 +5,7 @@ use crate::identifier::Identifier;
 use anyhow::Context;
 use async_trait::async_trait;
 use comfy_table::Table;
+use serde_json::{json, Value};
 use std::fmt::{self, Display, Formatter};
 use tracing::{event, Level};
 
@@ -54,6 +55,15 @@ impl CliCommand for GetConsumerGroupsCmd {
         )
     }
 
+    fn explain_structured(&self) -> Value {
+        json!({
+            "operation": "list_consumer_groups",
+            "stream_id": self.get_consumer_groups.stream_id.to_string(),
+            "topic_id": self.get_consumer_groups.topic_id.to_string(),
+            "mode": self.output.to_string(),
+        })
+    }
+
     async fn execute_cmd(&mut self, client: &dyn Client) -> anyhow::Result<(), anyhow::Error> {
         let consumer_groups = client
             .get_consumer_groups(&self.get_consumer_groups)
@@ -82,13 +92,7 @@ impl CliCommand for GetConsumerGroupsCmd {
             }
             GetConsumerGroupsOutput::List => {
                 consumer_groups.iter().for_each(|group| {
-                    event!(target: PRINT_TARGET, Level::INFO,
-                        "{}|{}|{}|{}",
-                        group.id,
-                        group.name,
-                        group.partitions_count,
-                        group.members_count,
-                    );
+                    event!(target: PRINT_TARGET, Level::INFO, "{group}");
                 });
             }
         }
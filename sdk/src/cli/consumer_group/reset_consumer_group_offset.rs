@@ -0,0 +1,88 @@
+use crate::cli_command::{CliCommand, PRINT_TARGET};
+use crate::client::Client;
+use crate::consumer_groups::reset_consumer_group_offset::{
+    ResetConsumerGroupOffset, ResetOffsetKind, ResetOffsetTarget,
+};
+use crate::identifier::Identifier;
+use crate::utils::timestamp::IggyTimestamp;
+use anyhow::Context;
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use tracing::{event, Level};
+
+pub struct ResetConsumerGroupOffsetCmd {
+    reset_consumer_group_offset: ResetConsumerGroupOffset,
+}
+
+impl ResetConsumerGroupOffsetCmd {
+    pub fn new(
+        stream_id: Identifier,
+        topic_id: Identifier,
+        consumer_group_id: Identifier,
+        to: ResetOffsetTarget,
+    ) -> Self {
+        Self {
+            reset_consumer_group_offset: ResetConsumerGroupOffset {
+                stream_id,
+                topic_id,
+                consumer_group_id,
+                to,
+            },
+        }
+    }
+
+    fn get_target(&self) -> String {
+        let to = &self.reset_consumer_group_offset.to;
+        match to.kind {
+            ResetOffsetKind::Timestamp => {
+                IggyTimestamp::from(to.value).to_string("%Y-%m-%dT%H:%M:%S%.fZ")
+            }
+            kind => kind.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl CliCommand for ResetConsumerGroupOffsetCmd {
+    fn explain(&self) -> String {
+        format!(
+            "reset offsets to: {} for consumer group with ID: {} for topic with ID: {} and stream with ID: {}",
+            self.get_target(),
+            self.reset_consumer_group_offset.consumer_group_id,
+            self.reset_consumer_group_offset.topic_id,
+            self.reset_consumer_group_offset.stream_id,
+        )
+    }
+
+    fn explain_structured(&self) -> Value {
+        json!({
+            "operation": "reset_consumer_group_offset",
+            "stream_id": self.reset_consumer_group_offset.stream_id.to_string(),
+            "topic_id": self.reset_consumer_group_offset.topic_id.to_string(),
+            "consumer_group_id": self.reset_consumer_group_offset.consumer_group_id.to_string(),
+            "to": self.get_target(),
+        })
+    }
+
+    async fn execute_cmd(&mut self, client: &dyn Client) -> anyhow::Result<(), anyhow::Error> {
+        client
+            .reset_consumer_group_offset(&self.reset_consumer_group_offset)
+            .await
+            .with_context(|| {
+                format!(
+                    "Problem resetting offsets to: {} for consumer group with ID: {} for topic with ID: {} and stream with ID: {}",
+                    self.get_target(), self.reset_consumer_group_offset.consumer_group_id, self.reset_consumer_group_offset.topic_id, self.reset_consumer_group_offset.stream_id
+                )
+            })?;
+
+        event!(target: PRINT_TARGET, Level::INFO,
+            "Offsets reset to: {} for consumer group with ID: {} for topic with ID: {} and stream with ID: {}",
+            self.get_target(),
+            self.reset_consumer_group_offset.consumer_group_id,
+            self.reset_consumer_group_offset.topic_id,
+            self.reset_consumer_group_offset.stream_id,
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,156 @@
+use crate::error::{IggyError, IggyErrorDiscriminants};
+use thiserror::Error;
+
+/// The structured error returned by the CLI commands, which is mapped to the process exit code,
+/// so that the scripts can branch on the kind of failure.
+#[derive(Debug, Error)]
+pub enum CliError {
+    /// The command failed due to the client or server error.
+    #[error("Iggy client error: {0}")]
+    ClientError(IggyError),
+    /// The requested resource was not found.
+    #[error("Resource not found: {0}")]
+    NotFound(String),
+    /// The user is not authenticated or not allowed to perform the command.
+    #[error("Permission denied")]
+    PermissionDenied,
+    /// IO error.
+    #[error("IO error")]
+    Io(#[from] std::io::Error),
+}
+
+impl CliError {
+    /// Returns the process exit code for the error.
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            CliError::ClientError(_) => 2,
+            CliError::NotFound(_) => 3,
+            CliError::PermissionDenied => 4,
+            CliError::Io(_) => 5,
+        }
+    }
+
+    /// Returns the process exit code for the error returned by any command, based on the first `CliError`,
+    /// `IggyError` (mapped like `CliError::from`) or IO error in its chain of causes, if any.
+    pub fn exit_code_of(error: &anyhow::Error) -> Option<u8> {
+        error.chain().find_map(|cause| {
+            if let Some(error) = cause.downcast_ref::<CliError>() {
+                return Some(error.exit_code());
+            }
+            if let Some(error) = cause.downcast_ref::<IggyError>() {
+                return Some(
+                    CliError::not_found_or_denied(error).map_or(2, |error| error.exit_code()),
+                );
+            }
+            cause.downcast_ref::<std::io::Error>().map(|_| 5)
+        })
+    }
+
+    // Map the client or server error to `NotFound` or `PermissionDenied`, if it's either of them.
+    fn not_found_or_denied(error: &IggyError) -> Option<CliError> {
+        let code = match error {
+            IggyError::InvalidResponse(code) => *code,
+            IggyError::HttpResponseError(401 | 403, _) => return Some(CliError::PermissionDenied),
+            IggyError::HttpResponseError(404, reason) => {
+                return Some(CliError::NotFound(reason.clone()))
+            }
+            _ => error.as_code(),
+        };
+
+        // The server only returns the error code, so the mapping is done on the discriminant.
+        match IggyErrorDiscriminants::from_repr(code) {
+            Some(
+                IggyErrorDiscriminants::Unauthenticated | IggyErrorDiscriminants::Unauthorized,
+            ) => Some(CliError::PermissionDenied),
+            Some(
+                discriminant @ (IggyErrorDiscriminants::ResourceNotFound
+                | IggyErrorDiscriminants::ClientNotFound
+                | IggyErrorDiscriminants::StreamIdNotFound
+                | IggyErrorDiscriminants::StreamNameNotFound
+                | IggyErrorDiscriminants::TopicIdNotFound
+                | IggyErrorDiscriminants::TopicNameNotFound
+                | IggyErrorDiscriminants::PartitionNotFound
+                | IggyErrorDiscriminants::SegmentNotFound
+                | IggyErrorDiscriminants::ConsumerGroupIdNotFound
+                | IggyErrorDiscriminants::ConsumerGroupNameNotFound
+                | IggyErrorDiscriminants::ConsumerGroupMemberNotFound),
+            ) => {
+                let name: &'static str = discriminant.into();
+                Some(CliError::NotFound(name.to_string()))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl From<IggyError> for CliError {
+    fn from(error: IggyError) -> Self {
+        CliError::not_found_or_denied(&error).unwrap_or(CliError::ClientError(error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_stream_should_be_mapped_to_not_found() {
+        let error = CliError::from(IggyError::StreamIdNotFound(1));
+        assert!(matches!(error, CliError::NotFound(_)));
+        assert_eq!(error.exit_code(), 3);
+    }
+
+    #[test]
+    fn missing_stream_returned_by_server_should_be_mapped_to_not_found() {
+        let code = IggyError::StreamNameNotFound("stream".to_string()).as_code();
+        let error = CliError::from(IggyError::InvalidResponse(code));
+        assert!(matches!(error, CliError::NotFound(_)));
+    }
+
+    #[test]
+    fn unauthorized_should_be_mapped_to_permission_denied() {
+        let error = CliError::from(IggyError::Unauthorized);
+        assert!(matches!(error, CliError::PermissionDenied));
+        assert_eq!(error.exit_code(), 4);
+    }
+
+    #[test]
+    fn client_error_in_command_context_should_be_mapped_to_exit_code() {
+        let error = anyhow::Error::new(IggyError::ConsumerGroupIdNotFound(3, 2))
+            .context("Problem deleting consumer group with ID: 3");
+        assert_eq!(CliError::exit_code_of(&error), Some(3));
+
+        let error = anyhow::Error::new(IggyError::InvalidResponse(
+            IggyError::Unauthorized.as_code(),
+        ))
+        .context("Problem getting stream with ID: 1");
+        assert_eq!(CliError::exit_code_of(&error), Some(4));
+
+        let error =
+            anyhow::Error::new(IggyError::InvalidCommand).context("Problem sending messages");
+        assert_eq!(CliError::exit_code_of(&error), Some(2));
+    }
+
+    #[test]
+    fn io_error_in_command_context_should_be_mapped_to_exit_code() {
+        let error = anyhow::Error::new(std::io::Error::from(std::io::ErrorKind::NotFound))
+            .context("Problem reading messages from file");
+        assert_eq!(CliError::exit_code_of(&error), Some(5));
+    }
+
+    #[test]
+    fn error_without_client_or_io_cause_should_not_be_mapped_to_exit_code() {
+        let error = anyhow::anyhow!("Cannot confirm deletion in non-interactive mode");
+        assert_eq!(CliError::exit_code_of(&error), None);
+    }
+
+    #[test]
+    fn other_errors_should_be_mapped_to_client_error() {
+        let error = CliError::from(IggyError::InvalidCommand);
+        assert!(matches!(
+            error,
+            CliError::ClientError(IggyError::InvalidCommand)
+        ));
+        assert_eq!(error.exit_code(), 2);
+    }
+}
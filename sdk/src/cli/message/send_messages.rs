@@ -56,8 +56,10 @@ impl SendMessagesCmd {
 impl CliCommand for SendMessagesCmd {
     fn explain(&self) -> String {
         format!(
-            "send messages to topic with ID: {} and stream with ID: {}",
-            self.topic_id, self.stream_id
+            "send messages to topic with ID: {} and stream with ID: {}, {}",
+            self.topic_id,
+            self.stream_id,
+            self.partitioning.describe()
         )
     }
 
@@ -78,12 +80,12 @@ impl CliCommand for SendMessagesCmd {
         };
 
         client
-            .send_messages(&mut SendMessages {
-                stream_id: self.stream_id.clone(),
-                topic_id: self.topic_id.clone(),
-                partitioning: self.partitioning.clone(),
+            .send_messages(&mut SendMessages::new(
+                self.stream_id.clone().into(),
+                self.topic_id.clone().into(),
+                self.partitioning.clone(),
                 messages,
-            })
+            ))
             .await
             .with_context(|| {
                 format!(
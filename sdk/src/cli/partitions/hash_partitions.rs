@@ -0,0 +1,226 @@
+use crate::cli_command::{CliCommand, PRINT_TARGET};
+use crate::client::Client;
+use crate::error::IggyError;
+use crate::identifier::Identifier;
+use crate::messages::send_messages::Partitioning;
+use crate::topics::get_topic::GetTopic;
+use crate::utils::key_hash::HashAlgo;
+use anyhow::Context;
+use async_trait::async_trait;
+use comfy_table::Table;
+use std::fmt::{self, Display, Formatter};
+use std::sync::atomic::AtomicU32;
+use tracing::{event, Level};
+
+pub enum HashPartitionsOutput {
+    Table,
+    List,
+}
+
+/// The partitioning kind used to hash the keys, the same as used by the producer sending the messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashPartitionsKind {
+    MessagesKey,
+    ConsistentKey,
+    HashedKey(HashAlgo),
+}
+
+impl HashPartitionsKind {
+    fn partitioning(&self, key: &str) -> Result<Partitioning, IggyError> {
+        match self {
+            HashPartitionsKind::MessagesKey => Partitioning::messages_key_str(key),
+            HashPartitionsKind::ConsistentKey => Partitioning::consistent_key(key.as_bytes()),
+            HashPartitionsKind::HashedKey(algo) => {
+                Partitioning::messages_key_with(key.as_bytes(), *algo)
+            }
+        }
+    }
+}
+
+impl Display for HashPartitionsKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            HashPartitionsKind::MessagesKey => write!(f, "messages key"),
+            HashPartitionsKind::ConsistentKey => write!(f, "consistent key"),
+            HashPartitionsKind::HashedKey(algo) => write!(f, "{algo} hashed key"),
+        }
+    }
+}
+
+/// The partition resolved for the single key.
+#[derive(Debug, PartialEq)]
+struct KeyPartition {
+    key: String,
+    partition_id: u32,
+    description: String,
+}
+
+/// Resolves the partition of each key using the same algorithm as the server, see `partitioner::resolve_partition_id`.
+fn resolve_partitions(
+    keys: &[String],
+    kind: HashPartitionsKind,
+    partitions_count: u32,
+) -> Result<Vec<KeyPartition>, IggyError> {
+    let round_robin_counter = AtomicU32::new(0);
+    keys.iter()
+        .map(|key| {
+            let partitioning = kind.partitioning(key)?;
+            Ok(KeyPartition {
+                key: key.clone(),
+                partition_id: partitioning
+                    .resolve_partition(partitions_count, &round_robin_counter)?,
+                description: partitioning.describe(),
+            })
+        })
+        .collect()
+}
+
+pub struct HashPartitionsCmd {
+    get_topic: GetTopic,
+    keys: Vec<String>,
+    kind: HashPartitionsKind,
+    partitions_count: Option<u32>,
+    output: HashPartitionsOutput,
+}
+
+impl HashPartitionsCmd {
+    pub fn new(
+        stream_id: Identifier,
+        topic_id: Identifier,
+        keys: Vec<String>,
+        kind: HashPartitionsKind,
+        partitions_count: Option<u32>,
+        output: HashPartitionsOutput,
+    ) -> Self {
+        Self {
+            get_topic: GetTopic {
+                stream_id,
+                topic_id,
+            },
+            keys,
+            kind,
+            partitions_count,
+            output,
+        }
+    }
+}
+
+#[async_trait]
+impl CliCommand for HashPartitionsCmd {
+    fn explain(&self) -> String {
+        let mode = match self.output {
+            HashPartitionsOutput::Table => "table",
+            HashPartitionsOutput::List => "list",
+        };
+        format!(
+            "resolve partitions by {} for topic with ID: {} and stream with ID: {} in {mode} mode",
+            self.kind, self.get_topic.topic_id, self.get_topic.stream_id
+        )
+    }
+
+    fn login_required(&self) -> bool {
+        self.partitions_count.is_none()
+    }
+
+    fn connection_required(&self) -> bool {
+        self.partitions_count.is_none()
+    }
+
+    async fn execute_cmd(&mut self, client: &dyn Client) -> anyhow::Result<(), anyhow::Error> {
+        let partitions_count = match self.partitions_count {
+            Some(partitions_count) => partitions_count,
+            None => {
+                client
+                    .get_topic(&self.get_topic)
+                    .await
+                    .with_context(|| {
+                        format!(
+                            "Problem getting topic with ID: {} and stream with ID: {}",
+                            self.get_topic.topic_id, self.get_topic.stream_id
+                        )
+                    })?
+                    .partitions_count
+            }
+        };
+
+        let partitions = resolve_partitions(&self.keys, self.kind, partitions_count)
+            .with_context(|| format!("Problem resolving partitions by {}", self.kind))?;
+
+        match self.output {
+            HashPartitionsOutput::Table => {
+                let mut table = Table::new();
+
+                table.set_header(vec!["Key", "Partition ID", "Partitioning"]);
+
+                partitions.iter().for_each(|partition| {
+                    table.add_row(vec![
+                        partition.key.clone(),
+                        format!("{}", partition.partition_id),
+                        partition.description.clone(),
+                    ]);
+                });
+
+                event!(target: PRINT_TARGET, Level::INFO, "{table}");
+            }
+            HashPartitionsOutput::List => {
+                partitions.iter().for_each(|partition| {
+                    event!(target: PRINT_TARGET, Level::INFO,
+                        "{}|{}",
+                        partition.key,
+                        partition.partition_id
+                    );
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_resolve_partitions_like_server() {
+        let keys = vec![String::from("orders"), String::from("customer-42")];
+        let kinds = [
+            (HashPartitionsKind::MessagesKey, [7, 2]),
+            (HashPartitionsKind::HashedKey(HashAlgo::Fnv1a64), [2, 6]),
+            (HashPartitionsKind::HashedKey(HashAlgo::Xxh64), [5, 5]),
+        ];
+
+        for (kind, partition_ids) in kinds {
+            let partitions = resolve_partitions(&keys, kind, 10).unwrap();
+
+            assert_eq!(
+                partitions
+                    .iter()
+                    .map(|partition| partition.partition_id)
+                    .collect::<Vec<_>>(),
+                partition_ids
+            );
+            assert_eq!(partitions[0].key, "orders");
+        }
+
+        let partitions = resolve_partitions(&keys, HashPartitionsKind::ConsistentKey, 10).unwrap();
+        assert_eq!(
+            partitions[0].partition_id,
+            Partitioning::consistent_key(b"orders")
+                .unwrap()
+                .resolve_partition(10, &AtomicU32::new(0))
+                .unwrap()
+        );
+        assert_eq!(
+            partitions[0].description,
+            "consistently hashed by key 'orders'"
+        );
+    }
+
+    #[test]
+    fn should_reject_empty_key() {
+        let keys = vec![String::new()];
+
+        assert!(resolve_partitions(&keys, HashPartitionsKind::MessagesKey, 10).is_err());
+    }
+}
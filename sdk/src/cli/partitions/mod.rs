@@ -1,2 +1,3 @@
 pub mod create_partitions;
 pub mod delete_partitions;
+pub mod hash_partitions;
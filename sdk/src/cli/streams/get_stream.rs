@@ -1,21 +1,61 @@
 use crate::cli_command::{CliCommand, PRINT_TARGET};
 use crate::client::Client;
 use crate::identifier::Identifier;
+use crate::models::stream::StreamDetails;
 use crate::streams::get_stream::GetStream;
 use crate::utils::timestamp::IggyTimestamp;
 use anyhow::Context;
 use async_trait::async_trait;
 use comfy_table::Table;
+use serde::Serialize;
+use std::fmt::{self, Display, Formatter};
 use tracing::{event, Level};
 
+pub enum GetStreamOutput {
+    Table,
+    List,
+    Json,
+}
+
+impl Display for GetStreamOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            GetStreamOutput::Table => write!(f, "table"),
+            GetStreamOutput::List => write!(f, "list"),
+            GetStreamOutput::Json => write!(f, "json"),
+        }?;
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct StreamJson<'a> {
+    #[serde(flatten)]
+    stream: &'a StreamDetails,
+    created_at_iso: String,
+}
+
+impl<'a> From<&'a StreamDetails> for StreamJson<'a> {
+    fn from(stream: &'a StreamDetails) -> Self {
+        Self {
+            stream,
+            created_at_iso: IggyTimestamp::from(stream.created_at)
+                .to_string("%Y-%m-%dT%H:%M:%S%.6fZ"),
+        }
+    }
+}
+
 pub struct GetStreamCmd {
     get_stream: GetStream,
+    output: GetStreamOutput,
 }
 
 impl GetStreamCmd {
-    pub fn new(stream_id: Identifier) -> Self {
+    pub fn new(stream_id: Identifier, output: GetStreamOutput) -> Self {
         Self {
             get_stream: GetStream { stream_id },
+            output,
         }
     }
 }
@@ -34,31 +74,52 @@ impl CliCommand for GetStreamCmd {
             )
         })?;
 
-        let mut table = Table::new();
+        match self.output {
+            GetStreamOutput::Table => {
+                let mut table = Table::new();
 
-        table.set_header(vec!["Property", "Value"]);
-        table.add_row(vec!["Stream ID", format!("{}", stream.id).as_str()]);
-        table.add_row(vec![
-            "Created",
-            IggyTimestamp::from(stream.created_at)
-                .to_string("%Y-%m-%d %H:%M:%S")
-                .as_str(),
-        ]);
-        table.add_row(vec!["Stream name", stream.name.as_str()]);
-        table.add_row(vec![
-            "Stream size",
-            format!("{}", stream.size_bytes).as_str(),
-        ]);
-        table.add_row(vec![
-            "Stream message count",
-            format!("{}", stream.messages_count).as_str(),
-        ]);
-        table.add_row(vec![
-            "Stream topics count",
-            format!("{}", stream.topics_count).as_str(),
-        ]);
+                table.set_header(vec!["Property", "Value"]);
+                table.add_row(vec!["Stream ID", format!("{}", stream.id).as_str()]);
+                table.add_row(vec![
+                    "Created",
+                    IggyTimestamp::from(stream.created_at)
+                        .to_string("%Y-%m-%d %H:%M:%S")
+                        .as_str(),
+                ]);
+                table.add_row(vec!["Stream name", stream.name.as_str()]);
+                table.add_row(vec![
+                    "Stream size",
+                    format!("{}", stream.size_bytes).as_str(),
+                ]);
+                table.add_row(vec![
+                    "Stream message count",
+                    format!("{}", stream.messages_count).as_str(),
+                ]);
+                table.add_row(vec![
+                    "Stream topics count",
+                    format!("{}", stream.topics_count).as_str(),
+                ]);
 
-        event!(target: PRINT_TARGET, Level::INFO, "{table}");
+                event!(target: PRINT_TARGET, Level::INFO, "{table}");
+            }
+            GetStreamOutput::List => {
+                event!(target: PRINT_TARGET, Level::INFO,
+                    "{}|{}|{}|{}|{}|{}",
+                    stream.id,
+                    IggyTimestamp::from(stream.created_at).to_string("%Y-%m-%d %H:%M:%S"),
+                    stream.name,
+                    stream.size_bytes,
+                    stream.messages_count,
+                    stream.topics_count
+                );
+            }
+            GetStreamOutput::Json => {
+                let json = serde_json::to_string_pretty(&StreamJson::from(&stream))
+                    .with_context(|| "Problem serializing stream to JSON")?;
+
+                event!(target: PRINT_TARGET, Level::INFO, "{json}");
+            }
+        }
 
         Ok(())
     }
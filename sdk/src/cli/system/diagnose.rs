@@ -0,0 +1,170 @@
+use crate::cli_command::{CliCommand, PRINT_TARGET};
+use crate::client::Client;
+use crate::error::IggyError;
+use crate::personal_access_tokens::login_with_personal_access_token::LoginWithPersonalAccessToken;
+use crate::system::get_stats::GetStats;
+use crate::system::ping::Ping;
+use crate::users::login_user::LoginUser;
+use crate::users::logout_user::LogoutUser;
+use anyhow::bail;
+use async_trait::async_trait;
+use std::fmt::{self, Display, Formatter};
+use tracing::{event, Level};
+
+/// The credentials used by the login step of the diagnosis.
+pub enum DiagnoseLogin {
+    User(LoginUser),
+    PersonalAccessToken(LoginWithPersonalAccessToken),
+}
+
+pub struct DiagnoseCmd {
+    login: Option<DiagnoseLogin>,
+}
+
+impl DiagnoseCmd {
+    pub fn new(login: Option<DiagnoseLogin>) -> Self {
+        Self { login }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum StepStatus {
+    Passed,
+    Failed(String),
+    Skipped,
+}
+
+impl From<Result<(), IggyError>> for StepStatus {
+    fn from(result: Result<(), IggyError>) -> Self {
+        match result {
+            Ok(()) => StepStatus::Passed,
+            Err(error) => StepStatus::Failed(format!("{error} (error code: {})", error.as_code())),
+        }
+    }
+}
+
+struct DiagnoseStep {
+    name: &'static str,
+    status: StepStatus,
+}
+
+impl DiagnoseStep {
+    fn new(name: &'static str, status: StepStatus) -> Self {
+        Self { name, status }
+    }
+
+    fn passed(&self) -> bool {
+        self.status == StepStatus::Passed
+    }
+}
+
+impl Display for DiagnoseStep {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.status {
+            StepStatus::Passed => write!(f, "[PASS] {}", self.name),
+            StepStatus::Failed(reason) => write!(f, "[FAIL] {}: {}", self.name, reason),
+            StepStatus::Skipped => write!(f, "[SKIP] {} (previous step failed)", self.name),
+        }
+    }
+}
+
+#[async_trait]
+impl CliCommand for DiagnoseCmd {
+    fn explain(&self) -> String {
+        "diagnose command".to_owned()
+    }
+
+    fn login_required(&self) -> bool {
+        false
+    }
+
+    fn connection_required(&self) -> bool {
+        false
+    }
+
+    async fn execute_cmd(&mut self, client: &dyn Client) -> anyhow::Result<(), anyhow::Error> {
+        let connect = DiagnoseStep::new("connect to server", client.connect().await.into());
+
+        let login = if !connect.passed() {
+            DiagnoseStep::new("login", StepStatus::Skipped)
+        } else {
+            let status = match &self.login {
+                Some(DiagnoseLogin::User(login_user)) => {
+                    client.login_user(login_user).await.map(|_| ()).into()
+                }
+                Some(DiagnoseLogin::PersonalAccessToken(login_with_token)) => client
+                    .login_with_personal_access_token(login_with_token)
+                    .await
+                    .map(|_| ())
+                    .into(),
+                None => StepStatus::Failed(
+                    "no credentials provided, use --username and --password, --token or IGGY_USERNAME and IGGY_PASSWORD".to_owned(),
+                ),
+            };
+            DiagnoseStep::new("login", status)
+        };
+
+        let ping = match connect.passed() {
+            true => DiagnoseStep::new("ping", client.ping(&Ping {}).await.into()),
+            false => DiagnoseStep::new("ping", StepStatus::Skipped),
+        };
+
+        let logged_in = login.passed();
+        let stats = match logged_in {
+            true => DiagnoseStep::new(
+                "get stats",
+                client.get_stats(&GetStats {}).await.map(|_| ()).into(),
+            ),
+            false => DiagnoseStep::new("get stats", StepStatus::Skipped),
+        };
+
+        let steps = [connect, login, ping, stats];
+        steps.iter().for_each(|step| {
+            event!(target: PRINT_TARGET, Level::INFO, "{step}");
+        });
+
+        if logged_in {
+            let _ = client.logout_user(&LogoutUser {}).await;
+        }
+
+        let failed = steps.iter().filter(|step| !step.passed()).count();
+        if failed > 0 {
+            bail!(
+                "Diagnosis failed, {} of {} steps didn't pass",
+                failed,
+                steps.len()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_format_steps_as_checklist() {
+        let passed = DiagnoseStep::new("connect to server", Ok(()).into());
+        let failed = DiagnoseStep::new("login", Err(IggyError::InvalidCredentials).into());
+        let skipped = DiagnoseStep::new("get stats", StepStatus::Skipped);
+
+        assert_eq!(passed.to_string(), "[PASS] connect to server");
+        assert_eq!(
+            failed.to_string(),
+            format!(
+                "[FAIL] login: {} (error code: {})",
+                IggyError::InvalidCredentials,
+                IggyError::InvalidCredentials.as_code()
+            )
+        );
+        assert_eq!(
+            skipped.to_string(),
+            "[SKIP] get stats (previous step failed)"
+        );
+        assert!(passed.passed());
+        assert!(!failed.passed());
+        assert!(!skipped.passed());
+    }
+}
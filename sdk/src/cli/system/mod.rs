@@ -1,3 +1,4 @@
+pub mod diagnose;
 pub mod me;
 pub mod ping;
 pub mod stats;
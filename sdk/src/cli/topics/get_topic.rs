@@ -1,6 +1,7 @@
 use crate::cli_command::{CliCommand, PRINT_TARGET};
 use crate::client::Client;
 use crate::identifier::Identifier;
+use crate::models::topic::TopicDetails;
 use crate::topics::get_topic::GetTopic;
 use crate::utils::timestamp::IggyTimestamp;
 use anyhow::Context;
@@ -40,45 +41,52 @@ impl CliCommand for GetTopicCmd {
             )
         })?;
 
-        let mut table = Table::new();
-
-        table.set_header(vec!["Property", "Value"]);
-        table.add_row(vec!["Topic id", format!("{}", topic.id).as_str()]);
-        table.add_row(vec![
-            "Created",
-            IggyTimestamp::from(topic.created_at)
-                .to_string("%Y-%m-%d %H:%M:%S")
-                .as_str(),
-        ]);
-        table.add_row(vec!["Topic name", topic.name.as_str()]);
-        table.add_row(vec!["Topic size", format!("{}", topic.size).as_str()]);
-        table.add_row(vec![
-            "Message expiry",
-            match topic.message_expiry {
-                Some(value) => format!("{}", value),
-                None => String::from("unlimited"),
-            }
-            .as_str(),
-        ]);
-        table.add_row(vec![
-            "Max topic size",
-            match topic.max_topic_size {
-                Some(value) => format!("{}", value),
-                None => String::from("unlimited"),
-            }
-            .as_str(),
-        ]);
-        table.add_row(vec![
-            "Topic message count",
-            format!("{}", topic.messages_count).as_str(),
-        ]);
-        table.add_row(vec![
-            "Partitions count",
-            format!("{}", topic.partitions_count).as_str(),
-        ]);
+        let table = topic_details_table(&topic);
 
         event!(target: PRINT_TARGET, Level::INFO,"{table}");
 
         Ok(())
     }
 }
+
+/// Render the topic details as the property/value table, shared by `GetTopicCmd` and `WatchTopicCmd`.
+pub(crate) fn topic_details_table(topic: &TopicDetails) -> Table {
+    let mut table = Table::new();
+
+    table.set_header(vec!["Property", "Value"]);
+    table.add_row(vec!["Topic id", format!("{}", topic.id).as_str()]);
+    table.add_row(vec![
+        "Created",
+        IggyTimestamp::from(topic.created_at)
+            .to_string("%Y-%m-%d %H:%M:%S")
+            .as_str(),
+    ]);
+    table.add_row(vec!["Topic name", topic.name.as_str()]);
+    table.add_row(vec!["Topic size", format!("{}", topic.size).as_str()]);
+    table.add_row(vec![
+        "Message expiry",
+        match topic.message_expiry {
+            Some(value) => format!("{}", value),
+            None => String::from("unlimited"),
+        }
+        .as_str(),
+    ]);
+    table.add_row(vec![
+        "Max topic size",
+        match topic.max_topic_size {
+            Some(value) => format!("{}", value),
+            None => String::from("unlimited"),
+        }
+        .as_str(),
+    ]);
+    table.add_row(vec![
+        "Topic message count",
+        format!("{}", topic.messages_count).as_str(),
+    ]);
+    table.add_row(vec![
+        "Partitions count",
+        format!("{}", topic.partitions_count).as_str(),
+    ]);
+
+    table
+}
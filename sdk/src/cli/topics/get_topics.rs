@@ -1,40 +1,193 @@
+use crate::cli::error::CliError;
 use crate::cli_command::{CliCommand, PRINT_TARGET};
 use crate::client::Client;
 use crate::identifier::Identifier;
+use crate::models::topic::Topic;
 use crate::topics::get_topics::GetTopics;
 use crate::utils::timestamp::IggyTimestamp;
 use anyhow::Context;
 use async_trait::async_trait;
 use comfy_table::Table;
+use passterm::{isatty, Stream};
+use serde::Serialize;
+use serde_json::{json, Value};
 use std::fmt::{self, Display, Formatter};
 use tracing::{event, Level};
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum GetTopicsOutput {
+    /// Table when stdout is a terminal, list otherwise (e.g. when piped), resolved when the command is executed.
+    Auto,
     Table,
     List,
+    Json,
+    Csv,
 }
 
 impl Display for GetTopicsOutput {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
+            GetTopicsOutput::Auto => write!(f, "auto"),
             GetTopicsOutput::Table => write!(f, "table"),
             GetTopicsOutput::List => write!(f, "list"),
+            GetTopicsOutput::Json => write!(f, "json"),
+            GetTopicsOutput::Csv => write!(f, "csv"),
         }?;
 
         Ok(())
     }
 }
 
+impl GetTopicsOutput {
+    /// Resolves the `Auto` output to `Table` for a terminal or `List` otherwise, keeping the explicit outputs unchanged.
+    pub fn resolve(self, is_terminal: bool) -> Self {
+        match self {
+            GetTopicsOutput::Auto if is_terminal => GetTopicsOutput::Table,
+            GetTopicsOutput::Auto => GetTopicsOutput::List,
+            output => output,
+        }
+    }
+}
+
+/// The column of the topics list, used to select a subset and the order of the displayed fields.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TopicColumn {
+    Id,
+    Created,
+    Name,
+    Size,
+    MaxTopicSize,
+    MessageExpiry,
+    MessagesCount,
+    PartitionsCount,
+}
+
+impl TopicColumn {
+    /// Returns all the columns in the default order.
+    pub fn all() -> Vec<TopicColumn> {
+        vec![
+            TopicColumn::Id,
+            TopicColumn::Created,
+            TopicColumn::Name,
+            TopicColumn::Size,
+            TopicColumn::MaxTopicSize,
+            TopicColumn::MessageExpiry,
+            TopicColumn::MessagesCount,
+            TopicColumn::PartitionsCount,
+        ]
+    }
+
+    /// Returns the header of the column.
+    pub fn header(&self) -> &'static str {
+        match self {
+            TopicColumn::Id => "ID",
+            TopicColumn::Created => "Created",
+            TopicColumn::Name => "Name",
+            TopicColumn::Size => "Size (B)",
+            TopicColumn::MaxTopicSize => "Max Topic Size (B)",
+            TopicColumn::MessageExpiry => "Message Expiry (s)",
+            TopicColumn::MessagesCount => "Messages Count",
+            TopicColumn::PartitionsCount => "Partitions Count",
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct TopicJson<'a> {
+    #[serde(flatten)]
+    topic: &'a Topic,
+    created_at_iso: String,
+}
+
+impl<'a> From<&'a Topic> for TopicJson<'a> {
+    fn from(topic: &'a Topic) -> Self {
+        Self {
+            topic,
+            created_at_iso: IggyTimestamp::from(topic.created_at)
+                .to_string("%Y-%m-%dT%H:%M:%S%.6fZ"),
+        }
+    }
+}
+
+/// Returns the header and the formatted value of each topic field, in the default column order.
+fn topic_fields(topic: &Topic) -> Vec<(&'static str, String)> {
+    TopicColumn::all()
+        .into_iter()
+        .map(|column| {
+            let value = match column {
+                TopicColumn::Id => format!("{}", topic.id),
+                TopicColumn::Created => {
+                    IggyTimestamp::from(topic.created_at).to_string("%Y-%m-%d %H:%M:%S")
+                }
+                TopicColumn::Name => topic.name.clone(),
+                TopicColumn::Size => format!("{}", topic.size),
+                TopicColumn::MaxTopicSize => match topic.max_topic_size {
+                    Some(value) => format!("{}", value),
+                    None => String::from("unlimited"),
+                },
+                TopicColumn::MessageExpiry => match topic.message_expiry {
+                    Some(value) => format!("{}", value),
+                    None => String::from("unlimited"),
+                },
+                TopicColumn::MessagesCount => format!("{}", topic.messages_count),
+                TopicColumn::PartitionsCount => format!("{}", topic.partitions_count),
+            };
+            (column.header(), value)
+        })
+        .collect()
+}
+
+/// Returns the values of the selected columns in the requested order.
+fn select_columns(topic: &Topic, columns: &[TopicColumn]) -> Vec<String> {
+    let fields = topic_fields(topic);
+    columns
+        .iter()
+        .filter_map(|column| {
+            fields
+                .iter()
+                .find(|(header, _)| *header == column.header())
+                .map(|(_, value)| value.clone())
+        })
+        .collect()
+}
+
+/// Quotes the value if it contains the separator, a quote or a line break, so the record can be parsed unambiguously.
+fn quote_value(value: &str, separator: &str) -> String {
+    if value.contains(separator) || value.contains(['"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Joins the values into a single record using the provided separator, quoting them when needed.
+fn join_values<T: AsRef<str>>(values: &[T], separator: &str) -> String {
+    values
+        .iter()
+        .map(|value| quote_value(value.as_ref(), separator))
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
 pub struct GetTopicsCmd {
     get_topics: GetTopics,
     output: GetTopicsOutput,
+    columns: Vec<TopicColumn>,
+    separator: String,
 }
 
 impl GetTopicsCmd {
-    pub fn new(stream_id: Identifier, output: GetTopicsOutput) -> Self {
+    pub fn new(
+        stream_id: Identifier,
+        output: GetTopicsOutput,
+        columns: Vec<TopicColumn>,
+        separator: String,
+    ) -> Self {
         Self {
             get_topics: GetTopics { stream_id },
             output,
+            columns,
+            separator,
         }
     }
 }
@@ -42,52 +195,48 @@ impl GetTopicsCmd {
 #[async_trait]
 impl CliCommand for GetTopicsCmd {
     fn explain(&self) -> String {
+        let fields = self.explain_structured();
         format!(
             "list topics from stream with ID: {} in {} mode",
-            self.get_topics.stream_id, self.output
+            fields["stream_id"].as_str().unwrap_or_default(),
+            fields["mode"].as_str().unwrap_or_default(),
         )
     }
 
+    fn explain_structured(&self) -> Value {
+        json!({
+            "operation": "list_topics",
+            "stream_id": self.get_topics.stream_id.to_string(),
+            "mode": self.output.to_string(),
+        })
+    }
+
     async fn execute_cmd(&mut self, client: &dyn Client) -> anyhow::Result<(), anyhow::Error> {
-        let topics = client.get_topics(&self.get_topics).await.with_context(|| {
-            format!(
-                "Problem getting topics from stream {}",
-                self.get_topics.stream_id
-            )
-        })?;
-
-        match self.output {
+        let topics = client
+            .get_topics(&self.get_topics)
+            .await
+            .map_err(CliError::from)
+            .with_context(|| {
+                format!(
+                    "Problem getting topics from stream {}",
+                    self.get_topics.stream_id
+                )
+            })?;
+
+        match self.output.resolve(isatty(Stream::Stdout)) {
+            GetTopicsOutput::Auto => unreachable!("auto output is resolved before printing"),
             GetTopicsOutput::Table => {
                 let mut table = Table::new();
 
-                table.set_header(vec![
-                    "ID",
-                    "Created",
-                    "Name",
-                    "Size (B)",
-                    "Max Topic Size (B)",
-                    "Message Expiry (s)",
-                    "Messages Count",
-                    "Partitions Count",
-                ]);
+                table.set_header(
+                    self.columns
+                        .iter()
+                        .map(|column| column.header())
+                        .collect::<Vec<_>>(),
+                );
 
                 topics.iter().for_each(|topic| {
-                    table.add_row(vec![
-                        format!("{}", topic.id),
-                        IggyTimestamp::from(topic.created_at).to_string("%Y-%m-%d %H:%M:%S"),
-                        topic.name.clone(),
-                        format!("{}", topic.size),
-                        match topic.max_topic_size {
-                            Some(value) => format!("{}", value),
-                            None => String::from("unlimited"),
-                        },
-                        match topic.message_expiry {
-                            Some(value) => format!("{}", value),
-                            None => String::from("unlimited"),
-                        },
-                        format!("{}", topic.messages_count),
-                        format!("{}", topic.partitions_count),
-                    ]);
+                    table.add_row(select_columns(topic, &self.columns));
                 });
 
                 event!(target: PRINT_TARGET, Level::INFO, "{table}");
@@ -95,26 +244,124 @@ impl CliCommand for GetTopicsCmd {
             GetTopicsOutput::List => {
                 topics.iter().for_each(|topic| {
                     event!(target: PRINT_TARGET, Level::INFO,
-                        "{}|{}|{}|{}|{}|{}|{}|{}",
-                        topic.id,
-                        IggyTimestamp::from(topic.created_at).to_string("%Y-%m-%d %H:%M:%S"),
-                        topic.name,
-                        topic.size,
-                        match topic.max_topic_size {
-                            Some(value) => format!("{}", value),
-                            None => String::from("unlimited"),
-                        },
-                        match topic.message_expiry {
-                            Some(value) => format!("{}", value),
-                            None => String::from("unlimited"),
-                        },
-                        topic.messages_count,
-                        topic.partitions_count
+                        "{}",
+                        join_values(&select_columns(topic, &self.columns), &self.separator)
                     );
                 });
             }
+            GetTopicsOutput::Csv => {
+                let headers = self
+                    .columns
+                    .iter()
+                    .map(|column| column.header())
+                    .collect::<Vec<_>>();
+                let mut lines = vec![join_values(&headers, ",")];
+                topics.iter().for_each(|topic| {
+                    lines.push(join_values(&select_columns(topic, &self.columns), ","));
+                });
+
+                event!(target: PRINT_TARGET, Level::INFO, "{}", lines.join("\n"));
+            }
+            GetTopicsOutput::Json => {
+                let topics = topics.iter().map(TopicJson::from).collect::<Vec<_>>();
+                let json = serde_json::to_string_pretty(&topics)
+                    .with_context(|| "Problem serializing topics to JSON")?;
+
+                event!(target: PRINT_TARGET, Level::INFO, "{json}");
+            }
         }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_select_columns_in_requested_order() {
+        let topic = Topic {
+            id: 7,
+            created_at: 0,
+            name: "orders".to_string(),
+            size: 100.into(),
+            message_expiry: None,
+            max_topic_size: None,
+            replication_factor: 1,
+            messages_count: 42,
+            partitions_count: 3,
+        };
+
+        let values = select_columns(
+            &topic,
+            &[
+                TopicColumn::PartitionsCount,
+                TopicColumn::Name,
+                TopicColumn::Id,
+                TopicColumn::MessageExpiry,
+            ],
+        );
+
+        assert_eq!(values, vec!["3", "orders", "7", "unlimited"]);
+        assert_eq!(topic_fields(&topic).len(), TopicColumn::all().len());
+    }
+
+    #[test]
+    fn should_resolve_auto_output_depending_on_terminal() {
+        assert_eq!(GetTopicsOutput::Auto.resolve(true), GetTopicsOutput::Table);
+        assert_eq!(GetTopicsOutput::Auto.resolve(false), GetTopicsOutput::List);
+        assert_eq!(
+            GetTopicsOutput::Table.resolve(false),
+            GetTopicsOutput::Table
+        );
+        assert_eq!(GetTopicsOutput::Csv.resolve(true), GetTopicsOutput::Csv);
+        assert_eq!(GetTopicsOutput::Auto.to_string(), "auto");
+    }
+
+    #[test]
+    fn should_explain_command_as_discrete_fields() {
+        let command = GetTopicsCmd::new(
+            Identifier::named("prod").unwrap(),
+            GetTopicsOutput::Auto,
+            TopicColumn::all(),
+            String::from("|"),
+        );
+
+        assert_eq!(
+            command.explain_structured(),
+            json!({ "operation": "list_topics", "stream_id": "prod", "mode": "auto" })
+        );
+        assert_eq!(
+            command.explain(),
+            "list topics from stream with ID: prod in auto mode"
+        );
+    }
+
+    #[test]
+    fn should_quote_csv_values_containing_separators_or_quotes() {
+        assert_eq!(quote_value("orders", ","), "orders");
+        assert_eq!(quote_value("orders,eu", ","), "\"orders,eu\"");
+        assert_eq!(
+            quote_value("the \"best\" topic", ","),
+            "\"the \"\"best\"\" topic\""
+        );
+        assert_eq!(quote_value("Size (B)", ","), "Size (B)");
+    }
+
+    #[test]
+    fn should_quote_list_name_containing_default_separator() {
+        let values = vec!["1".to_string(), "orders|eu".to_string(), "3".to_string()];
+
+        assert_eq!(join_values(&values, "|"), "1|\"orders|eu\"|3");
+    }
+
+    #[test]
+    fn should_quote_list_name_containing_custom_separator_only() {
+        let values = vec!["1".to_string(), "orders|eu".to_string()];
+        assert_eq!(join_values(&values, ";"), "1;orders|eu");
+
+        let values = vec!["1".to_string(), "orders;eu".to_string()];
+        assert_eq!(join_values(&values, ";"), "1;\"orders;eu\"");
+    }
+}
@@ -4,3 +4,4 @@ pub mod get_topic;
 pub mod get_topics;
 pub mod purge_topic;
 pub mod update_topic;
+pub mod watch_topic;
@@ -0,0 +1,74 @@
+use crate::cli::topics::get_topic::topic_details_table;
+use crate::cli_command::{CliCommand, PRINT_TARGET};
+use crate::client::Client;
+use crate::identifier::Identifier;
+use crate::topics::get_topic::GetTopic;
+use crate::utils::duration::IggyDuration;
+use crate::utils::timestamp::IggyTimestamp;
+use anyhow::{bail, Context};
+use async_trait::async_trait;
+use tokio::time::sleep;
+use tracing::{event, Level};
+
+// Clear the whole screen and move the cursor to the top left corner.
+const CLEAR_SCREEN: &str = "\x1B[2J\x1B[1;1H";
+
+pub struct WatchTopicCmd {
+    get_topic: GetTopic,
+    interval: IggyDuration,
+}
+
+impl WatchTopicCmd {
+    pub fn new(stream_id: Identifier, topic_id: Identifier, interval: IggyDuration) -> Self {
+        Self {
+            get_topic: GetTopic {
+                stream_id,
+                topic_id,
+            },
+            interval,
+        }
+    }
+}
+
+#[async_trait]
+impl CliCommand for WatchTopicCmd {
+    fn explain(&self) -> String {
+        format!(
+            "watch topic with ID: {} from stream with ID: {} every {}",
+            self.get_topic.topic_id,
+            self.get_topic.stream_id,
+            self.interval.as_human_time_string()
+        )
+    }
+
+    async fn execute_cmd(&mut self, client: &dyn Client) -> anyhow::Result<(), anyhow::Error> {
+        if self.interval.get_duration().is_zero() {
+            bail!("Watch interval must be greater than zero");
+        }
+
+        let ctrl_c = tokio::signal::ctrl_c();
+        tokio::pin!(ctrl_c);
+
+        loop {
+            let topic = client.get_topic(&self.get_topic).await.with_context(|| {
+                format!(
+                    "Problem getting topic with ID: {} in stream {}",
+                    self.get_topic.topic_id, self.get_topic.stream_id
+                )
+            })?;
+
+            let table = topic_details_table(&topic);
+
+            event!(target: PRINT_TARGET, Level::INFO, "{CLEAR_SCREEN}Every {}, last update: {}, press Ctrl-C to stop\n{table}",
+                self.interval.as_human_time_string(),
+                IggyTimestamp::now().to_local("%Y-%m-%d %H:%M:%S"));
+
+            tokio::select! {
+                _ = sleep(self.interval.get_duration()) => {}
+                _ = &mut ctrl_c => break,
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,12 +1,18 @@
 use crate::client::Client;
 use anyhow::{Error, Result};
 use async_trait::async_trait;
+use serde_json::{json, Value};
 
 pub static PRINT_TARGET: &str = "iggy::cli::output";
 
 #[async_trait]
 pub trait CliCommand {
     fn explain(&self) -> String;
+    /// The machine readable counterpart of `explain`, with the operation name, the IDs of the targeted resources
+    /// and the mode as the discrete fields. The commands not providing it return only the human readable explanation.
+    fn explain_structured(&self) -> Value {
+        json!({ "explanation": self.explain() })
+    }
     fn use_tracing(&self) -> bool {
         true
     }
@@ -4,11 +4,14 @@ use crate::consumer_groups::get_consumer_group::GetConsumerGroup;
 use crate::consumer_groups::get_consumer_groups::GetConsumerGroups;
 use crate::consumer_groups::join_consumer_group::JoinConsumerGroup;
 use crate::consumer_groups::leave_consumer_group::LeaveConsumerGroup;
+use crate::consumer_groups::reset_consumer_group_offset::ResetConsumerGroupOffset;
 use crate::consumer_offsets::get_consumer_offset::GetConsumerOffset;
 use crate::consumer_offsets::store_consumer_offset::StoreConsumerOffset;
 use crate::error::IggyError;
+use crate::messages::message_id::IdKind;
 use crate::messages::poll_messages::PollMessages;
 use crate::messages::send_messages::SendMessages;
+use crate::messages::send_messages_conditional::SendMessagesConditional;
 use crate::models::client_info::{ClientInfo, ClientInfoDetails};
 use crate::models::consumer_group::{ConsumerGroup, ConsumerGroupDetails};
 use crate::models::consumer_offset_info::ConsumerOffsetInfo;
@@ -78,6 +81,13 @@ pub trait Client:
 
     /// Disconnect from the server. If the client is not connected, it will do nothing.
     async fn disconnect(&self) -> Result<(), IggyError>;
+
+    /// Returns true if the client is authenticated. It becomes false after the logout or disconnect,
+    /// and any secured command invoked in this state fails with `IggyError::Unauthenticated` until the client logs in again.
+    /// The default implementation doesn't track the authentication state and returns true, leaving the check to the server.
+    async fn is_authenticated(&self) -> bool {
+        true
+    }
 }
 
 /// This trait defines the methods to interact with the system module.
@@ -251,6 +261,42 @@ pub trait MessageClient {
     ///
     /// Authentication is required, and the permission to send the messages.
     async fn send_messages(&self, command: &mut SendMessages) -> Result<(), IggyError>;
+    /// Send messages like `send_messages` and return their IDs in the order of the batch, to correlate them with the source records.
+    /// The server doesn't return the IDs it generates, so the messages without the ID (0) are assigned the UUIDv7 ID
+    /// on the client side before sending, while the IDs set by the client are returned as is.
+    ///
+    /// Authentication is required, and the permission to send the messages.
+    async fn send_messages_with_ids(
+        &self,
+        command: &mut SendMessages,
+    ) -> Result<Vec<u128>, IggyError> {
+        for message in command
+            .messages_mut()
+            .iter_mut()
+            .filter(|message| message.id == 0)
+        {
+            message.id = IdKind::UuidV7.generate();
+        }
+        let ids = command
+            .messages()
+            .iter()
+            .map(|message| message.id)
+            .collect();
+        self.send_messages(command).await?;
+        Ok(ids)
+    }
+    /// Send messages to the given stream and topic by unique IDs or names, only if the ID of the last message in the target partition matches the expected one.
+    /// The server rejects the whole batch with `IggyError::ConditionFailed` on mismatch.
+    ///
+    /// Authentication is required, and the permission to send the messages.
+    ///
+    /// The default implementation fails with `IggyError::FeatureUnavailable`, as the condition can't be emulated on the client side.
+    async fn send_messages_conditional(
+        &self,
+        _command: &mut SendMessagesConditional,
+    ) -> Result<(), IggyError> {
+        Err(IggyError::FeatureUnavailable)
+    }
 }
 
 /// This trait defines the methods to interact with the consumer offset module.
@@ -302,4 +348,68 @@ pub trait ConsumerGroupClient {
     ///
     /// Authentication is required, and the permission to read the streams or topics.
     async fn leave_consumer_group(&self, command: &LeaveConsumerGroup) -> Result<(), IggyError>;
+    /// Reset the stored offsets of a consumer group by unique ID or name in all the partitions of the given stream and topic by unique IDs or names.
+    ///
+    /// Authentication is required, and the permission to manage the streams or topics.
+    async fn reset_consumer_group_offset(
+        &self,
+        command: &ResetConsumerGroupOffset,
+    ) -> Result<(), IggyError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::send_messages::Message;
+    use bytes::Bytes;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct MockMessageClient {
+        sent_ids: Mutex<Vec<u128>>,
+    }
+
+    #[async_trait]
+    impl MessageClient for MockMessageClient {
+        async fn poll_messages(&self, _: &PollMessages) -> Result<PolledMessages, IggyError> {
+            Err(IggyError::FeatureUnavailable)
+        }
+
+        async fn send_messages(&self, command: &mut SendMessages) -> Result<(), IggyError> {
+            let mut sent_ids = self.sent_ids.lock().unwrap();
+            sent_ids.extend(command.messages().iter().map(|message| message.id));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn send_messages_with_ids_should_return_ids_in_batch_order() {
+        let client = MockMessageClient::default();
+        let mut command: SendMessages = [
+            Message::new(Some(7), Bytes::from("first"), None),
+            Message::new(None, Bytes::from("second"), None),
+            Message::new(Some(3), Bytes::from("third"), None),
+        ]
+        .into_iter()
+        .collect();
+
+        let ids = client.send_messages_with_ids(&mut command).await.unwrap();
+
+        assert_eq!(ids.len(), 3);
+        assert_eq!(ids[0], 7);
+        assert_ne!(ids[1], 0);
+        assert_eq!(ids[2], 3);
+        assert_eq!(*client.sent_ids.lock().unwrap(), ids);
+    }
+
+    #[tokio::test]
+    async fn send_messages_conditional_should_be_unavailable_by_default() {
+        let client = MockMessageClient::default();
+        let mut command = SendMessagesConditional::new(0, SendMessages::default());
+
+        let result = client.send_messages_conditional(&mut command).await;
+
+        assert!(matches!(result, Err(IggyError::FeatureUnavailable)));
+        assert!(client.sent_ids.lock().unwrap().is_empty());
+    }
 }
@@ -2,6 +2,7 @@ use crate::client::{
     Client, ConsumerGroupClient, ConsumerOffsetClient, MessageClient, PartitionClient,
     PersonalAccessTokenClient, StreamClient, SystemClient, TopicClient, UserClient,
 };
+use crate::clients::send_retry::is_transient;
 use crate::consumer::Consumer;
 use crate::consumer_groups::create_consumer_group::CreateConsumerGroup;
 use crate::consumer_groups::delete_consumer_group::DeleteConsumerGroup;
@@ -9,6 +10,7 @@ use crate::consumer_groups::get_consumer_group::GetConsumerGroup;
 use crate::consumer_groups::get_consumer_groups::GetConsumerGroups;
 use crate::consumer_groups::join_consumer_group::JoinConsumerGroup;
 use crate::consumer_groups::leave_consumer_group::LeaveConsumerGroup;
+use crate::consumer_groups::reset_consumer_group_offset::ResetConsumerGroupOffset;
 use crate::consumer_offsets::get_consumer_offset::GetConsumerOffset;
 use crate::consumer_offsets::store_consumer_offset::StoreConsumerOffset;
 use crate::error::IggyError;
@@ -16,6 +18,7 @@ use crate::identifier::Identifier;
 use crate::message_handler::MessageHandler;
 use crate::messages::poll_messages::{PollMessages, PollingKind};
 use crate::messages::send_messages::{Partitioning, PartitioningKind, SendMessages};
+use crate::messages::send_messages_conditional::SendMessagesConditional;
 use crate::models::client_info::{ClientInfo, ClientInfoDetails};
 use crate::models::consumer_group::{ConsumerGroup, ConsumerGroupDetails};
 use crate::models::consumer_offset_info::ConsumerOffsetInfo;
@@ -69,24 +72,35 @@ use std::collections::VecDeque;
 use std::fmt::Debug;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::{Mutex, RwLock};
+use tokio::sync::{watch, Mutex, RwLock};
 use tokio::task::JoinHandle;
 use tokio::time::sleep;
 use tracing::{error, info, warn};
 
 /// The main client struct which implements all the `Client` traits and wraps the underlying low-level client for the specific transport.
 /// It also provides additional functionality (outside of the shared trait) like sending messages in background, partitioning, client-side encryption or message handling via channels.
+/// When the messages are sent in background, `close()` must be awaited before the client is dropped, otherwise the buffered messages might be lost.
+/// The drop only spawns the best-effort flush on the current Tokio runtime, which doesn't run without the runtime and is abandoned if the runtime
+/// shuts down before it completes.
 #[derive(Debug)]
 pub struct IggyClient {
     client: Arc<RwLock<Box<dyn Client>>>,
     config: Option<IggyClientConfig>,
     send_messages_batch: Option<Arc<Mutex<SendMessagesBatch>>>,
+    send_messages_task: Option<SendMessagesTask>,
     partitioner: Option<Box<dyn Partitioner>>,
     encryptor: Option<Box<dyn Encryptor>>,
     message_handler: Option<Arc<Box<dyn MessageHandler>>>,
     message_channel_sender: Option<Arc<Sender<Message>>>,
 }
 
+// The task sending the buffered messages in the background, which stops once the shutdown is signaled.
+#[derive(Debug)]
+struct SendMessagesTask {
+    shutdown: watch::Sender<bool>,
+    handle: JoinHandle<()>,
+}
+
 /// The builder for the `IggyClient` instance, which allows to configure and provide custom implementations for the partitioner, encryptor or message handler.
 #[derive(Debug)]
 pub struct IggyClientBuilder {
@@ -132,9 +146,21 @@ impl IggyClientBuilder {
     }
 }
 
+// The messages buffered for sending in the background, along with the limits applied to them.
 #[derive(Debug)]
 struct SendMessagesBatch {
-    pub commands: VecDeque<SendMessages>,
+    commands: VecDeque<BufferedSendMessages>,
+    messages_count: usize,
+    max_messages: usize,
+    max_retries: u32,
+    max_buffered_messages: usize,
+}
+
+// The buffered command along with the number of the failed attempts to send it.
+#[derive(Debug)]
+struct BufferedSendMessages {
+    send_messages: SendMessages,
+    failed_attempts: u32,
 }
 
 /// The optional configuration for the `IggyClient` instance, consisting of the optional configuration for sending and polling the messages in the background.
@@ -146,7 +172,8 @@ pub struct IggyClientConfig {
     pub poll_messages: PollMessagesConfig,
 }
 
-/// The configuration for sending the messages in the background. It allows to configure the interval between sending the messages as batches in the background and the maximum number of messages in the batch.
+/// The configuration for sending the messages in the background. It allows to configure the interval between sending the messages as batches in the background,
+/// the maximum number of messages in the batch, how many times the messages which failed to be sent are retried and how many messages can be buffered.
 #[derive(Debug)]
 pub struct SendMessagesConfig {
     /// Whether the sending messages as batches in the background is enabled. Interval must be greater than 0.
@@ -155,6 +182,11 @@ pub struct SendMessagesConfig {
     pub interval: u64,
     /// The maximum number of messages in the batch.
     pub max_messages: u32,
+    /// The maximum number of retries for the messages which failed to be sent due to the transient error (e.g. connection failure), after which they are dropped.
+    /// The messages which failed due to any other error are dropped without retrying.
+    pub max_retries: u32,
+    /// The maximum number of messages which can be buffered, once it's reached sending the messages fails with `SendMessagesBufferFull` error.
+    pub max_buffered_messages: u32,
 }
 
 /// The configuration for polling the messages in the background. It allows to configure the interval between polling the messages and the offset storing strategy.
@@ -185,6 +217,8 @@ impl Default for SendMessagesConfig {
             enabled: false,
             interval: 100,
             max_messages: 1000,
+            max_retries: 3,
+            max_buffered_messages: 100_000,
         }
     }
 }
@@ -216,6 +250,7 @@ impl IggyClient {
             client: Arc::new(RwLock::new(client)),
             config: None,
             send_messages_batch: None,
+            send_messages_task: None,
             partitioner: None,
             encryptor: None,
             message_handler: None,
@@ -240,23 +275,23 @@ impl IggyClient {
         }
 
         let client = Arc::new(RwLock::new(client));
-        let send_messages_batch = Arc::new(Mutex::new(SendMessagesBatch {
-            commands: VecDeque::new(),
-        }));
+        let send_messages_batch =
+            Arc::new(Mutex::new(SendMessagesBatch::new(&config.send_messages)));
+        let mut send_messages_task = None;
         if config.send_messages.enabled && config.send_messages.interval > 0 {
             info!("Messages will be sent in background.");
-            Self::send_messages_in_background(
+            send_messages_task = Some(Self::send_messages_in_background(
                 config.send_messages.interval,
-                config.send_messages.max_messages,
                 client.clone(),
                 send_messages_batch.clone(),
-            );
+            ));
         }
 
         IggyClient {
             client,
             config: Some(config),
             send_messages_batch: Some(send_messages_batch),
+            send_messages_task,
             message_handler: message_handler.map(Arc::new),
             message_channel_sender: None,
             partitioner,
@@ -370,7 +405,7 @@ impl IggyClient {
             &command.stream_id,
             &command.topic_id,
             &command.partitioning,
-            &command.messages,
+            command.messages(),
         )?;
         command.partitioning = Partitioning::partition_id(partition_id);
         self.send_messages(command).await
@@ -391,104 +426,253 @@ impl IggyClient {
         }
     }
 
+    /// Sends all the messages which are currently buffered for sending in the background.
+    /// Returns the last error encountered, while the messages which failed due to the transient error are kept in the buffer until their retries are exhausted,
+    /// and all the other ones are dropped and logged.
+    pub async fn flush(&self) -> Result<(), IggyError> {
+        let Some(send_messages_batch) = &self.send_messages_batch else {
+            return Ok(());
+        };
+
+        let mut send_messages_batch = send_messages_batch.lock().await;
+        Self::send_buffered_messages(&self.client, &mut send_messages_batch).await
+    }
+
+    /// Gracefully shuts down the sending of the messages in the background. It signals the background task to stop, waits until it completes
+    /// (so the batch which is being sent isn't interrupted), and flushes the remaining buffered messages, returning the error if any of them couldn't be sent.
+    /// It must be awaited before the client is dropped, as the drop can't wait for the buffered messages to be sent.
+    pub async fn close(&mut self) -> Result<(), IggyError> {
+        if let Some(send_messages_task) = self.send_messages_task.take() {
+            let _ = send_messages_task.shutdown.send(true);
+            if let Err(error) = send_messages_task.handle.await {
+                error!(
+                    "The task sending the messages in background failed: {:?}",
+                    error
+                );
+            }
+        }
+        self.flush().await
+    }
+
+    fn prepare_messages(&self, command: &mut SendMessages) -> Result<(), IggyError> {
+        if let Some(partitioner) = &self.partitioner {
+            let partition_id = partitioner.calculate_partition_id(
+                &command.stream_id,
+                &command.topic_id,
+                &command.partitioning,
+                command.messages(),
+            )?;
+            command.partitioning = Partitioning::partition_id(partition_id);
+        }
+
+        if let Some(encryptor) = &self.encryptor {
+            for message in command.messages_mut() {
+                message.payload = Bytes::from(encryptor.encrypt(&message.payload)?);
+                message.length = message.payload.len() as u32;
+            }
+        }
+
+        Ok(())
+    }
+
     fn send_messages_in_background(
         interval: u64,
-        max_messages: u32,
         client: Arc<RwLock<Box<dyn Client>>>,
         send_messages_batch: Arc<Mutex<SendMessagesBatch>>,
-    ) {
-        tokio::spawn(async move {
-            let max_messages = max_messages as usize;
+    ) -> SendMessagesTask {
+        let (shutdown, mut shutdown_receiver) = watch::channel(false);
+        let handle = tokio::spawn(async move {
             let interval = Duration::from_millis(interval);
             loop {
-                sleep(interval).await;
+                // The shutdown is awaited only between the sends, so the batch which is being sent is never interrupted.
+                tokio::select! {
+                    _ = sleep(interval) => {}
+                    _ = shutdown_receiver.changed() => break,
+                }
                 let mut send_messages_batch = send_messages_batch.lock().await;
-                if send_messages_batch.commands.is_empty() {
-                    continue;
+                if let Err(error) =
+                    Self::send_buffered_messages(&client, &mut send_messages_batch).await
+                {
+                    error!(
+                        "There was an error when sending the messages in background: {:?}",
+                        error
+                    );
                 }
+            }
+        });
+        SendMessagesTask { shutdown, handle }
+    }
 
-                let mut initialized = false;
-                let mut stream_id = Identifier::numeric(1).unwrap();
-                let mut topic_id = Identifier::numeric(1).unwrap();
-                let mut key = Partitioning::partition_id(1);
-                let mut batch_messages = true;
-
-                for send_messages in &send_messages_batch.commands {
-                    if !initialized {
-                        if send_messages.partitioning.kind != PartitioningKind::PartitionId {
-                            batch_messages = false;
-                            break;
-                        }
-
-                        stream_id = Identifier::from_identifier(&send_messages.stream_id);
-                        topic_id = Identifier::from_identifier(&send_messages.topic_id);
-                        key.value = send_messages.partitioning.value.clone();
-                        initialized = true;
-                    }
+    async fn send_buffered_messages(
+        client: &RwLock<Box<dyn Client>>,
+        send_messages_batch: &mut SendMessagesBatch,
+    ) -> Result<(), IggyError> {
+        if send_messages_batch.commands.is_empty() {
+            return Ok(());
+        }
 
-                    // Batching the messages is only possible for the same stream, topic and partition.
-                    if send_messages.stream_id != stream_id
-                        || send_messages.topic_id != topic_id
-                        || send_messages.partitioning.kind != PartitioningKind::PartitionId
-                        || send_messages.partitioning.value != key.value
-                    {
-                        batch_messages = false;
-                        break;
-                    }
+        let mut initialized = false;
+        let mut stream_id = Identifier::numeric(1).unwrap();
+        let mut topic_id = Identifier::numeric(1).unwrap();
+        let mut key = Partitioning::partition_id(1);
+        let mut batch_messages = true;
+
+        for BufferedSendMessages { send_messages, .. } in &send_messages_batch.commands {
+            if !initialized {
+                if send_messages.partitioning.kind != PartitioningKind::PartitionId {
+                    batch_messages = false;
+                    break;
                 }
 
-                if !batch_messages {
-                    for send_messages in &mut send_messages_batch.commands {
-                        if let Err(error) = client.read().await.send_messages(send_messages).await {
-                            error!("There was an error when sending the messages: {:?}", error);
-                        }
-                    }
-                    send_messages_batch.commands.clear();
-                    continue;
-                }
+                stream_id = Identifier::from_identifier(&send_messages.stream_id);
+                topic_id = Identifier::from_identifier(&send_messages.topic_id);
+                key.value = send_messages.partitioning.value.clone();
+                initialized = true;
+            }
 
-                let mut batches = VecDeque::new();
-                let mut messages = Vec::new();
-                while let Some(send_messages) = send_messages_batch.commands.pop_front() {
-                    messages.extend(send_messages.messages);
-                    if messages.len() >= max_messages {
-                        batches.push_back(messages);
-                        messages = Vec::new();
-                    }
-                }
+            // Batching the messages is only possible for the same stream, topic and partition.
+            if send_messages.stream_id != stream_id
+                || send_messages.topic_id != topic_id
+                || send_messages.partitioning.kind != PartitioningKind::PartitionId
+                || send_messages.partitioning.value != key.value
+            {
+                batch_messages = false;
+                break;
+            }
+        }
 
-                if !messages.is_empty() {
-                    batches.push_back(messages);
+        let mut result = Ok(());
+        if !batch_messages {
+            let mut failed_commands = Vec::new();
+            while let Some(mut buffered) = send_messages_batch.pop() {
+                if let Err(error) = client
+                    .read()
+                    .await
+                    .send_messages(&mut buffered.send_messages)
+                    .await
+                {
+                    error!("There was an error when sending the messages: {:?}", error);
+                    failed_commands.push((buffered, error));
                 }
+            }
+            for (buffered, error) in failed_commands {
+                send_messages_batch.requeue(buffered, &error);
+                result = Err(error);
+            }
+            return result;
+        }
 
-                while let Some(messages) = batches.pop_front() {
-                    let mut send_messages = SendMessages {
-                        stream_id: Identifier::from_identifier(&stream_id),
-                        topic_id: Identifier::from_identifier(&topic_id),
-                        partitioning: Partitioning {
-                            kind: PartitioningKind::PartitionId,
-                            length: 4,
-                            value: key.value.clone(),
-                        },
-                        messages,
-                    };
-
-                    if let Err(error) = client.read().await.send_messages(&mut send_messages).await
-                    {
-                        error!(
-                            "There was an error when sending the messages batch: {:?}",
-                            error
-                        );
-
-                        if !send_messages.messages.is_empty() {
-                            batches.push_back(send_messages.messages);
-                        }
-                    }
-                }
+        // The batch allows the tombstones if any of the merged commands allowed them,
+        // and it has as many failed attempts as the most retried of the merged commands.
+        let mut batches = VecDeque::new();
+        let mut messages = Vec::new();
+        let mut allow_tombstones = false;
+        let mut failed_attempts = 0;
+        while let Some(buffered) = send_messages_batch.pop() {
+            allow_tombstones |= buffered.send_messages.allow_tombstones;
+            messages.extend(buffered.send_messages.into_messages());
+            failed_attempts = failed_attempts.max(buffered.failed_attempts);
+            if messages.len() >= send_messages_batch.max_messages {
+                batches.push_back((messages, allow_tombstones, failed_attempts));
+                messages = Vec::new();
+                allow_tombstones = false;
+                failed_attempts = 0;
+            }
+        }
+
+        if !messages.is_empty() {
+            batches.push_back((messages, allow_tombstones, failed_attempts));
+        }
 
-                send_messages_batch.commands.clear();
+        while let Some((messages, allow_tombstones, failed_attempts)) = batches.pop_front() {
+            let mut send_messages = SendMessages::new(
+                Identifier::from_identifier(&stream_id).into(),
+                Identifier::from_identifier(&topic_id).into(),
+                Partitioning {
+                    kind: PartitioningKind::PartitionId,
+                    length: 4,
+                    value: key.value.clone(),
+                },
+                messages,
+            );
+            send_messages.allow_tombstones = allow_tombstones;
+
+            if let Err(error) = client.read().await.send_messages(&mut send_messages).await {
+                error!(
+                    "There was an error when sending the messages batch: {:?}",
+                    error
+                );
+
+                send_messages_batch.requeue(
+                    BufferedSendMessages {
+                        send_messages,
+                        failed_attempts,
+                    },
+                    &error,
+                );
+                result = Err(error);
             }
+        }
+
+        result
+    }
+}
+
+impl SendMessagesBatch {
+    fn new(config: &SendMessagesConfig) -> Self {
+        SendMessagesBatch {
+            commands: VecDeque::new(),
+            messages_count: 0,
+            max_messages: config.max_messages as usize,
+            max_retries: config.max_retries,
+            max_buffered_messages: config.max_buffered_messages as usize,
+        }
+    }
+
+    fn push(&mut self, send_messages: SendMessages) -> Result<(), IggyError> {
+        let messages_count = self.messages_count + send_messages.messages().len();
+        if messages_count > self.max_buffered_messages {
+            return Err(IggyError::SendMessagesBufferFull(
+                self.max_buffered_messages as u32,
+            ));
+        }
+
+        self.messages_count = messages_count;
+        self.commands.push_back(BufferedSendMessages {
+            send_messages,
+            failed_attempts: 0,
         });
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Option<BufferedSendMessages> {
+        let buffered = self.commands.pop_front()?;
+        self.messages_count -= buffered.send_messages.messages().len();
+        Some(buffered)
+    }
+
+    // Keep the command which couldn't be sent in the buffer, so it can be retried later, unless the error isn't transient
+    // or the retries are exhausted, in which case its messages are dropped.
+    fn requeue(&mut self, mut buffered: BufferedSendMessages, error: &IggyError) {
+        if buffered.send_messages.messages().is_empty() {
+            return;
+        }
+
+        buffered.failed_attempts += 1;
+        if !is_transient(error) || buffered.failed_attempts > self.max_retries {
+            error!(
+                "Dropping {} buffered message(s) for stream: {}, topic: {} after {} failed attempt(s), the last error: {}",
+                buffered.send_messages.messages().len(),
+                buffered.send_messages.stream_id,
+                buffered.send_messages.topic_id,
+                buffered.failed_attempts,
+                error
+            );
+            return;
+        }
+
+        self.messages_count += buffered.send_messages.messages().len();
+        self.commands.push_back(buffered);
     }
 }
 
@@ -587,6 +771,10 @@ impl Client for IggyClient {
     async fn disconnect(&self) -> Result<(), IggyError> {
         self.client.read().await.disconnect().await
     }
+
+    async fn is_authenticated(&self) -> bool {
+        self.client.read().await.is_authenticated().await
+    }
 }
 
 #[async_trait]
@@ -691,28 +879,15 @@ impl MessageClient for IggyClient {
     }
 
     async fn send_messages(&self, command: &mut SendMessages) -> Result<(), IggyError> {
-        if command.messages.is_empty() {
+        if command.messages().is_empty() {
             return Ok(());
         }
 
-        if let Some(partitioner) = &self.partitioner {
-            let partition_id = partitioner.calculate_partition_id(
-                &command.stream_id,
-                &command.topic_id,
-                &command.partitioning,
-                &command.messages,
-            )?;
-            command.partitioning = Partitioning::partition_id(partition_id);
-        }
-
-        if let Some(encryptor) = &self.encryptor {
-            for message in &mut command.messages {
-                message.payload = Bytes::from(encryptor.encrypt(&message.payload)?);
-                message.length = message.payload.len() as u32;
-            }
-        }
-
+        self.prepare_messages(command)?;
+        // The batch with the idempotency key is sent as is, so it's never merged with the other buffered batches,
+        // which would make the key identify the different messages.
         let send_messages_now = self.send_messages_batch.is_none()
+            || command.idempotency_key.is_some()
             || match &self.config {
                 Some(config) => !config.send_messages.enabled || config.send_messages.interval == 0,
                 None => true,
@@ -722,26 +897,29 @@ impl MessageClient for IggyClient {
             return self.client.read().await.send_messages(command).await;
         }
 
-        let mut messages = Vec::with_capacity(command.messages.len());
-        for message in &command.messages {
-            let message = crate::messages::send_messages::Message {
-                id: message.id,
-                length: message.length,
-                payload: message.payload.clone(),
-                headers: message.headers.clone(),
-            };
-            messages.push(message);
-        }
-        let send_messages = SendMessages {
-            stream_id: Identifier::from_identifier(&command.stream_id),
-            topic_id: Identifier::from_identifier(&command.topic_id),
-            partitioning: Partitioning::from_partitioning(&command.partitioning),
-            messages,
-        };
+        let mut send_messages = SendMessages::new(
+            Identifier::from_identifier(&command.stream_id).into(),
+            Identifier::from_identifier(&command.topic_id).into(),
+            Partitioning::from_partitioning(&command.partitioning),
+            command.messages().to_vec(),
+        );
+        send_messages.allow_tombstones = command.allow_tombstones;
 
         let mut batch = self.send_messages_batch.as_ref().unwrap().lock().await;
-        batch.commands.push_back(send_messages);
-        Ok(())
+        batch.push(send_messages)
+    }
+
+    async fn send_messages_conditional(
+        &self,
+        command: &mut SendMessagesConditional,
+    ) -> Result<(), IggyError> {
+        // The condition must be checked against the current state of the partition, so the messages are never sent in background.
+        self.prepare_messages(&mut command.send_messages)?;
+        self.client
+            .read()
+            .await
+            .send_messages_conditional(command)
+            .await
     }
 }
 
@@ -802,6 +980,64 @@ impl ConsumerGroupClient for IggyClient {
     async fn leave_consumer_group(&self, command: &LeaveConsumerGroup) -> Result<(), IggyError> {
         self.client.read().await.leave_consumer_group(command).await
     }
+
+    async fn reset_consumer_group_offset(
+        &self,
+        command: &ResetConsumerGroupOffset,
+    ) -> Result<(), IggyError> {
+        self.client
+            .read()
+            .await
+            .reset_consumer_group_offset(command)
+            .await
+    }
+}
+
+impl Drop for IggyClient {
+    fn drop(&mut self) {
+        // The background task is signaled to stop rather than aborted, so the batch which is being sent isn't interrupted.
+        let send_messages_task = self.send_messages_task.take().map(|send_messages_task| {
+            let _ = send_messages_task.shutdown.send(true);
+            send_messages_task.handle
+        });
+
+        let Some(send_messages_batch) = self.send_messages_batch.take() else {
+            return;
+        };
+
+        // Only `close()` guarantees that the buffered messages are sent, as the drop cannot be awaited.
+        match send_messages_batch.try_lock() {
+            Ok(send_messages_batch) if send_messages_batch.commands.is_empty() => return,
+            Ok(send_messages_batch) => warn!(
+                "The client is dropped with {} buffered message(s) which might be lost, `close()` should be awaited before dropping it.",
+                send_messages_batch.messages_count
+            ),
+            Err(_) => warn!(
+                "The client is dropped while the buffered messages are being sent in background, the remaining ones might be lost, `close()` should be awaited before dropping it."
+            ),
+        }
+
+        // The buffered messages are flushed on the best-effort basis: the flush is spawned only if the Tokio runtime is available,
+        // it's abandoned if the runtime shuts down before it completes, and it's sent through the same underlying client,
+        // so it can interleave with the messages sent by the other users of that client in the meantime.
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            warn!("The buffered messages cannot be flushed on drop without the Tokio runtime.");
+            return;
+        };
+
+        let client = self.client.clone();
+        runtime.spawn(async move {
+            if let Some(send_messages_task) = send_messages_task {
+                let _ = send_messages_task.await;
+            }
+            let mut send_messages_batch = send_messages_batch.lock().await;
+            if let Err(error) =
+                Self::send_buffered_messages(&client, &mut send_messages_batch).await
+            {
+                warn!("Failed to flush the buffered messages on drop: {:?}", error);
+            }
+        });
+    }
 }
 
 #[async_trait]
@@ -810,3 +1046,73 @@ impl AsyncDrop for IggyClient {
         let _ = self.client.read().await.logout_user(&LogoutUser {}).await;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::send_messages::Message;
+    use std::str::FromStr;
+
+    #[test]
+    fn messages_should_not_be_buffered_above_the_limit() {
+        let mut batch = send_messages_batch(3, 2);
+        batch.push(send_messages(2)).unwrap();
+        let error = batch.push(send_messages(2)).unwrap_err();
+
+        assert_eq!(
+            error.as_code(),
+            IggyError::SendMessagesBufferFull(2).as_code()
+        );
+        assert_eq!(batch.commands.len(), 1);
+        assert_eq!(batch.messages_count, 2);
+    }
+
+    #[test]
+    fn command_failed_with_transient_error_should_be_requeued_until_retries_are_exhausted() {
+        let mut batch = send_messages_batch(2, 10);
+        batch.push(send_messages(3)).unwrap();
+
+        for attempt in 1..=2 {
+            let buffered = batch.pop().unwrap();
+            batch.requeue(buffered, &IggyError::NotConnected);
+            assert_eq!(batch.commands.len(), 1);
+            assert_eq!(batch.commands[0].failed_attempts, attempt);
+            assert_eq!(batch.messages_count, 3);
+        }
+
+        let buffered = batch.pop().unwrap();
+        batch.requeue(buffered, &IggyError::NotConnected);
+        assert!(batch.commands.is_empty());
+        assert_eq!(batch.messages_count, 0);
+    }
+
+    #[test]
+    fn command_failed_with_non_transient_error_should_be_dropped() {
+        let mut batch = send_messages_batch(3, 10);
+        batch.push(send_messages(3)).unwrap();
+
+        let buffered = batch.pop().unwrap();
+        batch.requeue(buffered, &IggyError::InvalidCommand);
+        assert!(batch.commands.is_empty());
+        assert_eq!(batch.messages_count, 0);
+    }
+
+    fn send_messages_batch(max_retries: u32, max_buffered_messages: u32) -> SendMessagesBatch {
+        SendMessagesBatch::new(&SendMessagesConfig {
+            max_retries,
+            max_buffered_messages,
+            ..SendMessagesConfig::default()
+        })
+    }
+
+    fn send_messages(messages_count: usize) -> SendMessages {
+        SendMessages::new(
+            Identifier::numeric(1).unwrap().into(),
+            Identifier::numeric(1).unwrap().into(),
+            Partitioning::partition_id(1),
+            (0..messages_count)
+                .map(|_| Message::from_str("hello").unwrap())
+                .collect(),
+        )
+    }
+}
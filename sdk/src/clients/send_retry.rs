@@ -0,0 +1,255 @@
+use crate::client::Client;
+use crate::error::IggyError;
+use crate::messages::send_messages::{Partitioning, PartitioningKind, SendMessages};
+use crate::partitioner;
+use crate::topics::get_topic::GetTopic;
+use std::sync::atomic::AtomicU32;
+use std::time::Duration;
+use tracing::warn;
+
+const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+const DEFAULT_BASE_DELAY: Duration = Duration::from_millis(100);
+
+/// The helper sending the messages with the retries and exponential backoff in case of the transient failures.
+/// Before the first attempt, the partitioning is resolved to the concrete `PartitionId`, so all the attempts land
+/// on the same partition:
+/// - `Balanced` - the partition is picked by the client-side round robin.
+/// - `MessagesKey`, `ConsistentKey` and `HashedKey` - the partition is calculated using the same hash as the server does.
+/// - `PartitionId` - the partition is used as is.
+///
+/// The partition is resolved by `partitioner::resolve_partition_id`, the same way as on the server.
+///
+/// The idempotency key is never assigned automatically, so the retried batch without the key might be appended twice,
+/// if the previous attempt was appended but its response was lost. To make the retries safe, set the key before sending,
+/// e.g. to the batch fingerprint using `SendMessages::with_auto_idempotency_key`, keeping in mind that the same batch
+/// sent again within the batch deduplication expiry is then ignored as well.
+///
+/// The partitions count is fetched from the topic metadata, whenever the partitioning needs to be resolved.
+#[derive(Debug)]
+pub struct SendMessagesRetry {
+    max_attempts: u32,
+    base_delay: Duration,
+    round_robin_counter: AtomicU32,
+}
+
+impl Default for SendMessagesRetry {
+    fn default() -> Self {
+        SendMessagesRetry::new(DEFAULT_MAX_ATTEMPTS, DEFAULT_BASE_DELAY)
+    }
+}
+
+impl SendMessagesRetry {
+    /// Create a new retry helper with the maximum number of attempts (at least 1) and the delay before the first retry,
+    /// which is doubled for every subsequent retry.
+    pub fn new(max_attempts: u32, base_delay: Duration) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            base_delay,
+            round_robin_counter: AtomicU32::default(),
+        }
+    }
+
+    /// Get the maximum number of attempts.
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Get the delay before the first retry.
+    pub fn base_delay(&self) -> Duration {
+        self.base_delay
+    }
+
+    /// Send the messages, pinning the partitioning to the resolved partition ID and retrying the transient failures.
+    /// The last error is returned if all the attempts fail, and the non-transient errors are returned immediately.
+    pub async fn send(
+        &self,
+        client: &dyn Client,
+        command: &mut SendMessages,
+    ) -> Result<(), IggyError> {
+        if command.partitioning.kind != PartitioningKind::PartitionId {
+            let topic = client
+                .get_topic(&GetTopic {
+                    stream_id: command.stream_id.clone(),
+                    topic_id: command.topic_id.clone(),
+                })
+                .await?;
+            self.pin_partitioning(command, topic.partitions_count)?;
+        }
+
+        let mut attempt = 1;
+        loop {
+            match client.send_messages(command).await {
+                Ok(()) => return Ok(()),
+                Err(error) if attempt < self.max_attempts && is_transient(&error) => {
+                    let delay = self.get_delay(attempt);
+                    warn!(
+                        "Failed to send messages to partition: {}, attempt: {attempt}/{}, retrying in {delay:?}. {error}",
+                        command.partitioning, self.max_attempts
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+    }
+
+    /// Replace the partitioning of the command with the concrete partition ID, based on the provided partitions count.
+    pub fn pin_partitioning(
+        &self,
+        command: &mut SendMessages,
+        partitions_count: u32,
+    ) -> Result<(), IggyError> {
+        if command.partitioning.kind == PartitioningKind::PartitionId {
+            return Ok(());
+        }
+
+        let partition_id = partitioner::resolve_partition_id(
+            &command.partitioning,
+            partitions_count,
+            &self.round_robin_counter,
+        )?;
+        if partition_id == 0 {
+            return Err(IggyError::InvalidCommand);
+        }
+
+        command.partitioning = Partitioning::partition_id(partition_id);
+        Ok(())
+    }
+
+    /// Get the delay before the retry following the provided attempt, doubled for every attempt.
+    fn get_delay(&self, attempt: u32) -> Duration {
+        self.base_delay
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+    }
+}
+
+/// Returns true for the errors caused by the connection or the server failure, which might succeed when retried.
+pub(crate) fn is_transient(error: &IggyError) -> bool {
+    match error {
+        IggyError::NotConnected
+        | IggyError::RequestError(_)
+        | IggyError::RequestMiddlewareError(_)
+        | IggyError::IoError(_)
+        | IggyError::WriteError(_)
+        | IggyError::ReadError(_)
+        | IggyError::ConnectionError(_)
+        | IggyError::ReadToEndError(_)
+        | IggyError::EmptyResponse => true,
+        IggyError::HttpResponseError(status, _) => *status >= 500,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::identifier::Identifier;
+    use crate::messages::send_messages::Message;
+    use crate::utils::key_hash;
+    use std::str::FromStr;
+
+    #[test]
+    fn balanced_partitioning_should_be_pinned_to_next_partition_id() {
+        let retry = SendMessagesRetry::default();
+        let mut command = send_messages(Partitioning::balanced());
+
+        retry.pin_partitioning(&mut command, 3).unwrap();
+        assert_eq!(command.partitioning, Partitioning::partition_id(1));
+
+        let mut command = send_messages(Partitioning::balanced());
+        retry.pin_partitioning(&mut command, 3).unwrap();
+        assert_eq!(command.partitioning, Partitioning::partition_id(2));
+    }
+
+    #[test]
+    fn idempotency_key_should_not_be_changed_when_partitioning_is_pinned() {
+        let retry = SendMessagesRetry::default();
+        let mut command = send_messages(Partitioning::balanced());
+        retry.pin_partitioning(&mut command, 3).unwrap();
+        assert_eq!(command.idempotency_key, None);
+
+        let mut command = send_messages(Partitioning::balanced()).with_auto_idempotency_key();
+        let idempotency_key = command.idempotency_key;
+        retry.pin_partitioning(&mut command, 3).unwrap();
+        assert_eq!(command.idempotency_key, idempotency_key);
+    }
+
+    #[test]
+    fn messages_key_partitioning_should_be_pinned_to_partition_calculated_by_server() {
+        let retry = SendMessagesRetry::default();
+        let mut command = send_messages(Partitioning::messages_key_str("order-1").unwrap());
+
+        retry.pin_partitioning(&mut command, 10).unwrap();
+
+        assert_eq!(
+            command.partitioning,
+            Partitioning::partition_id(key_hash::calculate_partition_id(b"order-1", 10))
+        );
+    }
+
+    #[test]
+    fn pinned_partitioning_should_not_change() {
+        let retry = SendMessagesRetry::default();
+        let mut command = send_messages(Partitioning::messages_key_str("order-1").unwrap());
+
+        retry.pin_partitioning(&mut command, 10).unwrap();
+        let pinned_partitioning = command.partitioning.clone();
+        retry.pin_partitioning(&mut command, 10).unwrap();
+
+        assert_eq!(command.partitioning, pinned_partitioning);
+    }
+
+    #[test]
+    fn partitioning_should_not_be_pinned_without_partitions() {
+        let retry = SendMessagesRetry::default();
+        let mut command = send_messages(Partitioning::balanced());
+
+        let result = retry.pin_partitioning(&mut command, 0);
+
+        assert!(matches!(result, Err(IggyError::InvalidCommand)));
+        assert_eq!(command.partitioning, Partitioning::balanced());
+    }
+
+    #[test]
+    fn delay_should_grow_exponentially() {
+        let retry = SendMessagesRetry::new(5, Duration::from_millis(10));
+
+        assert_eq!(retry.get_delay(1), Duration::from_millis(10));
+        assert_eq!(retry.get_delay(2), Duration::from_millis(20));
+        assert_eq!(retry.get_delay(3), Duration::from_millis(40));
+        assert_eq!(
+            retry.get_delay(100),
+            Duration::from_millis(10).saturating_mul(u32::MAX)
+        );
+    }
+
+    #[test]
+    fn max_attempts_should_be_at_least_one() {
+        assert_eq!(SendMessagesRetry::new(0, Duration::ZERO).max_attempts(), 1);
+    }
+
+    #[test]
+    fn only_connection_and_server_errors_should_be_transient() {
+        assert!(is_transient(&IggyError::NotConnected));
+        assert!(is_transient(&IggyError::HttpResponseError(
+            503,
+            "unavailable".to_string()
+        )));
+        assert!(!is_transient(&IggyError::HttpResponseError(
+            400,
+            "bad request".to_string()
+        )));
+        assert!(!is_transient(&IggyError::Unauthenticated));
+        assert!(!is_transient(&IggyError::InvalidMessagesCount));
+    }
+
+    fn send_messages(partitioning: Partitioning) -> SendMessages {
+        SendMessages::new(
+            Identifier::numeric(1).unwrap().into(),
+            Identifier::numeric(1).unwrap().into(),
+            partitioning,
+            vec![Message::from_str("hello").unwrap()],
+        )
+    }
+}
@@ -5,11 +5,13 @@ use crate::consumer_groups::get_consumer_group::GetConsumerGroup;
 use crate::consumer_groups::get_consumer_groups::GetConsumerGroups;
 use crate::consumer_groups::join_consumer_group::JoinConsumerGroup;
 use crate::consumer_groups::leave_consumer_group::LeaveConsumerGroup;
+use crate::consumer_groups::reset_consumer_group_offset::ResetConsumerGroupOffset;
 use crate::consumer_offsets::get_consumer_offset::GetConsumerOffset;
 use crate::consumer_offsets::store_consumer_offset::StoreConsumerOffset;
 use crate::error::IggyError;
 use crate::messages::poll_messages::PollMessages;
 use crate::messages::send_messages::SendMessages;
+use crate::messages::send_messages_conditional::SendMessagesConditional;
 use crate::partitions::create_partitions::CreatePartitions;
 use crate::partitions::delete_partitions::DeletePartitions;
 use crate::personal_access_tokens::create_personal_access_token::CreatePersonalAccessToken;
@@ -86,6 +88,8 @@ pub const POLL_MESSAGES: &str = "message.poll";
 pub const POLL_MESSAGES_CODE: u32 = 100;
 pub const SEND_MESSAGES: &str = "message.send";
 pub const SEND_MESSAGES_CODE: u32 = 101;
+pub const SEND_MESSAGES_CONDITIONAL: &str = "message.send_conditional";
+pub const SEND_MESSAGES_CONDITIONAL_CODE: u32 = 102;
 pub const GET_CONSUMER_OFFSET: &str = "consumer_offset.get";
 pub const GET_CONSUMER_OFFSET_CODE: u32 = 120;
 pub const STORE_CONSUMER_OFFSET: &str = "consumer_offset.store";
@@ -130,6 +134,8 @@ pub const JOIN_CONSUMER_GROUP: &str = "consumer_group.join";
 pub const JOIN_CONSUMER_GROUP_CODE: u32 = 604;
 pub const LEAVE_CONSUMER_GROUP: &str = "consumer_group.leave";
 pub const LEAVE_CONSUMER_GROUP_CODE: u32 = 605;
+pub const RESET_CONSUMER_GROUP_OFFSET: &str = "consumer_group.reset_offset";
+pub const RESET_CONSUMER_GROUP_OFFSET_CODE: u32 = 606;
 
 #[derive(Debug, PartialEq, EnumString)]
 pub enum Command {
@@ -152,6 +158,7 @@ pub enum Command {
     DeletePersonalAccessToken(DeletePersonalAccessToken),
     LoginWithPersonalAccessToken(LoginWithPersonalAccessToken),
     SendMessages(SendMessages),
+    SendMessagesConditional(SendMessagesConditional),
     PollMessages(PollMessages),
     GetConsumerOffset(GetConsumerOffset),
     StoreConsumerOffset(StoreConsumerOffset),
@@ -175,6 +182,7 @@ pub enum Command {
     DeleteConsumerGroup(DeleteConsumerGroup),
     JoinConsumerGroup(JoinConsumerGroup),
     LeaveConsumerGroup(LeaveConsumerGroup),
+    ResetConsumerGroupOffset(ResetConsumerGroupOffset),
 }
 
 /// A trait for all command payloads.
@@ -212,6 +220,9 @@ impl BytesSerializable for Command {
                 as_bytes(LOGIN_WITH_PERSONAL_ACCESS_TOKEN_CODE, payload.as_bytes())
             }
             Command::SendMessages(payload) => as_bytes(SEND_MESSAGES_CODE, payload.as_bytes()),
+            Command::SendMessagesConditional(payload) => {
+                as_bytes(SEND_MESSAGES_CONDITIONAL_CODE, payload.as_bytes())
+            }
             Command::PollMessages(payload) => as_bytes(POLL_MESSAGES_CODE, payload.as_bytes()),
             Command::StoreConsumerOffset(payload) => {
                 as_bytes(STORE_CONSUMER_OFFSET_CODE, payload.as_bytes())
@@ -255,6 +266,9 @@ impl BytesSerializable for Command {
             Command::LeaveConsumerGroup(payload) => {
                 as_bytes(LEAVE_CONSUMER_GROUP_CODE, payload.as_bytes())
             }
+            Command::ResetConsumerGroupOffset(payload) => {
+                as_bytes(RESET_CONSUMER_GROUP_OFFSET_CODE, payload.as_bytes())
+            }
         }
     }
 
@@ -292,7 +306,13 @@ impl BytesSerializable for Command {
             LOGIN_WITH_PERSONAL_ACCESS_TOKEN_CODE => Ok(Command::LoginWithPersonalAccessToken(
                 LoginWithPersonalAccessToken::from_bytes(payload)?,
             )),
-            SEND_MESSAGES_CODE => Ok(Command::SendMessages(SendMessages::from_bytes(payload)?)),
+            // The messages are deserialized strictly, so the trailing bytes of the malformed frame are rejected rather than ignored.
+            SEND_MESSAGES_CODE => Ok(Command::SendMessages(SendMessages::from_bytes_checked(
+                payload,
+            )?)),
+            SEND_MESSAGES_CONDITIONAL_CODE => Ok(Command::SendMessagesConditional(
+                SendMessagesConditional::from_bytes_checked(payload)?,
+            )),
             POLL_MESSAGES_CODE => Ok(Command::PollMessages(PollMessages::from_bytes(payload)?)),
             STORE_CONSUMER_OFFSET_CODE => Ok(Command::StoreConsumerOffset(
                 StoreConsumerOffset::from_bytes(payload)?,
@@ -336,6 +356,9 @@ impl BytesSerializable for Command {
             LEAVE_CONSUMER_GROUP_CODE => Ok(Command::LeaveConsumerGroup(
                 LeaveConsumerGroup::from_bytes(payload)?,
             )),
+            RESET_CONSUMER_GROUP_OFFSET_CODE => Ok(Command::ResetConsumerGroupOffset(
+                ResetConsumerGroupOffset::from_bytes(payload)?,
+            )),
             _ => Err(IggyError::InvalidCommand),
         }
     }
@@ -401,6 +424,9 @@ impl Display for Command {
             }
             Command::PollMessages(payload) => write!(formatter, "{POLL_MESSAGES}|{payload}"),
             Command::SendMessages(payload) => write!(formatter, "{SEND_MESSAGES}|{payload}"),
+            Command::SendMessagesConditional(payload) => {
+                write!(formatter, "{SEND_MESSAGES_CONDITIONAL}|{payload}")
+            }
             Command::StoreConsumerOffset(payload) => {
                 write!(formatter, "{STORE_CONSUMER_OFFSET}|{payload}")
             }
@@ -425,6 +451,9 @@ impl Display for Command {
             Command::LeaveConsumerGroup(payload) => {
                 write!(formatter, "{LEAVE_CONSUMER_GROUP}|{payload}")
             }
+            Command::ResetConsumerGroupOffset(payload) => {
+                write!(formatter, "{RESET_CONSUMER_GROUP_OFFSET}|{payload}")
+            }
         }
     }
 }
@@ -530,6 +559,11 @@ mod tests {
             SEND_MESSAGES_CODE,
             &SendMessages::default(),
         );
+        assert_serialized_as_bytes_and_deserialized_from_bytes(
+            &Command::SendMessagesConditional(SendMessagesConditional::default()),
+            SEND_MESSAGES_CONDITIONAL_CODE,
+            &SendMessagesConditional::default(),
+        );
         assert_serialized_as_bytes_and_deserialized_from_bytes(
             &Command::PollMessages(PollMessages::default()),
             POLL_MESSAGES_CODE,
@@ -645,6 +679,11 @@ mod tests {
             LEAVE_CONSUMER_GROUP_CODE,
             &LeaveConsumerGroup::default(),
         );
+        assert_serialized_as_bytes_and_deserialized_from_bytes(
+            &Command::ResetConsumerGroupOffset(ResetConsumerGroupOffset::default()),
+            RESET_CONSUMER_GROUP_OFFSET_CODE,
+            &ResetConsumerGroupOffset::default(),
+        );
     }
 
     fn assert_serialized_as_bytes_and_deserialized_from_bytes(
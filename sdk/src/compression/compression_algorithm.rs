@@ -1,20 +1,35 @@
+use flate2::bufread::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
 use serde::{
     de::{self, Deserializer, Visitor},
     Deserialize, Serialize, Serializer,
 };
 use std::{
     fmt::{Display, Formatter},
+    io::{Read, Write},
     str::FromStr,
 };
 
 use crate::error::IggyError;
+#[cfg(feature = "lz4")]
+use lz4_flex::frame::{FrameDecoder, FrameEncoder, FrameInfo};
 
-// for now only those, in the future will add snappy, lz4, zstd (same as in confluent kafka) in addition to that
+#[cfg(feature = "zstd")]
+const ZSTD_MIN_WINDOW_LOG: u32 = 10;
+#[cfg(feature = "zstd")]
+const ZSTD_MAX_WINDOW_LOG: u32 = 27;
+
+// for now only those, in the future will add snappy (same as in confluent kafka) in addition to that
 // we should consider brotli as well.
-#[derive(Debug, PartialEq, Clone)]
+// Zstd and Lz4 are always recognized, but they can be used only with the `zstd` and `lz4` features enabled.
+#[derive(Debug, PartialEq, Clone, Default)]
 pub enum CompressionAlgorithm {
+    #[default]
     None,
     Gzip,
+    Zstd,
+    Lz4,
 }
 impl FromStr for CompressionAlgorithm {
     type Err = String;
@@ -22,6 +37,8 @@ impl FromStr for CompressionAlgorithm {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.to_lowercase().as_str() {
             "gzip" => Ok(CompressionAlgorithm::Gzip),
+            "zstd" => Ok(CompressionAlgorithm::Zstd),
+            "lz4" => Ok(CompressionAlgorithm::Lz4),
             "none" => Ok(CompressionAlgorithm::None),
             _ => Err(format!("Unknown compression type: {}", s)),
         }
@@ -33,6 +50,8 @@ impl CompressionAlgorithm {
         match self {
             CompressionAlgorithm::None => 1,
             CompressionAlgorithm::Gzip => 2,
+            CompressionAlgorithm::Zstd => 3,
+            CompressionAlgorithm::Lz4 => 4,
         }
     }
 
@@ -40,9 +59,139 @@ impl CompressionAlgorithm {
         match code {
             1 => Ok(CompressionAlgorithm::None),
             2 => Ok(CompressionAlgorithm::Gzip),
+            3 => Ok(CompressionAlgorithm::Zstd),
+            4 => Ok(CompressionAlgorithm::Lz4),
             _ => Err(IggyError::InvalidCommand),
         }
     }
+
+    /// Compress the provided data using the algorithm.
+    /// Zstandard and LZ4 require the `zstd` and `lz4` features respectively.
+    pub fn compress(&self, data: &[u8]) -> Result<Vec<u8>, IggyError> {
+        match self {
+            CompressionAlgorithm::None => Ok(data.to_vec()),
+            CompressionAlgorithm::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+                encoder
+                    .write_all(data)
+                    .map_err(|_| IggyError::CannotCompressData)?;
+                encoder.finish().map_err(|_| IggyError::CannotCompressData)
+            }
+            #[cfg(feature = "zstd")]
+            CompressionAlgorithm::Zstd => {
+                // The frame declares the content size, so the decompression limit can be checked up front.
+                zstd::bulk::compress(data, zstd::DEFAULT_COMPRESSION_LEVEL)
+                    .map_err(|_| IggyError::CannotCompressData)
+            }
+            #[cfg(feature = "lz4")]
+            CompressionAlgorithm::Lz4 => {
+                let frame_info = FrameInfo::new()
+                    .content_size(Some(data.len() as u64))
+                    .content_checksum(true);
+                let mut encoder = FrameEncoder::with_frame_info(frame_info, Vec::new());
+                encoder
+                    .write_all(data)
+                    .map_err(|_| IggyError::CannotCompressData)?;
+                encoder.finish().map_err(|_| IggyError::CannotCompressData)
+            }
+            #[allow(unreachable_patterns)]
+            _ => Err(IggyError::CompressionAlgorithmNotEnabled(self.to_string())),
+        }
+    }
+
+    /// Decompress the provided data using the algorithm.
+    /// The decompression is aborted as soon as the output exceeds `max_decompressed` bytes,
+    /// which guards against the decompression bombs.
+    pub fn decompress(&self, data: &[u8], max_decompressed: usize) -> Result<Vec<u8>, IggyError> {
+        let decompressed = match self {
+            CompressionAlgorithm::None => data.to_vec(),
+            CompressionAlgorithm::Gzip => {
+                let mut decompressed = Vec::new();
+                let mut decoder = GzDecoder::new(data);
+                (&mut decoder)
+                    .take(max_decompressed as u64 + 1)
+                    .read_to_end(&mut decompressed)
+                    .map_err(|_| IggyError::CannotDecompressData)?;
+                // The single member must take all the data, so its trailer declares the decompressed length.
+                if decompressed.len() <= max_decompressed && !decoder.into_inner().is_empty() {
+                    return Err(IggyError::CannotDecompressData);
+                }
+                decompressed
+            }
+            #[cfg(feature = "zstd")]
+            CompressionAlgorithm::Zstd => {
+                if let Some(length) = self.decompressed_length(data) {
+                    if length > max_decompressed as u64 {
+                        return Err(IggyError::DecompressionLimitExceeded);
+                    }
+                }
+
+                let mut decoder = zstd::stream::read::Decoder::with_buffer(data)
+                    .map_err(|_| IggyError::CannotDecompressData)?
+                    .single_frame();
+                // The window doesn't have to be larger than the output, which bounds the memory of the decoder too.
+                let window_log = max_decompressed
+                    .next_power_of_two()
+                    .trailing_zeros()
+                    .clamp(ZSTD_MIN_WINDOW_LOG, ZSTD_MAX_WINDOW_LOG);
+                decoder
+                    .window_log_max(window_log)
+                    .map_err(|_| IggyError::CannotDecompressData)?;
+                let mut decompressed = Vec::new();
+                (&mut decoder)
+                    .take(max_decompressed as u64 + 1)
+                    .read_to_end(&mut decompressed)
+                    .map_err(|_| IggyError::CannotDecompressData)?;
+                // The single frame must take all the data.
+                if decompressed.len() <= max_decompressed && !decoder.finish().is_empty() {
+                    return Err(IggyError::CannotDecompressData);
+                }
+                decompressed
+            }
+            #[cfg(feature = "lz4")]
+            CompressionAlgorithm::Lz4 => {
+                let mut decompressed = Vec::new();
+                let mut decoder = FrameDecoder::new(data);
+                (&mut decoder)
+                    .take(max_decompressed as u64 + 1)
+                    .read_to_end(&mut decompressed)
+                    .map_err(|_| IggyError::CannotDecompressData)?;
+                // The frames must take all the data.
+                if decompressed.len() <= max_decompressed && !decoder.into_inner().is_empty() {
+                    return Err(IggyError::CannotDecompressData);
+                }
+                decompressed
+            }
+            #[allow(unreachable_patterns)]
+            _ => return Err(IggyError::CompressionAlgorithmNotEnabled(self.to_string())),
+        };
+
+        if decompressed.len() > max_decompressed {
+            return Err(IggyError::DecompressionLimitExceeded);
+        }
+
+        Ok(decompressed)
+    }
+
+    /// Get the length of the data after decompression as declared by the compressed data itself, without decompressing it,
+    /// i.e. the gzip trailer or the content size of the Zstandard frame, `None` if it's not declared or can't be read.
+    /// The declared length is verified when the data is decompressed, so it can't be trusted for the untrusted data before that.
+    pub fn decompressed_length(&self, data: &[u8]) -> Option<u64> {
+        match self {
+            CompressionAlgorithm::None => Some(data.len() as u64),
+            CompressionAlgorithm::Gzip => match data {
+                [.., a, b, c, d] if data.len() >= 18 => {
+                    Some(u64::from(u32::from_le_bytes([*a, *b, *c, *d])))
+                }
+                _ => None,
+            },
+            #[cfg(feature = "zstd")]
+            CompressionAlgorithm::Zstd => {
+                zstd::zstd_safe::get_frame_content_size(data).ok().flatten()
+            }
+            _ => None,
+        }
+    }
 }
 
 impl Display for CompressionAlgorithm {
@@ -50,6 +199,8 @@ impl Display for CompressionAlgorithm {
         match self {
             CompressionAlgorithm::None => write!(f, "none"),
             CompressionAlgorithm::Gzip => write!(f, "gzip"),
+            CompressionAlgorithm::Zstd => write!(f, "zstd"),
+            CompressionAlgorithm::Lz4 => write!(f, "lz4"),
         }
     }
 }
@@ -62,6 +213,8 @@ impl Serialize for CompressionAlgorithm {
         match self {
             CompressionAlgorithm::None => serializer.serialize_str("none"),
             CompressionAlgorithm::Gzip => serializer.serialize_str("gzip"),
+            CompressionAlgorithm::Zstd => serializer.serialize_str("zstd"),
+            CompressionAlgorithm::Lz4 => serializer.serialize_str("lz4"),
         }
     }
 }
@@ -71,6 +224,8 @@ impl From<CompressionAlgorithm> for String {
         match value {
             CompressionAlgorithm::None => "none".to_string(),
             CompressionAlgorithm::Gzip => "gzip".to_string(),
+            CompressionAlgorithm::Zstd => "zstd".to_string(),
+            CompressionAlgorithm::Lz4 => "lz4".to_string(),
         }
     }
 }
@@ -104,6 +259,16 @@ impl<'de> Deserialize<'de> for CompressionAlgorithm {
 mod tests {
     use super::*;
 
+    fn enabled_algorithms() -> Vec<CompressionAlgorithm> {
+        vec![
+            CompressionAlgorithm::Gzip,
+            #[cfg(feature = "zstd")]
+            CompressionAlgorithm::Zstd,
+            #[cfg(feature = "lz4")]
+            CompressionAlgorithm::Lz4,
+        ]
+    }
+
     #[test]
     fn test_from() {
         let none_alg = CompressionAlgorithm::from_str("none");
@@ -121,6 +286,14 @@ mod tests {
         let gzip_alg = CompressionAlgorithm::from_str("Gzip");
         assert!(gzip_alg.is_ok());
         assert_eq!(gzip_alg.unwrap(), CompressionAlgorithm::Gzip);
+
+        let zstd_alg = CompressionAlgorithm::from_str("zstd");
+        assert!(zstd_alg.is_ok());
+        assert_eq!(zstd_alg.unwrap(), CompressionAlgorithm::Zstd);
+
+        let lz4_alg = CompressionAlgorithm::from_str("LZ4");
+        assert!(lz4_alg.is_ok());
+        assert_eq!(lz4_alg.unwrap(), CompressionAlgorithm::Lz4);
     }
 
     #[test]
@@ -153,6 +326,14 @@ mod tests {
         let gzip = CompressionAlgorithm::Gzip;
         let gzip_code = gzip.as_code();
         assert_eq!(gzip_code, 2);
+
+        let zstd = CompressionAlgorithm::Zstd;
+        let zstd_code = zstd.as_code();
+        assert_eq!(zstd_code, 3);
+
+        let lz4 = CompressionAlgorithm::Lz4;
+        let lz4_code = lz4.as_code();
+        assert_eq!(lz4_code, 4);
     }
     #[test]
     fn test_from_code() {
@@ -163,6 +344,14 @@ mod tests {
         let gzip = CompressionAlgorithm::from_code(2);
         assert!(gzip.is_ok());
         assert_eq!(gzip.unwrap(), CompressionAlgorithm::Gzip);
+
+        let zstd = CompressionAlgorithm::from_code(3);
+        assert!(zstd.is_ok());
+        assert_eq!(zstd.unwrap(), CompressionAlgorithm::Zstd);
+
+        let lz4 = CompressionAlgorithm::from_code(4);
+        assert!(lz4.is_ok());
+        assert_eq!(lz4.unwrap(), CompressionAlgorithm::Lz4);
     }
     #[test]
     fn test_from_code_invalid_input() {
@@ -175,4 +364,56 @@ mod tests {
         let invalid_compression_kind = CompressionAlgorithm::from_code(255);
         assert!(invalid_compression_kind.is_err());
     }
+
+    #[test]
+    fn test_compress_and_decompress() {
+        let data = b"Hello World! ".repeat(100);
+        for algorithm in enabled_algorithms() {
+            let compressed = algorithm.compress(&data).unwrap();
+            assert!(compressed.len() < data.len());
+            if algorithm != CompressionAlgorithm::Lz4 {
+                assert_eq!(
+                    algorithm.decompressed_length(&compressed),
+                    Some(data.len() as u64)
+                );
+            }
+
+            let decompressed = algorithm.decompress(&compressed, data.len()).unwrap();
+            assert_eq!(decompressed, data);
+        }
+    }
+
+    #[test]
+    fn test_decompress_trailing_data() {
+        let data = b"Hello World! ".repeat(100);
+        for algorithm in enabled_algorithms() {
+            let mut compressed = algorithm.compress(&data).unwrap();
+            compressed.push(0);
+            assert!(algorithm.decompress(&compressed, data.len()).is_err());
+        }
+    }
+
+    #[test]
+    fn test_decompress_exceeding_limit() {
+        let data = vec![0; 1024 * 1024];
+        for algorithm in enabled_algorithms() {
+            let compressed = algorithm.compress(&data).unwrap();
+
+            let error = algorithm.decompress(&compressed, 1024).unwrap_err();
+            assert_eq!(
+                error.as_code(),
+                IggyError::DecompressionLimitExceeded.as_code()
+            );
+        }
+    }
+
+    #[cfg(not(feature = "zstd"))]
+    #[test]
+    fn test_compress_without_enabled_feature() {
+        let error = CompressionAlgorithm::Zstd.compress(b"data").unwrap_err();
+        assert_eq!(
+            error.as_code(),
+            IggyError::CompressionAlgorithmNotEnabled("zstd".to_string()).as_code()
+        );
+    }
 }
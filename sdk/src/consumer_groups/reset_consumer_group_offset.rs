@@ -0,0 +1,293 @@
+use crate::bytes_serializable::BytesSerializable;
+use crate::command::CommandPayload;
+use crate::error::IggyError;
+use crate::identifier::Identifier;
+use crate::utils::timestamp::IggyTimestamp;
+use crate::validatable::Validatable;
+use bytes::{BufMut, Bytes, BytesMut};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// `ResetConsumerGroupOffset` command resets the stored offsets of the consumer group in all the partitions of the topic.
+/// It has additional payload:
+/// - `stream_id` - unique stream ID (numeric or name).
+/// - `topic_id` - unique topic ID (numeric or name).
+/// - `consumer_group_id` - unique consumer group ID (numeric or name).
+/// - `to` - the target to which the offsets are reset, see `ResetOffsetTarget`.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Default)]
+pub struct ResetConsumerGroupOffset {
+    /// Unique stream ID (numeric or name).
+    #[serde(skip)]
+    pub stream_id: Identifier,
+    /// Unique topic ID (numeric or name).
+    #[serde(skip)]
+    pub topic_id: Identifier,
+    /// Unique consumer group ID (numeric or name).
+    #[serde(skip)]
+    pub consumer_group_id: Identifier,
+    /// The target to which the offsets are reset.
+    pub to: ResetOffsetTarget,
+}
+
+/// `ResetOffsetTarget` specifies from which message the consumer group will continue polling with the `Next` strategy.
+/// It consists of the following fields:
+/// - `kind` - the kind of the target, see `ResetOffsetKind`.
+/// - `value` - the timestamp in microseconds for the `Timestamp` kind, ignored otherwise.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Default, Copy, Clone)]
+pub struct ResetOffsetTarget {
+    /// The kind of the target.
+    pub kind: ResetOffsetKind,
+    /// The timestamp in microseconds for the `Timestamp` kind, ignored otherwise.
+    #[serde(default)]
+    pub value: u64,
+}
+
+/// `ResetOffsetKind` is an enum which specifies the kind of the target used by `ResetOffsetTarget`.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Default, Copy, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum ResetOffsetKind {
+    #[default]
+    /// Continue polling from the first message in the partition, the stored offset is removed.
+    Earliest,
+    /// Continue polling from the messages appended after the reset, skipping all the existing ones.
+    Latest,
+    /// Continue polling from the first message with the timestamp equal to or greater than the specified one.
+    Timestamp,
+}
+
+impl CommandPayload for ResetConsumerGroupOffset {}
+
+impl Validatable<IggyError> for ResetConsumerGroupOffset {
+    fn validate(&self) -> Result<(), IggyError> {
+        Ok(())
+    }
+}
+
+impl ResetOffsetTarget {
+    /// Reset the offsets to the first message in the partition.
+    pub fn earliest() -> Self {
+        Self {
+            kind: ResetOffsetKind::Earliest,
+            value: 0,
+        }
+    }
+
+    /// Reset the offsets to the last message in the partition.
+    pub fn latest() -> Self {
+        Self {
+            kind: ResetOffsetKind::Latest,
+            value: 0,
+        }
+    }
+
+    /// Reset the offsets to the first message with the timestamp (in microseconds) equal to or greater than the specified one.
+    pub fn timestamp(value: u64) -> Self {
+        Self {
+            kind: ResetOffsetKind::Timestamp,
+            value,
+        }
+    }
+}
+
+impl ResetOffsetKind {
+    /// Returns code of the reset offset kind.
+    pub fn as_code(&self) -> u8 {
+        match self {
+            ResetOffsetKind::Earliest => 1,
+            ResetOffsetKind::Latest => 2,
+            ResetOffsetKind::Timestamp => 3,
+        }
+    }
+
+    /// Returns reset offset kind from the specified code.
+    pub fn from_code(code: u8) -> Result<Self, IggyError> {
+        match code {
+            1 => Ok(ResetOffsetKind::Earliest),
+            2 => Ok(ResetOffsetKind::Latest),
+            3 => Ok(ResetOffsetKind::Timestamp),
+            _ => Err(IggyError::InvalidCommand),
+        }
+    }
+}
+
+impl FromStr for ResetOffsetTarget {
+    type Err = IggyError;
+
+    /// Parses `earliest`, `latest` or the RFC 3339 timestamp, e.g. `2024-01-31T12:00:00Z`.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "earliest" => Ok(ResetOffsetTarget::earliest()),
+            "latest" => Ok(ResetOffsetTarget::latest()),
+            _ => {
+                let timestamp = IggyTimestamp::from_rfc3339(input)?;
+                Ok(ResetOffsetTarget::timestamp(timestamp.to_micros()))
+            }
+        }
+    }
+}
+
+impl Display for ResetOffsetKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ResetOffsetKind::Earliest => write!(f, "earliest"),
+            ResetOffsetKind::Latest => write!(f, "latest"),
+            ResetOffsetKind::Timestamp => write!(f, "timestamp"),
+        }
+    }
+}
+
+impl Display for ResetOffsetTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}|{}", self.kind, self.value)
+    }
+}
+
+impl BytesSerializable for ResetConsumerGroupOffset {
+    fn as_bytes(&self) -> Bytes {
+        let stream_id_bytes = self.stream_id.as_bytes();
+        let topic_id_bytes = self.topic_id.as_bytes();
+        let consumer_group_id_bytes = self.consumer_group_id.as_bytes();
+        let mut bytes = BytesMut::with_capacity(
+            9 + stream_id_bytes.len() + topic_id_bytes.len() + consumer_group_id_bytes.len(),
+        );
+        bytes.put_slice(&stream_id_bytes);
+        bytes.put_slice(&topic_id_bytes);
+        bytes.put_slice(&consumer_group_id_bytes);
+        bytes.put_u8(self.to.kind.as_code());
+        bytes.put_u64_le(self.to.value);
+        bytes.freeze()
+    }
+
+    fn from_bytes(bytes: Bytes) -> Result<ResetConsumerGroupOffset, IggyError> {
+        if bytes.len() < 18 {
+            return Err(IggyError::InvalidCommand);
+        }
+
+        let mut position = 0;
+        let stream_id = Identifier::from_bytes(bytes.clone())?;
+        position += stream_id.get_size_bytes() as usize;
+        let topic_id = Identifier::from_bytes(bytes.slice(position..))?;
+        position += topic_id.get_size_bytes() as usize;
+        let consumer_group_id = Identifier::from_bytes(bytes.slice(position..))?;
+        position += consumer_group_id.get_size_bytes() as usize;
+        if bytes.len() != position + 9 {
+            return Err(IggyError::InvalidCommand);
+        }
+
+        let kind = ResetOffsetKind::from_code(bytes[position])?;
+        let value = u64::from_le_bytes(bytes[position + 1..position + 9].try_into()?);
+        let command = ResetConsumerGroupOffset {
+            stream_id,
+            topic_id,
+            consumer_group_id,
+            to: ResetOffsetTarget { kind, value },
+        };
+        command.validate()?;
+        Ok(command)
+    }
+}
+
+impl Display for ResetConsumerGroupOffset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}|{}|{}|{}",
+            self.stream_id, self.topic_id, self.consumer_group_id, self.to
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_be_serialized_as_bytes() {
+        let command = ResetConsumerGroupOffset {
+            stream_id: Identifier::numeric(1).unwrap(),
+            topic_id: Identifier::numeric(2).unwrap(),
+            consumer_group_id: Identifier::numeric(3).unwrap(),
+            to: ResetOffsetTarget::timestamp(1694968446131680),
+        };
+
+        let bytes = command.as_bytes();
+        let mut position = 0;
+        let stream_id = Identifier::from_bytes(bytes.clone()).unwrap();
+        position += stream_id.get_size_bytes() as usize;
+        let topic_id = Identifier::from_bytes(bytes.slice(position..)).unwrap();
+        position += topic_id.get_size_bytes() as usize;
+        let consumer_group_id = Identifier::from_bytes(bytes.slice(position..)).unwrap();
+        position += consumer_group_id.get_size_bytes() as usize;
+        let kind = ResetOffsetKind::from_code(bytes[position]).unwrap();
+        let value = u64::from_le_bytes(bytes[position + 1..position + 9].try_into().unwrap());
+
+        assert!(!bytes.is_empty());
+        assert_eq!(stream_id, command.stream_id);
+        assert_eq!(topic_id, command.topic_id);
+        assert_eq!(consumer_group_id, command.consumer_group_id);
+        assert_eq!(kind, command.to.kind);
+        assert_eq!(value, command.to.value);
+    }
+
+    #[test]
+    fn should_be_deserialized_from_bytes() {
+        let stream_id = Identifier::numeric(1).unwrap();
+        let topic_id = Identifier::numeric(2).unwrap();
+        let consumer_group_id = Identifier::numeric(3).unwrap();
+        let to = ResetOffsetTarget::latest();
+        let stream_id_bytes = stream_id.as_bytes();
+        let topic_id_bytes = topic_id.as_bytes();
+        let consumer_group_id_bytes = consumer_group_id.as_bytes();
+        let mut bytes = BytesMut::with_capacity(
+            9 + stream_id_bytes.len() + topic_id_bytes.len() + consumer_group_id_bytes.len(),
+        );
+        bytes.put_slice(&stream_id_bytes);
+        bytes.put_slice(&topic_id_bytes);
+        bytes.put_slice(&consumer_group_id_bytes);
+        bytes.put_u8(to.kind.as_code());
+        bytes.put_u64_le(to.value);
+        let command = ResetConsumerGroupOffset::from_bytes(bytes.freeze());
+        assert!(command.is_ok());
+
+        let command = command.unwrap();
+        assert_eq!(command.stream_id, stream_id);
+        assert_eq!(command.topic_id, topic_id);
+        assert_eq!(command.consumer_group_id, consumer_group_id);
+        assert_eq!(command.to, to);
+    }
+
+    #[test]
+    fn should_not_be_deserialized_from_bytes_with_invalid_kind() {
+        let mut bytes = BytesMut::new();
+        bytes.put_slice(&Identifier::numeric(1).unwrap().as_bytes());
+        bytes.put_slice(&Identifier::numeric(2).unwrap().as_bytes());
+        bytes.put_slice(&Identifier::numeric(3).unwrap().as_bytes());
+        bytes.put_u8(4);
+        bytes.put_u64_le(0);
+
+        let command = ResetConsumerGroupOffset::from_bytes(bytes.freeze());
+
+        assert!(matches!(command, Err(IggyError::InvalidCommand)));
+    }
+
+    #[test]
+    fn target_should_be_parsed_from_string() {
+        assert_eq!(
+            ResetOffsetTarget::from_str("earliest").unwrap(),
+            ResetOffsetTarget::earliest()
+        );
+        assert_eq!(
+            ResetOffsetTarget::from_str("latest").unwrap(),
+            ResetOffsetTarget::latest()
+        );
+        assert_eq!(
+            ResetOffsetTarget::from_str("2023-09-17T16:34:06.13168Z").unwrap(),
+            ResetOffsetTarget::timestamp(1694968446131680)
+        );
+        assert!(matches!(
+            ResetOffsetTarget::from_str("yesterday"),
+            Err(IggyError::CannotParseTimestamp(_))
+        ));
+    }
+}
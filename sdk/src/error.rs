@@ -20,6 +20,8 @@ pub enum IggyError {
     InvalidFormat = 4,
     #[error("Feature is unavailable")]
     FeatureUnavailable = 5,
+    #[error("Invalid identifier")]
+    InvalidIdentifier = 6,
     #[error("Cannot create base directory, Path: {0}")]
     CannotCreateBaseDirectory(String) = 10,
     #[error("Cannot create runtime directory, Path: {0}")]
@@ -108,6 +110,12 @@ pub enum IggyError {
     CannotParseSlice(#[from] std::array::TryFromSliceError) = 204,
     #[error("Cannot parse byte unit")]
     CannotParseByteUnit(#[from] byte_unit::ParseError) = 205,
+    #[error("Cannot parse timestamp: {0}")]
+    CannotParseTimestamp(String) = 206,
+    #[error("Cannot parse JSON: {0}")]
+    CannotParseJson(String) = 207,
+    #[error("Cannot parse field: {0}")]
+    CannotParseField(&'static str, #[source] std::array::TryFromSliceError) = 208,
     #[error("HTTP response error, status: {0}, body: {1}")]
     HttpResponseError(u16, String) = 300,
     #[error("Request middleware error")]
@@ -282,6 +290,58 @@ pub enum IggyError {
     InvalidMessageChecksum(u32, u32, u64) = 4027,
     #[error("Invalid key value length")]
     InvalidKeyValueLength = 4028,
+    #[error("Cannot compress data")]
+    CannotCompressData = 4029,
+    #[error("Cannot decompress data")]
+    CannotDecompressData = 4030,
+    #[error("Decompressed data exceeds the limit")]
+    DecompressionLimitExceeded = 4031,
+    #[error("The last message ID doesn't match the expected one")]
+    ConditionFailed = 4032,
+    #[error("Invalid message at index: {0}, error: {1}")]
+    InvalidMessageAtIndex(usize, Box<IggyError>) = 4033,
+    #[error("Invalid batch checksum: {0}, expected: {1}")]
+    InvalidBatchChecksum(u32, u32) = 4034,
+    #[error("Invalid text encoding: {0}")]
+    InvalidTextEncoding(String) = 4035,
+    #[error("Duplicate message ID: {0}")]
+    DuplicateMessageId(u128) = 4036,
+    #[error("Duplicate header key: {0}")]
+    DuplicateHeaderKey(String) = 4037,
+    #[error("Message size: {0} exceeds the combined limit: {1}")]
+    MessageTooLarge(u64, u32) = 4038,
+    #[error("Message has already expired at: {0}")]
+    MessageAlreadyExpired(u64) = 4039,
+    #[error("Unsupported partitioning kind: {0}")]
+    UnsupportedPartitioningKind(u8) = 4040,
+    #[error("Message partition ID: {0} conflicts with the batch partition ID: {1}")]
+    ConflictingMessagePartitionId(u32, u32) = 4041,
+    #[error("Batch mixes the messages with IDs generated by the server and the client")]
+    MixedMessageIds = 4042,
+    #[error(
+        "Partition ID: {0} is out of range, it must be between 1 and the partitions count: {1}"
+    )]
+    PartitionIdOutOfRange(u32, u32) = 4043,
+    #[error("Invalid payload checksum: {0}, expected: {1}")]
+    PayloadChecksumMismatch(u32, u32) = 4044,
+    #[error("Unsupported hash algorithm: {0}")]
+    UnsupportedHashAlgorithm(u8) = 4045,
+    #[error("Invalid partition ID: {0}, the partition IDs start from 1")]
+    InvalidPartitionId(u32) = 4046,
+    #[error(
+        "Batches with the different stream, topic, partitioning or idempotency key can't be merged"
+    )]
+    IncompatibleBatch = 4047,
+    #[error("Batch payload size: {0} exceeds the limit: {1}")]
+    BatchPayloadTooLarge(u64, u32) = 4048,
+    #[error("Batch headers size: {0} exceeds the limit: {1}")]
+    BatchHeadersTooLarge(u64, u32) = 4049,
+    #[error(
+        "Compression algorithm: {0} is not enabled, it requires the feature with the same name"
+    )]
+    CompressionAlgorithmNotEnabled(String) = 4050,
+    #[error("Cannot buffer the messages, the limit of {0} buffered messages would be exceeded")]
+    SendMessagesBufferFull(u32) = 4051,
     #[error("Invalid offset: {0}")]
     InvalidOffset(u64) = 4100,
     #[error("Failed to read consumers offsets for partition with ID: {0}")]
@@ -38,6 +38,11 @@ impl Client for HttpClient {
     async fn disconnect(&self) -> Result<(), IggyError> {
         Ok(())
     }
+
+    async fn is_authenticated(&self) -> bool {
+        let token = self.access_token.read().await;
+        !token.is_empty()
+    }
 }
 
 unsafe impl Send for HttpClient {}
@@ -179,12 +184,6 @@ impl HttpClient {
             .map_err(|_| IggyError::CannotParseUrl)
     }
 
-    /// Returns true if the client is authenticated.
-    pub async fn is_authenticated(&self) -> bool {
-        let token = self.access_token.read().await;
-        !token.is_empty()
-    }
-
     /// Set the refresh token.
     pub async fn set_refresh_token(&self, token: Option<String>) {
         let mut current_token = self.refresh_token.write().await;
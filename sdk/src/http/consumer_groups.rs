@@ -5,6 +5,7 @@ use crate::consumer_groups::get_consumer_group::GetConsumerGroup;
 use crate::consumer_groups::get_consumer_groups::GetConsumerGroups;
 use crate::consumer_groups::join_consumer_group::JoinConsumerGroup;
 use crate::consumer_groups::leave_consumer_group::LeaveConsumerGroup;
+use crate::consumer_groups::reset_consumer_group_offset::ResetConsumerGroupOffset;
 use crate::error::IggyError;
 use crate::http::client::HttpClient;
 use crate::models::consumer_group::{ConsumerGroup, ConsumerGroupDetails};
@@ -76,6 +77,22 @@ impl ConsumerGroupClient for HttpClient {
     async fn leave_consumer_group(&self, _command: &LeaveConsumerGroup) -> Result<(), IggyError> {
         Err(IggyError::FeatureUnavailable)
     }
+
+    async fn reset_consumer_group_offset(
+        &self,
+        command: &ResetConsumerGroupOffset,
+    ) -> Result<(), IggyError> {
+        let path = format!(
+            "{}/{}/offsets",
+            get_path(
+                &command.stream_id.as_cow_str(),
+                &command.topic_id.as_cow_str()
+            ),
+            command.consumer_group_id
+        );
+        self.put(&path, &command).await?;
+        Ok(())
+    }
 }
 
 fn get_path(stream_id: &str, topic_id: &str) -> String {
@@ -3,6 +3,7 @@ use crate::error::IggyError;
 use crate::http::client::HttpClient;
 use crate::messages::poll_messages::PollMessages;
 use crate::messages::send_messages::SendMessages;
+use crate::messages::send_messages_conditional::SendMessagesConditional;
 use crate::models::messages::PolledMessages;
 use async_trait::async_trait;
 
@@ -33,6 +34,24 @@ impl MessageClient for HttpClient {
         .await?;
         Ok(())
     }
+
+    async fn send_messages_conditional(
+        &self,
+        command: &mut SendMessagesConditional,
+    ) -> Result<(), IggyError> {
+        self.post(
+            &format!(
+                "{}/conditional",
+                get_path(
+                    &command.send_messages.stream_id.as_cow_str(),
+                    &command.send_messages.topic_id.as_cow_str(),
+                )
+            ),
+            &command,
+        )
+        .await?;
+        Ok(())
+    }
 }
 
 fn get_path(stream_id: &str, topic_id: &str) -> String {
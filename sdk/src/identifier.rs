@@ -7,8 +7,12 @@ use serde_with::base64::Base64;
 use serde_with::serde_as;
 use std::borrow::Cow;
 use std::fmt::Display;
+use std::ops::Deref;
 use std::str::FromStr;
 
+/// The maximum length of the named identifier in bytes.
+pub const MAX_NAME_LENGTH: usize = 255;
+
 /// `Identifier` represents the unique identifier of the resources such as stream, topic, partition, user etc.
 /// It consists of the following fields:
 /// - `kind`: the kind of the identifier.
@@ -27,6 +31,14 @@ pub struct Identifier {
     pub value: Vec<u8>,
 }
 
+/// `StreamId` is the typed wrapper of the stream `Identifier`, which prevents swapping it with the topic identifier.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct StreamId(pub Identifier);
+
+/// `TopicId` is the typed wrapper of the topic `Identifier`, which prevents swapping it with the stream identifier.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct TopicId(pub Identifier);
+
 /// `IdKind` represents the kind of the identifier.
 #[derive(Debug, Serialize, Deserialize, PartialEq, Default, Copy, Clone)]
 #[serde(rename_all = "snake_case")]
@@ -129,7 +141,7 @@ impl Identifier {
     /// Creates a new identifier from the given string value, either numeric or string.
     pub fn from_str_value(value: &str) -> Result<Self, IggyError> {
         let length = value.len();
-        if length == 0 || length > 255 {
+        if length == 0 || length > MAX_NAME_LENGTH {
             return Err(IggyError::InvalidCommand);
         }
 
@@ -155,8 +167,8 @@ impl Identifier {
     /// Creates a new identifier from the given string value. The name will be always converted to lowercase and all whitespaces will be replaced with dots.
     pub fn named(value: &str) -> Result<Self, IggyError> {
         let length = value.len();
-        if length == 0 || length > 255 {
-            return Err(IggyError::InvalidCommand);
+        if length == 0 || length > MAX_NAME_LENGTH {
+            return Err(IggyError::InvalidIdentifier);
         }
 
         Ok(Self {
@@ -168,6 +180,63 @@ impl Identifier {
     }
 }
 
+impl TryFrom<u32> for Identifier {
+    type Error = IggyError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        Identifier::numeric(value)
+    }
+}
+
+impl TryFrom<&str> for Identifier {
+    type Error = IggyError;
+
+    /// Creates the numeric identifier if the value can be parsed as `u32`, or the named one otherwise.
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Identifier::from_str_value(value)
+    }
+}
+
+impl From<Identifier> for StreamId {
+    fn from(identifier: Identifier) -> Self {
+        StreamId(identifier)
+    }
+}
+
+impl From<StreamId> for Identifier {
+    fn from(stream_id: StreamId) -> Self {
+        stream_id.0
+    }
+}
+
+impl Deref for StreamId {
+    type Target = Identifier;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<Identifier> for TopicId {
+    fn from(identifier: Identifier) -> Self {
+        TopicId(identifier)
+    }
+}
+
+impl From<TopicId> for Identifier {
+    fn from(topic_id: TopicId) -> Self {
+        topic_id.0
+    }
+}
+
+impl Deref for TopicId {
+    type Target = Identifier;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
 impl BytesSerializable for Identifier {
     fn as_bytes(&self) -> Bytes {
         let mut bytes = BytesMut::with_capacity(2 + self.length as usize);
@@ -266,3 +335,48 @@ impl Display for IdKind {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn named_identifier_with_max_length_should_be_created() {
+        let name = "a".repeat(MAX_NAME_LENGTH);
+        let identifier = Identifier::named(&name).unwrap();
+        assert_eq!(identifier.kind, IdKind::String);
+        assert_eq!(identifier.length as usize, MAX_NAME_LENGTH);
+        assert_eq!(identifier.value, name.as_bytes());
+    }
+
+    #[test]
+    fn named_identifier_exceeding_max_length_should_fail() {
+        let name = "a".repeat(MAX_NAME_LENGTH + 1);
+        let error = Identifier::named(&name).unwrap_err();
+        assert_eq!(error.as_code(), IggyError::InvalidIdentifier.as_code());
+    }
+
+    #[test]
+    fn empty_named_identifier_should_fail() {
+        let error = Identifier::named("").unwrap_err();
+        assert_eq!(error.as_code(), IggyError::InvalidIdentifier.as_code());
+    }
+
+    #[test]
+    fn identifier_should_be_converted_from_number_or_str() {
+        assert_eq!(
+            Identifier::try_from(1u32).unwrap(),
+            Identifier::numeric(1).unwrap()
+        );
+        assert_eq!(
+            Identifier::try_from("orders").unwrap(),
+            Identifier::named("orders").unwrap()
+        );
+        assert_eq!(
+            Identifier::try_from("2").unwrap(),
+            Identifier::numeric(2).unwrap()
+        );
+        assert!(Identifier::try_from(0u32).is_err());
+        assert!(Identifier::try_from("").is_err());
+    }
+}
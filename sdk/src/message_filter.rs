@@ -0,0 +1,242 @@
+use crate::models::header::{HeaderKey, HeaderValue};
+use crate::models::messages::Message;
+
+/// The trait represent the predicate deciding which of the polled messages should be processed by the consumer.
+/// The filters can be composed using `and`, `or` and `not` combinators, and any closure taking `&Message` and returning `bool`
+/// can be used as the filter as well, e.g. to match the payload.
+pub trait MessageFilter {
+    /// Returns true if the message matches the filter.
+    fn accept(&self, message: &Message) -> bool;
+
+    /// Create the filter accepting the messages matched by both filters.
+    fn and<F: MessageFilter>(self, other: F) -> And<Self, F>
+    where
+        Self: Sized,
+    {
+        And(self, other)
+    }
+
+    /// Create the filter accepting the messages matched by any of the filters.
+    fn or<F: MessageFilter>(self, other: F) -> Or<Self, F>
+    where
+        Self: Sized,
+    {
+        Or(self, other)
+    }
+
+    /// Create the filter accepting the messages not matched by this filter.
+    fn not(self) -> Not<Self>
+    where
+        Self: Sized,
+    {
+        Not(self)
+    }
+
+    /// Returns the messages matching the filter, preserving their order.
+    fn apply<'a>(&self, messages: &'a [Message]) -> Vec<&'a Message> {
+        messages
+            .iter()
+            .filter(|message| self.accept(message))
+            .collect()
+    }
+}
+
+impl<F> MessageFilter for F
+where
+    F: Fn(&Message) -> bool,
+{
+    fn accept(&self, message: &Message) -> bool {
+        self(message)
+    }
+}
+
+/// The filter accepting the messages matched by both filters, created with `MessageFilter::and`.
+#[derive(Debug, Clone)]
+pub struct And<A, B>(A, B);
+
+impl<A: MessageFilter, B: MessageFilter> MessageFilter for And<A, B> {
+    fn accept(&self, message: &Message) -> bool {
+        self.0.accept(message) && self.1.accept(message)
+    }
+}
+
+/// The filter accepting the messages matched by any of the filters, created with `MessageFilter::or`.
+#[derive(Debug, Clone)]
+pub struct Or<A, B>(A, B);
+
+impl<A: MessageFilter, B: MessageFilter> MessageFilter for Or<A, B> {
+    fn accept(&self, message: &Message) -> bool {
+        self.0.accept(message) || self.1.accept(message)
+    }
+}
+
+/// The filter accepting the messages not matched by the inner filter, created with `MessageFilter::not`.
+#[derive(Debug, Clone)]
+pub struct Not<A>(A);
+
+impl<A: MessageFilter> MessageFilter for Not<A> {
+    fn accept(&self, message: &Message) -> bool {
+        !self.0.accept(message)
+    }
+}
+
+/// The filter accepting the messages having the header with the specified key and, optionally, the specified value.
+#[derive(Debug, Clone)]
+pub struct HasHeader {
+    key: HeaderKey,
+    value: Option<HeaderValue>,
+}
+
+impl HasHeader {
+    /// Accept the messages having the header with the specified key, regardless of its value.
+    pub fn new(key: HeaderKey) -> Self {
+        Self { key, value: None }
+    }
+
+    /// Accept the messages having the header with the specified key and value.
+    pub fn with_value(key: HeaderKey, value: HeaderValue) -> Self {
+        Self {
+            key,
+            value: Some(value),
+        }
+    }
+}
+
+impl MessageFilter for HasHeader {
+    fn accept(&self, message: &Message) -> bool {
+        let Some(value) = message
+            .headers
+            .as_ref()
+            .and_then(|headers| headers.get(&self.key))
+        else {
+            return false;
+        };
+
+        match &self.value {
+            Some(expected) => expected == value,
+            None => true,
+        }
+    }
+}
+
+/// The filter accepting the messages whose payload length in bytes is within the inclusive range.
+#[derive(Debug, Clone, Copy)]
+pub struct PayloadSizeBetween {
+    min: usize,
+    max: usize,
+}
+
+impl PayloadSizeBetween {
+    /// Accept the messages with the payload length between `min` and `max` bytes, both inclusive.
+    pub fn new(min: usize, max: usize) -> Self {
+        Self { min, max }
+    }
+}
+
+impl MessageFilter for PayloadSizeBetween {
+    fn accept(&self, message: &Message) -> bool {
+        (self.min..=self.max).contains(&message.payload.len())
+    }
+}
+
+/// The filter accepting the messages whose ID is within the inclusive range.
+#[derive(Debug, Clone, Copy)]
+pub struct IdRange {
+    min: u128,
+    max: u128,
+}
+
+impl IdRange {
+    /// Accept the messages with the ID between `min` and `max`, both inclusive.
+    pub fn new(min: u128, max: u128) -> Self {
+        Self { min, max }
+    }
+}
+
+impl MessageFilter for IdRange {
+    fn accept(&self, message: &Message) -> bool {
+        (self.min..=self.max).contains(&message.id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::messages::MessageState;
+    use bytes::Bytes;
+    use std::collections::HashMap;
+    use std::str::FromStr;
+
+    #[test]
+    fn filters_composed_with_and_should_accept_messages_matched_by_both() {
+        let messages = messages();
+        let filter = IdRange::new(2, 4).and(HasHeader::new(HeaderKey::new("trace").unwrap()));
+
+        let ids = filter
+            .apply(&messages)
+            .iter()
+            .map(|message| message.id)
+            .collect::<Vec<_>>();
+
+        assert_eq!(ids, vec![2, 4]);
+    }
+
+    #[test]
+    fn filters_composed_with_or_should_accept_messages_matched_by_any() {
+        let messages = messages();
+        let filter = PayloadSizeBetween::new(0, 3).or(HasHeader::with_value(
+            HeaderKey::new("trace").unwrap(),
+            HeaderValue::from_str("abc").unwrap(),
+        ));
+
+        let ids = filter
+            .apply(&messages)
+            .iter()
+            .map(|message| message.id)
+            .collect::<Vec<_>>();
+
+        assert_eq!(ids, vec![1, 2, 5]);
+    }
+
+    #[test]
+    fn negated_payload_predicate_should_reject_matching_messages() {
+        let messages = messages();
+        let filter = (|message: &Message| message.payload.starts_with(b"hello")).not();
+
+        let ids = filter
+            .apply(&messages)
+            .iter()
+            .map(|message| message.id)
+            .collect::<Vec<_>>();
+
+        assert_eq!(ids, vec![1, 5]);
+    }
+
+    fn messages() -> Vec<Message> {
+        vec![
+            message(1, "hi", None),
+            message(2, "hello 2", Some("abc")),
+            message(3, "hello 3", None),
+            message(4, "hello 4", Some("def")),
+            message(5, "hey", None),
+        ]
+    }
+
+    fn message(id: u128, payload: &str, trace: Option<&str>) -> Message {
+        let headers = trace.map(|trace| {
+            HashMap::from([(
+                HeaderKey::new("trace").unwrap(),
+                HeaderValue::from_str(trace).unwrap(),
+            )])
+        });
+        Message::create(
+            id as u64,
+            MessageState::Available,
+            0,
+            id,
+            Bytes::copy_from_slice(payload.as_bytes()),
+            0,
+            headers,
+        )
+    }
+}
@@ -0,0 +1,82 @@
+use crate::messages::send_messages::Message;
+use crate::models::header::{HeaderKey, HeaderValue};
+use bytes::Bytes;
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use std::collections::HashMap;
+
+/// Generate the collection of messages with random payloads of the provided size and optional headers,
+/// which can be used for the load testing.
+pub fn generate_messages(count: usize, payload_size: usize, with_headers: bool) -> Vec<Message> {
+    generate_messages_with_rng(&mut rand::thread_rng(), count, payload_size, with_headers)
+}
+
+/// Generate the collection of messages using the provided RNG, so that the output is reproducible when it's seeded.
+pub fn generate_messages_with_rng(
+    rng: &mut impl Rng,
+    count: usize,
+    payload_size: usize,
+    with_headers: bool,
+) -> Vec<Message> {
+    (0..count)
+        .map(|index| {
+            let payload = (0..payload_size)
+                .map(|_| rng.sample(Alphanumeric))
+                .collect::<Vec<u8>>();
+            let headers = if with_headers {
+                Some(generate_headers(rng, index))
+            } else {
+                None
+            };
+            Message::new(None, Bytes::from(payload), headers)
+        })
+        .collect()
+}
+
+fn generate_headers(rng: &mut impl Rng, index: usize) -> HashMap<HeaderKey, HeaderValue> {
+    HashMap::from([
+        (
+            HeaderKey::new("index").unwrap(),
+            HeaderValue::from_uint64(index as u64).unwrap(),
+        ),
+        (
+            HeaderKey::new("random").unwrap(),
+            HeaderValue::from_uint32(rng.gen()).unwrap(),
+        ),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn messages_should_be_generated_with_headers() {
+        let messages = generate_messages(10, 100, true);
+        assert_eq!(messages.len(), 10);
+        for message in messages {
+            assert_eq!(message.payload.len(), 100);
+            assert_eq!(message.length, 100);
+            assert_eq!(message.headers.unwrap().len(), 2);
+        }
+    }
+
+    #[test]
+    fn messages_should_be_generated_without_headers() {
+        let messages = generate_messages(5, 20, false);
+        assert_eq!(messages.len(), 5);
+        for message in messages {
+            assert_eq!(message.payload.len(), 20);
+            assert!(message.headers.is_none());
+        }
+    }
+
+    #[test]
+    fn messages_should_be_reproducible_for_seeded_rng() {
+        let first = generate_messages_with_rng(&mut StdRng::seed_from_u64(1), 3, 50, true);
+        let second = generate_messages_with_rng(&mut StdRng::seed_from_u64(1), 3, 50, true);
+        assert_eq!(first, second);
+    }
+}
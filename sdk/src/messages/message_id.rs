@@ -0,0 +1,152 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+
+const VERSION_SHIFT: u32 = 76;
+const VERSION_MASK: u128 = 0xF << VERSION_SHIFT;
+const VARIANT_SHIFT: u32 = 62;
+const VARIANT_MASK: u128 = 0b11 << VARIANT_SHIFT;
+const RFC_4122_VARIANT: u128 = 0b10;
+const UUID_V7_VERSION: u128 = 7;
+// The snowflake ID is packed as UUIDv8 (custom), so it shares the timestamp layout with UUIDv7.
+const SNOWFLAKE_VERSION: u128 = 8;
+const TIMESTAMP_SHIFT: u32 = 80;
+const SEQUENCE_SHIFT: u32 = 64;
+const SEQUENCE_BITS: u32 = 12;
+const SEQUENCE_MASK: u64 = (1 << SEQUENCE_BITS) - 1;
+const NODE_ID_SHIFT: u32 = 46;
+
+// The last generated snowflake state: the timestamp in milliseconds followed by the 12 bits of sequence.
+static SNOWFLAKE_STATE: AtomicU64 = AtomicU64::new(0);
+
+/// `IdKind` specifies how the message ID is generated.
+/// It has the following kinds:
+/// - `Server` - the ID is set to `0` and generated by the server.
+/// - `UuidV7` - the time-ordered UUIDv7 is generated by the client.
+/// - `Snowflake` - the time-ordered ID consisting of the timestamp, node ID and sequence is generated by the client.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum IdKind {
+    /// The ID is generated by the server.
+    #[default]
+    Server,
+    /// The UUIDv7 generated by the client.
+    UuidV7,
+    /// The snowflake ID generated by the client for the provided node ID.
+    Snowflake(u16),
+}
+
+impl IdKind {
+    /// Generate the message ID, `0` for the server-side generation.
+    pub fn generate(&self) -> u128 {
+        match self {
+            IdKind::Server => 0,
+            IdKind::UuidV7 => Uuid::now_v7().as_u128(),
+            IdKind::Snowflake(node_id) => snowflake(*node_id),
+        }
+    }
+
+    /// Detect the kind of the provided message ID. The randomly generated IDs are treated as generated by the server.
+    pub fn detect(id: u128) -> IdKind {
+        if (id & VARIANT_MASK) >> VARIANT_SHIFT != RFC_4122_VARIANT {
+            return IdKind::Server;
+        }
+
+        match (id & VERSION_MASK) >> VERSION_SHIFT {
+            UUID_V7_VERSION => IdKind::UuidV7,
+            SNOWFLAKE_VERSION => IdKind::Snowflake((id >> NODE_ID_SHIFT) as u16),
+            _ => IdKind::Server,
+        }
+    }
+}
+
+/// Extract the timestamp in microseconds embedded in the UUIDv7 or snowflake ID, or `None` for the other IDs.
+pub fn timestamp(id: u128) -> Option<u64> {
+    match IdKind::detect(id) {
+        IdKind::Server => None,
+        IdKind::UuidV7 | IdKind::Snowflake(_) => Some((id >> TIMESTAMP_SHIFT) as u64 * 1000),
+    }
+}
+
+fn snowflake(node_id: u16) -> u128 {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64;
+    // The sequence overflow moves the state to the next millisecond, so the IDs are always increasing.
+    let mut current = SNOWFLAKE_STATE.load(Ordering::Relaxed);
+    let state = loop {
+        let next = (now << SEQUENCE_BITS).max(current + 1);
+        match SNOWFLAKE_STATE.compare_exchange_weak(
+            current,
+            next,
+            Ordering::Relaxed,
+            Ordering::Relaxed,
+        ) {
+            Ok(_) => break next,
+            Err(actual) => current = actual,
+        }
+    };
+
+    let timestamp = (state >> SEQUENCE_BITS) as u128;
+    let sequence = (state & SEQUENCE_MASK) as u128;
+    (timestamp << TIMESTAMP_SHIFT)
+        | (SNOWFLAKE_VERSION << VERSION_SHIFT)
+        | (sequence << SEQUENCE_SHIFT)
+        | (RFC_4122_VARIANT << VARIANT_SHIFT)
+        | ((node_id as u128) << NODE_ID_SHIFT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn server_id_kind_should_generate_zero() {
+        assert_eq!(IdKind::Server.generate(), 0);
+        assert_eq!(IdKind::detect(0), IdKind::Server);
+        assert_eq!(timestamp(0), None);
+    }
+
+    #[test]
+    fn uuid_v7_should_be_detected_and_have_timestamp() {
+        let before = now_micros();
+        let id = IdKind::UuidV7.generate();
+        let after = now_micros();
+
+        assert_eq!(Uuid::from_u128(id).get_version_num(), 7);
+        assert_eq!(IdKind::detect(id), IdKind::UuidV7);
+        let timestamp = timestamp(id).unwrap();
+        assert!(timestamp >= before - before % 1000 && timestamp <= after);
+    }
+
+    #[test]
+    fn snowflake_should_be_detected_and_have_node_id_and_timestamp() {
+        let before = now_micros();
+        let id = IdKind::Snowflake(513).generate();
+        let after = now_micros();
+
+        assert_eq!(IdKind::detect(id), IdKind::Snowflake(513));
+        let timestamp = timestamp(id).unwrap();
+        assert!(timestamp >= before - before % 1000);
+        // The sequence overflow can move the timestamp slightly forward.
+        assert!(timestamp <= after + 1000 * 1000);
+    }
+
+    #[test]
+    fn snowflakes_should_be_unique_and_increasing() {
+        let ids = (0..10_000)
+            .map(|_| IdKind::Snowflake(1).generate())
+            .collect::<Vec<_>>();
+
+        for pair in ids.windows(2) {
+            assert!(pair[0] < pair[1]);
+        }
+    }
+
+    fn now_micros() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_micros() as u64
+    }
+}
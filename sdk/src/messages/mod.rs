@@ -1,5 +1,10 @@
+#[cfg(feature = "testing")]
+pub mod generator;
+pub mod message_id;
 pub mod poll_messages;
 pub mod send_messages;
+pub mod send_messages_conditional;
+pub mod send_messages_json;
 
-const MAX_HEADERS_SIZE: u32 = 100 * 1000;
+pub(crate) const MAX_HEADERS_SIZE: u32 = 100 * 1000;
 pub const MAX_PAYLOAD_SIZE: u32 = 10 * 1000 * 1000;
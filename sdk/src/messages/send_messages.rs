@@ -1,39 +1,177 @@
 use crate::bytes_serializable::BytesSerializable;
 use crate::command::CommandPayload;
+use crate::compression::compression_algorithm::CompressionAlgorithm;
 use crate::error::IggyError;
-use crate::identifier::Identifier;
-use crate::messages::{MAX_HEADERS_SIZE, MAX_PAYLOAD_SIZE};
-use crate::models::header;
-use crate::models::header::{HeaderKey, HeaderValue};
+use crate::header_key;
+use crate::identifier::{Identifier, StreamId, TopicId};
+use crate::messages::message_id::IdKind;
+use crate::messages::{message_id, MAX_HEADERS_SIZE, MAX_PAYLOAD_SIZE};
+use crate::models::header::{HeaderKey, HeaderValue, Headers};
+use crate::partitioner;
+use crate::utils::checksum;
+use crate::utils::clock::{Clock, SystemClock};
+use crate::utils::crypto::PayloadCipher;
+use crate::utils::key_hash::HashAlgo;
+use crate::utils::timestamp::IggyTimestamp;
 use crate::validatable::Validatable;
 use bytes::{BufMut, Bytes, BytesMut};
-use serde::{Deserialize, Serialize};
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Deserializer, Serialize};
 use serde_with::base64::Base64;
 use serde_with::serde_as;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Display;
 use std::str::FromStr;
+use std::sync::atomic::AtomicU32;
+use xxhash_rust::xxh3::xxh3_128;
 
+/// The header used to mark the messages whose payload has been encrypted with `PayloadCipher`.
+pub const ENCRYPTED_HEADER: &str = "iggy.encrypted";
+/// The header used to store the position of the message within the sent batch.
+pub const SEQUENCE_HEADER: &str = "iggy.seq";
+/// The header used to store the content type of the message payload, e.g. `application/json`.
+pub const CONTENT_TYPE_HEADER: &str = "iggy.content_type";
+/// The header used to store the ID of the schema the message payload was encoded with.
+pub const SCHEMA_ID_HEADER: &str = "iggy.schema_id";
+/// The header used to store the timestamp (in microseconds) after which the message should be dropped.
+pub const EXPIRE_AT_HEADER: &str = "iggy.expire_at";
+/// The header used to mark the control message appended at the end of the batch, see `SendMessages::with_batch_trailer`.
+pub const BATCH_END_HEADER: &str = "iggy.batch_end";
 const EMPTY_KEY_VALUE: Vec<u8> = vec![];
+// ID + Headers length + Payload length
+const MIN_MESSAGE_SIZE: usize = 24;
+// The upper bound of the messages preallocated when parsing the batch, so that the untrusted length can't force a huge allocation.
+const MAX_PREALLOCATED_MESSAGES: usize = 1024;
+// The highest bit of the headers length marks that the compression code byte follows it.
+// The legacy format never sets it, so such messages are treated as not compressed.
+const COMPRESSION_FLAG: u32 = 1 << 31;
+// The second highest bit of the headers length marks that the headers compression code byte follows,
+// after the payload compression code byte if present, and that the headers block is compressed.
+const HEADERS_COMPRESSION_FLAG: u32 = 1 << 30;
+// The third highest bit of the headers length marks that the partition ID (u32) of the message follows the compression code bytes.
+const PARTITION_ID_FLAG: u32 = 1 << 29;
+// The fourth highest bit of the headers length marks that the CRC32 checksum (u32) of the payload follows the partition ID.
+const PAYLOAD_CHECKSUM_FLAG: u32 = 1 << 28;
+// The fifth highest bit of the headers length marks the tombstone, whose payload is intentionally empty,
+// so it's not mistaken for the truncated message, which is rejected if its payload length is 0.
+const TOMBSTONE_FLAG: u32 = 1 << 27;
+const HEADERS_LENGTH_FLAGS: u32 = COMPRESSION_FLAG
+    | HEADERS_COMPRESSION_FLAG
+    | PARTITION_ID_FLAG
+    | PAYLOAD_CHECKSUM_FLAG
+    | TOMBSTONE_FLAG;
+// The highest bit of the partitioning kind marks that the batch checksum trails the serialized command.
+const BATCH_CHECKSUM_FLAG: u8 = 1 << 7;
+// The second highest bit of the partitioning kind marks that the idempotency key follows the partitioning.
+const IDEMPOTENCY_KEY_FLAG: u8 = 1 << 6;
+// The third highest bit of the partitioning kind marks that the batch allows the tombstones.
+const TOMBSTONES_FLAG: u8 = 1 << 5;
+const IDEMPOTENCY_KEY_SIZE: u64 = 16;
 
 /// `SendMessages` command is used to send messages to a topic in a stream.
 /// It has additional payload:
 /// - `stream_id` - unique stream ID (numeric or name).
 /// - `topic_id` - unique topic ID (numeric or name).
 /// - `partitioning` - to which partition the messages should be sent - either provided by the client or calculated by the server.
-/// - `messages` - collection of messages to be sent.
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+/// - `messages` - collection of messages to be sent, accessed with `messages` and `messages_mut`.
+/// - `idempotency_key` - optional key of the batch, used by the server to ignore the duplicates when the batch is retried.
+/// - `allow_tombstones` - whether the batch may contain the tombstones, i.e. the messages with the empty payload.
+///
+/// The unknown (e.g. misspelled) fields are rejected when the command is deserialized from JSON. The stream and topic IDs
+/// are taken from the request path, so they're not serialized, and ignored if present.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct SendMessages {
     /// Unique stream ID (numeric or name).
-    #[serde(skip)]
+    #[serde(skip_serializing, default, deserialize_with = "ignore_identifier")]
     pub stream_id: Identifier,
     /// Unique topic ID (numeric or name).
-    #[serde(skip)]
+    #[serde(skip_serializing, default, deserialize_with = "ignore_identifier")]
     pub topic_id: Identifier,
     /// To which partition the messages should be sent - either provided by the client or calculated by the server.
     pub partitioning: Partitioning,
     /// Collection of messages to be sent.
-    pub messages: Vec<Message>,
+    messages: Vec<Message>,
+    /// Optional key of the batch, used by the server to ignore the duplicates when the batch is retried.
+    /// The batch with the key is never buffered and merged with the others by `IggyClient`, it's sent immediately.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotency_key: Option<u128>,
+    /// Whether the batch may contain the tombstones (see `Message::tombstone`), i.e. the messages with the empty payload,
+    /// which are rejected by the validation otherwise. It's sent as the flag of the batch, so the server rejects
+    /// the tombstones which weren't explicitly allowed by the client.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub allow_tombstones: bool,
+    // The running totals of the messages used by `push`, or `None` if they have to be accounted for from scratch,
+    // e.g. because the messages were changed with `messages_mut`.
+    #[serde(skip)]
+    totals: Option<BatchTotals>,
+}
+
+// The totals of the headers and the uncompressed payloads sizes of the batch used by `SendMessages::push`.
+#[derive(Debug, Default, Clone)]
+struct BatchTotals {
+    messages_count: usize,
+    headers_size: u32,
+    payload_size: u32,
+}
+
+impl BatchTotals {
+    // Account for the messages from scratch.
+    fn of(messages: &[Message]) -> Result<Self, IggyError> {
+        let mut totals = BatchTotals::default();
+        for message in messages {
+            totals.add(message)?;
+        }
+        Ok(totals)
+    }
+
+    // Account for the message, failing with `TooBigHeadersPayload` or `TooBigMessagePayload` if the totals would exceed
+    // `MAX_HEADERS_SIZE` or `MAX_PAYLOAD_SIZE`, in which case the totals remain unchanged.
+    fn add(&mut self, message: &Message) -> Result<(), IggyError> {
+        let headers_size = self
+            .headers_size
+            .saturating_add(get_message_headers_size(message));
+        if headers_size > MAX_HEADERS_SIZE {
+            return Err(IggyError::TooBigHeadersPayload);
+        }
+
+        let payload_size = self
+            .payload_size
+            .saturating_add(message.get_uncompressed_payload_length()?);
+        if payload_size > MAX_PAYLOAD_SIZE {
+            return Err(IggyError::TooBigMessagePayload);
+        }
+
+        self.messages_count += 1;
+        self.headers_size = headers_size;
+        self.payload_size = payload_size;
+        Ok(())
+    }
+}
+
+// The totals aren't a part of the command, only a cache of its messages.
+impl PartialEq for SendMessages {
+    fn eq(&self, other: &Self) -> bool {
+        self.stream_id == other.stream_id
+            && self.topic_id == other.topic_id
+            && self.partitioning == other.partitioning
+            && self.messages == other.messages
+            && self.idempotency_key == other.idempotency_key
+            && self.allow_tombstones == other.allow_tombstones
+    }
+}
+
+// Serialize the headers and compress them if it makes them smaller, along with the flag whether they were compressed.
+fn compress_headers(headers: &Headers, compression: &CompressionAlgorithm) -> (Bytes, bool) {
+    let headers_bytes = headers.as_bytes();
+    if compression == &CompressionAlgorithm::None {
+        return (headers_bytes, false);
+    }
+
+    match compression.compress(&headers_bytes) {
+        Ok(compressed) if compressed.len() < headers_bytes.len() => (Bytes::from(compressed), true),
+        _ => (headers_bytes, false),
+    }
 }
 
 /// `Partitioning` is used to specify to which partition the messages should be sent.
@@ -41,8 +179,11 @@ pub struct SendMessages {
 /// - `Balanced` - the partition ID is calculated by the server using the round-robin algorithm.
 /// - `PartitionId` - the partition ID is provided by the client.
 /// - `MessagesKey` - the partition ID is calculated by the server using the hash of the provided messages key.
+/// - `ConsistentKey` - the partition ID is calculated by the server using the jump consistent hash of the provided key.
+/// - `HashedKey` - the partition ID is calculated by the server using the hash of the provided key with the algorithm encoded in the value.
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct Partitioning {
     /// The kind of partitioning.
     pub kind: PartitioningKind,
@@ -54,13 +195,35 @@ pub struct Partitioning {
     pub value: Vec<u8>,
 }
 
+/// The partitioning as read from the configuration of the producer, e.g. `{ "kind": "messages_key", "value": "orders" }`,
+/// which is validated and converted into the `Partitioning` with `Partitioning::try_from_config`.
+/// The keys are plain UTF-8 strings of 1 to 255 bytes.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PartitioningConfig {
+    /// The partition ID is calculated by the server using the round-robin algorithm.
+    #[default]
+    Balanced,
+    /// The partition ID is provided by the client.
+    PartitionId { value: u32 },
+    /// The partition ID is calculated by the server using the hash of the key.
+    MessagesKey { value: String },
+    /// The partition ID is calculated by the server using the jump consistent hash of the key.
+    ConsistentKey { value: String },
+}
+
 /// The single message to be sent. It has the following payload:
 /// - `id` - unique message ID, if not specified by the client (has value = 0), it will be generated by the server.
 /// - `length` - length of the payload.
 /// - `payload` - binary message payload.
 /// - `headers` - optional collection of headers.
+/// - `compression` - the compression algorithm used for the payload.
+/// - `headers_compression` - the compression algorithm used for the serialized headers block.
+/// - `partition_id` - optional partition ID overriding the batch partitioning for this message.
+/// - `payload_checksum` - whether the CRC32 checksum of the payload is sent along with the message.
 #[serde_as]
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct Message {
     /// Unique message ID, if not specified by the client (has value = 0), it will be generated by the server.
     #[serde(default = "default_message_id")]
@@ -71,8 +234,93 @@ pub struct Message {
     #[serde_as(as = "Base64")]
     /// Binary message payload.
     pub payload: Bytes,
+    /// Optional collection of headers, which keeps track of its size.
+    pub headers: Option<Headers>,
+    /// The compression algorithm used for the payload, which is decompressed when the message is deserialized
+    /// from the binary format, or by the server before it's appended, so the polled messages are never compressed.
+    #[serde(default)]
+    pub compression: CompressionAlgorithm,
+    /// The compression algorithm used for the serialized headers block, which is applied only if it makes the block smaller.
+    /// The headers are decompressed when the message is deserialized.
+    #[serde(default)]
+    pub headers_compression: CompressionAlgorithm,
+    /// Optional partition ID to which the message is appended, overriding the partitioning of the batch.
+    /// It can't conflict with the batch `PartitionId` partitioning.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub partition_id: Option<u32>,
+    /// Whether the CRC32 checksum of the payload (as sent on the wire) is sent along with the message,
+    /// so the corrupted payload is rejected with `PayloadChecksumMismatch` when the message is deserialized.
+    #[serde(default)]
+    pub payload_checksum: bool,
+}
+
+/// The routing metadata of the `Message`, borrowing its headers, so it can be inspected without copying them along with the payload:
+/// - `id` - unique message ID, equal to 0 if it will be generated by the server.
+/// - `length` - length of the payload.
+/// - `headers` - optional collection of headers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MessageMetadata<'a> {
+    /// Unique message ID, equal to 0 if it will be generated by the server.
+    pub id: u128,
+    /// Length of the payload.
+    pub length: u32,
     /// Optional collection of headers.
-    pub headers: Option<HashMap<HeaderKey, HeaderValue>>,
+    pub headers: Option<&'a Headers>,
+}
+
+/// The fluent builder of the `Message`, which validates the headers when the message is built.
+#[derive(Debug, Default)]
+pub struct MessageBuilder {
+    id: Option<u128>,
+    payload: Bytes,
+    headers: Vec<(HeaderKey, HeaderValue)>,
+    error: Option<IggyError>,
+}
+
+/// The borrowed, zero-copy view of the single serialized message.
+/// It validates the length fields upfront and exposes the message fields without allocating.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MessageView<'a> {
+    bytes: &'a [u8],
+    headers_position: usize,
+    headers_length: usize,
+    payload_length: usize,
+}
+
+/// The lazy reader of the concatenated serialized messages, which yields them one by one without collecting them into the `Vec`,
+/// so the huge batches can be processed with the bounded memory. The uncompressed payloads share the buffer instead of copying it.
+/// The malformed or truncated message yields the error, after which the reader stops.
+#[derive(Debug, Clone)]
+pub struct MessagesReader<'a> {
+    bytes: &'a Bytes,
+    position: usize,
+    failed: bool,
+    budget: DecompressionBudget,
+}
+
+// The budget of the decompressed payloads and headers of the whole batch, so the compressed messages together
+// can't inflate beyond `MAX_PAYLOAD_SIZE` and `MAX_HEADERS_SIZE`, while each of them is decompressed when deserialized.
+#[derive(Debug, Clone, Copy)]
+struct DecompressionBudget {
+    payload: usize,
+    headers: usize,
+}
+
+impl Default for DecompressionBudget {
+    fn default() -> Self {
+        DecompressionBudget {
+            payload: MAX_PAYLOAD_SIZE as usize,
+            headers: MAX_HEADERS_SIZE as usize,
+        }
+    }
+}
+
+/// The borrowed, zero-copy view of the serialized `Partitioning`.
+/// It's meant to be used in the hot paths, where allocating the `Vec<u8>` for the value on every deserialization is too expensive.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PartitioningView<'a> {
+    kind: PartitioningKind,
+    value: &'a [u8],
 }
 
 /// `PartitioningKind` is an enum which specifies the kind of partitioning and is used by `Partitioning`.
@@ -86,12 +334,23 @@ pub enum PartitioningKind {
     PartitionId,
     /// The partition ID is calculated by the server using the hash of the provided messages key.
     MessagesKey,
+    /// The partition ID is calculated by the server using the jump consistent hash of the provided key,
+    /// so that only a minimal fraction of the keys is remapped when the partitions count changes.
+    ConsistentKey,
+    /// The partition ID is calculated by the server using the hash of the provided messages key,
+    /// with the algorithm selected by the producer and encoded as the first byte of the value.
+    HashedKey,
 }
 
 fn default_message_id() -> u128 {
     0
 }
 
+fn ignore_identifier<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Identifier, D::Error> {
+    IgnoredAny::deserialize(deserializer)?;
+    Ok(Identifier::default())
+}
+
 impl Default for SendMessages {
     fn default() -> Self {
         SendMessages {
@@ -99,10 +358,64 @@ impl Default for SendMessages {
             topic_id: Identifier::default(),
             partitioning: Partitioning::default(),
             messages: vec![Message::default()],
+            idempotency_key: None,
+            allow_tombstones: false,
+            totals: None,
+        }
+    }
+}
+
+/// Collect the messages into the command with the default (balanced) partitioning,
+/// the stream and topic IDs are expected to be set afterward.
+impl FromIterator<Message> for SendMessages {
+    fn from_iter<T: IntoIterator<Item = Message>>(iter: T) -> Self {
+        let mut command = SendMessages {
+            stream_id: Identifier::default(),
+            topic_id: Identifier::default(),
+            partitioning: Partitioning::default(),
+            messages: Vec::new(),
+            idempotency_key: None,
+            allow_tombstones: false,
+            totals: Some(BatchTotals::default()),
+        };
+        command.extend(iter);
+        command
+    }
+}
+
+/// Append the messages to the batch without enforcing the limits, use `push` to enforce them for each message.
+impl Extend<Message> for SendMessages {
+    fn extend<T: IntoIterator<Item = Message>>(&mut self, iter: T) {
+        for message in iter {
+            // Once the limits are exceeded, the totals are accounted for from scratch by the next `push`, which then fails.
+            if let Some(totals) = &mut self.totals {
+                if totals.add(&message).is_err() {
+                    self.totals = None;
+                }
+            }
+            self.messages.push(message);
         }
     }
 }
 
+impl IntoIterator for SendMessages {
+    type Item = Message;
+    type IntoIter = std::vec::IntoIter<Message>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.messages.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a SendMessages {
+    type Item = &'a Message;
+    type IntoIter = std::slice::Iter<'a, Message>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.messages.iter()
+    }
+}
+
 impl Default for Partitioning {
     fn default() -> Self {
         Partitioning::balanced()
@@ -128,6 +441,26 @@ impl Partitioning {
         }
     }
 
+    /// Partition the messages using the provided partition ID, which must be in range of the known partitions count.
+    /// Fails with `InvalidPartitionId` for 0 or `PartitionIdOutOfRange` for the ID exceeding the count, without the round trip to the server.
+    pub fn partition_id_checked(
+        partition_id: u32,
+        partitions_count: u32,
+    ) -> Result<Self, IggyError> {
+        if partition_id == 0 {
+            return Err(IggyError::InvalidPartitionId(partition_id));
+        }
+
+        if partition_id > partitions_count {
+            return Err(IggyError::PartitionIdOutOfRange(
+                partition_id,
+                partitions_count,
+            ));
+        }
+
+        Ok(Self::partition_id(partition_id))
+    }
+
     /// Partition the messages using the provided messages key.
     pub fn messages_key(value: &[u8]) -> Result<Self, IggyError> {
         let length = value.len();
@@ -143,6 +476,19 @@ impl Partitioning {
         })
     }
 
+    /// Partition the messages using the messages key composed of the provided parts, e.g. `(tenant_id, entity_id)`.
+    /// Each part is prefixed with its length (single byte), so the different tuples never produce the same key,
+    /// e.g. `("a", "bc")` and `("ab", "c")`. The composed key must fit in 255 bytes.
+    pub fn messages_key_composite(parts: &[&[u8]]) -> Result<Self, IggyError> {
+        let mut value = Vec::with_capacity(parts.iter().map(|part| 1 + part.len()).sum());
+        for part in parts {
+            let length = u8::try_from(part.len()).map_err(|_| IggyError::InvalidCommand)?;
+            value.push(length);
+            value.extend_from_slice(part);
+        }
+        Self::messages_key(&value)
+    }
+
     /// Partition the messages using the provided messages key as str.
     pub fn messages_key_str(value: &str) -> Result<Self, IggyError> {
         Self::messages_key(value.as_bytes())
@@ -175,443 +521,2343 @@ impl Partitioning {
         }
     }
 
-    /// Create the partitioning from the provided partitioning.
-    pub fn from_partitioning(partitioning: &Partitioning) -> Self {
+    /// Partition the messages using the provided messages key as i32.
+    ///
+    /// The key is encoded as two's complement little-endian, so it's byte-exact with `messages_key_u32(value as u32)`
+    /// and the messages are routed to the same partition as the other producers using the same layout.
+    /// There's no signedness marker on the wire, thus the `Display` output renders the key as u32.
+    pub fn messages_key_i32(value: i32) -> Self {
         Partitioning {
-            kind: partitioning.kind,
-            length: partitioning.length,
-            value: partitioning.value.clone(),
+            kind: PartitioningKind::MessagesKey,
+            length: 4,
+            value: value.to_le_bytes().to_vec(),
         }
     }
 
-    /// Get the size of the partitioning in bytes.
-    pub fn get_size_bytes(&self) -> u32 {
-        2 + u32::from(self.length)
+    /// Partition the messages using the provided messages key as i64.
+    ///
+    /// The key is encoded as two's complement little-endian, so it's byte-exact with `messages_key_u64(value as u64)`
+    /// and the messages are routed to the same partition as the other producers using the same layout.
+    /// There's no signedness marker on the wire, thus the `Display` output renders the key as u64.
+    pub fn messages_key_i64(value: i64) -> Self {
+        Partitioning {
+            kind: PartitioningKind::MessagesKey,
+            length: 8,
+            value: value.to_le_bytes().to_vec(),
+        }
     }
-}
 
-impl CommandPayload for SendMessages {}
+    /// Partition the messages using the provided messages key as i128.
+    ///
+    /// The key is encoded as two's complement little-endian, so it's byte-exact with `messages_key_u128(value as u128)`
+    /// and the messages are routed to the same partition as the other producers using the same layout.
+    /// There's no signedness marker on the wire, thus the `Display` output renders the key as u128.
+    pub fn messages_key_i128(value: i128) -> Self {
+        Partitioning {
+            kind: PartitioningKind::MessagesKey,
+            length: 16,
+            value: value.to_le_bytes().to_vec(),
+        }
+    }
 
-impl Validatable<IggyError> for SendMessages {
-    fn validate(&self) -> Result<(), IggyError> {
-        if self.messages.is_empty() {
-            return Err(IggyError::InvalidMessagesCount);
+    /// Partition the messages using the jump consistent hash of the provided key, see `utils::consistent_hash`.
+    pub fn consistent_key(value: &[u8]) -> Result<Self, IggyError> {
+        let mut partitioning = Self::messages_key(value)?;
+        partitioning.kind = PartitioningKind::ConsistentKey;
+        Ok(partitioning)
+    }
+
+    /// Partition the messages using the provided messages key hashed with the provided algorithm, see `key_hash::HashAlgo`.
+    /// The algorithm code is prepended to the key, so the key must fit in 254 bytes.
+    pub fn messages_key_with(value: &[u8], algo: HashAlgo) -> Result<Self, IggyError> {
+        if value.is_empty() {
+            return Err(IggyError::InvalidCommand);
         }
 
-        let key_value_length = self.partitioning.value.len();
-        if key_value_length > 255
-            || (self.partitioning.kind != PartitioningKind::Balanced && key_value_length == 0)
-        {
-            return Err(IggyError::InvalidKeyValueLength);
+        let mut key = Vec::with_capacity(1 + value.len());
+        key.push(algo.as_code());
+        key.extend_from_slice(value);
+        let mut partitioning = Self::messages_key(&key)?;
+        partitioning.kind = PartitioningKind::HashedKey;
+        Ok(partitioning)
+    }
+
+    /// Get the hash algorithm of the `HashedKey` partitioning, or `None` for the other kinds and the unknown algorithm codes.
+    pub fn hash_algo(&self) -> Option<HashAlgo> {
+        match self.kind {
+            PartitioningKind::HashedKey => HashAlgo::from_code(*self.value.first()?).ok(),
+            _ => None,
         }
+    }
 
-        let mut headers_size = 0;
-        let mut payload_size = 0;
-        for message in &self.messages {
-            if let Some(headers) = &message.headers {
-                for value in headers.values() {
-                    headers_size += value.value.len() as u32;
-                    if headers_size > MAX_HEADERS_SIZE {
-                        return Err(IggyError::TooBigHeadersPayload);
-                    }
-                }
+    /// Create the partitioning from the configuration, failing with `InvalidKeyValueLength` for the empty or longer than 255 bytes key
+    /// and `InvalidPartitionId` for the partition ID equal to 0.
+    pub fn try_from_config(config: &PartitioningConfig) -> Result<Self, IggyError> {
+        match config {
+            PartitioningConfig::Balanced => Ok(Self::balanced()),
+            PartitioningConfig::PartitionId { value: 0 } => Err(IggyError::InvalidPartitionId(0)),
+            PartitioningConfig::PartitionId { value } => Ok(Self::partition_id(*value)),
+            PartitioningConfig::MessagesKey { value } => {
+                Self::messages_key(value.as_bytes()).map_err(|_| IggyError::InvalidKeyValueLength)
             }
-            payload_size += message.payload.len() as u32;
-            if payload_size > MAX_PAYLOAD_SIZE {
-                return Err(IggyError::TooBigMessagePayload);
+            PartitioningConfig::ConsistentKey { value } => {
+                Self::consistent_key(value.as_bytes()).map_err(|_| IggyError::InvalidKeyValueLength)
             }
         }
+    }
 
-        if payload_size == 0 {
-            return Err(IggyError::EmptyMessagePayload);
+    /// Create the partitioning from the provided partitioning.
+    pub fn from_partitioning(partitioning: &Partitioning) -> Self {
+        Partitioning {
+            kind: partitioning.kind,
+            length: partitioning.length,
+            value: partitioning.value.clone(),
         }
+    }
 
-        Ok(())
+    /// Get the size of the partitioning in bytes.
+    pub fn get_size_bytes(&self) -> u32 {
+        2 + u32::from(self.length)
     }
-}
 
-impl PartitioningKind {
-    /// Get the code of the partitioning kind.
-    pub fn as_code(&self) -> u8 {
-        match self {
-            PartitioningKind::Balanced => 1,
-            PartitioningKind::PartitionId => 2,
-            PartitioningKind::MessagesKey => 3,
+    /// Resolve the partition ID (starting from 1) to which the messages will be appended, using the same algorithm as the server
+    /// (see `partitioner::resolve_partition_id`), so the messages can be grouped by their partition on the client side.
+    /// The counter is used (and advanced) only by the `Balanced` partitioning.
+    /// Fails with `InvalidPartitionId` if the resolved partition ID is 0, i.e. it's provided as 0 or there are no partitions,
+    /// `InvalidCommand` if the partition ID value is malformed and `UnsupportedHashAlgorithm` if the hash algorithm is unknown.
+    pub fn resolve_partition(
+        &self,
+        partitions_count: u32,
+        round_robin_counter: &AtomicU32,
+    ) -> Result<u32, IggyError> {
+        let partition_id =
+            partitioner::resolve_partition_id(self, partitions_count, round_robin_counter)?;
+        if partition_id == 0 {
+            return Err(IggyError::InvalidPartitionId(partition_id));
         }
+
+        Ok(partition_id)
     }
 
-    /// Get the partitioning kind from the provided code.
-    pub fn from_code(code: u8) -> Result<Self, IggyError> {
-        match code {
-            1 => Ok(PartitioningKind::Balanced),
-            2 => Ok(PartitioningKind::PartitionId),
-            3 => Ok(PartitioningKind::MessagesKey),
-            _ => Err(IggyError::InvalidCommand),
+    /// Describe the partitioning in plain language for the user-facing output, e.g. `routed to partition 4`
+    /// or `hashed by key 'orders'`. The keys which aren't the printable UTF-8 text are shown as hex.
+    pub fn describe(&self) -> String {
+        match self.kind {
+            PartitioningKind::Balanced => "round-robin balanced across partitions".to_string(),
+            PartitioningKind::PartitionId => match self.value.as_slice().try_into() {
+                Ok(value) => format!("routed to partition {}", u32::from_le_bytes(value)),
+                Err(_) => format!("routed to partition {}", to_hex(&self.value)),
+            },
+            PartitioningKind::MessagesKey => format!("hashed by key {}", describe_key(&self.value)),
+            PartitioningKind::ConsistentKey => {
+                format!("consistently hashed by key {}", describe_key(&self.value))
+            }
+            PartitioningKind::HashedKey => match self.hash_algo() {
+                Some(algo) => format!(
+                    "hashed by key {} using {algo}",
+                    describe_key(&self.value[1..])
+                ),
+                None => format!("hashed by key {}", to_hex(&self.value)),
+            },
         }
     }
 }
 
-impl Message {
-    /// Create a new message with the optional ID, payload and headers.
+impl SendMessages {
+    /// Create a new command to send the messages to the topic in the stream.
+    /// The typed stream and topic IDs prevent passing them in the wrong order.
     pub fn new(
-        id: Option<u128>,
-        payload: Bytes,
-        headers: Option<HashMap<HeaderKey, HeaderValue>>,
+        stream_id: StreamId,
+        topic_id: TopicId,
+        partitioning: Partitioning,
+        messages: Vec<Message>,
     ) -> Self {
-        Message {
-            id: id.unwrap_or(0),
-            #[allow(clippy::cast_possible_truncation)]
-            length: payload.len() as u32,
-            payload,
-            headers,
+        SendMessages {
+            stream_id: stream_id.into(),
+            topic_id: topic_id.into(),
+            partitioning,
+            messages,
+            idempotency_key: None,
+            allow_tombstones: false,
+            totals: None,
         }
     }
 
-    /// Get the size of the message in bytes.
-    pub fn get_size_bytes(&self) -> u32 {
-        // ID + Length + Payload + Headers
-        16 + 4 + self.payload.len() as u32 + header::get_headers_size_bytes(&self.headers)
+    /// Create a new command to send the messages to the topic in the stream, which are converted from the numbers or names
+    /// (e.g. `1` or `"orders"`), and validate it up front. Use `new` for the already constructed identifiers.
+    pub fn try_new<S, T>(
+        stream_id: S,
+        topic_id: T,
+        partitioning: Partitioning,
+        messages: Vec<Message>,
+    ) -> Result<Self, IggyError>
+    where
+        S: TryInto<Identifier, Error = IggyError>,
+        T: TryInto<Identifier, Error = IggyError>,
+    {
+        let command = SendMessages {
+            stream_id: stream_id.try_into()?,
+            topic_id: topic_id.try_into()?,
+            partitioning,
+            messages,
+            idempotency_key: None,
+            allow_tombstones: false,
+            totals: None,
+        };
+        command.validate()?;
+        Ok(command)
     }
-}
 
-impl Default for Message {
-    fn default() -> Self {
-        let payload = Bytes::from("hello world");
-        Message {
-            id: 0,
-            length: payload.len() as u32,
-            payload,
-            headers: None,
+    /// Validate the command like `validate`, and additionally reject the batch containing the same non-zero message ID more than once.
+    /// The IDs equal to 0 are generated by the server, so they are exempt from this check.
+    pub fn validate_strict(&self) -> Result<(), IggyError> {
+        self.validate()?;
+
+        let mut ids = HashSet::with_capacity(self.messages.len());
+        for message in &self.messages {
+            if message.id != 0 && !ids.insert(message.id) {
+                return Err(IggyError::DuplicateMessageId(message.id));
+            }
         }
-    }
-}
 
-impl Display for Message {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let len = self.payload.len();
+        Ok(())
+    }
 
-        if len > 40 {
-            write!(
-                f,
-                "{}|{}...{}",
-                self.id,
-                String::from_utf8_lossy(&self.payload[..20]),
-                String::from_utf8_lossy(&self.payload[len - 20..])
-            )
-        } else {
-            write!(f, "{}|{}", self.id, String::from_utf8_lossy(&self.payload))
+    /// Check that either all the messages have the IDs generated by the server (equal to 0) or none of them,
+    /// failing with `MixedMessageIds` otherwise, as mixing them makes the ordering and the deduplication confusing.
+    /// It's not a part of `validate`, so the producers wanting the consistent IDs should call it before sending.
+    pub fn validate_consistent_ids(&self) -> Result<(), IggyError> {
+        let server_generated_ids = self
+            .messages
+            .iter()
+            .filter(|message| message.id == 0)
+            .count();
+        if server_generated_ids != 0 && server_generated_ids != self.messages.len() {
+            return Err(IggyError::MixedMessageIds);
         }
+
+        Ok(())
     }
-}
 
-impl BytesSerializable for Partitioning {
-    fn as_bytes(&self) -> Bytes {
-        let mut bytes = BytesMut::with_capacity(2 + self.length as usize);
-        bytes.put_u8(self.kind.as_code());
-        bytes.put_u8(self.length);
-        bytes.put_slice(&self.value);
-        bytes.freeze()
+    /// Consume the command and return its messages without cloning them.
+    pub fn into_messages(self) -> Vec<Message> {
+        self.messages
     }
 
-    fn from_bytes(bytes: Bytes) -> Result<Self, IggyError>
-    where
-        Self: Sized,
-    {
-        if bytes.len() < 3 {
-            return Err(IggyError::InvalidCommand);
+    /// Consume the command and return the new one with every message transformed by the provided function, e.g. to add a common header.
+    /// The stream and topic IDs, partitioning and idempotency key are kept as is.
+    pub fn map_messages<F: FnMut(Message) -> Message>(self, f: F) -> SendMessages {
+        SendMessages {
+            messages: self.messages.into_iter().map(f).collect(),
+            totals: None,
+            ..self
         }
+    }
 
-        let kind = PartitioningKind::from_code(bytes[0])?;
-        let length = bytes[1];
-        let value = bytes[2..2 + length as usize].to_vec();
-        if value.len() != length as usize {
-            return Err(IggyError::InvalidCommand);
-        }
+    /// Get the messages of the batch.
+    pub fn messages(&self) -> &[Message] {
+        &self.messages
+    }
 
-        Ok(Partitioning {
-            kind,
-            length,
-            value,
-        })
+    /// Get the mutable messages of the batch. The running totals used by `push` are dropped,
+    /// so the messages are accounted for from scratch by the next `push`.
+    pub fn messages_mut(&mut self) -> &mut Vec<Message> {
+        self.totals = None;
+        &mut self.messages
     }
-}
 
-impl BytesSerializable for Message {
-    fn as_bytes(&self) -> Bytes {
-        let mut bytes = BytesMut::with_capacity(self.get_size_bytes() as usize);
-        bytes.put_u128_le(self.id);
-        if let Some(headers) = &self.headers {
-            let headers_bytes = headers.as_bytes();
-            bytes.put_u32_le(headers_bytes.len() as u32);
-            bytes.put_slice(&headers_bytes);
-        } else {
-            bytes.put_u32_le(0);
+    /// Append the message to the batch, failing with `TooBigHeadersPayload` or `TooBigMessagePayload` if the headers
+    /// or the uncompressed payloads of the batch would exceed `MAX_HEADERS_SIZE` or `MAX_PAYLOAD_SIZE`,
+    /// in which case the batch remains unchanged. The running totals of the batch are updated by each message,
+    /// so only the pushed message is accounted for.
+    pub fn push(&mut self, message: Message) -> Result<(), IggyError> {
+        let mut totals = match self.totals.take() {
+            Some(totals) if totals.messages_count == self.messages.len() => totals,
+            _ => BatchTotals::of(&self.messages)?,
+        };
+        let result = totals.add(&message);
+        self.totals = Some(totals);
+        result?;
+        self.messages.push(message);
+        Ok(())
+    }
+
+    /// Get the total size of the messages in bytes.
+    pub fn get_messages_size_bytes(&self) -> u64 {
+        self.messages.iter().map(Message::get_size_bytes).sum()
+    }
+
+    /// Get the number of bytes the command takes on the wire, equal to the length of `as_bytes`, without serializing it.
+    pub fn wire_size(&self) -> u64 {
+        u64::from(self.stream_id.get_size_bytes())
+            + u64::from(self.topic_id.get_size_bytes())
+            + u64::from(self.partitioning.get_size_bytes())
+            + self.idempotency_key.map_or(0, |_| IDEMPOTENCY_KEY_SIZE)
+            + self.get_messages_size_bytes()
+    }
+
+    /// Serialize the command consuming it, equal to `as_bytes`, but each message is written straight into the output buffer
+    /// and released right after, so neither the command nor its payloads are retained until the serialization completes.
+    pub fn into_bytes(self) -> Bytes {
+        let mut bytes = BytesMut::with_capacity(self.wire_size() as usize);
+        bytes.put_slice(&self.stream_id.as_bytes());
+        bytes.put_slice(&self.topic_id.as_bytes());
+        self.write_partitioning(&mut bytes);
+        for message in self.messages {
+            message.write_bytes(&mut bytes);
         }
-        bytes.put_u32_le(self.length);
-        bytes.put_slice(&self.payload);
+
         bytes.freeze()
     }
 
-    fn from_bytes(bytes: Bytes) -> Result<Self, IggyError> {
-        if bytes.len() < 24 {
-            return Err(IggyError::InvalidCommand);
-        }
+    /// Check whether the batch should be flushed, because it has reached either the wire size in bytes or the messages count.
+    pub fn should_flush(&self, target_bytes: u32, target_count: usize) -> bool {
+        self.messages.len() >= target_count || self.wire_size() >= u64::from(target_bytes)
+    }
 
-        let id = u128::from_le_bytes(bytes[..16].try_into()?);
-        let headers_length = u32::from_le_bytes(bytes[16..20].try_into()?);
-        let headers = if headers_length > 0 {
-            Some(HashMap::from_bytes(
-                bytes.slice(20..20 + headers_length as usize),
-            )?)
-        } else {
-            None
-        };
+    /// Stably sort the messages by their ID ascending, see `Message::order_key`.
+    /// The messages with the IDs to be generated by the server (equal to 0) are moved to the end, keeping their relative order.
+    pub fn sort_by_id(&mut self) {
+        self.messages.sort_by_key(Message::order_key);
+    }
 
-        let payload_length = u32::from_le_bytes(
-            bytes[20 + headers_length as usize..24 + headers_length as usize].try_into()?,
-        );
-        if payload_length == 0 {
-            return Err(IggyError::EmptyMessagePayload);
+    /// Get the first message in the batch, if any.
+    pub fn first_message(&self) -> Option<&Message> {
+        self.messages.first()
+    }
+
+    /// Get the last message in the batch, if any.
+    pub fn last_message(&self) -> Option<&Message> {
+        self.messages.last()
+    }
+
+    /// Get the minimum and maximum message ID in the batch, ignoring the IDs to be generated by the server (equal to 0).
+    pub fn id_range(&self) -> Option<(u128, u128)> {
+        self.messages
+            .iter()
+            .map(|message| message.id)
+            .filter(|id| *id != 0)
+            .fold(None, |range, id| match range {
+                None => Some((id, id)),
+                Some((min, max)) => Some((min.min(id), max.max(id))),
+            })
+    }
+
+    /// Get the iterator over the messages paired with the byte offset at which each message starts in the serialized command.
+    pub fn messages_with_offsets(&self) -> impl Iterator<Item = (usize, &Message)> {
+        let mut offset = (u64::from(self.stream_id.get_size_bytes())
+            + u64::from(self.topic_id.get_size_bytes())
+            + u64::from(self.partitioning.get_size_bytes())
+            + self.idempotency_key.map_or(0, |_| IDEMPOTENCY_KEY_SIZE))
+            as usize;
+        self.messages.iter().map(move |message| {
+            let message_offset = offset;
+            offset += message.get_size_bytes() as usize;
+            (message_offset, message)
+        })
+    }
+
+    /// Split the command into multiple commands, each one with the messages fitting in `MAX_PAYLOAD_SIZE` and `MAX_HEADERS_SIZE`,
+    /// preserving the stream ID, topic ID, partitioning and the order of the messages.
+    /// Fails if any single message exceeds the limits on its own.
+    pub fn split_into_valid_batches(self) -> Result<Vec<SendMessages>, IggyError> {
+        let mut batches = Vec::new();
+        let mut messages = Vec::new();
+        let mut payload_size = 0;
+        let mut headers_size = 0;
+        for message in self.messages {
+            let message_payload_size = message.get_uncompressed_payload_length()?;
+            if message_payload_size > MAX_PAYLOAD_SIZE {
+                return Err(IggyError::TooBigMessagePayload);
+            }
+
+            let message_headers_size = get_message_headers_size(&message);
+            if message_headers_size > MAX_HEADERS_SIZE {
+                return Err(IggyError::TooBigHeadersPayload);
+            }
+
+            if !messages.is_empty()
+                && (payload_size + message_payload_size > MAX_PAYLOAD_SIZE
+                    || headers_size + message_headers_size > MAX_HEADERS_SIZE)
+            {
+                batches.push(std::mem::take(&mut messages));
+                payload_size = 0;
+                headers_size = 0;
+            }
+
+            payload_size += message_payload_size;
+            headers_size += message_headers_size;
+            messages.push(message);
         }
 
-        let payload = bytes.slice(
-            24 + headers_length as usize..24 + headers_length as usize + payload_length as usize,
-        );
-        if payload.len() != payload_length as usize {
-            return Err(IggyError::InvalidMessagePayloadLength);
+        if !messages.is_empty() {
+            batches.push(messages);
         }
 
-        Ok(Message {
-            id,
-            length: payload_length,
-            payload,
-            headers,
-        })
+        Ok(batches
+            .into_iter()
+            .map(|messages| SendMessages {
+                stream_id: self.stream_id.clone(),
+                topic_id: self.topic_id.clone(),
+                partitioning: self.partitioning.clone(),
+                messages,
+                idempotency_key: None,
+                allow_tombstones: self.allow_tombstones,
+                totals: None,
+            })
+            .collect())
     }
-}
 
-impl FromStr for Message {
-    type Err = IggyError;
-    fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let id = default_message_id();
-        let payload = Bytes::from(input.as_bytes().to_vec());
-        let length = payload.len() as u32;
-        if length == 0 {
-            return Err(IggyError::EmptyMessagePayload);
+    /// Set the `iggy.seq` header of every message in the batch to its index, so that the consumers can detect the reordering.
+    pub fn assign_sequence_headers(&mut self) -> Result<(), IggyError> {
+        let key = header_key!(SEQUENCE_HEADER);
+        for (index, message) in self.messages_mut().iter_mut().enumerate() {
+            message.set_header(key.clone(), HeaderValue::from_uint32(index as u32)?)?;
         }
+        Ok(())
+    }
 
-        Ok(Message {
-            id,
-            length,
-            payload,
-            headers: None,
-        })
+    /// Calculate the checksum of the whole serialized batch, which can be used to detect its truncation or corruption.
+    pub fn batch_checksum(&self) -> u32 {
+        checksum::calculate(&self.as_bytes())
     }
-}
 
-impl BytesSerializable for SendMessages {
-    fn as_bytes(&self) -> Bytes {
-        let messages_size = self
-            .messages
-            .iter()
-            .map(Message::get_size_bytes)
-            .sum::<u32>();
+    /// Serialize the command followed by the batch checksum, which is verified by `from_bytes`.
+    /// The trailing checksum is marked with the highest bit of the partitioning kind, so the format stays compatible.
+    pub fn as_bytes_with_checksum(&self) -> Bytes {
+        let bytes = self.as_bytes();
+        let checksum = checksum::calculate(&bytes);
+        let mut bytes = BytesMut::from(&bytes[..]);
+        let position = self.stream_id.get_size_bytes() + self.topic_id.get_size_bytes();
+        bytes[position as usize] |= BATCH_CHECKSUM_FLAG;
+        bytes.put_u32_le(checksum);
+        bytes.freeze()
+    }
 
-        let key_bytes = self.partitioning.as_bytes();
-        let stream_id_bytes = self.stream_id.as_bytes();
-        let topic_id_bytes = self.topic_id.as_bytes();
-        let mut bytes = BytesMut::with_capacity(
-            stream_id_bytes.len() + topic_id_bytes.len() + key_bytes.len() + messages_size as usize,
-        );
-        bytes.put_slice(&stream_id_bytes);
-        bytes.put_slice(&topic_id_bytes);
-        bytes.put_slice(&key_bytes);
+    /// Calculate the 128-bit fingerprint of the batch content (the stream and topic IDs, the partitioning and the messages),
+    /// the idempotency key itself is not included, so the same batch always yields the same fingerprint.
+    pub fn fingerprint(&self) -> u128 {
+        let mut bytes = BytesMut::with_capacity(self.wire_size() as usize);
+        bytes.put_slice(&self.stream_id.as_bytes());
+        bytes.put_slice(&self.topic_id.as_bytes());
+        bytes.put_slice(&self.partitioning.as_bytes());
         for message in &self.messages {
-            bytes.put_slice(&message.as_bytes());
+            message.write_bytes(&mut bytes);
         }
 
-        bytes.freeze()
+        xxh3_128(&bytes)
     }
 
-    fn from_bytes(bytes: Bytes) -> Result<SendMessages, IggyError> {
-        if bytes.len() < 11 {
-            return Err(IggyError::InvalidCommand);
+    /// Set the idempotency key to the batch fingerprint, so that the retried batch is ignored by the server if it was already appended.
+    /// The key should be set once the batch is complete, as any later change of its content yields a different fingerprint.
+    /// Since the same content always yields the same key, the batch deliberately sent again within the deduplication expiry is ignored as well.
+    pub fn with_auto_idempotency_key(mut self) -> Self {
+        self.idempotency_key = Some(self.fingerprint());
+        self
+    }
+
+    /// Append the messages of the other batch targeting the same stream, topic and partitioning, failing with `IncompatibleBatch` otherwise.
+    /// The batches with the idempotency key can't be merged, as the key no longer identifies the merged content.
+    /// The merged batch is validated, and left unchanged if it's invalid, e.g. exceeds `MAX_PAYLOAD_SIZE` or `MAX_HEADERS_SIZE`.
+    pub fn try_merge(&mut self, other: SendMessages) -> Result<(), IggyError> {
+        self.merge(other).map_err(|(error, _)| error)
+    }
+
+    // Merge the other batch, returning it back (boxed, so the error stays small) along with the error if it can't be merged.
+    fn merge(&mut self, mut other: SendMessages) -> Result<(), (IggyError, Box<SendMessages>)> {
+        if self.stream_id != other.stream_id
+            || self.topic_id != other.topic_id
+            || self.partitioning != other.partitioning
+            || self.idempotency_key.is_some()
+            || other.idempotency_key.is_some()
+        {
+            return Err((IggyError::IncompatibleBatch, Box::new(other)));
         }
 
-        let mut position = 0;
-        let stream_id = Identifier::from_bytes(bytes.clone())?;
-        position += stream_id.get_size_bytes() as usize;
-        let topic_id = Identifier::from_bytes(bytes.slice(position..))?;
-        position += topic_id.get_size_bytes() as usize;
-        let key = Partitioning::from_bytes(bytes.slice(position..))?;
-        position += key.get_size_bytes() as usize;
-        let messages_payloads = bytes.slice(position..);
-        position = 0;
-        let mut messages = Vec::new();
-        while position < messages_payloads.len() {
-            let message = Message::from_bytes(messages_payloads.slice(position..))?;
-            position += message.get_size_bytes() as usize;
-            messages.push(message);
+        let messages_count = self.messages.len();
+        let allow_tombstones = self.allow_tombstones;
+        self.totals = None;
+        self.messages.append(&mut other.messages);
+        self.allow_tombstones |= other.allow_tombstones;
+        if let Err(error) = self.validate() {
+            other.messages = self.messages.split_off(messages_count);
+            self.allow_tombstones = allow_tombstones;
+            return Err((error, Box::new(other)));
         }
 
-        let command = SendMessages {
-            stream_id,
-            topic_id,
-            partitioning: key,
-            messages,
-        };
-        command.validate()?;
-        Ok(command)
+        Ok(())
     }
-}
 
-impl Display for SendMessages {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}|{}|{}|{}",
-            self.stream_id,
-            self.topic_id,
-            self.partitioning,
-            self.messages
-                .iter()
-                .map(std::string::ToString::to_string)
-                .collect::<Vec<String>>()
-                .join("|")
-        )
+    /// Append the trailer, i.e. the control message with the empty payload and the `iggy.batch_end` header (see `Message::batch_trailer`),
+    /// so the consumers know that the logical group of messages is complete. Only the trailer is exempt from the tombstones check,
+    /// the other messages with the empty payload are still rejected unless the batch allows the tombstones.
+    /// The trailer should be appended once the batch is complete, and before `with_auto_idempotency_key`.
+    pub fn with_batch_trailer(mut self) -> Result<Self, IggyError> {
+        self.messages_mut().push(Message::batch_trailer()?);
+        Ok(self)
     }
-}
 
-impl Display for Partitioning {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.kind {
-            PartitioningKind::Balanced => write!(f, "{}|0", self.kind),
-            PartitioningKind::PartitionId => write!(
-                f,
-                "{}|{}",
-                self.kind,
-                u32::from_le_bytes(self.value[..4].try_into().unwrap())
-            ),
-            PartitioningKind::MessagesKey => {
-                write!(f, "{}|{}", self.kind, String::from_utf8_lossy(&self.value))
-            }
+    // Write the partitioning followed by the idempotency key, if any, which is marked with the flag on the partitioning kind,
+    // along with the flag allowing the tombstones.
+    fn write_partitioning(&self, bytes: &mut BytesMut) {
+        let position = bytes.len();
+        bytes.put_slice(&self.partitioning.as_bytes());
+        if self.allow_tombstones {
+            bytes[position] |= TOMBSTONES_FLAG;
+        }
+        if let Some(idempotency_key) = self.idempotency_key {
+            bytes[position] |= IDEMPOTENCY_KEY_FLAG;
+            bytes.put_u128_le(idempotency_key);
+        }
+    }
+
+    /// Set the ID of every message in the batch to 0, so that the new IDs will be generated by the server.
+    pub fn clear_message_ids(&mut self) {
+        for message in &mut self.messages {
+            message.id = 0;
         }
     }
+
+    /// Record the `stream_id`, `topic_id`, `partitioning_kind`, `message_count` and `total_bytes` (the wire size) of the batch on the span.
+    /// The span has to declare these fields up front (e.g. as `tracing::field::Empty`), as `tracing` ignores the undeclared ones.
+    pub fn record_fields(&self, span: &tracing::Span) {
+        span.record("stream_id", tracing::field::display(&self.stream_id));
+        span.record("topic_id", tracing::field::display(&self.topic_id));
+        span.record("partitioning_kind", self.partitioning.kind.name());
+        span.record("message_count", self.messages.len() as u64);
+        span.record("total_bytes", self.wire_size());
+    }
 }
 
-impl Display for PartitioningKind {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            PartitioningKind::Balanced => write!(f, "balanced"),
-            PartitioningKind::PartitionId => write!(f, "partition_id"),
-            PartitioningKind::MessagesKey => write!(f, "messages_key"),
+impl CommandPayload for SendMessages {}
+
+/// The limits of the total size of the headers and the uncompressed payloads of the batch, checked by `SendMessages::validate_with_limits`,
+/// so they can be lowered to match the maximums configured on the server. The defaults are `MAX_PAYLOAD_SIZE` and `MAX_HEADERS_SIZE`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ValidationLimits {
+    /// The maximum total size of the uncompressed payloads in bytes.
+    pub max_payload: u32,
+    /// The maximum total size of the headers in bytes.
+    pub max_headers: u32,
+}
+
+impl Default for ValidationLimits {
+    fn default() -> Self {
+        Self {
+            max_payload: MAX_PAYLOAD_SIZE,
+            max_headers: MAX_HEADERS_SIZE,
         }
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// The accounting of the batch accepted by `SendMessages::check`:
+/// - `total_payload` - the total size of the uncompressed payloads in bytes.
+/// - `total_headers` - the total size of the headers in bytes.
+/// - `message_count` - the number of messages.
+/// - `remaining_payload_budget` - how many more payload bytes the batch can take before it exceeds the limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchStats {
+    pub total_payload: u64,
+    pub total_headers: u64,
+    pub message_count: usize,
+    pub remaining_payload_budget: u32,
+}
 
-    #[test]
-    fn should_be_serialized_as_bytes() {
-        let message_1 = Message::from_str("hello 1").unwrap();
-        let message_2 = Message::new(Some(2), "hello 2".into(), None);
-        let message_3 = Message::new(Some(3), "hello 3".into(), None);
-        let messages = vec![message_1, message_2, message_3];
-        let command = SendMessages {
-            stream_id: Identifier::numeric(1).unwrap(),
-            topic_id: Identifier::numeric(2).unwrap(),
-            partitioning: Partitioning::partition_id(4),
-            messages,
-        };
+/// The batch is validated with the default limits, and its messages can't be already expired according to the system clock.
+impl Validatable<IggyError> for SendMessages {
+    fn validate(&self) -> Result<(), IggyError> {
+        self.validate_with_limits(&ValidationLimits::default())?;
+        self.validate_expiry(&SystemClock)
+    }
+}
 
-        let bytes = command.as_bytes();
+impl SendMessages {
+    /// Check whether the batch would be accepted, returning its accounting, so the caller can decide whether to split or flush it.
+    /// The batch is validated like with `validate`, but the exceeded limit of the total payload or headers size is reported
+    /// with `BatchPayloadTooLarge` or `BatchHeadersTooLarge` carrying the total size of the whole batch and the limit.
+    pub fn check(&self) -> Result<BatchStats, IggyError> {
+        let stats = self.check_with_limits(&ValidationLimits::default())?;
+        self.validate_expiry(&SystemClock)?;
+        Ok(stats)
+    }
 
-        let mut position = 0;
-        let stream_id = Identifier::from_bytes(bytes.clone()).unwrap();
-        position += stream_id.get_size_bytes() as usize;
-        let topic_id = Identifier::from_bytes(bytes.slice(position..)).unwrap();
-        position += topic_id.get_size_bytes() as usize;
-        let key = Partitioning::from_bytes(bytes.slice(position..)).unwrap();
-        position += key.get_size_bytes() as usize;
-        let messages = bytes.slice(position..);
-        let command_messages = command
-            .messages
-            .iter()
-            .fold(BytesMut::new(), |mut bytes_mut, message| {
-                bytes_mut.put(message.as_bytes());
-                bytes_mut
-            })
-            .freeze();
+    /// Check the batch like `check`, but with the provided limits and without the expiry check, see `validate_with_limits`.
+    pub fn check_with_limits(&self, limits: &ValidationLimits) -> Result<BatchStats, IggyError> {
+        let mut total_headers = 0;
+        let mut total_payload = 0;
+        for (index, message) in self.messages.iter().enumerate() {
+            total_headers += u64::from(get_message_headers_size(message));
+            total_payload += u64::from(
+                message
+                    .get_uncompressed_payload_length()
+                    .map_err(|error| IggyError::InvalidMessageAtIndex(index, Box::new(error)))?,
+            );
+        }
 
-        assert!(!bytes.is_empty());
-        assert_eq!(stream_id, command.stream_id);
-        assert_eq!(topic_id, command.topic_id);
-        assert_eq!(key, command.partitioning);
-        assert_eq!(messages, command_messages);
+        match self.validate_with_limits(limits) {
+            Ok(()) => Ok(BatchStats {
+                total_payload,
+                total_headers,
+                message_count: self.messages.len(),
+                remaining_payload_budget: u32::try_from(
+                    u64::from(limits.max_payload).saturating_sub(total_payload),
+                )
+                .unwrap_or(u32::MAX),
+            }),
+            Err(IggyError::InvalidMessageAtIndex(_, error))
+                if matches!(*error, IggyError::TooBigMessagePayload) =>
+            {
+                Err(IggyError::BatchPayloadTooLarge(
+                    total_payload,
+                    limits.max_payload,
+                ))
+            }
+            Err(IggyError::InvalidMessageAtIndex(_, error))
+                if matches!(*error, IggyError::TooBigHeadersPayload) =>
+            {
+                Err(IggyError::BatchHeadersTooLarge(
+                    total_headers,
+                    limits.max_headers,
+                ))
+            }
+            Err(error) => Err(error),
+        }
     }
 
-    #[test]
-    fn should_be_deserialized_from_bytes() {
-        let stream_id = Identifier::numeric(1).unwrap();
-        let topic_id = Identifier::numeric(2).unwrap();
-        let key = Partitioning::partition_id(4);
+    /// Validate the command like `validate`, but with the provided limits of the total size of the headers and the payloads,
+    /// failing with `TooBigHeadersPayload` or `TooBigMessagePayload` attached to the index of the message at which the limit is exceeded.
+    /// The validation doesn't depend on the current time, the expiry of the messages is checked with `validate_expiry`.
+    pub fn validate_with_limits(&self, limits: &ValidationLimits) -> Result<(), IggyError> {
+        if self.messages.is_empty() {
+            return Err(IggyError::InvalidMessagesCount);
+        }
 
-        let message_1 = Message::from_str("hello 1").unwrap();
-        let message_2 = Message::new(Some(2), "hello 2".into(), None);
-        let message_3 = Message::new(Some(3), "hello 3".into(), None);
-        let messages = [
-            message_1.as_bytes(),
-            message_2.as_bytes(),
-            message_3.as_bytes(),
-        ]
-        .concat();
+        let key_value_length = self.partitioning.value.len();
+        if key_value_length > 255
+            || (self.partitioning.kind != PartitioningKind::Balanced && key_value_length == 0)
+            || (self.partitioning.kind == PartitioningKind::HashedKey && key_value_length < 2)
+        {
+            return Err(IggyError::InvalidKeyValueLength);
+        }
 
-        let key_bytes = key.as_bytes();
-        let stream_id_bytes = stream_id.as_bytes();
-        let topic_id_bytes = topic_id.as_bytes();
-        let current_position = stream_id_bytes.len() + topic_id_bytes.len() + key_bytes.len();
-        let mut bytes = BytesMut::with_capacity(current_position);
-        bytes.put_slice(&stream_id_bytes);
-        bytes.put_slice(&topic_id_bytes);
-        bytes.put_slice(&key_bytes);
-        bytes.put_slice(&messages);
-        let bytes = bytes.freeze();
-        let command = SendMessages::from_bytes(bytes.clone());
-        assert!(command.is_ok());
+        let batch_partition_id = match self.partitioning.kind {
+            PartitioningKind::PartitionId => self
+                .partitioning
+                .value
+                .as_slice()
+                .try_into()
+                .ok()
+                .map(u32::from_le_bytes),
+            _ => None,
+        };
 
-        let messages_payloads = bytes.slice(current_position..);
-        let mut position = 0;
-        let mut messages = Vec::new();
-        while position < messages_payloads.len() {
-            let message = Message::from_bytes(messages_payloads.slice(position..)).unwrap();
-            position += message.get_size_bytes() as usize;
-            messages.push(message);
+        // The index of the message at which the limit is exceeded is attached to the error, so the culprit can be found in large batches.
+        let mut headers_size = 0;
+        let mut payload_size = 0;
+        for (index, message) in self.messages.iter().enumerate() {
+            if let (Some(partition_id), Some(batch_partition_id)) =
+                (message.partition_id, batch_partition_id)
+            {
+                if partition_id != batch_partition_id {
+                    return Err(IggyError::InvalidMessageAtIndex(
+                        index,
+                        Box::new(IggyError::ConflictingMessagePartitionId(
+                            partition_id,
+                            batch_partition_id,
+                        )),
+                    ));
+                }
+            }
+            headers_size += get_message_headers_size(message);
+            if headers_size > limits.max_headers {
+                return Err(IggyError::InvalidMessageAtIndex(
+                    index,
+                    Box::new(IggyError::TooBigHeadersPayload),
+                ));
+            }
+            payload_size += message
+                .get_uncompressed_payload_length()
+                .map_err(|error| IggyError::InvalidMessageAtIndex(index, Box::new(error)))?;
+            if payload_size > limits.max_payload {
+                return Err(IggyError::InvalidMessageAtIndex(
+                    index,
+                    Box::new(IggyError::TooBigMessagePayload),
+                ));
+            }
         }
 
-        let command = command.unwrap();
-        assert_eq!(command.stream_id, stream_id);
-        assert_eq!(command.topic_id, topic_id);
-        assert_eq!(command.partitioning, key);
-        for (index, message) in command.messages.iter().enumerate() {
-            let command_message = &command.messages[index];
-            assert_eq!(command_message.id, message.id);
-            assert_eq!(command_message.length, message.length);
-            assert_eq!(command_message.payload, message.payload);
+        if !self.allow_tombstones {
+            if payload_size == 0 {
+                return Err(IggyError::EmptyMessagePayload);
+            }
+
+            if let Some(index) = self
+                .messages
+                .iter()
+                .position(|message| message.is_tombstone() && !message.is_batch_end())
+            {
+                return Err(IggyError::InvalidMessageAtIndex(
+                    index,
+                    Box::new(IggyError::EmptyMessagePayload),
+                ));
+            }
         }
-    }
 
-    #[test]
-    fn key_of_type_balanced_should_have_empty_value() {
-        let key = Partitioning::balanced();
-        assert_eq!(key.kind, PartitioningKind::Balanced);
-        assert_eq!(key.length, 0);
-        assert_eq!(key.value, EMPTY_KEY_VALUE);
-        assert_eq!(
-            PartitioningKind::from_code(1).unwrap(),
-            PartitioningKind::Balanced
-        );
+        Ok(())
+    }
+
+    /// Validate that none of the messages has already expired according to the provided clock (see `Message::is_expired`),
+    /// failing with `MessageAlreadyExpired` attached to the index of the first expired message.
+    pub fn validate_expiry(&self, clock: &dyn Clock) -> Result<(), IggyError> {
+        let now = clock.now().to_micros();
+        for (index, message) in self.messages.iter().enumerate() {
+            if let Some(expire_at) = message.expire_at() {
+                if expire_at.to_micros() < now {
+                    return Err(IggyError::InvalidMessageAtIndex(
+                        index,
+                        Box::new(IggyError::MessageAlreadyExpired(expire_at.to_micros())),
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Coalesce the commands into the fewer ones, by merging the commands targeting the same stream, topic and partitioning
+/// (see `SendMessages::try_merge`) in their original order. The command which can't be merged, e.g. because the merged batch
+/// would be too big or it has the idempotency key, starts the new batch in its group.
+pub fn merge_all(commands: Vec<SendMessages>) -> Vec<SendMessages> {
+    let mut merged: Vec<SendMessages> = Vec::with_capacity(commands.len());
+    for command in commands {
+        let target = merged.iter_mut().rev().find(|target| {
+            target.stream_id == command.stream_id
+                && target.topic_id == command.topic_id
+                && target.partitioning == command.partitioning
+        });
+        match target {
+            Some(target) => {
+                if let Err((_, command)) = target.merge(command) {
+                    merged.push(*command);
+                }
+            }
+            None => merged.push(command),
+        }
+    }
+    merged
+}
+
+/// Read the little-endian `u32` field, failing with `CannotParseField` naming the field if the slice isn't 4 bytes long.
+fn read_u32_le(bytes: &[u8], field: &'static str) -> Result<u32, IggyError> {
+    bytes
+        .try_into()
+        .map(u32::from_le_bytes)
+        .map_err(|error| IggyError::CannotParseField(field, error))
+}
+
+/// Read the little-endian `u128` field, failing with `CannotParseField` naming the field if the slice isn't 16 bytes long.
+fn read_u128_le(bytes: &[u8], field: &'static str) -> Result<u128, IggyError> {
+    bytes
+        .try_into()
+        .map(u128::from_le_bytes)
+        .map_err(|error| IggyError::CannotParseField(field, error))
+}
+
+/// Get the size of the message headers accounted for against `MAX_HEADERS_SIZE`, 0 if the message has no headers.
+fn get_message_headers_size(message: &Message) -> u32 {
+    message.headers.as_ref().map_or(0, Headers::get_size_bytes)
+}
+
+impl PartitioningKind {
+    /// Get the code of the partitioning kind.
+    pub fn as_code(&self) -> u8 {
+        match self {
+            PartitioningKind::Balanced => 1,
+            PartitioningKind::PartitionId => 2,
+            PartitioningKind::MessagesKey => 3,
+            PartitioningKind::ConsistentKey => 4,
+            PartitioningKind::HashedKey => 5,
+        }
+    }
+
+    /// Get the partitioning kind from the provided code.
+    pub fn from_code(code: u8) -> Result<Self, IggyError> {
+        match code {
+            1 => Ok(PartitioningKind::Balanced),
+            2 => Ok(PartitioningKind::PartitionId),
+            3 => Ok(PartitioningKind::MessagesKey),
+            4 => Ok(PartitioningKind::ConsistentKey),
+            5 => Ok(PartitioningKind::HashedKey),
+            _ => Err(IggyError::UnsupportedPartitioningKind(code)),
+        }
+    }
+
+    /// Get the stable lowercase name of the partitioning kind, matching the `Display` output without allocating.
+    pub fn name(&self) -> &'static str {
+        match self {
+            PartitioningKind::Balanced => "balanced",
+            PartitioningKind::PartitionId => "partition_id",
+            PartitioningKind::MessagesKey => "messages_key",
+            PartitioningKind::ConsistentKey => "consistent_key",
+            PartitioningKind::HashedKey => "hashed_key",
+        }
+    }
+}
+
+impl Message {
+    /// Create a new message with the optional ID, payload and headers.
+    /// The size of the headers isn't verified until the message is validated, use `MessageBuilder` to fail early.
+    pub fn new(
+        id: Option<u128>,
+        payload: Bytes,
+        headers: Option<HashMap<HeaderKey, HeaderValue>>,
+    ) -> Self {
+        Message {
+            id: id.unwrap_or(0),
+            #[allow(clippy::cast_possible_truncation)]
+            length: payload.len() as u32,
+            payload,
+            headers: headers.map(Headers::from_map_unchecked),
+            compression: CompressionAlgorithm::None,
+            headers_compression: CompressionAlgorithm::None,
+            partition_id: None,
+            payload_checksum: false,
+        }
+    }
+
+    /// Create a new message with the optional ID and headers, taking the ownership of the existing payload without copying it,
+    /// e.g. the slice of the larger buffer obtained with `Bytes::slice`, which shares the allocation of the buffer.
+    /// The payload isn't copied until the message is serialized, see `as_bytes_parts` for the serialization sharing the payload.
+    pub fn from_shared(
+        id: Option<u128>,
+        payload: Bytes,
+        headers: Option<HashMap<HeaderKey, HeaderValue>>,
+    ) -> Self {
+        Message::new(id, payload, headers)
+    }
+
+    /// Create a new message with the constant payload, which is referenced without copying it.
+    pub fn from_static(payload: &'static [u8]) -> Self {
+        Message::new(None, Bytes::from_static(payload), None)
+    }
+
+    /// Create a new tombstone, i.e. the message with the empty payload and the headers identifying the key of the deleted entry,
+    /// which signals the deletion in the log-compacted topic. It can only be sent in the batch allowing the tombstones,
+    /// see `SendMessages::allow_tombstones`, and is marked with the dedicated flag on the wire.
+    pub fn tombstone(key_headers: HashMap<HeaderKey, HeaderValue>) -> Self {
+        let headers = if key_headers.is_empty() {
+            None
+        } else {
+            Some(key_headers)
+        };
+        Message::new(None, Bytes::new(), headers)
+    }
+
+    /// Check whether the message is a tombstone, i.e. its payload is empty.
+    pub fn is_tombstone(&self) -> bool {
+        self.payload.is_empty()
+    }
+
+    /// Create a new batch trailer, i.e. the control message with the empty payload and the `iggy.batch_end` header marking the end of the batch.
+    /// Unlike the tombstone, it can be sent in the batch not allowing the tombstones, see `SendMessages::with_batch_trailer`.
+    pub fn batch_trailer() -> Result<Self, IggyError> {
+        let headers =
+            HashMap::from([(header_key!(BATCH_END_HEADER), HeaderValue::from_bool(true)?)]);
+        Ok(Message::tombstone(headers))
+    }
+
+    /// Check whether the message is the batch trailer, i.e. it has the empty payload and the `iggy.batch_end` header.
+    pub fn is_batch_end(&self) -> bool {
+        self.is_tombstone()
+            && self
+                .headers
+                .as_ref()
+                .and_then(|headers| headers.get(BATCH_END_HEADER))
+                .map(|value| value.as_bool().unwrap_or(false))
+                .unwrap_or(false)
+    }
+
+    /// Create a new message with the optional ID, payload and headers, failing with `TooBigHeadersPayload`
+    /// or `TooBigMessagePayload` if the headers or the payload alone exceed `MAX_HEADERS_SIZE` or `MAX_PAYLOAD_SIZE`,
+    /// instead of when the batch is validated. Use `new` for the trusted input.
+    pub fn new_checked(
+        id: Option<u128>,
+        payload: Bytes,
+        headers: Option<HashMap<HeaderKey, HeaderValue>>,
+    ) -> Result<Self, IggyError> {
+        if payload.len() > MAX_PAYLOAD_SIZE as usize {
+            return Err(IggyError::TooBigMessagePayload);
+        }
+
+        let message = Message::new(id, payload, headers);
+        if get_message_headers_size(&message) > MAX_HEADERS_SIZE {
+            return Err(IggyError::TooBigHeadersPayload);
+        }
+
+        Ok(message)
+    }
+
+    /// Create a new message with the ID generated on the client side using the provided kind, the payload and headers.
+    pub fn with_id_kind(
+        id_kind: IdKind,
+        payload: Bytes,
+        headers: Option<HashMap<HeaderKey, HeaderValue>>,
+    ) -> Self {
+        Message::new(Some(id_kind.generate()), payload, headers)
+    }
+
+    /// Create a new message with the time-ordered UUIDv7 ID generated on the client side, the payload and headers.
+    pub fn with_uuid_v7(payload: Bytes, headers: Option<HashMap<HeaderKey, HeaderValue>>) -> Self {
+        Message::with_id_kind(IdKind::UuidV7, payload, headers)
+    }
+
+    /// Create a new message with the snowflake ID (timestamp, node ID and sequence) generated on the client side, the payload and headers.
+    pub fn with_snowflake(
+        node_id: u16,
+        payload: Bytes,
+        headers: Option<HashMap<HeaderKey, HeaderValue>>,
+    ) -> Self {
+        Message::with_id_kind(IdKind::Snowflake(node_id), payload, headers)
+    }
+
+    /// Get the timestamp in microseconds embedded in the UUIDv7 or snowflake ID, or `None` for the other IDs.
+    pub fn id_timestamp(&self) -> Option<u64> {
+        message_id::timestamp(self.id)
+    }
+
+    /// Create a new message with the optional ID, headers and payload compressed using the provided algorithm.
+    pub fn new_compressed(
+        id: Option<u128>,
+        payload: Bytes,
+        compression: CompressionAlgorithm,
+        headers: Option<HashMap<HeaderKey, HeaderValue>>,
+    ) -> Result<Self, IggyError> {
+        let payload = Bytes::from(compression.compress(&payload)?);
+        let mut message = Message::new(id, payload, headers);
+        message.compression = compression;
+        Ok(message)
+    }
+
+    /// Decompress the payload, so the message no longer depends on the compression it was sent with,
+    /// failing with `DecompressionLimitExceeded` if the payload would exceed `max_decompressed` bytes.
+    pub fn decompress(&mut self, max_decompressed: usize) -> Result<(), IggyError> {
+        if self.compression == CompressionAlgorithm::None {
+            return Ok(());
+        }
+
+        let payload = self
+            .compression
+            .decompress(&self.payload, max_decompressed)?;
+        self.length = payload.len() as u32;
+        self.payload = Bytes::from(payload);
+        self.compression = CompressionAlgorithm::None;
+        Ok(())
+    }
+
+    /// Compress the serialized headers block using the provided algorithm, which pays off for the many repetitive headers.
+    /// The headers are compressed when the message is serialized, so they remain accessible, and sent uncompressed
+    /// if the compression doesn't make them smaller.
+    pub fn with_compressed_headers(mut self, compression: CompressionAlgorithm) -> Self {
+        self.headers_compression = compression;
+        self
+    }
+
+    /// Append the message to the provided partition, regardless of the partitioning of the batch it's sent in.
+    pub fn with_partition_id(mut self, partition_id: u32) -> Self {
+        self.partition_id = Some(partition_id);
+        self
+    }
+
+    /// Send the CRC32 checksum of the payload along with the message, so its corruption in transit is detected.
+    pub fn with_payload_checksum(mut self) -> Self {
+        self.payload_checksum = true;
+        self
+    }
+
+    /// Get the CRC32 checksum of the payload (as sent on the wire), if the message is sent with it.
+    pub fn checksum(&self) -> Option<u32> {
+        self.payload_checksum
+            .then(|| checksum::calculate(&self.payload))
+    }
+
+    /// Add the header to the message, failing with `DuplicateHeaderKey` if the key already exists,
+    /// or with `TooBigHeadersPayload` if the headers would exceed `MAX_HEADERS_SIZE`.
+    /// Use `set_header` to overwrite the existing value instead.
+    pub fn try_with_header(
+        mut self,
+        key: HeaderKey,
+        value: HeaderValue,
+    ) -> Result<Self, IggyError> {
+        if let Some(headers) = &self.headers {
+            if headers.contains_key(&key) {
+                return Err(IggyError::DuplicateHeaderKey(key.as_str().to_string()));
+            }
+        }
+
+        self.set_header(key, value)?;
+        Ok(self)
+    }
+
+    /// Add all the headers to the message, failing with `DuplicateHeaderKey` if any key is repeated or already exists.
+    pub fn try_with_headers(
+        self,
+        headers: impl IntoIterator<Item = (HeaderKey, HeaderValue)>,
+    ) -> Result<Self, IggyError> {
+        headers.into_iter().try_fold(self, |message, (key, value)| {
+            message.try_with_header(key, value)
+        })
+    }
+
+    /// Set the header of the message, overwriting and returning the previous value if the key already exists.
+    /// Fails with `TooBigHeadersPayload` if the headers would exceed `MAX_HEADERS_SIZE`, in which case they remain unchanged.
+    pub fn set_header(
+        &mut self,
+        key: HeaderKey,
+        value: HeaderValue,
+    ) -> Result<Option<HeaderValue>, IggyError> {
+        match &mut self.headers {
+            Some(headers) => headers.insert(key, value),
+            None => {
+                let mut headers = Headers::new();
+                headers.insert(key, value)?;
+                self.headers = Some(headers);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Set the content type of the payload in the reserved `CONTENT_TYPE_HEADER`, overwriting the previous one.
+    /// Fails with `TooBigHeadersPayload` if the headers would exceed `MAX_HEADERS_SIZE`, in which case they remain unchanged.
+    pub fn set_content_type(&mut self, content_type: &str) -> Result<(), IggyError> {
+        self.set_reserved_header(
+            header_key!(CONTENT_TYPE_HEADER),
+            HeaderValue::from_str(content_type)?,
+        )
+    }
+
+    /// Get the content type of the payload from the reserved `CONTENT_TYPE_HEADER`, if it's set as a string.
+    pub fn content_type(&self) -> Option<&str> {
+        self.get_reserved_header(CONTENT_TYPE_HEADER)
+            .and_then(|value| value.as_str().ok())
+    }
+
+    /// Set the ID of the payload schema in the reserved `SCHEMA_ID_HEADER`, overwriting the previous one.
+    /// Fails with `TooBigHeadersPayload` if the headers would exceed `MAX_HEADERS_SIZE`, in which case they remain unchanged.
+    pub fn set_schema_id(&mut self, schema_id: u32) -> Result<(), IggyError> {
+        self.set_reserved_header(
+            header_key!(SCHEMA_ID_HEADER),
+            HeaderValue::from_uint32(schema_id)?,
+        )
+    }
+
+    /// Get the ID of the payload schema from the reserved `SCHEMA_ID_HEADER`, if it's set as `u32`.
+    pub fn schema_id(&self) -> Option<u32> {
+        self.get_reserved_header(SCHEMA_ID_HEADER)
+            .and_then(|value| value.as_uint32().ok())
+    }
+
+    /// Set the timestamp after which the message should be dropped in the reserved `EXPIRE_AT_HEADER`, overwriting the previous one.
+    /// Fails with `TooBigHeadersPayload` if the headers would exceed `MAX_HEADERS_SIZE`, in which case they remain unchanged.
+    pub fn set_expire_at(&mut self, expire_at: IggyTimestamp) -> Result<(), IggyError> {
+        self.set_reserved_header(
+            header_key!(EXPIRE_AT_HEADER),
+            HeaderValue::from_uint64(expire_at.to_micros())?,
+        )
+    }
+
+    /// Get the timestamp after which the message should be dropped from the reserved `EXPIRE_AT_HEADER`, if it's set as `u64`.
+    pub fn expire_at(&self) -> Option<IggyTimestamp> {
+        self.get_reserved_header(EXPIRE_AT_HEADER)
+            .and_then(|value| value.as_uint64().ok())
+            .map(IggyTimestamp::from)
+    }
+
+    /// Returns true if the message has the expiry timestamp set in the reserved `EXPIRE_AT_HEADER`,
+    /// which is earlier than the current time of the clock.
+    pub fn is_expired(&self, clock: &dyn Clock) -> bool {
+        match self.expire_at() {
+            Some(expire_at) => expire_at.to_micros() < clock.now().to_micros(),
+            None => false,
+        }
+    }
+
+    /// Replace the payload (and its length) with the one returned by the transform, or keep it untouched if it returns `None`.
+    /// The transform receives the payload as it's stored, so it's compressed if the message uses the payload compression.
+    pub fn map_payload_cow<F: FnOnce(&Bytes) -> Option<Bytes>>(&mut self, f: F) {
+        if let Some(payload) = f(&self.payload) {
+            #[allow(clippy::cast_possible_truncation)]
+            let length = payload.len() as u32;
+            self.length = length;
+            self.payload = payload;
+        }
+    }
+
+    fn get_reserved_header(&self, key: &str) -> Option<&HeaderValue> {
+        self.headers.as_ref().and_then(|headers| headers.get(key))
+    }
+
+    fn set_reserved_header(&mut self, key: HeaderKey, value: HeaderValue) -> Result<(), IggyError> {
+        self.set_header(key, value)?;
+        Ok(())
+    }
+
+    /// Write the serialized message to the buffer, so the batch can be serialized without the intermediate allocations.
+    fn write_bytes(&self, bytes: &mut BytesMut) {
+        self.write_metadata(bytes);
+        bytes.put_slice(&self.payload);
+    }
+
+    /// Serialize the message into the metadata preceding the payload and the payload itself, which shares the allocation
+    /// of the message payload, so it can be written (e.g. with the vectored write) without copying it like `as_bytes` does.
+    /// The concatenated parts are equal to `as_bytes`.
+    pub fn as_bytes_parts(&self) -> (Bytes, Bytes) {
+        let mut metadata =
+            BytesMut::with_capacity(self.get_size_bytes() as usize - self.payload.len());
+        self.write_metadata(&mut metadata);
+        (metadata.freeze(), self.payload.clone())
+    }
+
+    // Write everything preceding the payload, i.e. the ID, flags, optional fields, headers and payload length.
+    fn write_metadata(&self, bytes: &mut BytesMut) {
+        bytes.put_u128_le(self.id);
+        let (headers_bytes, headers_compressed) = self.headers_as_bytes();
+        let mut headers_length = headers_bytes.len() as u32;
+        if self.compression != CompressionAlgorithm::None {
+            headers_length |= COMPRESSION_FLAG;
+        }
+        if headers_compressed {
+            headers_length |= HEADERS_COMPRESSION_FLAG;
+        }
+        if self.partition_id.is_some() {
+            headers_length |= PARTITION_ID_FLAG;
+        }
+        if self.payload_checksum {
+            headers_length |= PAYLOAD_CHECKSUM_FLAG;
+        }
+        if self.is_tombstone() {
+            headers_length |= TOMBSTONE_FLAG;
+        }
+        bytes.put_u32_le(headers_length);
+        if self.compression != CompressionAlgorithm::None {
+            bytes.put_u8(self.compression.as_code());
+        }
+        if headers_compressed {
+            bytes.put_u8(self.headers_compression.as_code());
+        }
+        if let Some(partition_id) = self.partition_id {
+            bytes.put_u32_le(partition_id);
+        }
+        if let Some(checksum) = self.checksum() {
+            bytes.put_u32_le(checksum);
+        }
+        bytes.put_slice(&headers_bytes);
+        bytes.put_u32_le(self.length);
+    }
+
+    /// Serialize the headers, compressed if it makes them smaller, along with the flag whether they were compressed.
+    /// The headers are compressed on every call, as they can be changed at any time.
+    fn headers_as_bytes(&self) -> (Bytes, bool) {
+        match &self.headers {
+            Some(headers) => compress_headers(headers, &self.headers_compression),
+            None => (Bytes::new(), false),
+        }
+    }
+
+    /// Get the length of the payload after decompression, which is limited by `MAX_PAYLOAD_SIZE`.
+    /// The length declared by the compressed payload itself is used if available, so the payload isn't decompressed
+    /// just to be measured. It's verified once the payload is decompressed, either when deserialized or by the server.
+    pub fn get_uncompressed_payload_length(&self) -> Result<u32, IggyError> {
+        if self.compression == CompressionAlgorithm::None {
+            return Ok(self.payload.len() as u32);
+        }
+
+        if let Some(length) = self.compression.decompressed_length(&self.payload) {
+            return match u32::try_from(length) {
+                Ok(length) if length <= MAX_PAYLOAD_SIZE => Ok(length),
+                _ => Err(IggyError::TooBigMessagePayload),
+            };
+        }
+
+        match self
+            .compression
+            .decompress(&self.payload, MAX_PAYLOAD_SIZE as usize)
+        {
+            Ok(payload) => Ok(payload.len() as u32),
+            Err(IggyError::DecompressionLimitExceeded) => Err(IggyError::TooBigMessagePayload),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Validate that the uncompressed payload along with the headers (accounted for the same way as against `MAX_HEADERS_SIZE`)
+    /// fit the combined per-message limit, failing with `MessageTooLarge` otherwise.
+    pub fn validate_combined(&self, max_combined: u32) -> Result<(), IggyError> {
+        let size = u64::from(self.get_uncompressed_payload_length()?)
+            + u64::from(get_message_headers_size(self));
+        if size > u64::from(max_combined) {
+            return Err(IggyError::MessageTooLarge(size, max_combined));
+        }
+
+        Ok(())
+    }
+
+    /// Create a new message with the random non-zero ID generated by the provided RNG and the payload.
+    /// The zero ID is reserved for the server-side ID generation, so such a draw is retried.
+    #[cfg(feature = "rand")]
+    pub fn with_random_id(rng: &mut impl rand::RngCore, payload: Bytes) -> Self {
+        let mut bytes = [0u8; 16];
+        let mut id = 0;
+        while id == 0 {
+            rng.fill_bytes(&mut bytes);
+            id = u128::from_le_bytes(bytes);
+        }
+        Message::new(Some(id), payload, None)
+    }
+
+    /// Create a new message with the optional ID and the payload encrypted using the provided cipher.
+    /// The message is marked with the `iggy.encrypted` header, and since the payload is encrypted upfront,
+    /// the size validation applies to the encrypted payload.
+    pub fn encrypted(
+        id: Option<u128>,
+        payload: Bytes,
+        cipher: &dyn PayloadCipher,
+    ) -> Result<Self, IggyError> {
+        let payload = cipher.encrypt(payload)?;
+        let headers =
+            HashMap::from([(header_key!(ENCRYPTED_HEADER), HeaderValue::from_bool(true)?)]);
+        Ok(Message::new(id, payload, Some(headers)))
+    }
+
+    /// Get the iterator over the keys of the message headers, which is empty if the message has no headers.
+    pub fn header_keys(&self) -> impl Iterator<Item = &HeaderKey> {
+        self.headers.iter().flat_map(|headers| headers.keys())
+    }
+
+    /// Get the header value by the key ignoring its case, e.g. `Trace-Id` matches `trace-id`, as the keys of the headers
+    /// received from the wire keep the case used by the producer. The exact match takes precedence over the other keys
+    /// differing only by case, use `normalize_header_keys` to make the lookup unambiguous.
+    pub fn header_ci(&self, key: &str) -> Option<&HeaderValue> {
+        let headers = self.headers.as_ref()?;
+        if let Some(value) = headers.get(key) {
+            return Some(value);
+        }
+
+        let key = key.to_lowercase();
+        headers
+            .iter()
+            .find(|(header_key, _)| header_key.as_str().to_lowercase() == key)
+            .map(|(_, value)| value)
+    }
+
+    /// Lowercase the keys of the headers in place, so they can be looked up by the lowercase key, like the ones created with `HeaderKey::new`.
+    /// Fails with `DuplicateHeaderKey` if the distinct keys differ only by case, in which case the headers remain unchanged.
+    pub fn normalize_header_keys(&mut self) -> Result<(), IggyError> {
+        let Some(headers) = self.headers.as_mut() else {
+            return Ok(());
+        };
+
+        let mut normalized = HashMap::with_capacity(headers.len());
+        for (key, value) in headers.iter() {
+            let key = HeaderKey::new(key.as_str())?;
+            if normalized.contains_key(&key) {
+                return Err(IggyError::DuplicateHeaderKey(key.as_str().to_string()));
+            }
+            normalized.insert(key, value.clone());
+        }
+        *headers = Headers::from_map_unchecked(normalized);
+        Ok(())
+    }
+
+    /// Check if the message payload is marked as encrypted.
+    pub fn is_encrypted(&self) -> bool {
+        self.headers
+            .as_ref()
+            .and_then(|headers| headers.get(ENCRYPTED_HEADER))
+            .map(|value| value.as_bool().unwrap_or(false))
+            .unwrap_or(false)
+    }
+
+    /// Get the decrypted payload using the provided cipher, or the original payload if the message is not encrypted.
+    pub fn decrypt_payload(&self, cipher: &dyn PayloadCipher) -> Result<Bytes, IggyError> {
+        if !self.is_encrypted() {
+            return Ok(self.payload.clone());
+        }
+
+        cipher.decrypt(self.payload.clone())
+    }
+
+    /// Consume the message and return its payload.
+    pub fn take_payload(self) -> Bytes {
+        self.payload
+    }
+
+    /// Consume the message and return its headers.
+    pub fn take_headers(self) -> Option<HashMap<HeaderKey, HeaderValue>> {
+        self.headers.map(Into::into)
+    }
+
+    /// Get the size of the message in bytes.
+    /// It's returned as `u64`, so summing the sizes of the large batch doesn't overflow.
+    pub fn get_size_bytes(&self) -> u64 {
+        // ID + Length + Payload + Headers + Compression + Partition ID
+        16 + 4
+            + self.payload.len() as u64
+            + self.get_headers_wire_size_bytes()
+            + self.get_compression_size_bytes()
+            + self.partition_id.map_or(0, |_| 4)
+            + if self.payload_checksum { 4 } else { 0 }
+    }
+
+    /// Returns true if both messages are serialized to the same bytes, which (as the headers are serialized in the order of their keys)
+    /// is the canonical equality, also covering the wire settings such as the compression, the partition ID and the payload checksum.
+    pub fn serialized_eq(&self, other: &Message) -> bool {
+        self.as_bytes() == other.as_bytes()
+    }
+
+    /// Get the key ordering the messages by their ID ascending, which for the time-ordered IDs (e.g. UUIDv7) is the causal order,
+    /// with the IDs to be generated by the server (equal to 0) ordered after all the others.
+    pub fn order_key(&self) -> (bool, u128) {
+        (self.id == 0, self.id)
+    }
+
+    /// Wrap the message into the command sending it alone to the topic in the stream, using the provided partitioning.
+    pub fn into_send_messages(
+        self,
+        stream_id: Identifier,
+        topic_id: Identifier,
+        partitioning: Partitioning,
+    ) -> SendMessages {
+        SendMessages {
+            stream_id,
+            topic_id,
+            partitioning,
+            messages: vec![self],
+            idempotency_key: None,
+            allow_tombstones: false,
+            totals: None,
+        }
+    }
+
+    /// Get the size of the message in bytes excluding the payload, i.e. the ID, the headers and the length fields.
+    pub fn size_without_payload(&self) -> u64 {
+        self.get_size_bytes() - self.payload.len() as u64
+    }
+
+    /// Get the ID, the payload length and the borrowed headers of the message, without cloning the headers map.
+    pub fn metadata(&self) -> MessageMetadata<'_> {
+        MessageMetadata {
+            id: self.id,
+            length: self.length,
+            headers: self.headers.as_ref(),
+        }
+    }
+
+    fn get_headers_wire_size_bytes(&self) -> u64 {
+        if self.headers_compression == CompressionAlgorithm::None {
+            // The headers length field is present even if there are no headers.
+            return u64::from(self.headers.as_ref().map_or(4, Headers::get_size_bytes));
+        }
+
+        // The headers length field + the compressed headers + the headers compression code.
+        let (headers_bytes, compressed) = self.headers_as_bytes();
+        4 + headers_bytes.len() as u64 + u64::from(compressed)
+    }
+
+    fn get_compression_size_bytes(&self) -> u64 {
+        match self.compression {
+            CompressionAlgorithm::None => 0,
+            _ => 1,
+        }
+    }
+
+    /// Deserialize the message and return it along with the number of bytes it took on the wire,
+    /// which differs from `get_size_bytes` when the payload was decompressed within the remaining budget of the batch.
+    fn from_bytes_with_size(
+        bytes: Bytes,
+        budget: &mut DecompressionBudget,
+    ) -> Result<(Self, usize), IggyError> {
+        if bytes.len() < MIN_MESSAGE_SIZE {
+            return Err(IggyError::InvalidCommand);
+        }
+
+        let id = read_u128_le(&bytes[..16], "id")?;
+        let headers_length = read_u32_le(&bytes[16..20], "headers_length")?;
+        let mut position = 20;
+        let compression = if headers_length & COMPRESSION_FLAG != 0 {
+            if bytes.len() < position + 1 + 4 {
+                return Err(IggyError::InvalidCommand);
+            }
+            position += 1;
+            CompressionAlgorithm::from_code(bytes[position - 1])?
+        } else {
+            CompressionAlgorithm::None
+        };
+        let headers_compression = if headers_length & HEADERS_COMPRESSION_FLAG != 0 {
+            if bytes.len() < position + 1 + 4 {
+                return Err(IggyError::InvalidCommand);
+            }
+            position += 1;
+            CompressionAlgorithm::from_code(bytes[position - 1])?
+        } else {
+            CompressionAlgorithm::None
+        };
+        let partition_id = if headers_length & PARTITION_ID_FLAG != 0 {
+            if bytes.len() < position + 4 + 4 {
+                return Err(IggyError::InvalidCommand);
+            }
+            position += 4;
+            Some(read_u32_le(&bytes[position - 4..position], "partition_id")?)
+        } else {
+            None
+        };
+        let expected_checksum = if headers_length & PAYLOAD_CHECKSUM_FLAG != 0 {
+            if bytes.len() < position + 4 + 4 {
+                return Err(IggyError::InvalidCommand);
+            }
+            position += 4;
+            Some(read_u32_le(
+                &bytes[position - 4..position],
+                "payload_checksum",
+            )?)
+        } else {
+            None
+        };
+        let tombstone = headers_length & TOMBSTONE_FLAG != 0;
+        let headers_length = (headers_length & !HEADERS_LENGTH_FLAGS) as usize;
+        if bytes.len() < position + headers_length + 4 {
+            return Err(IggyError::InvalidCommand);
+        }
+
+        let headers = if headers_length > 0 {
+            // The headers are accounted for along with their length field, the same way as against `MAX_HEADERS_SIZE`.
+            let max_headers_length = budget.headers.saturating_sub(4);
+            let headers_bytes = bytes.slice(position..position + headers_length);
+            let headers_bytes = match headers_compression {
+                CompressionAlgorithm::None if headers_length > max_headers_length => {
+                    return Err(IggyError::TooBigHeadersPayload);
+                }
+                CompressionAlgorithm::None => headers_bytes,
+                _ => Bytes::from(
+                    headers_compression
+                        .decompress(&headers_bytes, max_headers_length)
+                        .map_err(|error| match error {
+                            IggyError::DecompressionLimitExceeded => {
+                                IggyError::TooBigHeadersPayload
+                            }
+                            error => error,
+                        })?,
+                ),
+            };
+            budget.headers = max_headers_length - headers_bytes.len();
+            Some(HashMap::from_bytes(headers_bytes)?)
+        } else {
+            None
+        };
+        position += headers_length;
+
+        let payload_length =
+            read_u32_le(&bytes[position..position + 4], "payload_length")? as usize;
+        if payload_length == 0 && !tombstone {
+            return Err(IggyError::EmptyMessagePayload);
+        }
+        if payload_length > 0 && tombstone {
+            return Err(IggyError::InvalidCommand);
+        }
+        position += 4;
+
+        // The length fields come from the untrusted input, so they are validated against the remaining bytes before slicing.
+        if bytes.len() - position < payload_length {
+            return Err(IggyError::InvalidCommand);
+        }
+        let payload = bytes.slice(position..position + payload_length);
+        position += payload_length;
+        if let Some(expected_checksum) = expected_checksum {
+            let checksum = checksum::calculate(&payload);
+            if checksum != expected_checksum {
+                return Err(IggyError::PayloadChecksumMismatch(
+                    checksum,
+                    expected_checksum,
+                ));
+            }
+        }
+
+        let payload = match compression {
+            CompressionAlgorithm::None => payload,
+            _ => Bytes::from(compression.decompress(&payload, budget.payload).map_err(
+                |error| match error {
+                    IggyError::DecompressionLimitExceeded => IggyError::TooBigMessagePayload,
+                    error => error,
+                },
+            )?),
+        };
+        budget.payload = budget.payload.saturating_sub(payload.len());
+
+        let mut message = Message::new(Some(id), payload, headers);
+        message.partition_id = partition_id;
+        message.payload_checksum = expected_checksum.is_some();
+        Ok((message, position))
+    }
+}
+
+impl Default for Message {
+    fn default() -> Self {
+        let payload = Bytes::from("hello world");
+        Message {
+            id: 0,
+            length: payload.len() as u32,
+            payload,
+            headers: None,
+            compression: CompressionAlgorithm::None,
+            headers_compression: CompressionAlgorithm::None,
+            partition_id: None,
+            payload_checksum: false,
+        }
+    }
+}
+
+impl Display for Message {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let len = self.payload.len();
+
+        if len > 40 {
+            write!(
+                f,
+                "{}|{}...{}",
+                self.id,
+                String::from_utf8_lossy(&self.payload[..20]),
+                String::from_utf8_lossy(&self.payload[len - 20..])
+            )
+        } else {
+            write!(f, "{}|{}", self.id, String::from_utf8_lossy(&self.payload))
+        }
+    }
+}
+
+impl BytesSerializable for Partitioning {
+    fn as_bytes(&self) -> Bytes {
+        let mut bytes = BytesMut::with_capacity(2 + self.length as usize);
+        bytes.put_u8(self.kind.as_code());
+        bytes.put_u8(self.length);
+        bytes.put_slice(&self.value);
+        bytes.freeze()
+    }
+
+    fn from_bytes(bytes: Bytes) -> Result<Self, IggyError>
+    where
+        Self: Sized,
+    {
+        if bytes.len() < 3 {
+            return Err(IggyError::InvalidCommand);
+        }
+
+        Ok(PartitioningView::new(&bytes)?.to_partitioning())
+    }
+}
+
+impl BytesSerializable for Message {
+    fn as_bytes(&self) -> Bytes {
+        let mut bytes = BytesMut::with_capacity(self.get_size_bytes() as usize);
+        self.write_bytes(&mut bytes);
+        bytes.freeze()
+    }
+
+    fn from_bytes(bytes: Bytes) -> Result<Self, IggyError> {
+        let (message, _) =
+            Message::from_bytes_with_size(bytes, &mut DecompressionBudget::default())?;
+        Ok(message)
+    }
+
+    fn from_bytes_checked(bytes: Bytes) -> Result<Self, IggyError> {
+        let length = bytes.len();
+        let (message, size) =
+            Message::from_bytes_with_size(bytes, &mut DecompressionBudget::default())?;
+        if size != length {
+            return Err(IggyError::InvalidCommand);
+        }
+
+        Ok(message)
+    }
+}
+
+impl MessageBuilder {
+    /// Create a new message builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the message ID, if not set, it will be generated by the server.
+    pub fn id(mut self, id: u128) -> Self {
+        self.id = Some(id);
+        self
+    }
+
+    /// Set the message payload.
+    pub fn payload(mut self, payload: Bytes) -> Self {
+        self.payload = payload;
+        self
+    }
+
+    /// Add the header to the message.
+    pub fn header(mut self, key: HeaderKey, value: HeaderValue) -> Self {
+        self.headers.push((key, value));
+        self
+    }
+
+    /// Add the string header to the message, the invalid key or value is reported by `build()`.
+    pub fn header_str(mut self, key: &str, value: &str) -> Self {
+        match (HeaderKey::new(key), HeaderValue::from_str(value)) {
+            (Ok(key), Ok(value)) => self.headers.push((key, value)),
+            (Err(error), _) | (_, Err(error)) => {
+                if self.error.is_none() {
+                    self.error = Some(error);
+                }
+            }
+        }
+        self
+    }
+
+    /// Build the message, validating that the payload is not empty,
+    /// the header keys are unique and the headers fit in `MAX_HEADERS_SIZE`.
+    pub fn build(self) -> Result<Message, IggyError> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+
+        if self.payload.is_empty() {
+            return Err(IggyError::EmptyMessagePayload);
+        }
+
+        let headers = if self.headers.is_empty() {
+            None
+        } else {
+            let mut headers = HashMap::with_capacity(self.headers.len());
+            for (key, value) in self.headers {
+                if headers.contains_key(&key) {
+                    return Err(IggyError::DuplicateHeaderKey(key.as_str().to_string()));
+                }
+                headers.insert(key, value);
+            }
+            Some(Headers::try_from(headers)?.into())
+        };
+
+        Ok(Message::new(self.id, self.payload, headers))
+    }
+
+    /// Build the collection of messages from the provided builders, failing on the first invalid one.
+    pub fn build_many(
+        builders: impl IntoIterator<Item = MessageBuilder>,
+    ) -> Result<Vec<Message>, IggyError> {
+        builders.into_iter().map(MessageBuilder::build).collect()
+    }
+}
+
+impl<'a> MessageView<'a> {
+    /// Create the view of the message from the beginning of the provided bytes.
+    pub fn new(bytes: &'a [u8]) -> Result<Self, IggyError> {
+        if bytes.len() < MIN_MESSAGE_SIZE {
+            return Err(IggyError::InvalidCommand);
+        }
+
+        let headers_length = read_u32_le(&bytes[16..20], "headers_length")?;
+        let tombstone = headers_length & TOMBSTONE_FLAG != 0;
+        let headers_position = 20
+            + usize::from(headers_length & COMPRESSION_FLAG != 0)
+            + usize::from(headers_length & HEADERS_COMPRESSION_FLAG != 0)
+            + 4 * usize::from(headers_length & PARTITION_ID_FLAG != 0)
+            + 4 * usize::from(headers_length & PAYLOAD_CHECKSUM_FLAG != 0);
+        let headers_length = (headers_length & !HEADERS_LENGTH_FLAGS) as usize;
+        let payload_position = headers_position + headers_length + 4;
+        if bytes.len() < payload_position {
+            return Err(IggyError::InvalidCommand);
+        }
+
+        let payload_length = read_u32_le(
+            &bytes[payload_position - 4..payload_position],
+            "payload_length",
+        )? as usize;
+        if payload_length == 0 && !tombstone {
+            return Err(IggyError::EmptyMessagePayload);
+        }
+        if payload_length > 0 && tombstone {
+            return Err(IggyError::InvalidCommand);
+        }
+
+        if bytes.len() < payload_position + payload_length {
+            return Err(IggyError::InvalidMessagePayloadLength);
+        }
+
+        Ok(MessageView {
+            bytes: &bytes[..payload_position + payload_length],
+            headers_position,
+            headers_length,
+            payload_length,
+        })
+    }
+
+    /// Get the message ID.
+    pub fn id(&self) -> u128 {
+        u128::from_le_bytes(self.bytes[..16].try_into().unwrap())
+    }
+
+    /// Get the compression algorithm used for the payload.
+    pub fn compression(&self) -> Result<CompressionAlgorithm, IggyError> {
+        if self.headers_length_flags() & COMPRESSION_FLAG == 0 {
+            return Ok(CompressionAlgorithm::None);
+        }
+
+        CompressionAlgorithm::from_code(self.bytes[20])
+    }
+
+    /// Get the compression algorithm used for the serialized headers block.
+    pub fn headers_compression(&self) -> Result<CompressionAlgorithm, IggyError> {
+        if self.headers_length_flags() & HEADERS_COMPRESSION_FLAG == 0 {
+            return Ok(CompressionAlgorithm::None);
+        }
+
+        let position = 20 + usize::from(self.headers_length_flags() & COMPRESSION_FLAG != 0);
+        CompressionAlgorithm::from_code(self.bytes[position])
+    }
+
+    /// Get the partition ID overriding the partitioning of the batch, if set.
+    pub fn partition_id(&self) -> Option<u32> {
+        if self.headers_length_flags() & PARTITION_ID_FLAG == 0 {
+            return None;
+        }
+
+        let position = self.headers_position
+            - 4
+            - 4 * usize::from(self.headers_length_flags() & PAYLOAD_CHECKSUM_FLAG != 0);
+        Some(u32::from_le_bytes(
+            self.bytes[position..position + 4].try_into().unwrap(),
+        ))
+    }
+
+    /// Get the CRC32 checksum of the payload sent along with the message, if any, which isn't verified by the view.
+    pub fn checksum(&self) -> Option<u32> {
+        if self.headers_length_flags() & PAYLOAD_CHECKSUM_FLAG == 0 {
+            return None;
+        }
+
+        let position = self.headers_position - 4;
+        Some(u32::from_le_bytes(
+            self.bytes[position..position + 4].try_into().unwrap(),
+        ))
+    }
+
+    fn headers_length_flags(&self) -> u32 {
+        u32::from_le_bytes(self.bytes[16..20].try_into().unwrap()) & HEADERS_LENGTH_FLAGS
+    }
+
+    /// Get the serialized headers as sent on the wire, which might be compressed, empty if the message has no headers.
+    pub fn headers_bytes(&self) -> &'a [u8] {
+        &self.bytes[self.headers_position..self.headers_position + self.headers_length]
+    }
+
+    /// Get the message payload as sent on the wire, which might be compressed.
+    pub fn payload(&self) -> &'a [u8] {
+        &self.bytes[self.bytes.len() - self.payload_length..]
+    }
+
+    /// Get the size of the viewed message in bytes.
+    pub fn get_size_bytes(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Parse the viewed message into the owned `Message`.
+    pub fn to_owned(&self) -> Result<Message, IggyError> {
+        Message::from_bytes(Bytes::copy_from_slice(self.bytes))
+    }
+}
+
+impl<'a> MessagesReader<'a> {
+    /// Create the reader of the messages concatenated in the provided bytes, starting from the beginning.
+    pub fn new(bytes: &'a Bytes) -> Self {
+        MessagesReader {
+            bytes,
+            position: 0,
+            failed: false,
+            budget: DecompressionBudget::default(),
+        }
+    }
+
+    /// Get the number of bytes consumed by the messages read so far.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+impl Iterator for MessagesReader<'_> {
+    type Item = Result<Message, IggyError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.position >= self.bytes.len() {
+            return None;
+        }
+
+        match Message::from_bytes_with_size(self.bytes.slice(self.position..), &mut self.budget) {
+            Ok((message, size)) => {
+                self.position += size;
+                Some(Ok(message))
+            }
+            Err(error) => {
+                self.failed = true;
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+impl Partitioning {
+    /// Create the borrowed view of the partitioning from the provided bytes, without copying the value.
+    pub fn from_bytes_ref(bytes: &Bytes) -> Result<PartitioningView<'_>, IggyError> {
+        PartitioningView::new(bytes)
+    }
+}
+
+impl<'a> PartitioningView<'a> {
+    /// Create the view of the partitioning from the beginning of the provided bytes.
+    pub fn new(bytes: &'a [u8]) -> Result<Self, IggyError> {
+        if bytes.len() < 3 {
+            return Err(IggyError::InvalidCommand);
+        }
+
+        let kind = PartitioningKind::from_code(bytes[0])?;
+        let length = bytes[1] as usize;
+        if bytes.len() < 2 + length {
+            return Err(IggyError::InvalidCommand);
+        }
+
+        if kind == PartitioningKind::HashedKey {
+            if length < 2 {
+                return Err(IggyError::InvalidCommand);
+            }
+            HashAlgo::from_code(bytes[2])?;
+        }
+
+        Ok(PartitioningView {
+            kind,
+            value: &bytes[2..2 + length],
+        })
+    }
+
+    /// Get the kind of partitioning.
+    pub fn kind(&self) -> PartitioningKind {
+        self.kind
+    }
+
+    /// Get the length of the value payload.
+    pub fn length(&self) -> u8 {
+        self.value.len() as u8
+    }
+
+    /// Get the binary value payload.
+    pub fn value(&self) -> &'a [u8] {
+        self.value
+    }
+
+    /// Get the size of the viewed partitioning in bytes.
+    pub fn get_size_bytes(&self) -> usize {
+        2 + self.value.len()
+    }
+
+    /// Copy the viewed partitioning into the owned `Partitioning`.
+    pub fn to_partitioning(&self) -> Partitioning {
+        Partitioning {
+            kind: self.kind,
+            length: self.length(),
+            value: self.value.to_vec(),
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for PartitioningView<'a> {
+    type Error = IggyError;
+
+    fn try_from(bytes: &'a [u8]) -> Result<Self, Self::Error> {
+        PartitioningView::new(bytes)
+    }
+}
+
+impl Message {
+    /// Parse the message from the `[id|][key=value;key2=value2|]payload` syntax, e.g. `42|trace=abc;env=prod|my payload`.
+    /// The leading segment is the ID only if it's a number, and the next one is the headers only if each of its `;` entries
+    /// is a single `key=value` pair with the unique key, otherwise the rest of the input (including any `|` and `=`) is the payload.
+    /// The header values are parsed as strings. The payload that would be read as the ID or the headers is escaped
+    /// with the empty segment in place of the headers, e.g. both `|42|a=b|c` and `7||a=b|c` have the payload `a=b|c`.
+    pub fn parse(input: &str) -> Result<Self, IggyError> {
+        let mut id = default_message_id();
+        let mut rest = input;
+        if let Some((segment, remaining)) = rest.split_once('|') {
+            if let Some(parsed_id) = Self::parse_id(segment) {
+                id = parsed_id;
+                rest = remaining;
+            }
+        }
+
+        let mut headers = None;
+        if let Some((segment, remaining)) = rest.split_once('|') {
+            if segment.is_empty() {
+                rest = remaining;
+            } else if let Some(parsed_headers) = Self::parse_headers(segment) {
+                headers = Some(parsed_headers);
+                rest = remaining;
+            }
+        }
+
+        let mut message = Message::from_str(rest)?;
+        message.id = id;
+        message.headers = headers.map(Headers::from_map_unchecked);
+        Ok(message)
+    }
+
+    fn parse_id(input: &str) -> Option<u128> {
+        if input.is_empty() || !input.bytes().all(|byte| byte.is_ascii_digit()) {
+            return None;
+        }
+
+        input.parse().ok()
+    }
+
+    fn parse_headers(input: &str) -> Option<HashMap<HeaderKey, HeaderValue>> {
+        let mut headers = HashMap::new();
+        for entry in input.split(';') {
+            let (key, value) = entry.split_once('=')?;
+            if value.contains('=') {
+                return None;
+            }
+
+            let key = HeaderKey::new(key.trim()).ok()?;
+            let value = HeaderValue::from_str(value).ok()?;
+            if headers.insert(key, value).is_some() {
+                return None;
+            }
+        }
+        Some(headers)
+    }
+}
+
+impl FromStr for Message {
+    type Err = IggyError;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let id = default_message_id();
+        let payload = Bytes::from(input.as_bytes().to_vec());
+        let length = payload.len() as u32;
+        if length == 0 {
+            return Err(IggyError::EmptyMessagePayload);
+        }
+
+        Ok(Message {
+            id,
+            length,
+            payload,
+            headers: None,
+            compression: CompressionAlgorithm::None,
+            headers_compression: CompressionAlgorithm::None,
+            partition_id: None,
+            payload_checksum: false,
+        })
+    }
+}
+
+impl BytesSerializable for SendMessages {
+    fn as_bytes(&self) -> Bytes {
+        let stream_id_bytes = self.stream_id.as_bytes();
+        let topic_id_bytes = self.topic_id.as_bytes();
+        let mut bytes = BytesMut::with_capacity(self.wire_size() as usize);
+        bytes.put_slice(&stream_id_bytes);
+        bytes.put_slice(&topic_id_bytes);
+        self.write_partitioning(&mut bytes);
+        for message in &self.messages {
+            message.write_bytes(&mut bytes);
+        }
+
+        bytes.freeze()
+    }
+
+    fn from_bytes(bytes: Bytes) -> Result<SendMessages, IggyError> {
+        SendMessages::from_bytes_with_mode(bytes, false)
+    }
+
+    fn from_bytes_checked(bytes: Bytes) -> Result<SendMessages, IggyError> {
+        SendMessages::from_bytes_with_mode(bytes, true)
+    }
+}
+
+impl SendMessages {
+    // Deserialize the command, the lenient mode ignores the trailing bytes too short to hold another message,
+    // while in the strict mode the messages must take all the remaining bytes exactly.
+    fn from_bytes_with_mode(bytes: Bytes, strict: bool) -> Result<SendMessages, IggyError> {
+        if bytes.len() < 11 {
+            return Err(IggyError::InvalidCommand);
+        }
+
+        let mut position = 0;
+        let stream_id = Identifier::from_bytes(bytes.clone())?;
+        position += stream_id.get_size_bytes() as usize;
+        let topic_id = Identifier::from_bytes(bytes.slice(position..))?;
+        position += topic_id.get_size_bytes() as usize;
+        if position >= bytes.len() {
+            return Err(IggyError::InvalidCommand);
+        }
+
+        let flags =
+            bytes[position] & (BATCH_CHECKSUM_FLAG | IDEMPOTENCY_KEY_FLAG | TOMBSTONES_FLAG);
+        let bytes = if flags & BATCH_CHECKSUM_FLAG == 0 {
+            bytes
+        } else {
+            // Partitioning kind + Partitioning length + Checksum
+            if bytes.len() < position + 2 + 4 {
+                return Err(IggyError::InvalidCommand);
+            }
+
+            let checksum_position = bytes.len() - 4;
+            let expected_checksum = read_u32_le(&bytes[checksum_position..], "batch_checksum")?;
+            let kind = [bytes[position] & !BATCH_CHECKSUM_FLAG];
+            let checksum = checksum::calculate_parts(&[
+                &bytes[..position],
+                &kind,
+                &bytes[position + 1..checksum_position],
+            ]);
+            if checksum != expected_checksum {
+                return Err(IggyError::InvalidBatchChecksum(checksum, expected_checksum));
+            }
+
+            bytes.slice(..checksum_position)
+        };
+
+        let key = if flags == 0 {
+            Partitioning::from_bytes(bytes.slice(position..))?
+        } else {
+            let key_length = bytes.get(position + 1).map_or(0, |length| *length as usize);
+            let key_end = bytes.len().min(position + 2 + key_length);
+            let mut key_bytes = BytesMut::from(&bytes[position..key_end]);
+            key_bytes[0] &= !flags;
+            Partitioning::from_bytes(key_bytes.freeze())?
+        };
+        position += key.get_size_bytes() as usize;
+        let idempotency_key = if flags & IDEMPOTENCY_KEY_FLAG == 0 {
+            None
+        } else {
+            let key_end = position + IDEMPOTENCY_KEY_SIZE as usize;
+            if bytes.len() < key_end {
+                return Err(IggyError::InvalidCommand);
+            }
+
+            let idempotency_key = read_u128_le(&bytes[position..key_end], "idempotency_key")?;
+            position = key_end;
+            Some(idempotency_key)
+        };
+        let messages_payloads = bytes.slice(position..);
+        position = 0;
+        // The capacity is only a hint, each message takes at least MIN_MESSAGE_SIZE bytes.
+        let mut messages = Vec::with_capacity(
+            (messages_payloads.len() / MIN_MESSAGE_SIZE).min(MAX_PREALLOCATED_MESSAGES),
+        );
+        let mut budget = DecompressionBudget::default();
+        while messages_payloads.len() - position >= MIN_MESSAGE_SIZE {
+            let (message, size) =
+                Message::from_bytes_with_size(messages_payloads.slice(position..), &mut budget)?;
+            position += size;
+            messages.push(message);
+        }
+
+        if strict && position != messages_payloads.len() {
+            return Err(IggyError::InvalidCommand);
+        }
+
+        let allow_tombstones = flags & TOMBSTONES_FLAG != 0;
+        let command = SendMessages {
+            stream_id,
+            topic_id,
+            partitioning: key,
+            messages,
+            idempotency_key,
+            allow_tombstones,
+            totals: None,
+        };
+        command.validate()?;
+        Ok(command)
+    }
+}
+
+impl Display for SendMessages {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}|{}|{}|{}",
+            self.stream_id,
+            self.topic_id,
+            self.partitioning,
+            self.messages
+                .iter()
+                .map(std::string::ToString::to_string)
+                .collect::<Vec<String>>()
+                .join("|")
+        )
+    }
+}
+
+impl Display for Partitioning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.kind {
+            PartitioningKind::Balanced => write!(f, "{}|0", self.kind),
+            PartitioningKind::PartitionId => match self.value.as_slice().try_into() {
+                Ok(value) => write!(f, "{}|{}", self.kind, u32::from_le_bytes(value)),
+                Err(_) => write!(f, "{}|{}", self.kind, to_hex(&self.value)),
+            },
+            PartitioningKind::MessagesKey | PartitioningKind::ConsistentKey => {
+                write!(f, "{}|", self.kind)?;
+                write_key(f, &self.value)
+            }
+            PartitioningKind::HashedKey => match self.hash_algo() {
+                Some(algo) => {
+                    write!(f, "{}|{algo}:", self.kind)?;
+                    write_key(f, &self.value[1..])
+                }
+                None => write!(f, "{}|{}", self.kind, to_hex(&self.value)),
+            },
+        }
+    }
+}
+
+// The printable text is displayed as is, so e.g. the 4-byte "user" key isn't displayed as the number,
+// which is the fallback for the 4, 8 and 16-byte keys of the numeric types.
+fn write_key(f: &mut std::fmt::Formatter<'_>, value: &[u8]) -> std::fmt::Result {
+    if let Ok(key) = std::str::from_utf8(value) {
+        if !key.chars().any(char::is_control) {
+            return write!(f, "{key}");
+        }
+    }
+
+    match value.len() {
+        4 => write!(f, "{}", u32::from_le_bytes(value.try_into().unwrap())),
+        8 => write!(f, "{}", u64::from_le_bytes(value.try_into().unwrap())),
+        16 => write!(f, "{}", u128::from_le_bytes(value.try_into().unwrap())),
+        _ => match std::str::from_utf8(value) {
+            Ok(value) => write!(f, "{value}"),
+            Err(_) => write!(f, "{}", to_hex(value)),
+        },
+    }
+}
+
+fn describe_key(value: &[u8]) -> String {
+    match std::str::from_utf8(value) {
+        Ok(key) if !key.chars().any(char::is_control) => format!("'{key}'"),
+        _ => to_hex(value),
+    }
+}
+
+fn to_hex(value: &[u8]) -> String {
+    let hex = value
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>();
+    format!("0x{hex}")
+}
+
+impl Display for PartitioningKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// The wrapper displaying the command, message or partitioning without the potentially sensitive data, so it can be logged,
+/// while the regular `Display` remains available for debugging:
+/// - message - the payload is replaced with its length and the header values are masked, e.g. `1|trace=***|<5 bytes>`.
+/// - partitioning - the `MessagesKey`, `ConsistentKey` and `HashedKey` keys are replaced with their length, e.g. `messages_key|<5 bytes>`.
+/// - command - the stream and topic IDs along with the redacted partitioning and messages.
+#[derive(Debug, Clone, Copy)]
+pub struct Redacted<'a, T>(pub &'a T);
+
+impl SendMessages {
+    /// Get the wrapper displaying the command without the payloads, header values and partitioning key.
+    pub fn redacted(&self) -> Redacted<'_, Self> {
+        Redacted(self)
+    }
+}
+
+impl Message {
+    /// Get the wrapper displaying the message without the payload and header values.
+    pub fn redacted(&self) -> Redacted<'_, Self> {
+        Redacted(self)
+    }
+}
+
+impl Partitioning {
+    /// Get the wrapper displaying the partitioning without the messages key value.
+    pub fn redacted(&self) -> Redacted<'_, Self> {
+        Redacted(self)
+    }
+}
+
+impl Display for Redacted<'_, SendMessages> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}|{}|{}|{}",
+            self.0.stream_id,
+            self.0.topic_id,
+            self.0.partitioning.redacted(),
+            self.0
+                .messages
+                .iter()
+                .map(|message| message.redacted().to_string())
+                .collect::<Vec<String>>()
+                .join("|")
+        )
+    }
+}
+
+impl Display for Redacted<'_, Message> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}|", self.0.id)?;
+        if let Some(headers) = &self.0.headers {
+            let mut keys = headers.keys().map(HeaderKey::as_str).collect::<Vec<_>>();
+            keys.sort_unstable();
+            let headers = keys
+                .iter()
+                .map(|key| format!("{key}=***"))
+                .collect::<Vec<String>>()
+                .join(";");
+            write!(f, "{headers}|")?;
+        }
+        write!(f, "<{} bytes>", self.0.payload.len())
+    }
+}
+
+impl Display for Redacted<'_, Partitioning> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0.kind {
+            PartitioningKind::MessagesKey | PartitioningKind::ConsistentKey => {
+                write!(f, "{}|<{} bytes>", self.0.kind, self.0.value.len())
+            }
+            PartitioningKind::HashedKey => match self.0.hash_algo() {
+                Some(algo) => write!(
+                    f,
+                    "{}|{algo}:<{} bytes>",
+                    self.0.kind,
+                    self.0.value.len() - 1
+                ),
+                None => write!(f, "{}|<{} bytes>", self.0.kind, self.0.value.len()),
+            },
+            PartitioningKind::Balanced | PartitioningKind::PartitionId => {
+                write!(f, "{}", self.0)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::header;
+    use crate::utils::clock::MockClock;
+    use std::time::Duration;
+
+    // The command sending the messages to the stream 1 and topic 2, without the idempotency key and tombstones.
+    fn send_messages(partitioning: Partitioning, messages: Vec<Message>) -> SendMessages {
+        SendMessages::new(
+            StreamId(Identifier::numeric(1).unwrap()),
+            TopicId(Identifier::numeric(2).unwrap()),
+            partitioning,
+            messages,
+        )
+    }
+
+    #[test]
+    fn should_be_serialized_as_bytes() {
+        let message_1 = Message::from_str("hello 1").unwrap();
+        let message_2 = Message::new(Some(2), "hello 2".into(), None);
+        let message_3 = Message::new(Some(3), "hello 3".into(), None);
+        let messages = vec![message_1, message_2, message_3];
+        let command = send_messages(Partitioning::partition_id(4), messages);
+
+        let bytes = command.as_bytes();
+
+        let mut position = 0;
+        let stream_id = Identifier::from_bytes(bytes.clone()).unwrap();
+        position += stream_id.get_size_bytes() as usize;
+        let topic_id = Identifier::from_bytes(bytes.slice(position..)).unwrap();
+        position += topic_id.get_size_bytes() as usize;
+        let key = Partitioning::from_bytes(bytes.slice(position..)).unwrap();
+        position += key.get_size_bytes() as usize;
+        let messages = bytes.slice(position..);
+        let command_messages = command
+            .messages
+            .iter()
+            .fold(BytesMut::new(), |mut bytes_mut, message| {
+                bytes_mut.put(message.as_bytes());
+                bytes_mut
+            })
+            .freeze();
+
+        assert!(!bytes.is_empty());
+        assert_eq!(stream_id, command.stream_id);
+        assert_eq!(topic_id, command.topic_id);
+        assert_eq!(key, command.partitioning);
+        assert_eq!(messages, command_messages);
+    }
+
+    #[test]
+    fn should_be_deserialized_from_bytes() {
+        let stream_id = Identifier::numeric(1).unwrap();
+        let topic_id = Identifier::numeric(2).unwrap();
+        let key = Partitioning::partition_id(4);
+
+        let message_1 = Message::from_str("hello 1").unwrap();
+        let message_2 = Message::new(Some(2), "hello 2".into(), None);
+        let message_3 = Message::new(Some(3), "hello 3".into(), None);
+        let messages = [
+            message_1.as_bytes(),
+            message_2.as_bytes(),
+            message_3.as_bytes(),
+        ]
+        .concat();
+
+        let key_bytes = key.as_bytes();
+        let stream_id_bytes = stream_id.as_bytes();
+        let topic_id_bytes = topic_id.as_bytes();
+        let current_position = stream_id_bytes.len() + topic_id_bytes.len() + key_bytes.len();
+        let mut bytes = BytesMut::with_capacity(current_position);
+        bytes.put_slice(&stream_id_bytes);
+        bytes.put_slice(&topic_id_bytes);
+        bytes.put_slice(&key_bytes);
+        bytes.put_slice(&messages);
+        let bytes = bytes.freeze();
+        let command = SendMessages::from_bytes(bytes.clone());
+        assert!(command.is_ok());
+
+        let messages_payloads = bytes.slice(current_position..);
+        let mut position = 0;
+        let mut messages = Vec::new();
+        while position < messages_payloads.len() {
+            let message = Message::from_bytes(messages_payloads.slice(position..)).unwrap();
+            position += message.get_size_bytes() as usize;
+            messages.push(message);
+        }
+
+        let command = command.unwrap();
+        assert_eq!(command.stream_id, stream_id);
+        assert_eq!(command.topic_id, topic_id);
+        assert_eq!(command.partitioning, key);
+        for (index, message) in command.messages.iter().enumerate() {
+            let command_message = &command.messages[index];
+            assert_eq!(command_message.id, message.id);
+            assert_eq!(command_message.length, message.length);
+            assert_eq!(command_message.payload, message.payload);
+        }
+    }
+
+    #[test]
+    fn key_of_type_balanced_should_have_empty_value() {
+        let key = Partitioning::balanced();
+        assert_eq!(key.kind, PartitioningKind::Balanced);
+        assert_eq!(key.length, 0);
+        assert_eq!(key.value, EMPTY_KEY_VALUE);
+        assert_eq!(
+            PartitioningKind::from_code(1).unwrap(),
+            PartitioningKind::Balanced
+        );
     }
 
     #[test]
@@ -622,35 +2868,3527 @@ mod tests {
         assert_eq!(key.length, 4);
         assert_eq!(key.value, partition_id.to_le_bytes());
         assert_eq!(
-            PartitioningKind::from_code(2).unwrap(),
-            PartitioningKind::PartitionId
+            PartitioningKind::from_code(2).unwrap(),
+            PartitioningKind::PartitionId
+        );
+    }
+
+    #[test]
+    fn key_of_type_messages_key_should_have_value_of_dynamic_length() {
+        let messages_key = "hello world";
+        let key = Partitioning::messages_key_str(messages_key).unwrap();
+        assert_eq!(key.kind, PartitioningKind::MessagesKey);
+        assert_eq!(key.length, messages_key.len() as u8);
+        assert_eq!(key.value, messages_key.as_bytes());
+        assert_eq!(
+            PartitioningKind::from_code(3).unwrap(),
+            PartitioningKind::MessagesKey
         );
     }
 
-    #[test]
-    fn key_of_type_messages_key_should_have_value_of_dynamic_length() {
-        let messages_key = "hello world";
-        let key = Partitioning::messages_key_str(messages_key).unwrap();
-        assert_eq!(key.kind, PartitioningKind::MessagesKey);
-        assert_eq!(key.length, messages_key.len() as u8);
-        assert_eq!(key.value, messages_key.as_bytes());
-        assert_eq!(
-            PartitioningKind::from_code(3).unwrap(),
-            PartitioningKind::MessagesKey
-        );
+    #[test]
+    fn key_of_type_messages_key_that_has_length_0_should_fail() {
+        let messages_key = "";
+        let key = Partitioning::messages_key_str(messages_key);
+        assert!(key.is_err());
+    }
+
+    #[test]
+    fn key_of_type_messages_key_that_has_length_greater_than_255_should_fail() {
+        let messages_key = "a".repeat(256);
+        let key = Partitioning::messages_key_str(&messages_key);
+        assert!(key.is_err());
+    }
+
+    struct Xorshift(u64);
+
+    impl Xorshift {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn next_below(&mut self, max: u64) -> u64 {
+            self.next() % max
+        }
+
+        fn bytes(&mut self, min_length: u64, max_length: u64) -> Vec<u8> {
+            let length = min_length + self.next_below(max_length - min_length + 1);
+            (0..length).map(|_| self.next() as u8).collect()
+        }
+
+        fn text(&mut self, min_length: u64, max_length: u64) -> String {
+            let length = min_length + self.next_below(max_length - min_length + 1);
+            (0..length)
+                .map(|_| (b'a' + self.next_below(26) as u8) as char)
+                .collect()
+        }
+    }
+
+    fn random_command(random: &mut Xorshift) -> SendMessages {
+        SendMessages::new(
+            StreamId(random_identifier(random)),
+            TopicId(random_identifier(random)),
+            match random.next_below(4) {
+                0 => Partitioning::balanced(),
+                1 => Partitioning::partition_id(random.next() as u32),
+                2 => Partitioning::messages_key(&random.bytes(1, 255)).unwrap(),
+                _ => Partitioning::consistent_key(&random.bytes(1, 255)).unwrap(),
+            },
+            (0..random.next_below(10) + 1)
+                .map(|_| random_message(random))
+                .collect(),
+        )
+    }
+
+    fn random_identifier(random: &mut Xorshift) -> Identifier {
+        if random.next_below(2) == 0 {
+            Identifier::numeric(random.next_below(u32::MAX as u64) as u32 + 1).unwrap()
+        } else {
+            Identifier::named(&random.text(1, 255)).unwrap()
+        }
+    }
+
+    fn random_message(random: &mut Xorshift) -> Message {
+        let headers = match random.next_below(3) {
+            0 => None,
+            _ => Some(
+                (0..random.next_below(5) + 1)
+                    .map(|_| {
+                        (
+                            HeaderKey::new(&random.text(1, 32)).unwrap(),
+                            HeaderValue::from_str(&random.text(1, 64)).unwrap(),
+                        )
+                    })
+                    .collect(),
+            ),
+        };
+        let id = match random.next_below(2) {
+            0 => None,
+            _ => Some(u128::from(random.next())),
+        };
+        let message = Message::new(id, Bytes::from(random.bytes(1, 1000)), headers);
+        match random.next_below(2) {
+            0 => message,
+            _ => message.with_compressed_headers(CompressionAlgorithm::Gzip),
+        }
+    }
+
+    mod serialization {
+        use super::*;
+
+        #[test]
+        fn cloned_command_should_be_serialized_identically() {
+            let mut headers = HashMap::new();
+            headers.insert(
+                HeaderKey::new("tenant").unwrap(),
+                HeaderValue::from_str("acme").unwrap(),
+            );
+            let mut message = Message::new(Some(2), "hello 2".into(), Some(headers));
+            message.partition_id = Some(3);
+            message.payload_checksum = true;
+            let command = SendMessages {
+                topic_id: Identifier::named("orders").unwrap(),
+                idempotency_key: Some(42),
+                ..send_messages(
+                    Partitioning::messages_key_str("customer-42").unwrap(),
+                    vec![Message::from_str("hello 1").unwrap(), message],
+                )
+            };
+
+            let cloned = command.clone();
+
+            assert_eq!(cloned, command);
+            assert_eq!(cloned.as_bytes(), command.as_bytes());
+        }
+
+        #[test]
+        fn large_batch_should_be_deserialized_with_preallocated_messages() {
+            let messages = (1..=1000)
+                .map(|id| Message::new(Some(id), format!("message {id}").into(), None))
+                .collect::<Vec<_>>();
+            let command = send_messages(Partitioning::partition_id(4), messages);
+
+            let bytes = command.as_bytes();
+            let deserialized_command = SendMessages::from_bytes(bytes).unwrap();
+
+            assert_eq!(deserialized_command, command);
+            assert!(deserialized_command.messages.capacity() >= command.messages.len());
+        }
+
+        #[test]
+        fn into_messages_should_return_the_original_messages_without_copying() {
+            let command = SendMessages {
+                messages: vec![
+                    Message::new(Some(1), "hello 1".into(), None),
+                    Message::new(Some(2), "hello 2".into(), None),
+                ],
+                ..SendMessages::default()
+            };
+            let payload_pointers = command
+                .messages
+                .iter()
+                .map(|message| message.payload.as_ptr())
+                .collect::<Vec<_>>();
+
+            let messages = command.into_messages();
+
+            assert_eq!(
+                messages,
+                vec![
+                    Message::new(Some(1), "hello 1".into(), None),
+                    Message::new(Some(2), "hello 2".into(), None),
+                ]
+            );
+            assert_eq!(
+                messages
+                    .iter()
+                    .map(|message| message.payload.as_ptr())
+                    .collect::<Vec<_>>(),
+                payload_pointers
+            );
+        }
+
+        #[test]
+        fn messages_size_exceeding_u32_should_not_overflow() {
+            // The payload is shared between the messages, so the batch doesn't allocate its whole logical size.
+            let payload = Bytes::from(vec![0; MAX_PAYLOAD_SIZE as usize]);
+            let count = u32::MAX / MAX_PAYLOAD_SIZE + 1;
+            let command = SendMessages {
+                messages: (0..count)
+                    .map(|_| Message::new(None, payload.clone(), None))
+                    .collect(),
+                ..SendMessages::default()
+            };
+
+            let size = command.get_messages_size_bytes();
+
+            assert!(size > u32::MAX as u64);
+            // ID + Headers length + Payload length + Payload
+            assert_eq!(size, count as u64 * (16 + 4 + 4 + MAX_PAYLOAD_SIZE as u64));
+        }
+
+        #[test]
+        fn shared_payload_should_not_be_copied() {
+            let buffer = Bytes::from(vec![1; 1024]);
+            let payload = buffer.slice(100..200);
+
+            let message = Message::from_shared(Some(1), payload.clone(), None);
+            let (metadata, serialized_payload) = message.as_bytes_parts();
+
+            assert_eq!(message.payload.as_ptr(), payload.as_ptr());
+            assert_eq!(message.payload.as_ptr(), buffer[100..].as_ptr());
+            assert_eq!(serialized_payload.as_ptr(), payload.as_ptr());
+            assert_eq!([metadata, serialized_payload].concat(), message.as_bytes());
+        }
+
+        #[test]
+        fn static_payload_should_not_be_copied() {
+            static PAYLOAD: &[u8] = b"hello";
+
+            let message = Message::from_static(PAYLOAD);
+
+            assert_eq!(message.payload.as_ptr(), PAYLOAD.as_ptr());
+            assert_eq!(message.length, 5);
+            assert_eq!(message.as_bytes_parts().1.as_ptr(), PAYLOAD.as_ptr());
+        }
+
+        #[test]
+        fn deserialized_payload_should_share_serialized_bytes() {
+            let mut headers = HashMap::new();
+            headers.insert(
+                HeaderKey::new("tenant").unwrap(),
+                HeaderValue::from_str("acme").unwrap(),
+            );
+            let message = Message::new(Some(1), "hello".into(), Some(headers))
+                .with_partition_id(3)
+                .with_payload_checksum();
+            let (metadata, _) = message.as_bytes_parts();
+            let bytes = message.as_bytes();
+
+            let deserialized = Message::from_bytes(bytes.clone()).unwrap();
+
+            assert_eq!(deserialized, message);
+            assert_eq!(
+                deserialized.payload.as_ptr(),
+                bytes[metadata.len()..].as_ptr()
+            );
+        }
+
+        #[test]
+        fn metadata_should_borrow_message_headers() {
+            let message = Message::parse("7|trace=abc|hello").unwrap();
+
+            let metadata = message.metadata();
+
+            assert_eq!(metadata.id, 7);
+            assert_eq!(metadata.length, 5);
+            assert!(std::ptr::eq(
+                metadata.headers.unwrap(),
+                message.headers.as_ref().unwrap()
+            ));
+            assert!(Message::from_str("hello")
+                .unwrap()
+                .metadata()
+                .headers
+                .is_none());
+        }
+
+        #[test]
+        fn size_without_payload_should_exclude_only_payload() {
+            let message = Message::parse("7|trace=abc|hello").unwrap();
+
+            assert_eq!(
+                message.size_without_payload(),
+                message.as_bytes().len() as u64 - 5
+            );
+            assert_eq!(
+                Message::from_str("hello").unwrap().size_without_payload(),
+                MIN_MESSAGE_SIZE as u64
+            );
+        }
+
+        #[test]
+        fn checked_deserialization_should_reject_trailing_and_truncated_bytes() {
+            let command = send_messages(
+                Partitioning::partition_id(1),
+                vec![
+                    Message::new(Some(1), "hello 1".into(), None),
+                    Message::new(Some(2), "hello 2".into(), None),
+                ],
+            );
+            let bytes = command.as_bytes();
+            assert_eq!(
+                SendMessages::from_bytes_checked(bytes.clone()).unwrap(),
+                command
+            );
+
+            let mut too_many = BytesMut::from(&bytes[..]);
+            too_many.put_u8(0);
+            let too_many = too_many.freeze();
+            assert_eq!(SendMessages::from_bytes(too_many.clone()).unwrap(), command);
+            assert!(matches!(
+                SendMessages::from_bytes_checked(too_many),
+                Err(IggyError::InvalidCommand)
+            ));
+
+            let too_few = bytes.slice(..bytes.len() - 1);
+            assert!(matches!(
+                SendMessages::from_bytes_checked(too_few),
+                Err(IggyError::InvalidCommand)
+            ));
+        }
+
+        #[test]
+        fn checked_message_deserialization_should_reject_trailing_and_truncated_bytes() {
+            let message = Message::new(Some(1), "hello".into(), None);
+            let bytes = message.as_bytes();
+            assert_eq!(Message::from_bytes_checked(bytes.clone()).unwrap(), message);
+
+            let mut too_many = BytesMut::from(&bytes[..]);
+            too_many.put_u8(0);
+            let too_many = too_many.freeze();
+            assert_eq!(Message::from_bytes(too_many.clone()).unwrap(), message);
+            assert!(matches!(
+                Message::from_bytes_checked(too_many),
+                Err(IggyError::InvalidCommand)
+            ));
+
+            let too_few = bytes.slice(..bytes.len() - 1);
+            assert!(matches!(
+                Message::from_bytes_checked(too_few),
+                Err(IggyError::InvalidCommand)
+            ));
+        }
+
+        #[test]
+        fn map_payload_cow_should_keep_the_payload_when_transform_returns_none() {
+            let payload = Bytes::from_static(b"passthrough");
+            let mut message = Message::new(Some(1), payload.clone(), None);
+
+            message.map_payload_cow(|_| None);
+
+            assert_eq!(message.payload, payload);
+            assert_eq!(message.payload.as_ptr(), payload.as_ptr());
+            assert_eq!(message.length, payload.len() as u32);
+        }
+
+        #[test]
+        fn map_payload_cow_should_replace_the_payload_and_length_when_transform_returns_some() {
+            let mut message = Message::new(Some(1), Bytes::from_static(b"short"), None);
+
+            message.map_payload_cow(|payload| {
+                let mut rewritten = payload.to_vec();
+                rewritten.extend_from_slice(b" and rewritten");
+                Some(Bytes::from(rewritten))
+            });
+
+            assert_eq!(message.payload, Bytes::from_static(b"short and rewritten"));
+            assert_eq!(message.length, 19);
+            assert_eq!(
+                message.get_size_bytes(),
+                Message::new(Some(1), Bytes::from_static(b"short and rewritten"), None)
+                    .get_size_bytes()
+            );
+        }
+
+        #[test]
+        fn messages_with_headers_inserted_in_different_order_should_be_serialized_equal() {
+            let keys = ["trace", "tenant", "region", "priority", "source"];
+            let mut message = Message::new(Some(1), "hello".into(), None);
+            for key in keys {
+                message
+                    .set_header(
+                        HeaderKey::new(key).unwrap(),
+                        HeaderValue::from_str(key).unwrap(),
+                    )
+                    .unwrap();
+            }
+            let mut other = Message::new(Some(1), "hello".into(), None);
+            for key in keys.iter().rev() {
+                other
+                    .set_header(
+                        HeaderKey::new(key).unwrap(),
+                        HeaderValue::from_str(key).unwrap(),
+                    )
+                    .unwrap();
+            }
+
+            assert!(message.serialized_eq(&other));
+            assert!(other.serialized_eq(&message));
+
+            other
+                .set_header(
+                    HeaderKey::new("source").unwrap(),
+                    HeaderValue::from_str("other").unwrap(),
+                )
+                .unwrap();
+            assert!(!message.serialized_eq(&other));
+            assert!(!message.serialized_eq(&Message::new(Some(1), "hello".into(), None)));
+        }
+
+        #[test]
+        fn messages_reader_should_yield_messages_sharing_the_buffer() {
+            let messages = vec![
+                Message::new(Some(1), "first".into(), None),
+                Message::new(Some(2), "second".into(), None).with_partition_id(3),
+                Message::new(Some(3), "third".into(), None).with_payload_checksum(),
+            ];
+            let mut bytes = BytesMut::new();
+            for message in &messages {
+                message.write_bytes(&mut bytes);
+            }
+            let bytes = bytes.freeze();
+            let buffer = bytes.as_ptr_range();
+
+            let mut reader = MessagesReader::new(&bytes);
+            for expected in &messages {
+                let message = reader.next().unwrap().unwrap();
+                assert_eq!(&message, expected);
+                assert!(buffer.contains(&message.payload.as_ptr()));
+            }
+
+            assert!(reader.next().is_none());
+            assert_eq!(reader.position(), bytes.len());
+        }
+
+        #[test]
+        fn messages_reader_should_yield_error_for_truncated_final_message() {
+            let mut bytes = BytesMut::new();
+            Message::new(Some(1), "first".into(), None).write_bytes(&mut bytes);
+            Message::new(Some(2), "second".into(), None).write_bytes(&mut bytes);
+            let truncated_length = bytes.len() - 1;
+            let bytes = bytes.freeze().slice(..truncated_length);
+
+            let mut reader = MessagesReader::new(&bytes);
+
+            assert_eq!(reader.next().unwrap().unwrap().id, 1);
+            assert!(matches!(
+                reader.next(),
+                Some(Err(IggyError::InvalidCommand))
+            ));
+            assert!(reader.next().is_none());
+        }
+
+        #[test]
+        fn message_should_not_be_deserialized_from_any_truncated_bytes() {
+            let message = Message::new(
+                Some(1),
+                Bytes::from("hello world"),
+                Some(HashMap::from([(
+                    HeaderKey::new("key").unwrap(),
+                    HeaderValue::from_str("value").unwrap(),
+                )])),
+            );
+            let bytes = message.as_bytes();
+
+            for length in 0..bytes.len() {
+                assert!(
+                    Message::from_bytes(bytes.slice(..length)).is_err(),
+                    "truncated to: {length}"
+                );
+            }
+            assert!(Message::from_bytes(bytes).is_ok());
+        }
+
+        #[test]
+        fn message_should_not_be_deserialized_with_oversized_length_fields() {
+            let bytes = Message::new(Some(1), Bytes::from("hello world"), None).as_bytes();
+            let lengths = [
+                1,
+                u32::MAX >> 2,
+                !HEADERS_LENGTH_FLAGS,
+                u32::MAX,
+                COMPRESSION_FLAG | 1,
+                HEADERS_COMPRESSION_FLAG | (u32::MAX >> 2),
+            ];
+
+            for headers_length in lengths {
+                let mut malformed_bytes = BytesMut::from(&bytes[..]);
+                malformed_bytes[16..20].copy_from_slice(&headers_length.to_le_bytes());
+                let result = Message::from_bytes(malformed_bytes.freeze());
+                assert!(result.is_err(), "headers length: {headers_length}");
+            }
+
+            for payload_length in [12, u32::MAX >> 1, u32::MAX] {
+                let mut malformed_bytes = BytesMut::from(&bytes[..]);
+                malformed_bytes[20..24].copy_from_slice(&payload_length.to_le_bytes());
+                let result = Message::from_bytes(malformed_bytes.freeze());
+                assert!(
+                    matches!(result, Err(IggyError::InvalidCommand)),
+                    "payload length: {payload_length}"
+                );
+            }
+        }
+
+        #[test]
+        fn message_should_not_panic_for_random_bytes() {
+            let mut random = Xorshift(0x9e37_79b9_7f4a_7c15);
+            for _ in 0..10_000 {
+                let mut bytes = random.bytes(0, 64);
+                if bytes.len() >= MIN_MESSAGE_SIZE && random.next_below(2) == 0 {
+                    // Keep the length fields small, so they often point within the buffer.
+                    let headers_length = random.next_below(32) as u32
+                        | (random.next() as u32 & HEADERS_LENGTH_FLAGS);
+                    bytes[16..20].copy_from_slice(&headers_length.to_le_bytes());
+                }
+
+                let _ = Message::from_bytes(Bytes::from(bytes));
+            }
+        }
+
+        #[test]
+        fn wire_size_should_be_equal_to_serialized_length() {
+            let mut random = Xorshift(0x2545_f491_4f6c_dd1d);
+            for _ in 0..100 {
+                let command = random_command(&mut random);
+
+                assert_eq!(command.wire_size(), command.as_bytes().len() as u64);
+            }
+        }
+
+        #[test]
+        fn into_bytes_should_be_equal_to_as_bytes() {
+            let mut random = Xorshift(0x9e37_79b9_7f4a_7c15);
+            for _ in 0..100 {
+                let command = random_command(&mut random);
+                let bytes = command.as_bytes();
+
+                assert_eq!(command.into_bytes(), bytes);
+            }
+        }
+
+        #[test]
+        fn taken_payload_should_share_the_original_buffer() {
+            let payload = Bytes::from("hello world");
+            let message = Message::new(Some(1), payload.clone(), None);
+
+            let taken_payload = message.take_payload();
+            assert_eq!(taken_payload, payload);
+            assert_eq!(taken_payload.as_ptr(), payload.as_ptr());
+        }
+
+        #[test]
+        fn taken_headers_should_be_equal_to_the_original_ones() {
+            let headers = HashMap::from([(
+                HeaderKey::new("key").unwrap(),
+                HeaderValue::from_str("value").unwrap(),
+            )]);
+            let message = Message::new(Some(1), "hello".into(), Some(headers.clone()));
+
+            assert_eq!(message.take_headers(), Some(headers));
+        }
+
+        #[test]
+        fn message_view_fields_should_match_owned_message() {
+            let headers = HashMap::from([(
+                HeaderKey::new("key").unwrap(),
+                HeaderValue::from_str("value").unwrap(),
+            )]);
+            let message = Message::new(Some(7), "hello world".into(), Some(headers));
+            let bytes = message.as_bytes();
+
+            let view = MessageView::new(&bytes).unwrap();
+            assert_eq!(view.id(), message.id);
+            assert_eq!(view.payload(), &message.payload[..]);
+            assert_eq!(
+                view.headers_bytes(),
+                &message.headers.as_ref().unwrap().as_bytes()[..]
+            );
+            assert_eq!(view.get_size_bytes(), message.get_size_bytes() as usize);
+            assert_eq!(view.to_owned().unwrap(), message);
+        }
+
+        #[test]
+        fn message_view_should_not_be_created_for_truncated_payload() {
+            let message = Message::new(Some(1), "hello world".into(), None);
+            let bytes = message.as_bytes();
+
+            let view = MessageView::new(&bytes[..bytes.len() - 1]);
+            assert!(view.is_err());
+        }
+
+        #[test]
+        fn messages_offsets_should_match_serialized_positions() {
+            let mut command = SendMessages {
+                stream_id: Identifier::named("stream").unwrap(),
+                ..send_messages(
+                    Partitioning::messages_key_str("key").unwrap(),
+                    vec![
+                        Message::new(Some(1), "hello 1".into(), None),
+                        MessageBuilder::new()
+                            .id(2)
+                            .payload("hello 2".into())
+                            .header_str("key", "value")
+                            .build()
+                            .unwrap(),
+                        Message::new(Some(3), "hello 3".into(), None),
+                    ],
+                )
+            };
+
+            for idempotency_key in [None, Some(42)] {
+                command.idempotency_key = idempotency_key;
+                let bytes = command.as_bytes();
+
+                let offsets = command.messages_with_offsets().collect::<Vec<_>>();
+                assert_eq!(offsets.len(), 3);
+                let mut expected_offset = (command.stream_id.get_size_bytes()
+                    + command.topic_id.get_size_bytes()
+                    + command.partitioning.get_size_bytes())
+                    as usize
+                    + idempotency_key.map_or(0, |_| 16);
+                for (offset, message) in offsets {
+                    assert_eq!(offset, expected_offset);
+                    let deserialized_message = Message::from_bytes(bytes.slice(offset..)).unwrap();
+                    assert_eq!(&deserialized_message, message);
+                    expected_offset += message.get_size_bytes() as usize;
+                }
+                assert_eq!(expected_offset, bytes.len());
+            }
+        }
+
+        #[test]
+        fn field_parsing_error_should_name_field_and_carry_source() {
+            use std::error::Error;
+
+            let error = read_u32_le(&[1, 2], "payload_length").unwrap_err();
+
+            assert!(matches!(
+                error,
+                IggyError::CannotParseField("payload_length", _)
+            ));
+            assert_eq!(error.to_string(), "Cannot parse field: payload_length");
+            assert!(error
+                .source()
+                .unwrap()
+                .is::<std::array::TryFromSliceError>());
+            assert!(read_u128_le(&[1; 15], "id").unwrap_err().source().is_some());
+            assert_eq!(read_u32_le(&[1, 0, 0, 0], "payload_length").unwrap(), 1);
+            assert_eq!(
+                read_u128_le(&[1; 16], "id").unwrap(),
+                u128::from_le_bytes([1; 16])
+            );
+
+            let chain = anyhow::Error::from(error)
+                .context("Problem sending the messages")
+                .chain()
+                .map(|error| error.to_string())
+                .collect::<Vec<_>>();
+            assert_eq!(chain.len(), 3);
+            assert_eq!(chain[1], "Cannot parse field: payload_length");
+        }
+    }
+
+    mod json {
+        use super::*;
+
+        #[test]
+        fn command_with_misspelled_field_should_fail_to_deserialize() {
+            let json = r#"{
+                "partitioning": { "kind": "messages_key", "value": "b3JkZXJz" },
+                "messages": [{ "id": 1, "payload": "aGVsbG8=", "headers": null }],
+                "idempotency_kye": 42
+            }"#;
+
+            let command = serde_json::from_str::<SendMessages>(json);
+
+            assert!(command.is_err());
+            assert!(command
+                .unwrap_err()
+                .to_string()
+                .contains("unknown field `idempotency_kye`"));
+        }
+
+        #[test]
+        fn message_and_partitioning_with_misspelled_field_should_fail_to_deserialize() {
+            let message =
+                r#"{ "id": 1, "payload": "aGVsbG8=", "headers": null, "partiton_id": 2 }"#;
+            let partitioning = r#"{ "kind": "partition_id", "value": "AgAAAA==", "vaule": "" }"#;
+
+            assert!(serde_json::from_str::<Message>(message).is_err());
+            assert!(serde_json::from_str::<Partitioning>(partitioning).is_err());
+        }
+
+        #[test]
+        fn command_should_ignore_stream_and_topic_ids_when_deserialized() {
+            let json = r#"{
+                "stream_id": 1,
+                "topic_id": "orders",
+                "partitioning": { "kind": "partition_id", "value": "AgAAAA==" },
+                "messages": [{ "id": 1, "payload": "aGVsbG8=", "headers": null }]
+            }"#;
+
+            let command = serde_json::from_str::<SendMessages>(json).unwrap();
+
+            assert_eq!(command.stream_id, Identifier::default());
+            assert_eq!(command.topic_id, Identifier::default());
+            assert_eq!(command.partitioning.kind, PartitioningKind::PartitionId);
+            assert_eq!(command.partitioning.value, 2u32.to_le_bytes());
+            assert_eq!(command.messages[0].payload.as_ref(), b"hello");
+        }
+    }
+
+    mod construction {
+        use super::*;
+
+        #[test]
+        fn command_should_be_created_from_typed_stream_and_topic_ids() {
+            let stream_id = StreamId::from(Identifier::named("stream").unwrap());
+            let topic_id = TopicId::from(Identifier::numeric(2).unwrap());
+            assert_eq!(stream_id.as_string(), "stream");
+            assert_eq!(topic_id.get_u32_value().unwrap(), 2);
+
+            let command = SendMessages::new(
+                stream_id,
+                topic_id,
+                Partitioning::balanced(),
+                vec![Message::default()],
+            );
+
+            assert_eq!(command.stream_id, Identifier::named("stream").unwrap());
+            assert_eq!(command.topic_id, Identifier::numeric(2).unwrap());
+        }
+
+        #[test]
+        fn command_should_be_created_from_numbers_and_names() {
+            let command = SendMessages::try_new(
+                "orders",
+                2u32,
+                Partitioning::balanced(),
+                vec![Message::default()],
+            )
+            .unwrap();
+
+            assert_eq!(command.stream_id, Identifier::named("orders").unwrap());
+            assert_eq!(command.topic_id, Identifier::numeric(2).unwrap());
+            assert!(SendMessages::try_new(
+                0u32,
+                2u32,
+                Partitioning::balanced(),
+                vec![Message::default()]
+            )
+            .is_err());
+            assert!(matches!(
+                SendMessages::try_new(1u32, "orders", Partitioning::balanced(), vec![]),
+                Err(IggyError::InvalidMessagesCount)
+            ));
+        }
+
+        #[test]
+        fn empty_batch_should_not_have_boundaries() {
+            let command = SendMessages {
+                messages: vec![],
+                ..SendMessages::default()
+            };
+
+            assert!(command.first_message().is_none());
+            assert!(command.last_message().is_none());
+            assert!(command.id_range().is_none());
+        }
+
+        #[test]
+        fn single_message_batch_should_have_the_same_boundaries() {
+            let command = SendMessages {
+                messages: vec![Message::new(Some(5), "hello".into(), None)],
+                ..SendMessages::default()
+            };
+
+            assert_eq!(command.first_message().unwrap().id, 5);
+            assert_eq!(command.last_message().unwrap().id, 5);
+            assert_eq!(command.id_range(), Some((5, 5)));
+        }
+
+        #[test]
+        fn command_should_be_collected_from_messages_with_balanced_partitioning() {
+            let mut command = (1..=2)
+                .map(|index| Message::from_str(&format!("hello {index}")).unwrap())
+                .collect::<SendMessages>();
+            command.extend([Message::from_str("hello 3").unwrap()]);
+            command.push(Message::from_str("hello 4").unwrap()).unwrap();
+
+            assert_eq!(command.partitioning, Partitioning::balanced());
+            let payloads = command
+                .into_iter()
+                .map(|message| message.payload)
+                .collect::<Vec<_>>();
+            assert_eq!(
+                payloads,
+                ["hello 1", "hello 2", "hello 3", "hello 4"].map(Bytes::from)
+            );
+        }
+
+        #[test]
+        fn message_should_be_wrapped_into_single_message_command() {
+            let message = Message::new(Some(1), "hello".into(), None);
+
+            let command = message.into_send_messages(
+                Identifier::numeric(1).unwrap(),
+                Identifier::named("topic").unwrap(),
+                Partitioning::partition_id(2),
+            );
+
+            assert_eq!(command.stream_id, Identifier::numeric(1).unwrap());
+            assert_eq!(command.topic_id, Identifier::named("topic").unwrap());
+            assert_eq!(command.partitioning, Partitioning::partition_id(2));
+            assert_eq!(command.messages.len(), 1);
+            assert_eq!(command.messages[0].id, 1);
+            assert!(command.validate().is_ok());
+        }
+
+        #[test]
+        fn message_builder_should_build_message_with_headers() {
+            let message = MessageBuilder::new()
+                .id(1)
+                .payload("hello".into())
+                .header(
+                    HeaderKey::new("key-1").unwrap(),
+                    HeaderValue::from_uint32(1).unwrap(),
+                )
+                .header_str("key-2", "value")
+                .build()
+                .unwrap();
+
+            assert_eq!(message.id, 1);
+            assert_eq!(message.payload, "hello");
+            let headers = message.headers.unwrap();
+            assert_eq!(headers.len(), 2);
+            assert_eq!(
+                headers
+                    .get(&HeaderKey::new("key-2").unwrap())
+                    .unwrap()
+                    .as_str()
+                    .unwrap(),
+                "value"
+            );
+        }
+
+        #[test]
+        fn message_builder_should_fail_for_duplicated_header_keys() {
+            let error = MessageBuilder::new()
+                .payload("hello".into())
+                .header_str("key", "value-1")
+                .header_str("KEY", "value-2")
+                .build()
+                .unwrap_err();
+
+            assert!(matches!(error, IggyError::DuplicateHeaderKey(key) if key == "key"));
+        }
+
+        #[test]
+        fn message_builder_should_fail_for_too_big_headers() {
+            let value = "a".repeat(255);
+            let builder = (0..500).fold(
+                MessageBuilder::new().payload("hello".into()),
+                |builder, index| builder.header_str(&format!("key-{index}"), &value),
+            );
+
+            let error = builder.build().unwrap_err();
+            assert_eq!(error.as_code(), IggyError::TooBigHeadersPayload.as_code());
+        }
+
+        #[test]
+        fn message_builder_should_fail_for_invalid_header_key() {
+            let error = MessageBuilder::new()
+                .payload("hello".into())
+                .header_str("", "value")
+                .build()
+                .unwrap_err();
+
+            assert_eq!(error.as_code(), IggyError::InvalidHeaderKey.as_code());
+        }
+
+        #[test]
+        fn message_builder_should_build_many_messages() {
+            let messages = MessageBuilder::build_many((1..=3).map(|id| {
+                MessageBuilder::new()
+                    .id(id)
+                    .payload(format!("hello {id}").into())
+            }))
+            .unwrap();
+
+            assert_eq!(messages.len(), 3);
+            assert_eq!(messages[2].id, 3);
+            assert_eq!(messages[2].payload, "hello 3");
+        }
+
+        #[test]
+        fn message_should_be_parsed_with_id_headers_and_payload() {
+            let message = Message::parse("42|trace=abc;env=prod|my payload").unwrap();
+
+            assert_eq!(message.id, 42);
+            assert_eq!(message.payload, Bytes::from("my payload"));
+            let headers = message.headers.unwrap();
+            assert_eq!(headers.len(), 2);
+            assert_eq!(
+                headers
+                    .get(&HeaderKey::new("trace").unwrap())
+                    .unwrap()
+                    .as_str()
+                    .unwrap(),
+                "abc"
+            );
+            assert_eq!(
+                headers
+                    .get(&HeaderKey::new("env").unwrap())
+                    .unwrap()
+                    .as_str()
+                    .unwrap(),
+                "prod"
+            );
+        }
+
+        #[test]
+        fn message_should_be_parsed_with_optional_segments() {
+            let message = Message::parse("7|hello").unwrap();
+            assert_eq!(message.id, 7);
+            assert_eq!(message.payload, Bytes::from("hello"));
+            assert!(message.headers.is_none());
+
+            let message = Message::parse("env=prod|hello|world").unwrap();
+            assert_eq!(message.id, 0);
+            assert_eq!(message.payload, Bytes::from("hello|world"));
+            assert_eq!(message.headers.unwrap().len(), 1);
+
+            let message = Message::parse("hello").unwrap();
+            assert_eq!(message.id, 0);
+            assert_eq!(message.payload, Bytes::from("hello"));
+            assert!(message.headers.is_none());
+
+            let message = Message::parse(&Message::parse("5|hello").unwrap().to_string()).unwrap();
+            assert_eq!(message.id, 5);
+            assert_eq!(message.payload, Bytes::from("hello"));
+        }
+
+        #[test]
+        fn message_should_not_be_parsed_with_empty_payload() {
+            assert!(matches!(
+                Message::parse("42|"),
+                Err(IggyError::EmptyMessagePayload)
+            ));
+            assert!(matches!(
+                Message::parse("42|env=prod|"),
+                Err(IggyError::EmptyMessagePayload)
+            ));
+            assert!(matches!(
+                Message::parse("42||"),
+                Err(IggyError::EmptyMessagePayload)
+            ));
+        }
+
+        #[test]
+        fn malformed_headers_should_be_parsed_as_payload() {
+            for (input, payload) in [
+                ("42|env=prod;trace|payload", "env=prod;trace|payload"),
+                ("42|=prod|payload", "=prod|payload"),
+                ("42|env=prod;env=dev|payload", "env=prod;env=dev|payload"),
+                ("x=1&y=2|z", "x=1&y=2|z"),
+            ] {
+                let message = Message::parse(input).unwrap();
+
+                assert_eq!(message.payload, Bytes::from(payload), "{input}");
+                assert!(message.headers.is_none(), "{input}");
+            }
+        }
+
+        #[test]
+        fn payload_with_separators_should_be_parsed_when_escaped() {
+            let message = Message::parse("|a=b|c").unwrap();
+            assert_eq!(message.id, 0);
+            assert_eq!(message.payload, Bytes::from("a=b|c"));
+            assert!(message.headers.is_none());
+
+            let message = Message::parse("|2024|report").unwrap();
+            assert_eq!(message.id, 0);
+            assert_eq!(message.payload, Bytes::from("2024|report"));
+
+            let message = Message::parse("7||a=b|c").unwrap();
+            assert_eq!(message.id, 7);
+            assert_eq!(message.payload, Bytes::from("a=b|c"));
+            assert!(message.headers.is_none());
+
+            let message = Message::parse("7|env=prod|a=b|c").unwrap();
+            assert_eq!(message.id, 7);
+            assert_eq!(message.payload, Bytes::from("a=b|c"));
+            assert_eq!(message.headers.unwrap().len(), 1);
+        }
+
+        #[test]
+        fn payload_with_separators_should_be_parsed_without_escaping_when_unambiguous() {
+            let message = Message::parse("a=b=c|d").unwrap();
+            assert_eq!(message.id, 0);
+            assert_eq!(message.payload, Bytes::from("a=b=c|d"));
+
+            let message = Message::parse("7|x == y").unwrap();
+            assert_eq!(message.id, 7);
+            assert_eq!(message.payload, Bytes::from("x == y"));
+
+            let message = Message::parse("12a|b").unwrap();
+            assert_eq!(message.id, 0);
+            assert_eq!(message.payload, Bytes::from("12a|b"));
+
+            let message = Message::parse("340282366920938463463374607431768211456|b").unwrap();
+            assert_eq!(message.id, 0);
+            assert_eq!(
+                message.payload,
+                Bytes::from("340282366920938463463374607431768211456|b")
+            );
+        }
+    }
+
+    mod encryption {
+        use super::*;
+
+        #[derive(Debug)]
+        struct XorCipher(u8);
+
+        impl PayloadCipher for XorCipher {
+            fn encrypt(&self, payload: Bytes) -> Result<Bytes, IggyError> {
+                Ok(payload.iter().map(|byte| byte ^ self.0).collect())
+            }
+
+            fn decrypt(&self, payload: Bytes) -> Result<Bytes, IggyError> {
+                self.encrypt(payload)
+            }
+        }
+
+        #[test]
+        fn encrypted_message_payload_should_be_decrypted() {
+            let cipher = XorCipher(42);
+            let payload = Bytes::from("hello world");
+            let message = Message::encrypted(Some(1), payload.clone(), &cipher).unwrap();
+
+            assert!(message.is_encrypted());
+            assert_ne!(message.payload, payload);
+            assert_eq!(message.length, payload.len() as u32);
+            assert_eq!(message.decrypt_payload(&cipher).unwrap(), payload);
+        }
+
+        #[test]
+        fn not_encrypted_message_payload_should_be_returned_as_is() {
+            let cipher = XorCipher(42);
+            let message = Message::new(Some(1), "hello world".into(), None);
+
+            assert!(!message.is_encrypted());
+            assert_eq!(message.decrypt_payload(&cipher).unwrap(), message.payload);
+        }
+    }
+
+    mod message_ids {
+        use super::*;
+
+        #[cfg(feature = "rand")]
+        #[test]
+        fn message_with_random_id_should_be_deterministic_for_seeded_rng() {
+            use rand::rngs::StdRng;
+            use rand::SeedableRng;
+
+            let mut first_rng = StdRng::seed_from_u64(42);
+            let mut second_rng = StdRng::seed_from_u64(42);
+            let first_message = Message::with_random_id(&mut first_rng, "hello".into());
+            let second_message = Message::with_random_id(&mut second_rng, "hello".into());
+
+            assert_ne!(first_message.id, 0);
+            assert_eq!(first_message.id, second_message.id);
+        }
+
+        #[cfg(feature = "rand")]
+        #[test]
+        fn message_with_random_id_should_retry_zero_draw() {
+            struct ZeroFirstRng {
+                draws: u8,
+            }
+
+            impl rand::RngCore for ZeroFirstRng {
+                fn next_u32(&mut self) -> u32 {
+                    let mut bytes = [0; 4];
+                    self.fill_bytes(&mut bytes);
+                    u32::from_le_bytes(bytes)
+                }
+
+                fn next_u64(&mut self) -> u64 {
+                    let mut bytes = [0; 8];
+                    self.fill_bytes(&mut bytes);
+                    u64::from_le_bytes(bytes)
+                }
+
+                fn fill_bytes(&mut self, dest: &mut [u8]) {
+                    dest.fill(self.draws);
+                    self.draws += 1;
+                }
+
+                fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+                    self.fill_bytes(dest);
+                    Ok(())
+                }
+            }
+
+            let mut rng = ZeroFirstRng { draws: 0 };
+            let message = Message::with_random_id(&mut rng, "hello".into());
+            assert_eq!(rng.draws, 2);
+            assert_eq!(message.id, u128::from_le_bytes([1; 16]));
+        }
+
+        #[test]
+        fn messages_should_be_sorted_by_id_with_server_generated_ids_last() {
+            let mut command = send_messages(
+                Partitioning::balanced(),
+                vec![
+                    Message::new(None, "a".into(), None),
+                    Message::new(Some(3), "b".into(), None),
+                    Message::new(Some(u128::MAX), "c".into(), None),
+                    Message::new(None, "d".into(), None),
+                    Message::new(Some(1), "e".into(), None),
+                    Message::new(Some(3), "f".into(), None),
+                ],
+            );
+
+            command.sort_by_id();
+
+            let payloads = command
+                .messages
+                .iter()
+                .map(|message| String::from_utf8_lossy(&message.payload).to_string())
+                .collect::<Vec<_>>();
+            assert_eq!(payloads, vec!["e", "b", "f", "c", "a", "d"]);
+        }
+
+        #[test]
+        fn consistent_ids_validation_should_accept_only_server_or_client_generated_ids() {
+            let batch = |ids: &[Option<u128>]| {
+                send_messages(
+                    Partitioning::balanced(),
+                    ids.iter()
+                        .map(|id| Message::new(*id, "hello".into(), None))
+                        .collect(),
+                )
+            };
+
+            assert!(batch(&[None, None, None]).validate_consistent_ids().is_ok());
+            assert!(batch(&[Some(1), Some(2), Some(3)])
+                .validate_consistent_ids()
+                .is_ok());
+            assert!(matches!(
+                batch(&[Some(1), None, Some(3)]).validate_consistent_ids(),
+                Err(IggyError::MixedMessageIds)
+            ));
+            assert!(matches!(
+                batch(&[None, Some(2)]).validate_consistent_ids(),
+                Err(IggyError::MixedMessageIds)
+            ));
+        }
+
+        #[test]
+        fn strict_validation_should_accept_unique_and_server_generated_ids() {
+            let command = SendMessages {
+                messages: vec![
+                    Message::new(Some(1), "hello 1".into(), None),
+                    Message::new(None, "hello 2".into(), None),
+                    Message::new(Some(2), "hello 3".into(), None),
+                    Message::new(None, "hello 4".into(), None),
+                ],
+                ..SendMessages::default()
+            };
+
+            assert!(command.validate_strict().is_ok());
+        }
+
+        #[test]
+        fn strict_validation_should_reject_duplicate_ids() {
+            let command = SendMessages {
+                messages: vec![
+                    Message::new(Some(1), "hello 1".into(), None),
+                    Message::new(Some(7), "hello 2".into(), None),
+                    Message::new(Some(1), "hello 3".into(), None),
+                ],
+                ..SendMessages::default()
+            };
+
+            assert!(command.validate().is_ok());
+            assert!(matches!(
+                command.validate_strict(),
+                Err(IggyError::DuplicateMessageId(1))
+            ));
+        }
+
+        #[test]
+        fn id_range_should_ignore_ids_generated_by_server() {
+            let command = SendMessages {
+                messages: vec![
+                    Message::new(Some(7), "hello 1".into(), None),
+                    Message::new(None, "hello 2".into(), None),
+                    Message::new(Some(3), "hello 3".into(), None),
+                    Message::new(Some(9), "hello 4".into(), None),
+                    Message::new(None, "hello 5".into(), None),
+                ],
+                ..SendMessages::default()
+            };
+
+            assert_eq!(command.first_message().unwrap().id, 7);
+            assert_eq!(command.last_message().unwrap().id, 0);
+            assert_eq!(command.id_range(), Some((3, 9)));
+        }
+
+        #[test]
+        fn clearing_message_ids_should_not_change_payloads_and_headers() {
+            let headers = HashMap::from([(
+                HeaderKey::new("key").unwrap(),
+                HeaderValue::from_str("value").unwrap(),
+            )]);
+            let mut command = SendMessages {
+                messages: vec![
+                    Message::new(Some(1), "hello 1".into(), Some(headers.clone())),
+                    Message::new(Some(2), "hello 2".into(), None),
+                ],
+                ..SendMessages::default()
+            };
+
+            command.clear_message_ids();
+
+            assert!(command.messages.iter().all(|message| message.id == 0));
+            assert_eq!(command.messages[0].payload, "hello 1");
+            assert_eq!(command.messages[0].headers.as_ref().unwrap(), &headers);
+            assert_eq!(command.messages[1].payload, "hello 2");
+            assert!(command.messages[1].headers.is_none());
+        }
+
+        #[test]
+        fn messages_with_client_side_ids_should_expose_id_timestamp() {
+            let uuid_message = Message::with_uuid_v7(Bytes::from("hello"), None);
+            let snowflake_message = Message::with_snowflake(7, Bytes::from("hello"), None);
+            let server_message = Message::new(None, Bytes::from("hello"), None);
+
+            assert_ne!(uuid_message.id, 0);
+            assert_ne!(snowflake_message.id, 0);
+            assert!(uuid_message.id_timestamp().is_some());
+            assert!(snowflake_message.id_timestamp().is_some());
+            assert_eq!(server_message.id_timestamp(), None);
+        }
+    }
+
+    mod headers {
+        use super::*;
+
+        #[test]
+        fn header_should_be_added_when_key_is_unique() {
+            let message = Message::from_str("hello")
+                .unwrap()
+                .try_with_header(
+                    HeaderKey::new("key-1").unwrap(),
+                    HeaderValue::from_str("value-1").unwrap(),
+                )
+                .unwrap()
+                .try_with_header(
+                    HeaderKey::new("key-2").unwrap(),
+                    HeaderValue::from_str("value-2").unwrap(),
+                )
+                .unwrap();
+
+            let headers = message.headers.unwrap();
+            assert_eq!(headers.len(), 2);
+            assert_eq!(
+                headers[&HeaderKey::new("key-1").unwrap()],
+                HeaderValue::from_str("value-1").unwrap()
+            );
+        }
+
+        #[test]
+        fn duplicate_header_key_should_be_rejected() {
+            let message = Message::from_str("hello").unwrap().try_with_headers([
+                (
+                    HeaderKey::new("key").unwrap(),
+                    HeaderValue::from_str("value-1").unwrap(),
+                ),
+                (
+                    HeaderKey::new("KEY").unwrap(),
+                    HeaderValue::from_str("value-2").unwrap(),
+                ),
+            ]);
+
+            assert!(matches!(
+                message,
+                Err(IggyError::DuplicateHeaderKey(key)) if key == "key"
+            ));
+        }
+
+        #[test]
+        fn set_header_should_overwrite_existing_value() {
+            let mut message = Message::from_str("hello")
+                .unwrap()
+                .try_with_header(
+                    HeaderKey::new("key").unwrap(),
+                    HeaderValue::from_str("value-1").unwrap(),
+                )
+                .unwrap();
+
+            let previous_value = message
+                .set_header(
+                    HeaderKey::new("key").unwrap(),
+                    HeaderValue::from_str("value-2").unwrap(),
+                )
+                .unwrap();
+
+            assert_eq!(
+                previous_value,
+                Some(HeaderValue::from_str("value-1").unwrap())
+            );
+            let headers = message.headers.unwrap();
+            assert_eq!(headers.len(), 1);
+            assert_eq!(
+                headers[&HeaderKey::new("key").unwrap()],
+                HeaderValue::from_str("value-2").unwrap()
+            );
+        }
+
+        #[test]
+        fn content_type_and_schema_id_should_be_stored_in_reserved_headers() {
+            let mut message = Message::from_str("hello").unwrap();
+            assert_eq!(message.content_type(), None);
+            assert_eq!(message.schema_id(), None);
+
+            message.set_content_type("application/json").unwrap();
+            message.set_schema_id(7).unwrap();
+            message.set_schema_id(8).unwrap();
+
+            assert_eq!(message.content_type(), Some("application/json"));
+            assert_eq!(message.schema_id(), Some(8));
+            let headers = message.headers.as_ref().unwrap();
+            assert_eq!(headers.len(), 2);
+            assert_eq!(
+                headers[&HeaderKey::new(CONTENT_TYPE_HEADER).unwrap()],
+                HeaderValue::from_str("application/json").unwrap()
+            );
+
+            let bytes = message.as_bytes();
+            let deserialized = Message::from_bytes(bytes).unwrap();
+            assert_eq!(deserialized.content_type(), Some("application/json"));
+            assert_eq!(deserialized.schema_id(), Some(8));
+        }
+
+        #[test]
+        fn content_type_should_not_be_set_when_headers_would_be_too_big() {
+            let mut message = Message::from_str("hello").unwrap();
+            let value = HeaderValue::from_raw(&[1; 255]).unwrap();
+            let mut index = 0;
+            while message
+                .set_header(
+                    HeaderKey::new(&format!("key-{index}")).unwrap(),
+                    value.clone(),
+                )
+                .is_ok()
+            {
+                index += 1;
+            }
+            let headers = message.headers.clone();
+
+            let result = message.set_content_type(&"a".repeat(255));
+
+            assert!(matches!(result, Err(IggyError::TooBigHeadersPayload)));
+            assert_eq!(message.headers, headers);
+            assert_eq!(message.content_type(), None);
+        }
+
+        #[test]
+        fn map_messages_should_add_header_to_every_message() {
+            let command = send_messages(
+                Partitioning::partition_id(3),
+                vec![
+                    Message::from_str("hello 1").unwrap(),
+                    Message::from_str("hello 2").unwrap(),
+                    Message::from_str("hello 3").unwrap(),
+                ],
+            );
+            let key = HeaderKey::new("tenant").unwrap();
+            let value = HeaderValue::from_str("acme").unwrap();
+
+            let command = command.map_messages(|mut message| {
+                message.set_header(key.clone(), value.clone()).unwrap();
+                message
+            });
+
+            assert_eq!(command.stream_id, Identifier::numeric(1).unwrap());
+            assert_eq!(command.topic_id, Identifier::numeric(2).unwrap());
+            assert_eq!(command.partitioning, Partitioning::partition_id(3));
+            assert_eq!(command.messages.len(), 3);
+            for (index, message) in command.messages.iter().enumerate() {
+                assert_eq!(message.headers.as_ref().unwrap()[&key], value);
+                assert_eq!(message.payload, format!("hello {}", index + 1));
+            }
+        }
+
+        #[test]
+        fn sequence_headers_should_be_assigned_and_readable() {
+            let mut send_messages = SendMessages {
+                topic_id: Identifier::numeric(1).unwrap(),
+                ..send_messages(
+                    Partitioning::default(),
+                    (0..5)
+                        .map(|index| Message::from_str(&format!("message {index}")).unwrap())
+                        .collect(),
+                )
+            };
+
+            send_messages.assign_sequence_headers().unwrap();
+
+            for (index, message) in send_messages.messages.iter().enumerate() {
+                let polled_message = crate::models::messages::Message::from_message(message);
+                assert_eq!(polled_message.sequence(), Some(index as u32));
+            }
+        }
+
+        #[test]
+        fn header_keys_should_be_enumerated() {
+            let message = MessageBuilder::new()
+                .payload("hello".into())
+                .header_str("key-1", "value 1")
+                .header_str("key-2", "value 2")
+                .header_str("key-3", "value 3")
+                .build()
+                .unwrap();
+
+            let mut keys = message
+                .header_keys()
+                .map(|key| key.as_str())
+                .collect::<Vec<_>>();
+            keys.sort_unstable();
+
+            assert_eq!(keys, vec!["key-1", "key-2", "key-3"]);
+        }
+
+        fn mixed_case_key(key: &str) -> HeaderKey {
+            // The keys received from the wire keep their case, unlike the ones created with `HeaderKey::new`.
+            serde_json::from_str(&format!("\"{key}\"")).unwrap()
+        }
+
+        #[test]
+        fn header_should_be_looked_up_ignoring_case() {
+            let value = HeaderValue::from_str("abc").unwrap();
+            let message = Message::new(
+                None,
+                "hello".into(),
+                Some(HashMap::from([(mixed_case_key("Trace-Id"), value.clone())])),
+            );
+
+            assert_eq!(message.header_ci("trace-id"), Some(&value));
+            assert_eq!(message.header_ci("TRACE-ID"), Some(&value));
+            assert_eq!(message.header_ci("Trace-Id"), Some(&value));
+            assert_eq!(message.header_ci("span-id"), None);
+            assert!(message.headers.as_ref().unwrap().get("trace-id").is_none());
+            assert_eq!(
+                Message::from_str("hello").unwrap().header_ci("trace-id"),
+                None
+            );
+        }
+
+        #[test]
+        fn header_keys_should_be_normalized_to_lowercase() {
+            let trace_id = HeaderValue::from_str("abc").unwrap();
+            let tenant = HeaderValue::from_str("acme").unwrap();
+            let mut message = Message::new(
+                None,
+                "hello".into(),
+                Some(HashMap::from([
+                    (mixed_case_key("Trace-Id"), trace_id.clone()),
+                    (HeaderKey::new("tenant").unwrap(), tenant.clone()),
+                ])),
+            );
+
+            message.normalize_header_keys().unwrap();
+
+            let headers = message.headers.as_ref().unwrap();
+            assert_eq!(headers.len(), 2);
+            assert_eq!(headers.get("trace-id"), Some(&trace_id));
+            assert_eq!(headers.get("tenant"), Some(&tenant));
+        }
+
+        #[test]
+        fn header_keys_differing_only_by_case_should_fail_to_normalize() {
+            let headers = HashMap::from([
+                (
+                    mixed_case_key("Trace-Id"),
+                    HeaderValue::from_str("abc").unwrap(),
+                ),
+                (
+                    mixed_case_key("trace-id"),
+                    HeaderValue::from_str("def").unwrap(),
+                ),
+            ]);
+            let mut message = Message::new(None, "hello".into(), Some(headers.clone()));
+
+            assert!(matches!(
+                message.normalize_header_keys(),
+                Err(IggyError::DuplicateHeaderKey(key)) if key == "trace-id"
+            ));
+            assert_eq!(message.headers.as_ref().unwrap(), &headers);
+        }
+
+        #[test]
+        fn header_keys_should_be_empty_for_message_without_headers() {
+            let message = Message::new(None, "hello".into(), None);
+
+            assert_eq!(message.header_keys().count(), 0);
+        }
+    }
+
+    mod expiry {
+        use super::*;
+
+        #[test]
+        fn expire_at_should_be_stored_in_reserved_header() {
+            let mut message = Message::from_str("hello").unwrap();
+            assert!(message.expire_at().is_none());
+
+            message
+                .set_expire_at(IggyTimestamp::from(1694968446131680))
+                .unwrap();
+
+            assert_eq!(message.expire_at().unwrap().to_micros(), 1694968446131680);
+            assert_eq!(
+                message.headers.unwrap()[&HeaderKey::new(EXPIRE_AT_HEADER).unwrap()],
+                HeaderValue::from_uint64(1694968446131680).unwrap()
+            );
+        }
+
+        #[test]
+        fn validation_should_accept_messages_expiring_in_the_future() {
+            let mut message = Message::from_str("hello").unwrap();
+            message
+                .set_expire_at(IggyTimestamp::now() + Duration::from_secs(60))
+                .unwrap();
+            let command = send_messages(
+                Partitioning::balanced(),
+                vec![Message::from_str("hello").unwrap(), message],
+            );
+
+            assert!(command.validate().is_ok());
+        }
+
+        #[test]
+        fn validation_should_reject_already_expired_message() {
+            let mut message = Message::from_str("hello").unwrap();
+            let expire_at = IggyTimestamp::now() - Duration::from_secs(60);
+            let expire_at_micros = expire_at.to_micros();
+            message.set_expire_at(expire_at).unwrap();
+            let command = send_messages(
+                Partitioning::balanced(),
+                vec![Message::from_str("hello").unwrap(), message],
+            );
+
+            let error = command.validate().unwrap_err();
+
+            assert!(matches!(
+                error,
+                IggyError::InvalidMessageAtIndex(1, inner)
+                    if matches!(*inner, IggyError::MessageAlreadyExpired(micros) if micros == expire_at_micros)
+            ));
+        }
+
+        #[test]
+        fn expiry_should_be_validated_against_the_provided_clock() {
+            let clock = MockClock::new(IggyTimestamp::from(1_000_000));
+            let mut message = Message::from_str("hello").unwrap();
+            message
+                .set_expire_at(IggyTimestamp::from(2_000_000))
+                .unwrap();
+            let command = SendMessages {
+                messages: vec![Message::from_str("hello").unwrap(), message],
+                ..SendMessages::default()
+            };
+            assert!(command.validate_expiry(&clock).is_ok());
+            assert!(command
+                .validate_with_limits(&ValidationLimits::default())
+                .is_ok());
+
+            clock.advance(Duration::from_secs(2));
+
+            assert!(matches!(
+                command.validate_expiry(&clock),
+                Err(IggyError::InvalidMessageAtIndex(1, inner))
+                    if matches!(*inner, IggyError::MessageAlreadyExpired(2_000_000))
+            ));
+            // The validation with the limits doesn't depend on the time.
+            assert!(command
+                .validate_with_limits(&ValidationLimits::default())
+                .is_ok());
+        }
+
+        #[test]
+        fn message_should_expire_once_the_clock_passes_its_expiry() {
+            let clock = MockClock::new(IggyTimestamp::from(1_000_000));
+            let mut message = Message::from_str("hello").unwrap();
+            assert!(!message.is_expired(&clock));
+
+            message
+                .set_expire_at(IggyTimestamp::from(2_000_000))
+                .unwrap();
+            assert!(!message.is_expired(&clock));
+
+            clock.advance(Duration::from_secs(1));
+            assert!(!message.is_expired(&clock));
+
+            clock.advance(Duration::from_micros(1));
+            assert!(message.is_expired(&clock));
+        }
+    }
+
+    mod limits {
+        use super::*;
+
+        #[test]
+        fn validation_should_use_the_same_headers_size_as_serialization() {
+            let mut headers = HashMap::new();
+            let key = "k".repeat(200);
+            headers.insert(
+                HeaderKey::new(&key).unwrap(),
+                HeaderValue::from_raw(&[1]).unwrap(),
+            );
+            let message = Message::new(None, "hello".into(), Some(headers));
+            let headers_size = message.headers.as_ref().unwrap().get_size_bytes();
+            // Only the values would fit, but keys and length fields push the wire size over the limit.
+            let count = MAX_HEADERS_SIZE / headers_size + 1;
+            let messages = (0..count)
+                .map(|_| Message::new(None, "hello".into(), message.clone().take_headers()))
+                .collect::<Vec<_>>();
+            let command = send_messages(Partitioning::partition_id(1), messages);
+
+            let values_size = command
+                .messages
+                .iter()
+                .flat_map(|message| message.headers.as_ref().unwrap().values())
+                .map(|value| value.value.len() as u32)
+                .sum::<u32>();
+            assert!(values_size <= MAX_HEADERS_SIZE);
+            let wire_headers_size = command
+                .messages
+                .iter()
+                .map(|message| message.headers.as_ref().unwrap().get_size_bytes())
+                .sum::<u32>();
+            assert!(wire_headers_size > MAX_HEADERS_SIZE);
+            let error = command.validate().unwrap_err();
+            assert!(matches!(
+                &error,
+                IggyError::InvalidMessageAtIndex(index, inner)
+                    if *index == count as usize - 1 && matches!(**inner, IggyError::TooBigHeadersPayload)
+            ));
+        }
+
+        #[test]
+        fn batch_within_default_limits_should_be_rejected_by_tighter_custom_limits() {
+            let headers = HashMap::from([(
+                HeaderKey::new("tenant").unwrap(),
+                HeaderValue::from_str(&"a".repeat(100)).unwrap(),
+            )]);
+            let command = SendMessages {
+                messages: (0..2)
+                    .map(|_| Message::new(None, Bytes::from(vec![1; 600]), Some(headers.clone())))
+                    .collect(),
+                ..SendMessages::default()
+            };
+            assert!(command.validate().is_ok());
+            assert!(command
+                .validate_with_limits(&ValidationLimits::default())
+                .is_ok());
+
+            let payload_limits = ValidationLimits {
+                max_payload: 1000,
+                ..ValidationLimits::default()
+            };
+            assert!(matches!(
+                command.validate_with_limits(&payload_limits),
+                Err(IggyError::InvalidMessageAtIndex(1, inner)) if matches!(*inner, IggyError::TooBigMessagePayload)
+            ));
+
+            let headers_limits = ValidationLimits {
+                max_headers: 150,
+                ..ValidationLimits::default()
+            };
+            assert!(matches!(
+                command.validate_with_limits(&headers_limits),
+                Err(IggyError::InvalidMessageAtIndex(1, inner)) if matches!(*inner, IggyError::TooBigHeadersPayload)
+            ));
+        }
+
+        #[test]
+        fn check_should_return_batch_stats() {
+            let headers = HashMap::from([(
+                HeaderKey::new("tenant").unwrap(),
+                HeaderValue::from_str("acme").unwrap(),
+            )]);
+            let command = SendMessages {
+                messages: vec![
+                    Message::from_str("hello 1").unwrap(),
+                    Message::new(None, "hello 22".into(), Some(headers.clone())),
+                ],
+                ..SendMessages::default()
+            };
+
+            let stats = command.check().unwrap();
+
+            assert_eq!(
+                stats,
+                BatchStats {
+                    total_payload: 15,
+                    total_headers: u64::from(header::get_headers_size_bytes(&Some(headers))),
+                    message_count: 2,
+                    remaining_payload_budget: MAX_PAYLOAD_SIZE - 15,
+                }
+            );
+        }
+
+        #[test]
+        fn check_should_report_exceeded_payload_limit_by_total_size() {
+            let command = SendMessages {
+                messages: (0..3)
+                    .map(|_| Message::new(None, Bytes::from(vec![1; 600]), None))
+                    .collect(),
+                ..SendMessages::default()
+            };
+            let limits = ValidationLimits {
+                max_payload: 1000,
+                ..ValidationLimits::default()
+            };
+
+            assert!(matches!(
+                command.check_with_limits(&limits),
+                Err(IggyError::BatchPayloadTooLarge(1800, 1000))
+            ));
+            assert_eq!(
+                command.check().unwrap().remaining_payload_budget,
+                MAX_PAYLOAD_SIZE - 1800
+            );
+        }
+
+        #[test]
+        fn check_should_report_exceeded_headers_limit_by_total_size() {
+            let headers = HashMap::from([(
+                HeaderKey::new("tenant").unwrap(),
+                HeaderValue::from_str(&"a".repeat(100)).unwrap(),
+            )]);
+            let headers_size = u64::from(header::get_headers_size_bytes(&Some(headers.clone())));
+            let command = SendMessages {
+                messages: (0..3)
+                    .map(|_| Message::new(None, "hello".into(), Some(headers.clone())))
+                    .collect(),
+                ..SendMessages::default()
+            };
+            let limits = ValidationLimits {
+                max_headers: 150,
+                ..ValidationLimits::default()
+            };
+
+            assert!(matches!(
+                command.check_with_limits(&limits),
+                Err(IggyError::BatchHeadersTooLarge(total, 150)) if total == 3 * headers_size
+            ));
+        }
+
+        #[test]
+        fn check_should_report_empty_batch_and_payload() {
+            let mut command: SendMessages = std::iter::empty().collect();
+            assert!(matches!(
+                command.check(),
+                Err(IggyError::InvalidMessagesCount)
+            ));
+
+            command.messages.push(Message::tombstone(HashMap::new()));
+            assert!(matches!(
+                command.check(),
+                Err(IggyError::EmptyMessagePayload)
+            ));
+        }
+
+        #[test]
+        fn check_should_report_invalid_key_length() {
+            let command = SendMessages {
+                partitioning: Partitioning {
+                    kind: PartitioningKind::MessagesKey,
+                    length: 255,
+                    value: vec![1; 256],
+                },
+                messages: vec![Message::from_str("hello").unwrap()],
+                ..SendMessages::default()
+            };
+
+            assert!(matches!(
+                command.check(),
+                Err(IggyError::InvalidKeyValueLength)
+            ));
+        }
+
+        #[test]
+        fn push_should_fail_when_payloads_would_exceed_max_size() {
+            let mut command: SendMessages = std::iter::empty().collect();
+            let payload = Bytes::from(vec![1; MAX_PAYLOAD_SIZE as usize / 2]);
+            command
+                .push(Message::new(None, payload.clone(), None))
+                .unwrap();
+            command
+                .push(Message::new(None, payload.clone(), None))
+                .unwrap();
+
+            let result = command.push(Message::from_str("hello").unwrap());
+
+            assert!(matches!(result, Err(IggyError::TooBigMessagePayload)));
+            assert_eq!(command.messages.len(), 2);
+            assert!(command.validate().is_ok());
+        }
+
+        #[test]
+        fn push_should_account_for_messages_appended_directly() {
+            let mut command: SendMessages = std::iter::empty().collect();
+            let payload = Bytes::from(vec![1; MAX_PAYLOAD_SIZE as usize / 2]);
+            command
+                .messages_mut()
+                .push(Message::new(None, payload.clone(), None));
+            command
+                .messages_mut()
+                .push(Message::new(None, payload, None));
+
+            let result = command.push(Message::from_str("hello").unwrap());
+
+            assert!(matches!(result, Err(IggyError::TooBigMessagePayload)));
+            assert_eq!(command.messages().len(), 2);
+        }
+
+        #[test]
+        fn push_should_account_for_messages_changed_directly() {
+            let payload = Bytes::from(vec![1; MAX_PAYLOAD_SIZE as usize / 2]);
+            let mut command: SendMessages =
+                std::iter::once(Message::from_str("hello").unwrap()).collect();
+            command
+                .push(Message::new(None, payload.clone(), None))
+                .unwrap();
+            command.messages_mut()[0].payload = payload;
+
+            let result = command.push(Message::from_str("hello").unwrap());
+
+            assert!(matches!(result, Err(IggyError::TooBigMessagePayload)));
+            assert_eq!(command.messages().len(), 2);
+        }
+
+        #[test]
+        fn push_should_account_for_extended_and_collected_messages() {
+            let payload = Bytes::from(vec![1; MAX_PAYLOAD_SIZE as usize / 2]);
+            let mut command: SendMessages =
+                std::iter::once(Message::new(None, payload.clone(), None)).collect();
+            command.extend([Message::new(None, payload, None)]);
+
+            let result = command.push(Message::from_str("hello").unwrap());
+
+            assert!(matches!(result, Err(IggyError::TooBigMessagePayload)));
+            assert_eq!(command.messages().len(), 2);
+        }
+
+        #[test]
+        fn push_should_fail_when_extended_messages_exceed_max_size() {
+            let payload = Bytes::from(vec![1; MAX_PAYLOAD_SIZE as usize / 2 + 1]);
+            let mut command: SendMessages = std::iter::empty().collect();
+            command.extend((0..2).map(|_| Message::new(None, payload.clone(), None)));
+
+            let result = command.push(Message::from_str("hello").unwrap());
+
+            assert!(matches!(result, Err(IggyError::TooBigMessagePayload)));
+            assert_eq!(command.messages().len(), 2);
+        }
+
+        #[test]
+        fn checked_message_should_fail_when_headers_exceed_max_size() {
+            let value = HeaderValue::from_raw(&[1; 255]).unwrap();
+            let headers = (0..400)
+                .map(|index| {
+                    (
+                        HeaderKey::new(&format!("key-{index}")).unwrap(),
+                        value.clone(),
+                    )
+                })
+                .collect::<HashMap<_, _>>();
+            assert!(header::get_headers_size_bytes(&Some(headers.clone())) > MAX_HEADERS_SIZE);
+
+            let result = Message::new_checked(Some(1), "hello".into(), Some(headers));
+
+            assert!(matches!(result, Err(IggyError::TooBigHeadersPayload)));
+        }
+
+        #[test]
+        fn checked_message_should_fail_when_payload_exceeds_max_size() {
+            let payload = Bytes::from(vec![1; MAX_PAYLOAD_SIZE as usize + 1]);
+
+            let result = Message::new_checked(None, payload, None);
+
+            assert!(matches!(result, Err(IggyError::TooBigMessagePayload)));
+        }
+
+        #[test]
+        fn checked_message_should_be_created_within_limits() {
+            let payload = Bytes::from(vec![1; MAX_PAYLOAD_SIZE as usize]);
+
+            let message = Message::new_checked(Some(1), payload.clone(), None).unwrap();
+
+            assert_eq!(message, Message::new(Some(1), payload, None));
+        }
+
+        #[test]
+        fn push_should_fail_when_headers_would_exceed_max_size() {
+            let mut headers = HashMap::new();
+            headers.insert(
+                HeaderKey::new(&"k".repeat(200)).unwrap(),
+                HeaderValue::from_raw(&[1; 255]).unwrap(),
+            );
+            let mut command: SendMessages = std::iter::empty().collect();
+            let error = loop {
+                match command.push(Message::new(None, "hello".into(), Some(headers.clone()))) {
+                    Ok(()) => continue,
+                    Err(error) => break error,
+                }
+            };
+
+            assert!(matches!(error, IggyError::TooBigHeadersPayload));
+            assert!(command.validate().is_ok());
+            command
+                .messages_mut()
+                .push(Message::new(None, "hello".into(), Some(headers)));
+            assert!(command.validate().is_err());
+        }
+
+        #[test]
+        fn combined_validation_should_pass_at_the_limit() {
+            let mut headers = HashMap::new();
+            headers.insert(
+                HeaderKey::new("key").unwrap(),
+                HeaderValue::from_str("value").unwrap(),
+            );
+            let message = Message::new(None, "hello".into(), Some(headers));
+            let size = 5 + message.headers.as_ref().unwrap().get_size_bytes();
+
+            assert!(message.validate_combined(size).is_ok());
+            assert!(Message::from_str("hello")
+                .unwrap()
+                .validate_combined(5)
+                .is_ok());
+        }
+
+        #[test]
+        fn combined_validation_should_fail_over_the_limit() {
+            let mut headers = HashMap::new();
+            headers.insert(
+                HeaderKey::new("key").unwrap(),
+                HeaderValue::from_str("value").unwrap(),
+            );
+            let message = Message::new(None, "hello".into(), Some(headers));
+            let size = 5 + message.headers.as_ref().unwrap().get_size_bytes();
+
+            assert!(matches!(
+                message.validate_combined(size - 1),
+                Err(IggyError::MessageTooLarge(actual, limit)) if actual == size as u64 && limit == size - 1
+            ));
+        }
+
+        #[test]
+        fn combined_validation_should_use_uncompressed_payload_length() {
+            let payload = Bytes::from(vec![1; 1000]);
+            let message =
+                Message::new_compressed(None, payload, CompressionAlgorithm::Gzip, None).unwrap();
+            assert!(message.payload.len() < 1000);
+
+            assert!(message.validate_combined(1000).is_ok());
+            assert!(matches!(
+                message.validate_combined(999),
+                Err(IggyError::MessageTooLarge(1000, 999))
+            ));
+        }
+
+        #[test]
+        fn flush_should_be_recommended_when_messages_count_is_reached() {
+            let mut command = SendMessages {
+                messages: vec![Message::from_str("hello 1").unwrap()],
+                ..SendMessages::default()
+            };
+            assert!(!command.should_flush(u32::MAX, 2));
+
+            command.messages.push(Message::from_str("hello 2").unwrap());
+            assert!(command.should_flush(u32::MAX, 2));
+        }
+
+        #[test]
+        fn flush_should_be_recommended_when_wire_size_is_reached() {
+            let mut command = SendMessages {
+                messages: vec![Message::from_str("hello 1").unwrap()],
+                ..SendMessages::default()
+            };
+            let target_bytes = command.wire_size() as u32 + 1;
+            assert!(!command.should_flush(target_bytes, usize::MAX));
+
+            command.messages.push(Message::from_str("hello 2").unwrap());
+            assert!(command.should_flush(target_bytes, usize::MAX));
+        }
+
+        #[test]
+        fn validation_should_report_index_of_message_exceeding_payload_limit() {
+            let mut messages = (0..10)
+                .map(|index| Message::new(Some(index + 1), "hello".into(), None))
+                .collect::<Vec<_>>();
+            messages.insert(
+                7,
+                Message::new(None, Bytes::from(vec![1; MAX_PAYLOAD_SIZE as usize]), None),
+            );
+            let command = SendMessages {
+                messages,
+                ..SendMessages::default()
+            };
+
+            let error = command.validate().unwrap_err();
+
+            assert!(matches!(
+                &error,
+                IggyError::InvalidMessageAtIndex(7, inner) if matches!(**inner, IggyError::TooBigMessagePayload)
+            ));
+            assert_eq!(
+                error.to_string(),
+                "Invalid message at index: 7, error: Too big message payload"
+            );
+        }
+    }
+
+    mod merging {
+        use super::*;
+
+        fn command_for(topic_id: u32, key: &str, payloads: &[&str]) -> SendMessages {
+            SendMessages {
+                topic_id: Identifier::numeric(topic_id).unwrap(),
+                ..send_messages(
+                    Partitioning::messages_key_str(key).unwrap(),
+                    payloads
+                        .iter()
+                        .map(|payload| Message::from_str(payload).unwrap())
+                        .collect(),
+                )
+            }
+        }
+
+        #[test]
+        fn batches_targeting_same_topic_should_be_merged() {
+            let mut command = command_for(1, "a", &["hello 1"]);
+
+            command
+                .try_merge(command_for(1, "a", &["hello 2", "hello 3"]))
+                .unwrap();
+
+            assert_eq!(command.messages.len(), 3);
+            assert_eq!(command.messages[2].payload, Bytes::from_static(b"hello 3"));
+        }
+
+        #[test]
+        fn incompatible_batches_should_not_be_merged() {
+            let mut command = command_for(1, "a", &["hello 1"]);
+            let others = [
+                command_for(2, "a", &["hello 2"]),
+                command_for(1, "b", &["hello 2"]),
+                SendMessages {
+                    idempotency_key: Some(1),
+                    ..command_for(1, "a", &["hello 2"])
+                },
+            ];
+
+            for other in others {
+                assert!(matches!(
+                    command.try_merge(other),
+                    Err(IggyError::IncompatibleBatch)
+                ));
+            }
+            assert_eq!(command, command_for(1, "a", &["hello 1"]));
+        }
+
+        #[test]
+        fn too_big_merged_batch_should_be_rejected_and_left_unchanged() {
+            let payload = Bytes::from(vec![1; MAX_PAYLOAD_SIZE as usize / 2 + 1]);
+            let mut command = command_for(1, "a", &[]);
+            command
+                .messages
+                .push(Message::new(None, payload.clone(), None));
+            let mut other = command_for(1, "a", &[]);
+            other.messages.push(Message::new(None, payload, None));
+
+            assert!(matches!(
+                command.try_merge(other),
+                Err(IggyError::InvalidMessageAtIndex(1, inner)) if matches!(*inner, IggyError::TooBigMessagePayload)
+            ));
+            assert_eq!(command.messages.len(), 1);
+        }
+
+        #[test]
+        fn merge_all_should_group_commands_by_target() {
+            let commands = vec![
+                command_for(1, "a", &["a 1"]),
+                command_for(2, "a", &["b 1"]),
+                command_for(1, "a", &["a 2", "a 3"]),
+                command_for(1, "c", &["c 1"]),
+                command_for(2, "a", &["b 2"]),
+            ];
+
+            let merged = merge_all(commands);
+
+            assert_eq!(
+                merged,
+                vec![
+                    command_for(1, "a", &["a 1", "a 2", "a 3"]),
+                    command_for(2, "a", &["b 1", "b 2"]),
+                    command_for(1, "c", &["c 1"]),
+                ]
+            );
+        }
+
+        #[test]
+        fn too_big_batch_should_be_split_into_valid_batches() {
+            let payload = Bytes::from(vec![1; MAX_PAYLOAD_SIZE as usize / 3]);
+            let command = send_messages(
+                Partitioning::partition_id(3),
+                (1..=7)
+                    .map(|id| Message::new(Some(id), payload.clone(), None))
+                    .collect(),
+            );
+            assert!(command.validate().is_err());
+
+            let batches = command.split_into_valid_batches().unwrap();
+
+            assert_eq!(batches.len(), 3);
+            let mut ids = Vec::new();
+            for batch in &batches {
+                assert!(batch.validate().is_ok());
+                assert_eq!(batch.stream_id, Identifier::numeric(1).unwrap());
+                assert_eq!(batch.topic_id, Identifier::numeric(2).unwrap());
+                assert_eq!(batch.partitioning, Partitioning::partition_id(3));
+                ids.extend(batch.messages.iter().map(|message| message.id));
+            }
+            assert_eq!(ids, (1..=7).collect::<Vec<_>>());
+        }
+
+        #[test]
+        fn batch_with_too_big_single_message_should_not_be_split() {
+            let command = SendMessages {
+                messages: vec![
+                    Message::new(Some(1), "hello".into(), None),
+                    Message::new(
+                        Some(2),
+                        Bytes::from(vec![1; MAX_PAYLOAD_SIZE as usize + 1]),
+                        None,
+                    ),
+                ],
+                ..SendMessages::default()
+            };
+
+            let error = command.split_into_valid_batches().unwrap_err();
+            assert_eq!(error.as_code(), IggyError::TooBigMessagePayload.as_code());
+        }
+    }
+
+    mod tombstones {
+        use super::*;
+
+        #[test]
+        fn tombstones_should_round_trip_when_allowed() {
+            let key_headers = HashMap::from([(
+                HeaderKey::new("key").unwrap(),
+                HeaderValue::from_str("order-1").unwrap(),
+            )]);
+            let command = SendMessages {
+                allow_tombstones: true,
+                ..send_messages(
+                    Partitioning::messages_key_str("order-1").unwrap(),
+                    vec![
+                        Message::from_str("hello").unwrap(),
+                        Message::tombstone(key_headers.clone()),
+                    ],
+                )
+            };
+            assert!(command.validate().is_ok());
+
+            let deserialized = SendMessages::from_bytes(command.as_bytes()).unwrap();
+
+            assert_eq!(deserialized, command);
+            assert!(deserialized.messages[1].is_tombstone());
+            assert!(deserialized.messages[1].payload.is_empty());
+            assert_eq!(
+                deserialized.messages[1].headers.as_ref().unwrap(),
+                &key_headers
+            );
+            assert!(!deserialized.messages[0].is_tombstone());
+        }
+
+        #[test]
+        fn tombstones_should_be_rejected_when_batch_flag_is_missing() {
+            let command = SendMessages {
+                allow_tombstones: true,
+                ..send_messages(
+                    Partitioning::partition_id(1),
+                    vec![
+                        Message::from_str("hello").unwrap(),
+                        Message::tombstone(HashMap::new()),
+                    ],
+                )
+            };
+            let mut bytes = BytesMut::from(&command.as_bytes()[..]);
+            // Stream ID + Topic ID
+            let position = 6 + 6;
+            assert_ne!(bytes[position] & TOMBSTONES_FLAG, 0);
+            bytes[position] &= !TOMBSTONES_FLAG;
+
+            let result = SendMessages::from_bytes(bytes.freeze());
+
+            assert!(matches!(
+                result,
+                Err(IggyError::InvalidMessageAtIndex(1, inner)) if matches!(*inner, IggyError::EmptyMessagePayload)
+            ));
+        }
+
+        #[test]
+        fn batch_trailer_should_be_appended_and_detected() {
+            let command = SendMessages {
+                stream_id: Identifier::numeric(1).unwrap(),
+                topic_id: Identifier::numeric(2).unwrap(),
+                messages: vec![
+                    Message::from_str("hello 1").unwrap(),
+                    Message::from_str("hello 2").unwrap(),
+                ],
+                ..SendMessages::default()
+            }
+            .with_batch_trailer()
+            .unwrap();
+
+            assert!(!command.allow_tombstones);
+            assert!(command.validate().is_ok());
+
+            let deserialized = SendMessages::from_bytes(command.as_bytes()).unwrap();
+
+            assert_eq!(deserialized.messages.len(), 3);
+            assert!(deserialized.messages[2].is_batch_end());
+            assert!(deserialized.messages[2].payload.is_empty());
+            assert!(!deserialized.messages[0].is_batch_end());
+            assert!(!deserialized.messages[1].is_batch_end());
+        }
+
+        #[test]
+        fn batch_trailer_should_not_allow_other_tombstones() {
+            let mut command = SendMessages {
+                messages: vec![Message::from_str("hello").unwrap()],
+                ..SendMessages::default()
+            }
+            .with_batch_trailer()
+            .unwrap();
+            command
+                .messages
+                .insert(1, Message::tombstone(HashMap::new()));
+
+            assert!(matches!(
+                command.validate(),
+                Err(IggyError::InvalidMessageAtIndex(1, inner)) if matches!(*inner, IggyError::EmptyMessagePayload)
+            ));
+        }
+
+        #[test]
+        fn only_empty_message_with_batch_end_header_should_be_batch_end() {
+            let key_headers = HashMap::from([(
+                HeaderKey::new("key").unwrap(),
+                HeaderValue::from_str("order-1").unwrap(),
+            )]);
+            let mut message = Message::from_str("hello").unwrap();
+            message
+                .set_header(
+                    HeaderKey::new(BATCH_END_HEADER).unwrap(),
+                    HeaderValue::from_bool(true).unwrap(),
+                )
+                .unwrap();
+
+            assert!(Message::batch_trailer().unwrap().is_batch_end());
+            assert!(!Message::tombstone(key_headers).is_batch_end());
+            assert!(!message.is_batch_end());
+        }
+
+        #[test]
+        fn tombstones_should_be_rejected_by_default() {
+            let mut command = SendMessages {
+                messages: vec![
+                    Message::from_str("hello").unwrap(),
+                    Message::tombstone(HashMap::new()),
+                ],
+                ..SendMessages::default()
+            };
+
+            assert!(matches!(
+                command.validate(),
+                Err(IggyError::InvalidMessageAtIndex(1, inner)) if matches!(*inner, IggyError::EmptyMessagePayload)
+            ));
+
+            command.messages.remove(0);
+            assert!(matches!(
+                command.validate(),
+                Err(IggyError::EmptyMessagePayload)
+            ));
+
+            command.allow_tombstones = true;
+            assert!(command.validate().is_ok());
+        }
+
+        #[test]
+        fn empty_payload_should_be_distinguished_from_tombstone_on_the_wire() {
+            let tombstone = Message::tombstone(HashMap::new()).as_bytes();
+            assert!(Message::from_bytes(tombstone.clone())
+                .unwrap()
+                .is_tombstone());
+            assert!(MessageView::new(&tombstone).is_ok());
+
+            let mut empty = BytesMut::from(&tombstone[..]);
+            empty[16..20].copy_from_slice(&0u32.to_le_bytes());
+            assert!(matches!(
+                Message::from_bytes(empty.clone().freeze()),
+                Err(IggyError::EmptyMessagePayload)
+            ));
+            assert!(matches!(
+                MessageView::new(&empty),
+                Err(IggyError::EmptyMessagePayload)
+            ));
+
+            let mut flagged = BytesMut::from(&Message::from_str("hello").unwrap().as_bytes()[..]);
+            flagged[16..20].copy_from_slice(&TOMBSTONE_FLAG.to_le_bytes());
+            assert!(matches!(
+                Message::from_bytes(flagged.freeze()),
+                Err(IggyError::InvalidCommand)
+            ));
+        }
+    }
+
+    mod redaction {
+        use super::*;
+
+        #[test]
+        fn redacted_message_should_not_display_payload_and_header_values() {
+            let message = Message::parse("1|trace=abc;env=prod|secret payload").unwrap();
+
+            let redacted = message.redacted().to_string();
+
+            assert_eq!(redacted, "1|env=***;trace=***|<14 bytes>");
+            assert_eq!(
+                Message::from_str("secret").unwrap().redacted().to_string(),
+                "0|<6 bytes>"
+            );
+            assert_eq!(message.to_string(), "1|secret payload");
+        }
+
+        #[test]
+        fn redacted_partitioning_should_not_display_messages_key() {
+            assert_eq!(
+                Partitioning::messages_key_str("user-1")
+                    .unwrap()
+                    .redacted()
+                    .to_string(),
+                "messages_key|<6 bytes>"
+            );
+            assert_eq!(
+                Partitioning::consistent_key(b"user-1")
+                    .unwrap()
+                    .redacted()
+                    .to_string(),
+                "consistent_key|<6 bytes>"
+            );
+            assert_eq!(
+                Partitioning::partition_id(3).redacted().to_string(),
+                "partition_id|3"
+            );
+            assert_eq!(
+                Partitioning::balanced().redacted().to_string(),
+                "balanced|0"
+            );
+        }
+
+        #[test]
+        fn redacted_command_should_not_display_sensitive_data() {
+            let command = send_messages(
+                Partitioning::messages_key_str("user-1").unwrap(),
+                vec![
+                    Message::new(Some(1), "secret 1".into(), None),
+                    Message::new(Some(2), "secret 22".into(), None),
+                ],
+            );
+
+            let redacted = Redacted(&command).to_string();
+
+            assert_eq!(
+                redacted,
+                format!(
+                    "{}|{}|messages_key|<6 bytes>|1|<8 bytes>|2|<9 bytes>",
+                    command.stream_id, command.topic_id
+                )
+            );
+            assert!(!redacted.contains("secret"));
+            assert!(!redacted.contains("user-1"));
+        }
+    }
+
+    mod partitioning {
+        use super::*;
+        use crate::utils::{consistent_hash, key_hash};
+
+        #[test]
+        fn signed_messages_keys_should_be_byte_exact_with_unsigned_ones() {
+            assert_eq!(
+                Partitioning::messages_key_i32(-1),
+                Partitioning::messages_key_u32(u32::MAX)
+            );
+            assert_eq!(
+                Partitioning::messages_key_i64(-42),
+                Partitioning::messages_key_u64(-42i64 as u64)
+            );
+            assert_eq!(
+                Partitioning::messages_key_i64(i64::MIN).value,
+                vec![0, 0, 0, 0, 0, 0, 0, 0x80]
+            );
+            assert_eq!(
+                Partitioning::messages_key_i128(i128::MIN + 1),
+                Partitioning::messages_key_u128((i128::MIN + 1) as u128)
+            );
+            assert_eq!(
+                Partitioning::messages_key_i64(7),
+                Partitioning::messages_key_u64(7)
+            );
+        }
+
+        #[test]
+        fn signed_messages_key_should_be_deserialized_to_the_same_bytes() {
+            let partitioning = Partitioning::messages_key_i64(-1_000_000);
+
+            let deserialized = Partitioning::from_bytes(partitioning.as_bytes()).unwrap();
+
+            assert_eq!(deserialized, partitioning);
+            assert_eq!(
+                i64::from_le_bytes(deserialized.value[..].try_into().unwrap()),
+                -1_000_000
+            );
+            assert_eq!(
+                deserialized.to_string(),
+                format!("messages_key|{}", -1_000_000i64 as u64)
+            );
+        }
+
+        #[test]
+        fn unknown_partitioning_kind_code_should_be_unsupported() {
+            assert!(matches!(
+                PartitioningKind::from_code(99),
+                Err(IggyError::UnsupportedPartitioningKind(99))
+            ));
+            assert!(matches!(
+                Partitioning::from_bytes(Bytes::from_static(&[99, 1, 0])),
+                Err(IggyError::UnsupportedPartitioningKind(99))
+            ));
+        }
+
+        #[test]
+        fn messages_key_should_resolve_to_pinned_partitions() {
+            // The 32-bit xxHash (seed 0) values must never change, otherwise the keys would be routed to the other partitions.
+            assert_eq!(key_hash::calculate_32(b""), 0x02cc_5d05);
+            assert_eq!(key_hash::calculate_32(b"a"), 0x550d_7456);
+            assert_eq!(key_hash::calculate_32(b"abc"), 0x32d1_53ff);
+            assert_eq!(key_hash::calculate_32(b"order-1"), 0xc209_e3e8);
+
+            let counter = AtomicU32::new(1);
+            let cases: [(Partitioning, [u32; 3]); 4] = [
+                (Partitioning::messages_key_str("a").unwrap(), [3, 10, 6]),
+                (Partitioning::messages_key_str("abc").unwrap(), [3, 7, 15]),
+                (
+                    Partitioning::messages_key_str("order-1").unwrap(),
+                    [2, 2, 8],
+                ),
+                (Partitioning::messages_key_u32(1234), [1, 3, 5]),
+            ];
+            for (partitioning, partition_ids) in cases {
+                for (partitions_count, partition_id) in [3, 10, 16].into_iter().zip(partition_ids) {
+                    assert_eq!(
+                        partitioning
+                            .resolve_partition(partitions_count, &counter)
+                            .unwrap(),
+                        partition_id,
+                        "partitioning: {partitioning}, partitions count: {partitions_count}"
+                    );
+                }
+            }
+            assert_eq!(counter.load(std::sync::atomic::Ordering::SeqCst), 1);
+        }
+
+        #[test]
+        fn consistent_key_should_resolve_to_pinned_partitions() {
+            // The 64-bit FNV-1a values must never change, otherwise the keys would be routed to the other partitions.
+            assert_eq!(consistent_hash::fnv1a_64(b"a"), 0xaf63_dc4c_8601_ec8c);
+            assert_eq!(consistent_hash::fnv1a_64(b"abc"), 0xe71f_a219_0541_574b);
+
+            let counter = AtomicU32::new(1);
+            let cases: [(Partitioning, [u32; 3]); 3] = [
+                (Partitioning::consistent_key(b"a").unwrap(), [3, 3, 13]),
+                (Partitioning::consistent_key(b"abc").unwrap(), [3, 6, 6]),
+                (
+                    Partitioning::consistent_key(b"user-42").unwrap(),
+                    [1, 10, 10],
+                ),
+            ];
+            for (partitioning, partition_ids) in cases {
+                for (partitions_count, partition_id) in [3, 10, 16].into_iter().zip(partition_ids) {
+                    assert_eq!(
+                        partitioning
+                            .resolve_partition(partitions_count, &counter)
+                            .unwrap(),
+                        partition_id,
+                        "partitioning: {partitioning}, partitions count: {partitions_count}"
+                    );
+                }
+            }
+        }
+
+        #[test]
+        fn balanced_and_partition_id_should_resolve_like_server() {
+            let counter = AtomicU32::new(1);
+            let balanced = Partitioning::balanced();
+            let partition_ids = (0..5)
+                .map(|_| balanced.resolve_partition(2, &counter).unwrap())
+                .collect::<Vec<_>>();
+            assert_eq!(partition_ids, vec![1, 2, 1, 2, 1]);
+
+            assert_eq!(
+                Partitioning::partition_id(7)
+                    .resolve_partition(3, &counter)
+                    .unwrap(),
+                7
+            );
+            assert!(matches!(
+                balanced.resolve_partition(0, &counter),
+                Err(IggyError::InvalidPartitionId(0))
+            ));
+            assert!(matches!(
+                Partitioning::messages_key_str("a")
+                    .unwrap()
+                    .resolve_partition(0, &counter),
+                Err(IggyError::InvalidPartitionId(0))
+            ));
+        }
+
+        #[test]
+        fn partitioning_should_be_described_in_plain_language() {
+            assert_eq!(
+                Partitioning::balanced().describe(),
+                "round-robin balanced across partitions"
+            );
+            assert_eq!(
+                Partitioning::partition_id(4).describe(),
+                "routed to partition 4"
+            );
+            assert_eq!(
+                Partitioning::messages_key_str("orders").unwrap().describe(),
+                "hashed by key 'orders'"
+            );
+            assert_eq!(
+                Partitioning::consistent_key(b"orders").unwrap().describe(),
+                "consistently hashed by key 'orders'"
+            );
+            assert_eq!(
+                Partitioning::messages_key_u32(1).describe(),
+                "hashed by key 0x01000000"
+            );
+            assert_eq!(
+                Partitioning::messages_key(&[0xff, 0xfe])
+                    .unwrap()
+                    .describe(),
+                "hashed by key 0xfffe"
+            );
+        }
+
+        #[test]
+        fn partitioning_view_should_borrow_the_value_without_copying() {
+            let partitioning = Partitioning::messages_key_str("key-1").unwrap();
+            let bytes = partitioning.as_bytes();
+
+            let view = Partitioning::from_bytes_ref(&bytes).unwrap();
+
+            assert_eq!(view.kind(), PartitioningKind::MessagesKey);
+            assert_eq!(view.length(), partitioning.length);
+            assert_eq!(view.value(), partitioning.value.as_slice());
+            assert_eq!(view.value().as_ptr(), bytes[2..].as_ptr());
+            assert_eq!(view.get_size_bytes(), bytes.len());
+            assert_eq!(view.to_partitioning(), partitioning);
+        }
+
+        #[test]
+        fn partitioning_view_should_not_be_created_from_truncated_bytes() {
+            let partitioning = Partitioning::messages_key_str("key-1").unwrap();
+            let bytes = partitioning.as_bytes();
+
+            let view = PartitioningView::try_from(&bytes[..bytes.len() - 1]);
+
+            assert!(matches!(view, Err(IggyError::InvalidCommand)));
+            assert!(Partitioning::from_bytes(bytes.slice(..bytes.len() - 1)).is_err());
+        }
+
+        #[test]
+        fn hashed_key_partitioning_should_round_trip_with_hash_algorithm() {
+            for algo in [HashAlgo::Xxh32, HashAlgo::Fnv1a64, HashAlgo::Xxh64] {
+                let partitioning = Partitioning::messages_key_with(b"orders", algo).unwrap();
+                let deserialized = Partitioning::from_bytes(partitioning.as_bytes()).unwrap();
+
+                assert_eq!(deserialized, partitioning);
+                assert_eq!(deserialized.kind, PartitioningKind::HashedKey);
+                assert_eq!(deserialized.hash_algo(), Some(algo));
+                assert_eq!(
+                    deserialized.to_string(),
+                    format!("hashed_key|{algo}:orders")
+                );
+                assert_eq!(
+                    deserialized.redacted().to_string(),
+                    format!("hashed_key|{algo}:<6 bytes>")
+                );
+            }
+        }
+
+        #[test]
+        fn hashed_key_partitioning_should_resolve_pinned_partition_for_each_hash_algorithm() {
+            let counter = AtomicU32::new(0);
+            let partitions = [
+                (HashAlgo::Xxh32, 7),
+                (HashAlgo::Fnv1a64, 2),
+                (HashAlgo::Xxh64, 5),
+            ];
+
+            for (algo, partition_id) in partitions {
+                let partitioning = Partitioning::messages_key_with(b"orders", algo).unwrap();
+                assert_eq!(
+                    partitioning.resolve_partition(10, &counter).unwrap(),
+                    partition_id
+                );
+            }
+            assert_eq!(
+                Partitioning::messages_key_with(b"orders", HashAlgo::Xxh32)
+                    .unwrap()
+                    .resolve_partition(10, &counter)
+                    .unwrap(),
+                Partitioning::messages_key_str("orders")
+                    .unwrap()
+                    .resolve_partition(10, &counter)
+                    .unwrap()
+            );
+        }
+
+        #[test]
+        fn hashed_key_partitioning_should_be_rejected_without_key_or_with_unknown_hash_algorithm() {
+            assert!(Partitioning::messages_key_with(b"", HashAlgo::Xxh64).is_err());
+            assert!(Partitioning::messages_key_with(&[1; 255], HashAlgo::Xxh64).is_err());
+
+            let bytes = Bytes::from(vec![PartitioningKind::HashedKey.as_code(), 2, 9, b'k']);
+            assert!(matches!(
+                Partitioning::from_bytes(bytes),
+                Err(IggyError::UnsupportedHashAlgorithm(9))
+            ));
+
+            let bytes = Bytes::from(vec![PartitioningKind::HashedKey.as_code(), 1, 1]);
+            assert!(Partitioning::from_bytes(bytes).is_err());
+        }
+
+        #[test]
+        fn partitioning_kind_name_should_be_stable_and_match_display() {
+            let kinds = [
+                (PartitioningKind::Balanced, "balanced"),
+                (PartitioningKind::PartitionId, "partition_id"),
+                (PartitioningKind::MessagesKey, "messages_key"),
+                (PartitioningKind::ConsistentKey, "consistent_key"),
+                (PartitioningKind::HashedKey, "hashed_key"),
+            ];
+
+            for (kind, name) in kinds {
+                assert_eq!(kind.name(), name);
+                assert_eq!(kind.to_string(), name);
+            }
+        }
+
+        #[test]
+        fn composite_messages_keys_should_differ_for_tuples_with_same_concatenation() {
+            let first = Partitioning::messages_key_composite(&[b"a", b"bc"]).unwrap();
+            let second = Partitioning::messages_key_composite(&[b"ab", b"c"]).unwrap();
+
+            assert_eq!(first.kind, PartitioningKind::MessagesKey);
+            assert_eq!(first.value, vec![1, b'a', 2, b'b', b'c']);
+            assert_eq!(first.length, 5);
+            assert_ne!(first.value, second.value);
+            assert_eq!(Partitioning::from_bytes(first.as_bytes()).unwrap(), first);
+            assert_eq!(Partitioning::from_bytes(second.as_bytes()).unwrap(), second);
+        }
+
+        #[test]
+        fn composite_messages_key_should_not_exceed_max_length() {
+            let part = [1u8; 200];
+
+            assert!(Partitioning::messages_key_composite(&[&part, &part]).is_err());
+            assert!(Partitioning::messages_key_composite(&[]).is_err());
+        }
+
+        #[test]
+        fn partitioning_should_be_created_from_each_config_variant() {
+            let configs: Vec<PartitioningConfig> = serde_json::from_str(
+                r#"[
+                    { "kind": "balanced" },
+                    { "kind": "partition_id", "value": 3 },
+                    { "kind": "messages_key", "value": "orders" },
+                    { "kind": "consistent_key", "value": "tenant-1" }
+                ]"#,
+            )
+            .unwrap();
+
+            let partitionings = configs
+                .iter()
+                .map(|config| Partitioning::try_from_config(config).unwrap())
+                .collect::<Vec<_>>();
+
+            assert_eq!(
+                partitionings,
+                vec![
+                    Partitioning::balanced(),
+                    Partitioning::partition_id(3),
+                    Partitioning::messages_key_str("orders").unwrap(),
+                    Partitioning::consistent_key(b"tenant-1").unwrap(),
+                ]
+            );
+            assert_eq!(PartitioningConfig::default(), PartitioningConfig::Balanced);
+        }
+
+        #[test]
+        fn zero_partition_id_should_be_rejected_with_invalid_partition_id() {
+            let counter = AtomicU32::new(0);
+
+            assert!(matches!(
+                Partitioning::try_from_config(&PartitioningConfig::PartitionId { value: 0 }),
+                Err(IggyError::InvalidPartitionId(0))
+            ));
+            assert!(matches!(
+                Partitioning::partition_id(0).resolve_partition(3, &counter),
+                Err(IggyError::InvalidPartitionId(0))
+            ));
+            assert!(matches!(
+                Partitioning::messages_key_with(b"orders", HashAlgo::Xxh64)
+                    .unwrap()
+                    .resolve_partition(0, &counter),
+                Err(IggyError::InvalidPartitionId(0))
+            ));
+            assert_eq!(
+                IggyError::InvalidPartitionId(0).to_string(),
+                "Invalid partition ID: 0, the partition IDs start from 1"
+            );
+        }
+
+        #[test]
+        fn partitioning_config_with_invalid_key_length_should_fail() {
+            let too_long = "a".repeat(256);
+            let configs = [
+                PartitioningConfig::MessagesKey {
+                    value: too_long.clone(),
+                },
+                PartitioningConfig::ConsistentKey { value: too_long },
+                PartitioningConfig::MessagesKey {
+                    value: String::new(),
+                },
+            ];
+
+            for config in &configs {
+                assert!(matches!(
+                    Partitioning::try_from_config(config),
+                    Err(IggyError::InvalidKeyValueLength)
+                ));
+            }
+            assert!(
+                Partitioning::try_from_config(&PartitioningConfig::MessagesKey {
+                    value: "a".repeat(255)
+                })
+                .is_ok()
+            );
+        }
+
+        #[test]
+        fn key_of_type_partition_in_range_should_be_created() {
+            let key = Partitioning::partition_id_checked(3, 3).unwrap();
+            assert_eq!(key, Partitioning::partition_id(3));
+        }
+
+        #[test]
+        fn key_of_type_partition_with_id_0_should_fail() {
+            let key = Partitioning::partition_id_checked(0, 3);
+            assert!(matches!(key, Err(IggyError::InvalidPartitionId(0))));
+        }
+
+        #[test]
+        fn key_of_type_partition_with_id_out_of_range_should_fail() {
+            let key = Partitioning::partition_id_checked(4, 3);
+            assert!(matches!(key, Err(IggyError::PartitionIdOutOfRange(4, 3))));
+        }
+
+        #[test]
+        fn numeric_messages_keys_should_be_displayed_as_numbers() {
+            assert_eq!(
+                Partitioning::messages_key_u32(1234).to_string(),
+                "messages_key|1234"
+            );
+            assert_eq!(
+                Partitioning::messages_key_u64(u64::MAX).to_string(),
+                format!("messages_key|{}", u64::MAX)
+            );
+            assert_eq!(
+                Partitioning::messages_key_u128(u128::MAX).to_string(),
+                format!("messages_key|{}", u128::MAX)
+            );
+        }
+
+        #[test]
+        fn binary_messages_key_should_be_displayed_as_hex() {
+            let key = Partitioning::messages_key(&[0x01, 0xab, 0xff]).unwrap();
+            assert_eq!(key.to_string(), "messages_key|0x01abff");
+        }
+
+        #[test]
+        fn string_messages_key_should_be_displayed_as_string() {
+            let key = Partitioning::messages_key_str("hello world").unwrap();
+            assert_eq!(key.to_string(), "messages_key|hello world");
+        }
+
+        #[test]
+        fn string_messages_keys_of_numeric_lengths_should_be_displayed_as_strings() {
+            for key in ["user", "order-id", "0123456789abcdef"] {
+                assert_eq!(
+                    Partitioning::messages_key_str(key).unwrap().to_string(),
+                    format!("messages_key|{key}")
+                );
+            }
+        }
+
+        #[test]
+        fn malformed_partition_id_should_be_displayed_without_panicking() {
+            let key = Partitioning {
+                kind: PartitioningKind::PartitionId,
+                length: 2,
+                value: vec![0x01, 0x02],
+            };
+            assert_eq!(key.to_string(), "partition_id|0x0102");
+        }
+
+        #[test]
+        fn key_of_type_consistent_key_should_be_serialized_with_code_4() {
+            let key = Partitioning::consistent_key(b"hello").unwrap();
+            assert_eq!(key.kind, PartitioningKind::ConsistentKey);
+            assert_eq!(key.length, 5);
+            assert_eq!(key.value, b"hello");
+            assert_eq!(key.to_string(), "consistent_key|hello");
+
+            let bytes = key.as_bytes();
+            assert_eq!(bytes[0], 4);
+            assert_eq!(Partitioning::from_bytes(bytes).unwrap(), key);
+            assert_eq!(
+                PartitioningKind::from_code(4).unwrap(),
+                PartitioningKind::ConsistentKey
+            );
+        }
     }
 
-    #[test]
-    fn key_of_type_messages_key_that_has_length_0_should_fail() {
-        let messages_key = "";
-        let key = Partitioning::messages_key_str(messages_key);
-        assert!(key.is_err());
+    mod partition_ids {
+        use super::*;
+
+        #[test]
+        fn message_with_partition_id_should_be_serialized_and_deserialized() {
+            let message = Message::new(Some(7), "hello".into(), None).with_partition_id(3);
+
+            let bytes = message.as_bytes();
+            assert_eq!(bytes.len() as u64, message.get_size_bytes());
+            assert_eq!(
+                bytes.len() as u64,
+                Message::new(Some(7), "hello".into(), None).get_size_bytes() + 4
+            );
+
+            let view = MessageView::new(&bytes).unwrap();
+            assert_eq!(view.partition_id(), Some(3));
+            assert_eq!(view.payload(), b"hello");
+
+            let deserialized = Message::from_bytes(bytes).unwrap();
+            assert_eq!(deserialized, message);
+        }
+
+        #[test]
+        fn message_without_partition_id_should_keep_the_legacy_format() {
+            let message = Message::new(Some(7), "hello".into(), None);
+
+            let bytes = message.as_bytes();
+
+            assert_eq!(bytes.len(), MIN_MESSAGE_SIZE + 5);
+            assert_eq!(u32::from_le_bytes(bytes[16..20].try_into().unwrap()), 0);
+            assert_eq!(MessageView::new(&bytes).unwrap().partition_id(), None);
+            assert_eq!(Message::from_bytes(bytes).unwrap().partition_id, None);
+        }
+
+        #[test]
+        fn partition_id_should_follow_the_compression_codes() {
+            let headers = HashMap::from([(
+                HeaderKey::new("key").unwrap(),
+                HeaderValue::from_str(&"value".repeat(50)).unwrap(),
+            )]);
+            let message = Message::new_compressed(
+                Some(1),
+                Bytes::from("hello ".repeat(100)),
+                CompressionAlgorithm::Gzip,
+                Some(headers),
+            )
+            .unwrap()
+            .with_compressed_headers(CompressionAlgorithm::Gzip)
+            .with_partition_id(u32::MAX);
+
+            let bytes = message.as_bytes();
+            let view = MessageView::new(&bytes).unwrap();
+
+            assert_eq!(view.compression().unwrap(), CompressionAlgorithm::Gzip);
+            assert_eq!(
+                view.headers_compression().unwrap(),
+                CompressionAlgorithm::Gzip
+            );
+            assert_eq!(view.partition_id(), Some(u32::MAX));
+            assert_eq!(
+                Message::from_bytes(bytes).unwrap().partition_id,
+                Some(u32::MAX)
+            );
+        }
+
+        #[test]
+        fn validation_should_reject_partition_id_conflicting_with_batch_partition_id() {
+            let command = send_messages(
+                Partitioning::partition_id(1),
+                vec![
+                    Message::from_str("hello 1").unwrap().with_partition_id(1),
+                    Message::from_str("hello 2").unwrap().with_partition_id(2),
+                ],
+            );
+
+            let error = command.validate().unwrap_err();
+
+            assert!(matches!(
+                error,
+                IggyError::InvalidMessageAtIndex(1, inner)
+                    if matches!(*inner, IggyError::ConflictingMessagePartitionId(2, 1))
+            ));
+        }
+
+        #[test]
+        fn validation_should_accept_partition_ids_with_calculated_partitioning() {
+            for partitioning in [
+                Partitioning::balanced(),
+                Partitioning::messages_key_str("order-1").unwrap(),
+            ] {
+                let command = send_messages(
+                    partitioning,
+                    vec![
+                        Message::from_str("hello 1").unwrap().with_partition_id(2),
+                        Message::from_str("hello 2").unwrap(),
+                        Message::from_str("hello 3").unwrap().with_partition_id(3),
+                    ],
+                );
+
+                assert!(command.validate().is_ok());
+            }
+        }
     }
 
-    #[test]
-    fn key_of_type_messages_key_that_has_length_greater_than_255_should_fail() {
-        let messages_key = "a".repeat(256);
-        let key = Partitioning::messages_key_str(&messages_key);
-        assert!(key.is_err());
+    mod checksums {
+        use super::*;
+
+        #[test]
+        fn message_with_payload_checksum_should_be_serialized_and_deserialized() {
+            let message = Message::new(Some(7), "hello".into(), None)
+                .with_partition_id(3)
+                .with_payload_checksum();
+
+            let bytes = message.as_bytes();
+            assert_eq!(bytes.len() as u64, message.get_size_bytes());
+            assert_eq!(
+                bytes.len() as u64,
+                Message::new(Some(7), "hello".into(), None)
+                    .with_partition_id(3)
+                    .get_size_bytes()
+                    + 4
+            );
+            assert_eq!(message.checksum(), Some(checksum::calculate(b"hello")));
+
+            let view = MessageView::new(&bytes).unwrap();
+            assert_eq!(view.checksum(), message.checksum());
+            assert_eq!(view.partition_id(), Some(3));
+            assert_eq!(view.payload(), b"hello");
+
+            let deserialized = Message::from_bytes(bytes).unwrap();
+            assert_eq!(deserialized, message);
+        }
+
+        #[test]
+        fn message_without_payload_checksum_should_keep_the_same_format() {
+            let message = Message::new(Some(7), "hello".into(), None);
+
+            let bytes = message.as_bytes();
+
+            assert_eq!(message.checksum(), None);
+            assert_eq!(bytes.len(), MIN_MESSAGE_SIZE + 5);
+            assert_eq!(u32::from_le_bytes(bytes[16..20].try_into().unwrap()), 0);
+            assert_eq!(MessageView::new(&bytes).unwrap().checksum(), None);
+            assert!(!Message::from_bytes(bytes).unwrap().payload_checksum);
+        }
+
+        #[test]
+        fn message_with_corrupted_payload_should_fail_checksum_verification() {
+            let message = Message::new(Some(7), "hello".into(), None).with_payload_checksum();
+            let expected_checksum = message.checksum().unwrap();
+            let mut bytes = BytesMut::from(&message.as_bytes()[..]);
+            let last = bytes.len() - 1;
+            bytes[last] ^= 0xFF;
+
+            let result = Message::from_bytes(bytes.freeze());
+
+            assert!(matches!(
+                result,
+                Err(IggyError::PayloadChecksumMismatch(checksum, expected))
+                    if checksum == checksum::calculate(b"hell\x90") && expected == expected_checksum
+            ));
+        }
+
+        #[test]
+        fn batch_checksum_should_change_when_any_message_payload_changes() {
+            let mut command = send_messages(
+                Partitioning::partition_id(1),
+                vec![
+                    Message::new(Some(1), "hello 1".into(), None),
+                    Message::new(Some(2), "hello 2".into(), None),
+                ],
+            );
+            let checksum = command.batch_checksum();
+
+            command.messages[1].payload = "hello 3".into();
+
+            assert_ne!(checksum, command.batch_checksum());
+        }
+
+        #[test]
+        fn batch_with_checksum_should_be_deserialized_and_verified() {
+            let command = SendMessages {
+                topic_id: Identifier::named("topic").unwrap(),
+                ..send_messages(
+                    Partitioning::messages_key_str("key").unwrap(),
+                    vec![
+                        Message::new(Some(1), "hello 1".into(), None),
+                        Message::new(Some(2), "hello 2".into(), None),
+                    ],
+                )
+            };
+            let bytes = command.as_bytes_with_checksum();
+
+            assert_eq!(
+                u32::from_le_bytes(bytes[bytes.len() - 4..].try_into().unwrap()),
+                command.batch_checksum()
+            );
+            assert_eq!(SendMessages::from_bytes(bytes.clone()).unwrap(), command);
+            assert_eq!(
+                SendMessages::from_bytes(command.as_bytes()).unwrap(),
+                command
+            );
+
+            let mut corrupted_bytes = BytesMut::from(&bytes[..]);
+            let payload_position = bytes.len() - 4 - 1;
+            corrupted_bytes[payload_position] ^= 1;
+            let error = SendMessages::from_bytes(corrupted_bytes.freeze()).unwrap_err();
+            assert!(matches!(error, IggyError::InvalidBatchChecksum(_, _)));
+
+            let truncated_bytes = bytes.slice(..bytes.len() - 10);
+            assert!(SendMessages::from_bytes(truncated_bytes).is_err());
+        }
+    }
+
+    mod idempotency {
+        use super::*;
+
+        #[test]
+        fn same_batch_content_should_yield_the_same_idempotency_key() {
+            for seed in 1..100 {
+                let command = random_command(&mut Xorshift(seed)).with_auto_idempotency_key();
+                let copy = random_command(&mut Xorshift(seed)).with_auto_idempotency_key();
+
+                assert!(command.idempotency_key.is_some());
+                assert_eq!(command.idempotency_key, copy.idempotency_key);
+                assert_eq!(command.idempotency_key, Some(command.fingerprint()));
+            }
+        }
+
+        #[test]
+        fn different_batch_content_should_yield_different_idempotency_key() {
+            let command = send_messages(
+                Partitioning::partition_id(1),
+                vec![Message::new(Some(1), "hello".into(), None)],
+            )
+            .with_auto_idempotency_key();
+            let other_payload = send_messages(
+                Partitioning::partition_id(1),
+                vec![Message::new(Some(1), "hellO".into(), None)],
+            )
+            .with_auto_idempotency_key();
+            let other_partition = send_messages(
+                Partitioning::partition_id(2),
+                vec![Message::new(Some(1), "hello".into(), None)],
+            )
+            .with_auto_idempotency_key();
+
+            assert_ne!(command.idempotency_key, other_payload.idempotency_key);
+            assert_ne!(command.idempotency_key, other_partition.idempotency_key);
+        }
+
+        #[test]
+        fn idempotency_key_should_be_serialized_only_when_set() {
+            let mut random = Xorshift(29);
+            for _ in 0..100 {
+                let command = random_command(&mut random);
+                let bytes = command.as_bytes();
+                let command = command.with_auto_idempotency_key();
+                let bytes_with_key = command.as_bytes();
+
+                assert_eq!(
+                    bytes_with_key.len(),
+                    bytes.len() + IDEMPOTENCY_KEY_SIZE as usize
+                );
+                assert_eq!(bytes_with_key.len() as u64, command.wire_size());
+                assert_eq!(command.into_bytes(), bytes_with_key);
+            }
+        }
+
+        #[test]
+        fn command_with_idempotency_key_should_be_deserialized_from_bytes() {
+            let command = SendMessages {
+                topic_id: Identifier::named("topic").unwrap(),
+                ..send_messages(
+                    Partitioning::messages_key_str("order-1").unwrap(),
+                    vec![
+                        Message::new(Some(1), "hello 1".into(), None),
+                        Message::new(None, "hello 2".into(), None),
+                    ],
+                )
+            }
+            .with_auto_idempotency_key();
+
+            let deserialized = SendMessages::from_bytes(command.as_bytes()).unwrap();
+            assert_eq!(deserialized, command);
+
+            let deserialized = SendMessages::from_bytes(command.as_bytes_with_checksum()).unwrap();
+            assert_eq!(deserialized, command);
+        }
+
+        #[test]
+        fn truncated_idempotency_key_should_not_be_deserialized() {
+            let command = SendMessages {
+                idempotency_key: Some(7),
+                ..send_messages(Partitioning::partition_id(1), vec![])
+            };
+            let bytes = command.as_bytes();
+
+            let result = SendMessages::from_bytes(bytes.slice(..bytes.len() - 1));
+
+            assert!(matches!(result, Err(IggyError::InvalidCommand)));
+        }
+    }
+
+    mod compression {
+        use super::*;
+
+        #[test]
+        fn compressed_message_should_be_decompressed_when_deserialized() {
+            let payload = Bytes::from("hello world ".repeat(100));
+            let headers = HashMap::from([(
+                HeaderKey::new("key").unwrap(),
+                HeaderValue::from_str("value").unwrap(),
+            )]);
+            let compressions = vec![
+                CompressionAlgorithm::Gzip,
+                #[cfg(feature = "zstd")]
+                CompressionAlgorithm::Zstd,
+                #[cfg(feature = "lz4")]
+                CompressionAlgorithm::Lz4,
+            ];
+            for compression in compressions {
+                let message = Message::new_compressed(
+                    Some(1),
+                    payload.clone(),
+                    compression.clone(),
+                    Some(headers.clone()),
+                )
+                .unwrap();
+                assert!(message.payload.len() < payload.len());
+                assert_eq!(
+                    message.get_uncompressed_payload_length().unwrap(),
+                    payload.len() as u32
+                );
+
+                let bytes = message.as_bytes();
+                assert_eq!(bytes.len(), message.get_size_bytes() as usize);
+                let view = MessageView::new(&bytes).unwrap();
+                assert_eq!(view.compression().unwrap(), compression);
+                assert_eq!(view.payload(), &message.payload[..]);
+
+                let deserialized_message = Message::from_bytes(bytes).unwrap();
+                assert_eq!(deserialized_message.id, 1);
+                assert_eq!(deserialized_message.payload, payload);
+                assert_eq!(deserialized_message.length, payload.len() as u32);
+                assert_eq!(deserialized_message.headers.as_ref().unwrap(), &headers);
+                assert_eq!(deserialized_message.compression, CompressionAlgorithm::None);
+            }
+        }
+
+        #[test]
+        fn compressed_message_should_be_decompressed() {
+            let payload = Bytes::from("hello world ".repeat(100));
+            let mut message =
+                Message::new_compressed(Some(1), payload.clone(), CompressionAlgorithm::Gzip, None)
+                    .unwrap();
+
+            let error = message.clone().decompress(payload.len() - 1).unwrap_err();
+            assert_eq!(
+                error.as_code(),
+                IggyError::DecompressionLimitExceeded.as_code()
+            );
+
+            message.decompress(payload.len()).unwrap();
+            assert_eq!(message.payload, payload);
+            assert_eq!(message.length, payload.len() as u32);
+            assert_eq!(message.compression, CompressionAlgorithm::None);
+        }
+
+        #[test]
+        fn compressed_messages_should_not_be_decompressed_beyond_batch_payload_size() {
+            let payload = Bytes::from(vec![0; MAX_PAYLOAD_SIZE as usize / 2 + 1]);
+            let message =
+                Message::new_compressed(None, payload, CompressionAlgorithm::Gzip, None).unwrap();
+            let command = SendMessages {
+                messages: vec![message.clone(), message],
+                ..SendMessages::default()
+            };
+
+            // The second message exceeds the budget while it's decompressed, before the whole batch is validated.
+            let error = SendMessages::from_bytes(command.as_bytes()).unwrap_err();
+            assert_eq!(error.as_code(), IggyError::TooBigMessagePayload.as_code());
+        }
+
+        #[test]
+        fn message_with_compressed_headers_should_be_decompressed_when_deserialized() {
+            let headers = repetitive_headers();
+            let message = Message::new(Some(1), "hello".into(), Some(headers.clone()))
+                .with_compressed_headers(CompressionAlgorithm::Gzip);
+
+            let bytes = message.as_bytes();
+            assert_eq!(bytes.len(), message.get_size_bytes() as usize);
+            let view = MessageView::new(&bytes).unwrap();
+            assert_eq!(view.compression().unwrap(), CompressionAlgorithm::None);
+            assert_eq!(
+                view.headers_compression().unwrap(),
+                CompressionAlgorithm::Gzip
+            );
+            assert_eq!(view.payload(), b"hello");
+
+            let deserialized_message = Message::from_bytes(bytes).unwrap();
+            assert_eq!(deserialized_message.id, 1);
+            assert_eq!(deserialized_message.payload, Bytes::from("hello"));
+            assert_eq!(deserialized_message.headers.as_ref().unwrap(), &headers);
+            assert_eq!(
+                deserialized_message.headers_compression,
+                CompressionAlgorithm::None
+            );
+        }
+
+        #[test]
+        fn message_with_compressed_headers_and_payload_should_be_deserialized() {
+            let headers = repetitive_headers();
+            let payload = Bytes::from("hello world ".repeat(100));
+            let message = Message::new_compressed(
+                Some(1),
+                payload.clone(),
+                CompressionAlgorithm::Gzip,
+                Some(headers.clone()),
+            )
+            .unwrap()
+            .with_compressed_headers(CompressionAlgorithm::Gzip);
+
+            let bytes = message.as_bytes();
+            assert_eq!(bytes.len(), message.get_size_bytes() as usize);
+            let view = MessageView::new(&bytes).unwrap();
+            assert_eq!(view.compression().unwrap(), CompressionAlgorithm::Gzip);
+            assert_eq!(
+                view.headers_compression().unwrap(),
+                CompressionAlgorithm::Gzip
+            );
+
+            let deserialized_message = Message::from_bytes(bytes).unwrap();
+            assert_eq!(deserialized_message.payload, payload);
+            assert_eq!(deserialized_message.headers.as_ref().unwrap(), &headers);
+        }
+
+        #[test]
+        fn compressed_repetitive_headers_should_reduce_message_size() {
+            let headers = repetitive_headers();
+            let message = Message::new(Some(1), "hello".into(), Some(headers.clone()));
+            let compressed_message = Message::new(Some(1), "hello".into(), Some(headers))
+                .with_compressed_headers(CompressionAlgorithm::Gzip);
+
+            let size = message.as_bytes().len();
+            let compressed_size = compressed_message.as_bytes().len();
+
+            assert!(compressed_size < size / 2);
+        }
+
+        #[test]
+        fn compressed_headers_should_not_be_decompressed_beyond_batch_headers_size() {
+            let value = "a".repeat(255);
+            let headers: HashMap<HeaderKey, HeaderValue> = (0..200)
+                .map(|index| {
+                    (
+                        HeaderKey::new(&format!("key-{index}")).unwrap(),
+                        HeaderValue::from_str(&value).unwrap(),
+                    )
+                })
+                .collect();
+            assert!(header::get_headers_size_bytes(&Some(headers.clone())) > MAX_HEADERS_SIZE / 2);
+            let message = Message::new(None, "hello".into(), Some(headers))
+                .with_compressed_headers(CompressionAlgorithm::Gzip);
+            let command = SendMessages {
+                messages: vec![message.clone(), message],
+                ..SendMessages::default()
+            };
+
+            // The second message exceeds the budget while its headers are decompressed.
+            let error = SendMessages::from_bytes(command.as_bytes()).unwrap_err();
+            assert_eq!(error.as_code(), IggyError::TooBigHeadersPayload.as_code());
+        }
+
+        #[test]
+        fn compressed_headers_should_follow_headers_change() {
+            let mut message = Message::new(Some(1), "hello".into(), Some(repetitive_headers()))
+                .with_compressed_headers(CompressionAlgorithm::Gzip);
+            let size = message.get_size_bytes();
+            let (compressed_bytes, compressed) = message.headers_as_bytes();
+            assert!(compressed);
+            assert_eq!(message.as_bytes().len(), size as usize);
+
+            let mut headers = repetitive_headers();
+            headers.insert(
+                HeaderKey::new("extra").unwrap(),
+                HeaderValue::from_str("value").unwrap(),
+            );
+            message.headers = Some(Headers::try_from(headers.clone()).unwrap());
+
+            let bytes = message.as_bytes();
+            assert_ne!(message.headers_as_bytes().0, compressed_bytes);
+            assert_eq!(bytes.len(), message.get_size_bytes() as usize);
+            assert_eq!(
+                Message::from_bytes(bytes).unwrap().take_headers(),
+                Some(headers)
+            );
+        }
+
+        #[test]
+        fn headers_should_not_be_compressed_when_compression_does_not_reduce_size() {
+            let headers = HashMap::from([(
+                HeaderKey::new("key").unwrap(),
+                HeaderValue::from_str("value").unwrap(),
+            )]);
+            let message = Message::new(Some(1), "hello".into(), Some(headers.clone()))
+                .with_compressed_headers(CompressionAlgorithm::Gzip);
+
+            let bytes = message.as_bytes();
+            assert_eq!(
+                bytes,
+                Message::new(Some(1), "hello".into(), Some(headers.clone())).as_bytes()
+            );
+            let view = MessageView::new(&bytes).unwrap();
+            assert_eq!(
+                view.headers_compression().unwrap(),
+                CompressionAlgorithm::None
+            );
+            assert_eq!(
+                Message::from_bytes(bytes).unwrap().take_headers(),
+                Some(headers)
+            );
+        }
+
+        fn repetitive_headers() -> HashMap<HeaderKey, HeaderValue> {
+            (0..50)
+                .map(|index| {
+                    (
+                        HeaderKey::new(&format!("tenant.region.service.{index}")).unwrap(),
+                        HeaderValue::from_str("eu-west-1.production.orders").unwrap(),
+                    )
+                })
+                .collect()
+        }
+
+        #[test]
+        fn batch_with_compressed_messages_should_be_deserialized() {
+            let payload = Bytes::from("hello world ".repeat(100));
+            let command = SendMessages {
+                messages: vec![
+                    Message::new_compressed(
+                        Some(1),
+                        payload.clone(),
+                        CompressionAlgorithm::Gzip,
+                        None,
+                    )
+                    .unwrap(),
+                    Message::new(Some(2), payload.clone(), None),
+                ],
+                ..SendMessages::default()
+            };
+
+            let deserialized_command = SendMessages::from_bytes(command.as_bytes()).unwrap();
+            assert_eq!(deserialized_command.messages.len(), 2);
+            for message in deserialized_command.messages {
+                assert_eq!(message.payload, payload);
+            }
+        }
+
+        #[test]
+        fn compressed_message_payload_size_should_be_validated_when_uncompressed() {
+            let payload = Bytes::from(vec![0; MAX_PAYLOAD_SIZE as usize + 1]);
+            let message =
+                Message::new_compressed(None, payload, CompressionAlgorithm::Gzip, None).unwrap();
+            assert!(message.payload.len() < MAX_PAYLOAD_SIZE as usize);
+            let command = SendMessages {
+                messages: vec![message],
+                ..SendMessages::default()
+            };
+
+            let error = command.validate().unwrap_err();
+            assert!(matches!(
+                &error,
+                IggyError::InvalidMessageAtIndex(0, inner) if matches!(**inner, IggyError::TooBigMessagePayload)
+            ));
+        }
+    }
+
+    mod span_fields {
+        use super::*;
+
+        type CapturedFields = std::sync::Arc<std::sync::Mutex<HashMap<String, String>>>;
+
+        struct FieldsVisitor<'a>(&'a CapturedFields);
+
+        impl tracing::field::Visit for FieldsVisitor<'_> {
+            fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+                self.0
+                    .lock()
+                    .unwrap()
+                    .insert(field.name().to_string(), value.to_string());
+            }
+
+            fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+                self.0
+                    .lock()
+                    .unwrap()
+                    .insert(field.name().to_string(), format!("{value:?}"));
+            }
+        }
+
+        struct CapturingSubscriber(CapturedFields);
+
+        impl tracing::Subscriber for CapturingSubscriber {
+            fn enabled(&self, _: &tracing::Metadata<'_>) -> bool {
+                true
+            }
+
+            fn new_span(&self, span: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+                span.record(&mut FieldsVisitor(&self.0));
+                tracing::span::Id::from_u64(1)
+            }
+
+            fn record(&self, _: &tracing::span::Id, values: &tracing::span::Record<'_>) {
+                values.record(&mut FieldsVisitor(&self.0));
+            }
+
+            fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+
+            fn event(&self, _: &tracing::Event<'_>) {}
+
+            fn enter(&self, _: &tracing::span::Id) {}
+
+            fn exit(&self, _: &tracing::span::Id) {}
+        }
+
+        #[test]
+        fn record_fields_should_record_batch_details_on_span() {
+            let command = SendMessages {
+                stream_id: Identifier::named("orders").unwrap(),
+                ..send_messages(
+                    Partitioning::messages_key_str("customer-1").unwrap(),
+                    vec![
+                        Message::new(Some(1), Bytes::from_static(b"first"), None),
+                        Message::new(Some(2), Bytes::from_static(b"second"), None),
+                        Message::new(Some(3), Bytes::from_static(b"third"), None),
+                    ],
+                )
+            };
+            let fields = CapturedFields::default();
+
+            tracing::subscriber::with_default(CapturingSubscriber(fields.clone()), || {
+                let span = tracing::info_span!(
+                    "send_messages",
+                    stream_id = tracing::field::Empty,
+                    topic_id = tracing::field::Empty,
+                    partitioning_kind = tracing::field::Empty,
+                    message_count = tracing::field::Empty,
+                    total_bytes = tracing::field::Empty,
+                );
+                command.record_fields(&span);
+            });
+
+            let fields = fields.lock().unwrap();
+            assert_eq!(fields["stream_id"], "orders");
+            assert_eq!(fields["topic_id"], "2");
+            assert_eq!(fields["partitioning_kind"], "messages_key");
+            assert_eq!(fields["message_count"], "3");
+            assert_eq!(fields["total_bytes"], command.wire_size().to_string());
+            assert_eq!(fields.len(), 5);
+        }
     }
 }
@@ -0,0 +1,143 @@
+use crate::bytes_serializable::BytesSerializable;
+use crate::command::CommandPayload;
+use crate::error::IggyError;
+use crate::messages::send_messages::SendMessages;
+use crate::validatable::Validatable;
+use bytes::{BufMut, Bytes, BytesMut};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// `SendMessagesConditional` command is used to send messages to a topic in a stream only if the last message matches the expected ID (compare-and-swap).
+/// It has additional payload:
+/// - `expected_last_id` - the expected ID of the last message in the target partition, `0` if the partition is expected to be empty.
+/// - `send_messages` - the regular `SendMessages` command with the stream, topic, partitioning and messages to be sent.
+///
+/// The server contract is the following:
+/// - The target partition is resolved from the partitioning exactly as for `SendMessages`.
+/// - The ID of the last message in that partition is compared with `expected_last_id` while holding the partition write lock,
+///   so no other append can happen between the check and the append.
+/// - If the IDs match, the messages are appended, otherwise the whole batch is rejected with `IggyError::ConditionFailed`.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Default)]
+pub struct SendMessagesConditional {
+    /// The expected ID of the last message in the target partition, `0` if the partition is expected to be empty.
+    pub expected_last_id: u128,
+    /// The messages to be sent if the condition is met.
+    #[serde(flatten)]
+    pub send_messages: SendMessages,
+}
+
+impl CommandPayload for SendMessagesConditional {}
+
+impl SendMessagesConditional {
+    /// Create the new `SendMessagesConditional` command for the provided `SendMessages` and the expected ID of the last message.
+    pub fn new(expected_last_id: u128, send_messages: SendMessages) -> Self {
+        Self {
+            expected_last_id,
+            send_messages,
+        }
+    }
+
+    fn from_bytes_with(
+        bytes: Bytes,
+        send_messages_from_bytes: fn(Bytes) -> Result<SendMessages, IggyError>,
+    ) -> Result<SendMessagesConditional, IggyError> {
+        if bytes.len() < 16 {
+            return Err(IggyError::InvalidCommand);
+        }
+
+        let expected_last_id = u128::from_le_bytes(bytes[..16].try_into()?);
+        let send_messages = send_messages_from_bytes(bytes.slice(16..))?;
+        let command = SendMessagesConditional {
+            expected_last_id,
+            send_messages,
+        };
+        command.validate()?;
+        Ok(command)
+    }
+}
+
+impl Validatable<IggyError> for SendMessagesConditional {
+    fn validate(&self) -> Result<(), IggyError> {
+        self.send_messages.validate()
+    }
+}
+
+impl BytesSerializable for SendMessagesConditional {
+    fn as_bytes(&self) -> Bytes {
+        let send_messages_bytes = self.send_messages.as_bytes();
+        let mut bytes = BytesMut::with_capacity(16 + send_messages_bytes.len());
+        bytes.put_u128_le(self.expected_last_id);
+        bytes.put_slice(&send_messages_bytes);
+        bytes.freeze()
+    }
+
+    fn from_bytes(bytes: Bytes) -> Result<SendMessagesConditional, IggyError> {
+        Self::from_bytes_with(bytes, SendMessages::from_bytes)
+    }
+
+    fn from_bytes_checked(bytes: Bytes) -> Result<SendMessagesConditional, IggyError> {
+        Self::from_bytes_with(bytes, SendMessages::from_bytes_checked)
+    }
+}
+
+impl Display for SendMessagesConditional {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}|{}", self.expected_last_id, self.send_messages)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::identifier::Identifier;
+    use crate::messages::send_messages::{Message, Partitioning};
+    use std::str::FromStr;
+
+    #[test]
+    fn should_be_serialized_as_bytes_and_deserialized_from_bytes() {
+        let command = SendMessagesConditional::new(
+            123,
+            SendMessages::new(
+                Identifier::numeric(1).unwrap().into(),
+                Identifier::named("topic").unwrap().into(),
+                Partitioning::partition_id(2),
+                vec![
+                    Message::from_str("hello 1").unwrap(),
+                    Message::from_str("hello 2").unwrap(),
+                ],
+            ),
+        );
+
+        let bytes = command.as_bytes();
+        let deserialized_command = SendMessagesConditional::from_bytes(bytes.clone()).unwrap();
+
+        assert_eq!(u128::from_le_bytes(bytes[..16].try_into().unwrap()), 123);
+        assert_eq!(bytes.slice(16..), command.send_messages.as_bytes());
+        assert_eq!(deserialized_command, command);
+    }
+
+    #[test]
+    fn should_not_be_deserialized_from_too_short_bytes() {
+        let command = SendMessagesConditional::from_bytes(Bytes::from_static(&[1, 2, 3]));
+
+        assert!(matches!(command, Err(IggyError::InvalidCommand)));
+    }
+
+    #[test]
+    fn should_not_be_valid_without_messages() {
+        let command = SendMessagesConditional::new(
+            1,
+            SendMessages::new(
+                Identifier::numeric(1).unwrap().into(),
+                Identifier::numeric(1).unwrap().into(),
+                Partitioning::partition_id(1),
+                vec![],
+            ),
+        );
+
+        assert!(matches!(
+            command.validate(),
+            Err(IggyError::InvalidMessagesCount)
+        ));
+    }
+}
@@ -0,0 +1,529 @@
+use crate::compression::compression_algorithm::CompressionAlgorithm;
+use crate::error::IggyError;
+use crate::identifier::Identifier;
+use crate::messages::send_messages::{Message, Partitioning, PartitioningKind, SendMessages};
+use crate::models::header::{HeaderKey, HeaderKind, HeaderValue};
+use crate::utils::key_hash::HashAlgo;
+use crate::utils::text;
+use crate::validatable::Validatable;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+
+/// The prefix marking the text as the base64 encoded binary data, anything else is treated as the plain UTF-8 text.
+const BASE64_PREFIX: &str = "base64:";
+
+/// The human-friendly JSON representation of `SendMessages`, e.g.
+///
+/// ```json
+/// {
+///   "stream_id": 1,
+///   "topic_id": "orders",
+///   "partitioning": { "kind": "messages_key", "value": "order-1" },
+///   "messages": [
+///     { "id": 1, "payload": "hello", "headers": { "trace": { "kind": "string", "value": "abc" } } },
+///     { "payload": "base64:AAEC" }
+///   ]
+/// }
+/// ```
+///
+/// The payloads and the keys are plain UTF-8 strings, or base64 prefixed with `base64:` for the binary data.
+/// The header values are typed by the `HeaderKind` name, the 128-bit integers are written as the decimal strings.
+#[derive(Debug, Serialize, Deserialize)]
+struct JsonSendMessages {
+    stream_id: JsonIdentifier,
+    topic_id: JsonIdentifier,
+    #[serde(default)]
+    partitioning: JsonPartitioning,
+    messages: Vec<JsonMessage>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    allow_tombstones: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum JsonIdentifier {
+    Numeric(u32),
+    Named(String),
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum JsonPartitioning {
+    #[default]
+    Balanced,
+    PartitionId {
+        value: u32,
+    },
+    MessagesKey {
+        value: String,
+    },
+    ConsistentKey {
+        value: String,
+    },
+    HashedKey {
+        #[serde(default)]
+        algorithm: HashAlgo,
+        value: String,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct JsonMessage {
+    #[serde(default)]
+    id: u128,
+    payload: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    headers: Option<BTreeMap<String, JsonHeaderValue>>,
+    #[serde(default, skip_serializing_if = "is_uncompressed")]
+    compression: CompressionAlgorithm,
+    #[serde(default, skip_serializing_if = "is_uncompressed")]
+    headers_compression: CompressionAlgorithm,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    partition_id: Option<u32>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    payload_checksum: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct JsonHeaderValue {
+    kind: String,
+    value: Value,
+}
+
+impl SendMessages {
+    /// Serialize the command into the human-friendly JSON, see `from_json_str` for the format.
+    /// It's meant for the CLI and the test fixtures, and doesn't affect the binary wire format.
+    pub fn to_json(&self) -> String {
+        let json = JsonSendMessages {
+            stream_id: JsonIdentifier::from(&self.stream_id),
+            topic_id: JsonIdentifier::from(&self.topic_id),
+            partitioning: JsonPartitioning::from(&self.partitioning),
+            messages: self.messages().iter().map(JsonMessage::from).collect(),
+            allow_tombstones: self.allow_tombstones,
+        };
+        serde_json::to_string_pretty(&json).expect("JSON batch is always serializable")
+    }
+
+    /// Deserialize the command from the human-friendly JSON, in which:
+    /// - `stream_id` and `topic_id` are either numbers or names.
+    /// - `partitioning` is optional (balanced by default), e.g. `{ "kind": "partition_id", "value": 1 }`.
+    /// - `payload` and the messages key are plain UTF-8 strings, or base64 prefixed with `base64:` for the binary data.
+    /// - `id` is optional (generated by the server by default).
+    /// - `partition_id` is optional, overriding the partitioning for the message.
+    /// - `payload_checksum` is optional (false by default), sending the CRC32 checksum of the payload along with the message.
+    /// - `headers` map the keys to the values typed by the `HeaderKind` name, e.g. `{ "kind": "uint32", "value": 7 }`.
+    /// - `allow_tombstones` is optional (false by default), allowing the messages with the empty payload.
+    ///
+    /// The command is validated like the one deserialized from the bytes.
+    pub fn from_json_str(json: &str) -> Result<Self, IggyError> {
+        let json: JsonSendMessages = serde_json::from_str(json)
+            .map_err(|error| IggyError::CannotParseJson(error.to_string()))?;
+        let mut command = SendMessages::new(
+            Identifier::try_from(json.stream_id)?.into(),
+            Identifier::try_from(json.topic_id)?.into(),
+            json.partitioning.try_into()?,
+            json.messages
+                .into_iter()
+                .map(Message::try_from)
+                .collect::<Result<Vec<_>, _>>()?,
+        );
+        command.allow_tombstones = json.allow_tombstones;
+        command.validate()?;
+        Ok(command)
+    }
+}
+
+impl From<&Identifier> for JsonIdentifier {
+    fn from(identifier: &Identifier) -> Self {
+        match identifier.get_u32_value() {
+            Ok(value) => JsonIdentifier::Numeric(value),
+            Err(_) => JsonIdentifier::Named(identifier.as_string()),
+        }
+    }
+}
+
+impl TryFrom<JsonIdentifier> for Identifier {
+    type Error = IggyError;
+
+    fn try_from(identifier: JsonIdentifier) -> Result<Self, Self::Error> {
+        match identifier {
+            JsonIdentifier::Numeric(value) => Identifier::numeric(value),
+            JsonIdentifier::Named(value) => Identifier::from_str(&value),
+        }
+    }
+}
+
+impl From<&Partitioning> for JsonPartitioning {
+    fn from(partitioning: &Partitioning) -> Self {
+        match partitioning.kind {
+            PartitioningKind::Balanced => JsonPartitioning::Balanced,
+            PartitioningKind::PartitionId => JsonPartitioning::PartitionId {
+                value: partitioning
+                    .value
+                    .as_slice()
+                    .try_into()
+                    .map_or(0, u32::from_le_bytes),
+            },
+            PartitioningKind::MessagesKey => JsonPartitioning::MessagesKey {
+                value: encode_bytes(&partitioning.value),
+            },
+            PartitioningKind::ConsistentKey => JsonPartitioning::ConsistentKey {
+                value: encode_bytes(&partitioning.value),
+            },
+            PartitioningKind::HashedKey => JsonPartitioning::HashedKey {
+                algorithm: partitioning.hash_algo().unwrap_or_default(),
+                value: encode_bytes(partitioning.value.get(1..).unwrap_or_default()),
+            },
+        }
+    }
+}
+
+impl TryFrom<JsonPartitioning> for Partitioning {
+    type Error = IggyError;
+
+    fn try_from(partitioning: JsonPartitioning) -> Result<Self, Self::Error> {
+        match partitioning {
+            JsonPartitioning::Balanced => Ok(Partitioning::balanced()),
+            JsonPartitioning::PartitionId { value } => Ok(Partitioning::partition_id(value)),
+            JsonPartitioning::MessagesKey { value } => {
+                Partitioning::messages_key(&decode_bytes(&value)?)
+            }
+            JsonPartitioning::ConsistentKey { value } => {
+                Partitioning::consistent_key(&decode_bytes(&value)?)
+            }
+            JsonPartitioning::HashedKey { algorithm, value } => {
+                Partitioning::messages_key_with(&decode_bytes(&value)?, algorithm)
+            }
+        }
+    }
+}
+
+impl From<&Message> for JsonMessage {
+    fn from(message: &Message) -> Self {
+        JsonMessage {
+            id: message.id,
+            payload: encode_bytes(&message.payload),
+            headers: message.headers.as_ref().map(|headers| {
+                headers
+                    .iter()
+                    .map(|(key, value)| (key.as_str().to_string(), JsonHeaderValue::from(value)))
+                    .collect()
+            }),
+            compression: message.compression.clone(),
+            headers_compression: message.headers_compression.clone(),
+            partition_id: message.partition_id,
+            payload_checksum: message.payload_checksum,
+        }
+    }
+}
+
+impl TryFrom<JsonMessage> for Message {
+    type Error = IggyError;
+
+    fn try_from(message: JsonMessage) -> Result<Self, Self::Error> {
+        let headers = match message.headers {
+            Some(headers) => Some(
+                headers
+                    .into_iter()
+                    .map(|(key, value)| Ok((HeaderKey::new(&key)?, HeaderValue::try_from(value)?)))
+                    .collect::<Result<HashMap<_, _>, IggyError>>()?,
+            ),
+            None => None,
+        };
+        let mut result = Message::new(
+            Some(message.id),
+            decode_bytes(&message.payload)?.into(),
+            headers,
+        );
+        result.compression = message.compression;
+        result.headers_compression = message.headers_compression;
+        result.partition_id = message.partition_id;
+        result.payload_checksum = message.payload_checksum;
+        Ok(result)
+    }
+}
+
+impl From<&HeaderValue> for JsonHeaderValue {
+    fn from(header: &HeaderValue) -> Self {
+        let value = match header.kind {
+            HeaderKind::Raw => Value::from(encode_bytes(&header.value)),
+            HeaderKind::String => Value::from(String::from_utf8_lossy(&header.value)),
+            HeaderKind::Bool => header.as_bool().map(Value::from).unwrap_or_default(),
+            HeaderKind::Int8 => header.as_int8().map(Value::from).unwrap_or_default(),
+            HeaderKind::Int16 => header.as_int16().map(Value::from).unwrap_or_default(),
+            HeaderKind::Int32 => header.as_int32().map(Value::from).unwrap_or_default(),
+            HeaderKind::Int64 => header.as_int64().map(Value::from).unwrap_or_default(),
+            HeaderKind::Int128 => header
+                .as_int128()
+                .map(|value| Value::from(value.to_string()))
+                .unwrap_or_default(),
+            HeaderKind::Uint8 => header.as_uint8().map(Value::from).unwrap_or_default(),
+            HeaderKind::Uint16 => header.as_uint16().map(Value::from).unwrap_or_default(),
+            HeaderKind::Uint32 => header.as_uint32().map(Value::from).unwrap_or_default(),
+            HeaderKind::Uint64 => header.as_uint64().map(Value::from).unwrap_or_default(),
+            HeaderKind::Uint128 => header
+                .as_uint128()
+                .map(|value| Value::from(value.to_string()))
+                .unwrap_or_default(),
+            HeaderKind::Float32 => header.as_float32().map(Value::from).unwrap_or_default(),
+            HeaderKind::Float64 => header.as_float64().map(Value::from).unwrap_or_default(),
+        };
+        JsonHeaderValue {
+            kind: header.kind.to_string(),
+            value,
+        }
+    }
+}
+
+impl TryFrom<JsonHeaderValue> for HeaderValue {
+    type Error = IggyError;
+
+    fn try_from(header: JsonHeaderValue) -> Result<Self, Self::Error> {
+        let kind = HeaderKind::from_str(&header.kind)?;
+        let value = &header.value;
+        match kind {
+            HeaderKind::Raw => HeaderValue::from_raw(&decode_bytes(json_str(value)?)?),
+            HeaderKind::String => HeaderValue::from_str(json_str(value)?),
+            HeaderKind::Bool => {
+                HeaderValue::from_bool(value.as_bool().ok_or(IggyError::InvalidHeaderValue)?)
+            }
+            HeaderKind::Int8 => HeaderValue::from_int8(json_int(value)?),
+            HeaderKind::Int16 => HeaderValue::from_int16(json_int(value)?),
+            HeaderKind::Int32 => HeaderValue::from_int32(json_int(value)?),
+            HeaderKind::Int64 => HeaderValue::from_int64(json_int(value)?),
+            HeaderKind::Int128 => HeaderValue::from_int128(json_str(value)?.parse()?),
+            HeaderKind::Uint8 => HeaderValue::from_uint8(json_uint(value)?),
+            HeaderKind::Uint16 => HeaderValue::from_uint16(json_uint(value)?),
+            HeaderKind::Uint32 => HeaderValue::from_uint32(json_uint(value)?),
+            HeaderKind::Uint64 => HeaderValue::from_uint64(json_uint(value)?),
+            HeaderKind::Uint128 => HeaderValue::from_uint128(json_str(value)?.parse()?),
+            HeaderKind::Float32 => HeaderValue::from_float32(json_float(value)? as f32),
+            HeaderKind::Float64 => HeaderValue::from_float64(json_float(value)?),
+        }
+    }
+}
+
+fn json_str(value: &Value) -> Result<&str, IggyError> {
+    value.as_str().ok_or(IggyError::InvalidHeaderValue)
+}
+
+fn json_int<T: TryFrom<i64>>(value: &Value) -> Result<T, IggyError> {
+    value
+        .as_i64()
+        .and_then(|value| T::try_from(value).ok())
+        .ok_or(IggyError::InvalidHeaderValue)
+}
+
+fn json_uint<T: TryFrom<u64>>(value: &Value) -> Result<T, IggyError> {
+    value
+        .as_u64()
+        .and_then(|value| T::try_from(value).ok())
+        .ok_or(IggyError::InvalidHeaderValue)
+}
+
+fn json_float(value: &Value) -> Result<f64, IggyError> {
+    value.as_f64().ok_or(IggyError::InvalidHeaderValue)
+}
+
+fn is_uncompressed(compression: &CompressionAlgorithm) -> bool {
+    *compression == CompressionAlgorithm::None
+}
+
+/// Encode the bytes as the plain text if it's valid UTF-8 (and isn't ambiguous with the prefix), or as the prefixed base64 otherwise.
+fn encode_bytes(bytes: &[u8]) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(value) if !value.starts_with(BASE64_PREFIX) => value.to_string(),
+        _ => format!("{BASE64_PREFIX}{}", text::as_base64(bytes)),
+    }
+}
+
+fn decode_bytes(value: &str) -> Result<Vec<u8>, IggyError> {
+    match value.strip_prefix(BASE64_PREFIX) {
+        Some(value) => text::from_base64_as_bytes(value),
+        None => Ok(value.as_bytes().to_vec()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn command_should_be_deserialized_from_hand_written_json() {
+        let json = r#"{
+            "stream_id": 1,
+            "topic_id": "orders",
+            "partitioning": { "kind": "messages_key", "value": "order-1" },
+            "messages": [
+                {
+                    "id": 1,
+                    "payload": "hello",
+                    "headers": {
+                        "trace": { "kind": "string", "value": "abc" },
+                        "attempt": { "kind": "uint32", "value": 3 },
+                        "big": { "kind": "int128", "value": "-170141183460469231731687303715884105728" }
+                    }
+                },
+                { "payload": "base64:AAEC/w==" }
+            ]
+        }"#;
+
+        let command = SendMessages::from_json_str(json).unwrap();
+
+        assert_eq!(command.stream_id, Identifier::numeric(1).unwrap());
+        assert_eq!(command.topic_id, Identifier::named("orders").unwrap());
+        assert_eq!(
+            command.partitioning,
+            Partitioning::messages_key_str("order-1").unwrap()
+        );
+        assert_eq!(command.messages().len(), 2);
+        let headers = command.messages()[0].headers.as_ref().unwrap();
+        assert_eq!(
+            headers[&HeaderKey::new("trace").unwrap()].as_str().unwrap(),
+            "abc"
+        );
+        assert_eq!(
+            headers[&HeaderKey::new("attempt").unwrap()]
+                .as_uint32()
+                .unwrap(),
+            3
+        );
+        assert_eq!(
+            headers[&HeaderKey::new("big").unwrap()]
+                .as_int128()
+                .unwrap(),
+            i128::MIN
+        );
+        assert_eq!(command.messages()[1].id, 0);
+        assert_eq!(command.messages()[1].payload.as_ref(), &[0, 1, 2, 255]);
+    }
+
+    #[test]
+    fn command_should_round_trip_through_json_including_binary_payloads() {
+        let headers = HashMap::from([
+            (
+                HeaderKey::new("raw").unwrap(),
+                HeaderValue::from_raw(&[0, 159, 146, 150]).unwrap(),
+            ),
+            (
+                HeaderKey::new("flag").unwrap(),
+                HeaderValue::from_bool(true).unwrap(),
+            ),
+            (
+                HeaderKey::new("ratio").unwrap(),
+                HeaderValue::from_float32(0.25).unwrap(),
+            ),
+            (
+                HeaderKey::new("id").unwrap(),
+                HeaderValue::from_uint128(u128::MAX).unwrap(),
+            ),
+            (
+                HeaderKey::new("offset").unwrap(),
+                HeaderValue::from_int64(-42).unwrap(),
+            ),
+        ]);
+        let command = SendMessages::new(
+            Identifier::named("stream").unwrap().into(),
+            Identifier::numeric(2).unwrap().into(),
+            Partitioning::consistent_key(&[0, 255]).unwrap(),
+            vec![
+                Message::new(Some(1), "hello".into(), Some(headers)),
+                Message::new(Some(2), vec![0, 159, 146, 150].into(), None),
+                Message::new(None, "base64:looks encoded".into(), None),
+                Message::new_compressed(
+                    Some(3),
+                    "hello world ".repeat(10).into(),
+                    CompressionAlgorithm::Gzip,
+                    None,
+                )
+                .unwrap(),
+            ],
+        );
+
+        let json = command.to_json();
+        let deserialized = SendMessages::from_json_str(&json).unwrap();
+
+        assert_eq!(deserialized, command);
+        assert!(json.contains(r#""payload": "hello""#));
+        assert!(json.contains(r#""payload": "base64:AJ+Slg==""#));
+    }
+
+    #[test]
+    fn hashed_key_partitioning_should_be_deserialized_with_hash_algorithm() {
+        let json = r#"{
+            "stream_id": 1,
+            "topic_id": 1,
+            "partitioning": { "kind": "hashed_key", "algorithm": "xxh64", "value": "order-1" },
+            "messages": [{ "payload": "hello" }]
+        }"#;
+
+        let command = SendMessages::from_json_str(json).unwrap();
+
+        assert_eq!(
+            command.partitioning,
+            Partitioning::messages_key_with(b"order-1", HashAlgo::Xxh64).unwrap()
+        );
+        assert_eq!(
+            SendMessages::from_json_str(&command.to_json()).unwrap(),
+            command
+        );
+    }
+
+    #[test]
+    fn header_value_of_mismatched_type_should_be_rejected() {
+        let json = r#"{
+            "stream_id": 1,
+            "topic_id": 1,
+            "messages": [
+                { "payload": "hello", "headers": { "attempt": { "kind": "uint8", "value": 256 } } }
+            ]
+        }"#;
+
+        let command = SendMessages::from_json_str(json);
+
+        assert!(matches!(command, Err(IggyError::InvalidHeaderValue)));
+    }
+
+    #[test]
+    fn invalid_base64_payload_should_be_rejected() {
+        let json = r#"{
+            "stream_id": 1,
+            "topic_id": 1,
+            "messages": [{ "payload": "base64:not base64!" }]
+        }"#;
+
+        let command = SendMessages::from_json_str(json);
+
+        assert!(matches!(command, Err(IggyError::InvalidFormat)));
+    }
+
+    #[test]
+    fn batch_failing_validation_should_be_rejected() {
+        let json = r#"{
+            "stream_id": 1,
+            "topic_id": 1,
+            "partitioning": { "kind": "partition_id", "value": 1 },
+            "messages": [
+                { "payload": "hello 1" },
+                { "payload": "base64:aGVsbG8gMg==", "partition_id": 2 }
+            ]
+        }"#;
+
+        let command = SendMessages::from_json_str(json);
+
+        assert!(matches!(
+            command,
+            Err(IggyError::InvalidMessageAtIndex(1, inner))
+                if matches!(*inner, IggyError::ConflictingMessagePartitionId(2, 1))
+        ));
+    }
+
+    #[test]
+    fn malformed_json_should_be_rejected() {
+        let command = SendMessages::from_json_str(r#"{ "stream_id": 1 }"#);
+
+        assert!(matches!(command, Err(IggyError::CannotParseJson(_))));
+    }
+}
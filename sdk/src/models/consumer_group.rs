@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::fmt::Display;
 
 /// `ConsumerGroup` represents the information about a consumer group.
 /// It consists of the following fields:
@@ -38,6 +39,26 @@ pub struct ConsumerGroupDetails {
     pub members: Vec<ConsumerGroupMember>,
 }
 
+impl Display for ConsumerGroup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}|{}|{}|{}",
+            self.id, self.name, self.partitions_count, self.members_count
+        )
+    }
+}
+
+impl Display for ConsumerGroupDetails {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}|{}|{}|{}",
+            self.id, self.name, self.partitions_count, self.members_count
+        )
+    }
+}
+
 /// `ConsumerGroupMember` represents the information about a consumer group member.
 /// It consists of the following fields:
 /// - `id`: the unique identifier (numeric) of the consumer group member.
@@ -52,3 +73,21 @@ pub struct ConsumerGroupMember {
     /// The collection of partitions the consumer group member is consuming.
     pub partitions: Vec<u32>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn consumer_group_details_should_be_displayed_in_single_line() {
+        let consumer_group = ConsumerGroupDetails {
+            id: 1,
+            name: "group-1".to_string(),
+            partitions_count: 3,
+            members_count: 2,
+            members: vec![],
+        };
+
+        assert_eq!(consumer_group.to_string(), "1|group-1|3|2");
+    }
+}
@@ -1,14 +1,28 @@
 use crate::bytes_serializable::BytesSerializable;
 use crate::error::IggyError;
+use crate::messages::MAX_HEADERS_SIZE;
 use bytes::{BufMut, Bytes, BytesMut};
+use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
 use serde_with::base64::Base64;
 use serde_with::serde_as;
+use std::borrow::Borrow;
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 use std::hash::{Hash, Hasher};
+use std::ops::Index;
 use std::str::FromStr;
 
+lazy_static! {
+    static ref CONTENT_TYPE_JSON: HeaderValue = HeaderValue::from_str("application/json").unwrap();
+    static ref CONTENT_TYPE_TEXT: HeaderValue =
+        HeaderValue::from_str("text/plain; charset=utf-8").unwrap();
+    static ref CONTENT_TYPE_OCTET_STREAM: HeaderValue =
+        HeaderValue::from_str("application/octet-stream").unwrap();
+    static ref CONTENT_TYPE_PROTOBUF: HeaderValue =
+        HeaderValue::from_str("application/x-protobuf").unwrap();
+}
+
 /// Represents a header key with a unique name. The name is case-insensitive and wraps a string.
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct HeaderKey(String);
@@ -22,17 +36,78 @@ impl HeaderKey {
         Ok(Self(key.to_lowercase().to_string()))
     }
 
+    /// Creates the header key from the static string, e.g. one of the reserved keys, panicking if it's invalid.
+    /// Prefer `header_key!`, which validates the key at compile time, so the invalid key fails the build.
+    pub fn from_static(key: &'static str) -> Self {
+        Self::assert_valid(key);
+        Self(key.to_lowercase())
+    }
+
+    /// Panics if the key is empty or longer than 255 bytes, which fails the build when evaluated in the const context by `header_key!`.
+    pub const fn assert_valid(key: &str) {
+        assert!(
+            !key.is_empty() && key.len() <= 255,
+            "header key must be between 1 and 255 bytes long"
+        );
+    }
+
     pub fn as_str(&self) -> &str {
         &self.0
     }
 }
 
+/// Creates the `HeaderKey` from the string literal or constant, validating it at compile time, so the invalid key fails the build
+/// instead of returning `InvalidHeaderKey` when the message is built.
+///
+/// ```
+/// use iggy::header_key;
+/// use iggy::messages::send_messages::CONTENT_TYPE_HEADER;
+///
+/// let key = header_key!(CONTENT_TYPE_HEADER);
+/// assert_eq!(key.as_str(), "iggy.content_type");
+/// assert_eq!(header_key!("Trace-Id").as_str(), "trace-id");
+/// ```
+///
+/// The empty key fails the build:
+///
+/// ```compile_fail
+/// let key = iggy::header_key!("");
+/// ```
+///
+/// So does the key longer than 255 bytes:
+///
+/// ```compile_fail
+/// const KEY: &str = concat!(
+///     "0123456789012345678901234567890123456789012345678901234567890123",
+///     "0123456789012345678901234567890123456789012345678901234567890123",
+///     "0123456789012345678901234567890123456789012345678901234567890123",
+///     "0123456789012345678901234567890123456789012345678901234567890123",
+/// );
+/// let key = iggy::header_key!(KEY);
+/// ```
+#[macro_export]
+macro_rules! header_key {
+    ($key:expr) => {{
+        const _: () = $crate::models::header::HeaderKey::assert_valid($key);
+        $crate::models::header::HeaderKey::from_static($key)
+    }};
+}
+
+// The hash must be the same as of the borrowed `str`, so the headers can be looked up by `&str`.
 impl Hash for HeaderKey {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.0.hash(state);
     }
 }
 
+/// Allows looking up the headers by `&str` without allocating the `HeaderKey`, e.g. `headers.get("content-type")`.
+/// The keys are stored in lowercase, so the looked up key must be lowercase as well.
+impl Borrow<str> for HeaderKey {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
 impl FromStr for HeaderKey {
     type Err = IggyError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -252,6 +327,26 @@ impl FromStr for HeaderValue {
 }
 
 impl HeaderValue {
+    /// Returns the `application/json` content type string header value, cloned from the validated constant.
+    pub fn content_type_json() -> Self {
+        CONTENT_TYPE_JSON.clone()
+    }
+
+    /// Returns the `text/plain; charset=utf-8` content type string header value, cloned from the validated constant.
+    pub fn content_type_text() -> Self {
+        CONTENT_TYPE_TEXT.clone()
+    }
+
+    /// Returns the `application/octet-stream` content type string header value, cloned from the validated constant.
+    pub fn content_type_octet_stream() -> Self {
+        CONTENT_TYPE_OCTET_STREAM.clone()
+    }
+
+    /// Returns the `application/x-protobuf` content type string header value, cloned from the validated constant.
+    pub fn content_type_protobuf() -> Self {
+        CONTENT_TYPE_PROTOBUF.clone()
+    }
+
     /// Creates a new header value from the specified raw bytes.
     pub fn from_raw(value: &[u8]) -> Result<Self, IggyError> {
         Self::from(HeaderKind::Raw, value)
@@ -275,7 +370,7 @@ impl HeaderValue {
         Ok(std::str::from_utf8(&self.value)?)
     }
 
-    /// Creates a new header value from the specified string.
+    /// Creates a new header value from the specified boolean.
     pub fn from_bool(value: bool) -> Result<Self, IggyError> {
         Self::from(HeaderKind::Bool, if value { &[1] } else { &[0] })
     }
@@ -293,7 +388,7 @@ impl HeaderValue {
         }
     }
 
-    /// Creates a new header value from the specified boolean.
+    /// Creates a new header value from the specified i8.
     pub fn from_int8(value: i8) -> Result<Self, IggyError> {
         Self::from(HeaderKind::Int8, &value.to_le_bytes())
     }
@@ -312,7 +407,7 @@ impl HeaderValue {
         Ok(i8::from_le_bytes(value.unwrap()))
     }
 
-    /// Creates a new header value from the specified i8.
+    /// Creates a new header value from the specified i16.
     pub fn from_int16(value: i16) -> Result<Self, IggyError> {
         Self::from(HeaderKind::Int16, &value.to_le_bytes())
     }
@@ -331,7 +426,7 @@ impl HeaderValue {
         Ok(i16::from_le_bytes(value.unwrap()))
     }
 
-    /// Creates a new header value from the specified i16.
+    /// Creates a new header value from the specified i32.
     pub fn from_int32(value: i32) -> Result<Self, IggyError> {
         Self::from(HeaderKind::Int32, &value.to_le_bytes())
     }
@@ -350,7 +445,7 @@ impl HeaderValue {
         Ok(i32::from_le_bytes(value.unwrap()))
     }
 
-    /// Creates a new header value from the specified i32.
+    /// Creates a new header value from the specified i64.
     pub fn from_int64(value: i64) -> Result<Self, IggyError> {
         Self::from(HeaderKind::Int64, &value.to_le_bytes())
     }
@@ -535,8 +630,11 @@ impl BytesSerializable for HashMap<HeaderKey, HeaderValue> {
             return Bytes::new();
         }
 
+        // The headers are written in the order of their keys, so the logically equal maps are serialized to the same bytes.
+        let mut headers = self.iter().collect::<Vec<_>>();
+        headers.sort_unstable_by(|(first, _), (second, _)| first.0.cmp(&second.0));
         let mut bytes = BytesMut::new();
-        for (key, value) in self {
+        for (key, value) in headers {
             #[allow(clippy::cast_possible_truncation)]
             bytes.put_u32_le(key.0.len() as u32);
             bytes.put_slice(key.0.as_bytes());
@@ -560,11 +658,19 @@ impl BytesSerializable for HashMap<HeaderKey, HeaderValue> {
         let mut headers = Self::new();
         let mut position = 0;
         while position < bytes.len() {
+            // Every length prefix is validated against the remaining bytes before slicing, so the malformed input cannot cause a panic.
+            if bytes.len() - position < 4 {
+                return Err(IggyError::InvalidHeaderValue);
+            }
             let key_length = u32::from_le_bytes(bytes[position..position + 4].try_into()?) as usize;
             if key_length == 0 || key_length > 255 {
                 return Err(IggyError::InvalidHeaderKey);
             }
             position += 4;
+            // Key + Kind + Value length
+            if bytes.len() - position < key_length + 1 + 4 {
+                return Err(IggyError::InvalidHeaderValue);
+            }
             let key = String::from_utf8(bytes[position..position + key_length].to_vec());
             if key.is_err() {
                 return Err(IggyError::InvalidHeaderKey);
@@ -579,32 +685,268 @@ impl BytesSerializable for HashMap<HeaderKey, HeaderValue> {
                 return Err(IggyError::InvalidHeaderValue);
             }
             position += 4;
+            if bytes.len() - position < value_length {
+                return Err(IggyError::InvalidHeaderValue);
+            }
             let value = bytes[position..position + value_length].to_vec();
             position += value_length;
             headers.insert(HeaderKey(key), HeaderValue { kind, value });
         }
 
+        // The sum of all the entries sizes must be equal to the length of the headers block.
+        if position != bytes.len() {
+            return Err(IggyError::InvalidHeaderValue);
+        }
+
+        Ok(headers)
+    }
+}
+
+/// The collection of headers which keeps track of its serialized size,
+/// so that it never exceeds `MAX_HEADERS_SIZE` and the size doesn't have to be recalculated.
+/// The headers are looked up by the `HeaderKey` or its case-sensitive `&str` name, the same way as in the `HashMap`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Headers {
+    headers: HashMap<HeaderKey, HeaderValue>,
+    size_bytes: u32,
+}
+
+impl Headers {
+    /// Creates the empty collection of headers.
+    pub fn new() -> Self {
+        Self {
+            headers: HashMap::new(),
+            size_bytes: get_headers_size_bytes(&None),
+        }
+    }
+
+    // Wraps the headers without verifying their size, which is validated along with the rest of the message instead.
+    pub(crate) fn from_map_unchecked(headers: HashMap<HeaderKey, HeaderValue>) -> Self {
+        let size_bytes = get_map_size_bytes(&headers);
+        Self {
+            headers,
+            size_bytes,
+        }
+    }
+
+    /// Inserts the header, returning the previous value for the same key.
+    /// Fails with `TooBigHeadersPayload` if the headers would exceed `MAX_HEADERS_SIZE`, in which case the headers remain unchanged.
+    pub fn insert(
+        &mut self,
+        key: HeaderKey,
+        value: HeaderValue,
+    ) -> Result<Option<HeaderValue>, IggyError> {
+        let previous_size = self
+            .headers
+            .get_key_value(&key)
+            .map_or(0, |(key, value)| get_header_size_bytes(key, value));
+        let size_bytes = self.size_bytes - previous_size + get_header_size_bytes(&key, &value);
+        if size_bytes > MAX_HEADERS_SIZE {
+            return Err(IggyError::TooBigHeadersPayload);
+        }
+
+        self.size_bytes = size_bytes;
+        Ok(self.headers.insert(key, value))
+    }
+
+    /// Returns the value of the header for the specified key.
+    pub fn get<Q>(&self, key: &Q) -> Option<&HeaderValue>
+    where
+        HeaderKey: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.headers.get(key)
+    }
+
+    /// Checks if the header for the specified key exists.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        HeaderKey: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.headers.contains_key(key)
+    }
+
+    /// Removes the header for the specified key, returning its value.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<HeaderValue>
+    where
+        HeaderKey: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let (key, value) = self.headers.remove_entry(key)?;
+        self.size_bytes -= get_header_size_bytes(&key, &value);
+        Some(value)
+    }
+
+    /// Returns the number of headers.
+    pub fn len(&self) -> usize {
+        self.headers.len()
+    }
+
+    /// Checks if there are no headers.
+    pub fn is_empty(&self) -> bool {
+        self.headers.is_empty()
+    }
+
+    /// Returns the size in bytes of the serialized headers, including the headers length field.
+    pub fn get_size_bytes(&self) -> u32 {
+        self.size_bytes
+    }
+
+    /// Returns the iterator over the headers.
+    pub fn iter(&self) -> impl Iterator<Item = (&HeaderKey, &HeaderValue)> {
+        self.headers.iter()
+    }
+
+    /// Returns the iterator over the header keys.
+    pub fn keys(&self) -> impl Iterator<Item = &HeaderKey> {
+        self.headers.keys()
+    }
+
+    /// Returns the iterator over the header values.
+    pub fn values(&self) -> impl Iterator<Item = &HeaderValue> {
+        self.headers.values()
+    }
+
+    /// Returns the headers as the map.
+    pub fn as_map(&self) -> &HashMap<HeaderKey, HeaderValue> {
+        &self.headers
+    }
+}
+
+impl Default for Headers {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TryFrom<HashMap<HeaderKey, HeaderValue>> for Headers {
+    type Error = IggyError;
+
+    fn try_from(headers: HashMap<HeaderKey, HeaderValue>) -> Result<Self, Self::Error> {
+        let headers = Self::from_map_unchecked(headers);
+        if headers.size_bytes > MAX_HEADERS_SIZE {
+            return Err(IggyError::TooBigHeadersPayload);
+        }
+
         Ok(headers)
     }
 }
 
+impl From<Headers> for HashMap<HeaderKey, HeaderValue> {
+    fn from(headers: Headers) -> Self {
+        headers.headers
+    }
+}
+
+impl PartialEq<HashMap<HeaderKey, HeaderValue>> for Headers {
+    fn eq(&self, other: &HashMap<HeaderKey, HeaderValue>) -> bool {
+        &self.headers == other
+    }
+}
+
+/// Returns the value of the header for the specified key, panicking if it doesn't exist, the same way as the `HashMap`.
+impl<Q> Index<&Q> for Headers
+where
+    HeaderKey: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+{
+    type Output = HeaderValue;
+
+    fn index(&self, key: &Q) -> &HeaderValue {
+        &self.headers[key]
+    }
+}
+
+impl<'a> IntoIterator for &'a Headers {
+    type Item = (&'a HeaderKey, &'a HeaderValue);
+    type IntoIter = std::collections::hash_map::Iter<'a, HeaderKey, HeaderValue>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.headers.iter()
+    }
+}
+
+impl BytesSerializable for Headers {
+    fn as_bytes(&self) -> Bytes {
+        self.headers.as_bytes()
+    }
+
+    fn from_bytes(bytes: Bytes) -> Result<Self, IggyError>
+    where
+        Self: Sized,
+    {
+        Headers::try_from(HashMap::<HeaderKey, HeaderValue>::from_bytes(bytes)?)
+    }
+}
+
+/// The headers are serialized as the map, and fail to deserialize if they exceed `MAX_HEADERS_SIZE`.
+impl Serialize for Headers {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.headers.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Headers {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let headers = HashMap::<HeaderKey, HeaderValue>::deserialize(deserializer)?;
+        Headers::try_from(headers).map_err(serde::de::Error::custom)
+    }
+}
+
 /// Returns the size in bytes of the specified headers.
 pub fn get_headers_size_bytes(headers: &Option<HashMap<HeaderKey, HeaderValue>>) -> u32 {
-    // Headers length field
-    let mut size = 4;
-    if let Some(headers) = headers {
-        for (key, value) in headers {
-            // Key length + Key + Kind + Value length + Value
-            size += 4 + key.as_str().len() as u32 + 1 + 4 + value.value.len() as u32;
-        }
+    match headers {
+        Some(headers) => get_map_size_bytes(headers),
+        // Headers length field
+        None => 4,
     }
-    size
+}
+
+// Returns the size in bytes of the serialized headers, including the headers length field.
+fn get_map_size_bytes(headers: &HashMap<HeaderKey, HeaderValue>) -> u32 {
+    // Headers length field
+    4 + headers
+        .iter()
+        .map(|(key, value)| get_header_size_bytes(key, value))
+        .sum::<u32>()
+}
+
+/// Returns the size in bytes of the single serialized header.
+pub fn get_header_size_bytes(key: &HeaderKey, value: &HeaderValue) -> u32 {
+    // Key length + Key + Kind + Value length + Value
+    4 + key.as_str().len() as u32 + 1 + 4 + value.value.len() as u32
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn header_key_macro_should_match_runtime_validated_key() {
+        assert_eq!(
+            header_key!("content-type"),
+            HeaderKey::new("content-type").unwrap()
+        );
+        assert_eq!(header_key!("Trace-Id"), HeaderKey::new("trace-id").unwrap());
+        assert_eq!(
+            header_key!(crate::messages::send_messages::SEQUENCE_HEADER).as_str(),
+            "iggy.seq"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "header key must be between 1 and 255 bytes long")]
+    fn header_key_from_invalid_static_string_should_panic() {
+        HeaderKey::from_static("");
+    }
+
     #[test]
     fn header_key_should_be_created_for_valid_value() {
         let value = "key-1";
@@ -810,6 +1152,32 @@ mod tests {
         assert_eq!(header_value.as_float64().unwrap(), value);
     }
 
+    #[test]
+    fn header_value_should_reject_reading_as_a_different_kind() {
+        let value = HeaderValue::from_uint64(42).unwrap();
+        assert!(matches!(
+            value.as_int64(),
+            Err(IggyError::InvalidHeaderValue)
+        ));
+        assert!(matches!(
+            value.as_float64(),
+            Err(IggyError::InvalidHeaderValue)
+        ));
+        assert!(matches!(
+            value.as_bool(),
+            Err(IggyError::InvalidHeaderValue)
+        ));
+        assert!(matches!(value.as_str(), Err(IggyError::InvalidHeaderValue)));
+        assert_eq!(value.as_uint64().unwrap(), 42);
+
+        let value = HeaderValue::from_bool(true).unwrap();
+        assert!(matches!(
+            value.as_uint8(),
+            Err(IggyError::InvalidHeaderValue)
+        ));
+        assert!(value.as_bool().unwrap());
+    }
+
     #[test]
     fn should_be_serialized_as_bytes() {
         let mut headers = HashMap::new();
@@ -854,6 +1222,31 @@ mod tests {
         assert_eq!(headers_count, headers.len());
     }
 
+    #[test]
+    fn should_be_serialized_in_the_order_of_keys() {
+        let keys = ["delta", "alpha", "charlie", "bravo"];
+        let mut headers = HashMap::new();
+        for key in keys {
+            headers.insert(
+                HeaderKey::new(key).unwrap(),
+                HeaderValue::from_str(key).unwrap(),
+            );
+        }
+        let mut reversed_headers = HashMap::new();
+        for key in keys.iter().rev() {
+            reversed_headers.insert(
+                HeaderKey::new(key).unwrap(),
+                HeaderValue::from_str(key).unwrap(),
+            );
+        }
+
+        let bytes = headers.as_bytes();
+
+        assert_eq!(bytes, reversed_headers.as_bytes());
+        let key_length = u32::from_le_bytes(bytes[..4].try_into().unwrap()) as usize;
+        assert_eq!(&bytes[4..4 + key_length], b"alpha");
+    }
+
     #[test]
     fn should_be_deserialized_from_bytes() {
         let mut headers = HashMap::new();
@@ -893,4 +1286,215 @@ mod tests {
             assert_eq!(deserialized_value.value, value.value);
         }
     }
+
+    #[test]
+    fn should_fail_to_deserialize_from_bytes_with_value_length_exceeding_remaining_bytes() {
+        let mut bytes = BytesMut::new();
+        bytes.put_u32_le(5);
+        bytes.put_slice(b"key-1");
+        bytes.put_u8(HeaderKind::String.as_code());
+        bytes.put_u32_le(100);
+        bytes.put_slice(b"Value 1");
+
+        let deserialized_headers = HashMap::<HeaderKey, HeaderValue>::from_bytes(bytes.freeze());
+
+        assert!(matches!(
+            deserialized_headers,
+            Err(IggyError::InvalidHeaderValue)
+        ));
+    }
+
+    #[test]
+    fn should_fail_to_deserialize_from_bytes_with_truncated_final_entry() {
+        let mut bytes = BytesMut::new();
+        bytes.put_u32_le(5);
+        bytes.put_slice(b"key-1");
+        bytes.put_u8(HeaderKind::String.as_code());
+        bytes.put_u32_le(7);
+        bytes.put_slice(b"Value 1");
+        bytes.put_u32_le(5);
+        bytes.put_slice(b"key");
+
+        let deserialized_headers = HashMap::<HeaderKey, HeaderValue>::from_bytes(bytes.freeze());
+
+        assert!(matches!(
+            deserialized_headers,
+            Err(IggyError::InvalidHeaderValue)
+        ));
+    }
+
+    #[test]
+    fn headers_should_track_the_serialized_size() {
+        let mut headers = Headers::new();
+        headers
+            .insert(
+                HeaderKey::new("key-1").unwrap(),
+                HeaderValue::from_str("Value 1").unwrap(),
+            )
+            .unwrap();
+        headers
+            .insert(
+                HeaderKey::new("key-2").unwrap(),
+                HeaderValue::from_uint64(12345).unwrap(),
+            )
+            .unwrap();
+        headers
+            .insert(
+                HeaderKey::new("key-1").unwrap(),
+                HeaderValue::from_str("Value 11").unwrap(),
+            )
+            .unwrap();
+
+        assert_eq!(headers.len(), 2);
+        assert_eq!(
+            headers.get_size_bytes(),
+            4 + headers.as_bytes().len() as u32
+        );
+
+        let removed = headers.remove(&HeaderKey::new("key-2").unwrap());
+
+        assert!(removed.is_some());
+        assert_eq!(headers.len(), 1);
+        assert_eq!(
+            headers.get_size_bytes(),
+            4 + headers.as_bytes().len() as u32
+        );
+        let deserialized_headers = Headers::from_bytes(headers.as_bytes()).unwrap();
+        assert_eq!(deserialized_headers, headers);
+    }
+
+    #[test]
+    fn headers_should_be_looked_up_by_str() {
+        let mut headers = HashMap::new();
+        headers.insert(
+            HeaderKey::new("Content-Type").unwrap(),
+            HeaderValue::content_type_json(),
+        );
+        headers.insert(
+            HeaderKey::new("trace").unwrap(),
+            HeaderValue::from_str("abc").unwrap(),
+        );
+
+        assert_eq!(
+            headers.get("content-type"),
+            Some(&HeaderValue::content_type_json())
+        );
+        assert_eq!(headers.get("trace").unwrap().as_str().unwrap(), "abc");
+        assert!(headers.contains_key("trace"));
+        assert!(!headers.contains_key("missing"));
+        assert!(!headers.contains_key("Content-Type"));
+    }
+
+    #[test]
+    fn header_key_hash_should_be_equal_to_borrowed_str_hash() {
+        use std::collections::hash_map::DefaultHasher;
+
+        let key = HeaderKey::new("key-1").unwrap();
+        let mut key_hasher = DefaultHasher::new();
+        key.hash(&mut key_hasher);
+        let mut str_hasher = DefaultHasher::new();
+        "key-1".hash(&mut str_hasher);
+
+        assert_eq!(key_hasher.finish(), str_hasher.finish());
+    }
+
+    #[test]
+    fn headers_should_be_serialized_as_map() {
+        let map = HashMap::from([(
+            HeaderKey::new("key-1").unwrap(),
+            HeaderValue::from_str("Value 1").unwrap(),
+        )]);
+        let headers = Headers::try_from(map.clone()).unwrap();
+
+        let json = serde_json::to_string(&headers).unwrap();
+        assert_eq!(json, serde_json::to_string(&map).unwrap());
+        let deserialized_headers: Headers = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized_headers, headers);
+        assert_eq!(
+            deserialized_headers.get_size_bytes(),
+            headers.get_size_bytes()
+        );
+    }
+
+    #[test]
+    fn headers_exceeding_max_size_should_not_be_deserialized() {
+        let value = HeaderValue::from_raw(&[1; 255]).unwrap();
+        let map = (0..500)
+            .map(|index| {
+                (
+                    HeaderKey::new(&format!("key-{index}")).unwrap(),
+                    value.clone(),
+                )
+            })
+            .collect::<HashMap<_, _>>();
+        let json = serde_json::to_string(&map).unwrap();
+
+        assert!(serde_json::from_str::<Headers>(&json).is_err());
+        assert!(matches!(
+            Headers::try_from(map),
+            Err(IggyError::TooBigHeadersPayload)
+        ));
+    }
+
+    #[test]
+    fn headers_should_reject_insert_exceeding_max_size() {
+        let mut headers = Headers::new();
+        let value = HeaderValue::from_raw(&[1; 255]).unwrap();
+        let mut index = 0;
+        let error = loop {
+            let key = HeaderKey::new(&format!("key-{index}")).unwrap();
+            match headers.insert(key, value.clone()) {
+                Ok(_) => index += 1,
+                Err(error) => break error,
+            }
+        };
+
+        assert!(matches!(error, IggyError::TooBigHeadersPayload));
+        assert_eq!(headers.len(), index);
+        assert!(headers.get_size_bytes() <= MAX_HEADERS_SIZE);
+        assert_eq!(
+            headers.get_size_bytes(),
+            4 + headers.as_bytes().len() as u32
+        );
+    }
+
+    #[test]
+    fn content_type_header_values_should_be_strings() {
+        let values = [
+            (HeaderValue::content_type_json(), "application/json"),
+            (
+                HeaderValue::content_type_text(),
+                "text/plain; charset=utf-8",
+            ),
+            (
+                HeaderValue::content_type_octet_stream(),
+                "application/octet-stream",
+            ),
+            (
+                HeaderValue::content_type_protobuf(),
+                "application/x-protobuf",
+            ),
+        ];
+
+        for (value, expected_value) in values {
+            assert_eq!(value.kind, HeaderKind::String);
+            assert_eq!(value.as_str().unwrap(), expected_value);
+            assert_eq!(value, HeaderValue::from_str(expected_value).unwrap());
+        }
+    }
+
+    #[test]
+    fn content_type_header_value_should_be_serialized_and_deserialized() {
+        let key = HeaderKey::new("content-type").unwrap();
+        let headers = HashMap::from([(key.clone(), HeaderValue::content_type_json())]);
+
+        let deserialized_headers: HashMap<HeaderKey, HeaderValue> =
+            HashMap::from_bytes(headers.as_bytes()).unwrap();
+
+        assert_eq!(
+            deserialized_headers[&key].as_str().unwrap(),
+            "application/json"
+        );
+        assert_eq!(deserialized_headers, headers);
+    }
 }
@@ -1,9 +1,12 @@
 use crate::bytes_serializable::BytesSerializable;
 use crate::error::IggyError;
 use crate::messages::send_messages;
+use crate::messages::send_messages::SEQUENCE_HEADER;
 use crate::models::header;
 use crate::models::header::{HeaderKey, HeaderValue};
 use crate::sizeable::Sizeable;
+use crate::utils::clock::Clock;
+use crate::utils::text::TextEncoding;
 use crate::utils::{checksum, timestamp::IggyTimestamp};
 use bytes::{BufMut, Bytes, BytesMut};
 use serde::{Deserialize, Serialize};
@@ -134,7 +137,10 @@ impl Message {
     pub fn from_message(message: &send_messages::Message) -> Self {
         let timestamp = IggyTimestamp::now().to_micros();
         let checksum = checksum::calculate(&message.payload);
-        let headers = message.headers.as_ref().cloned();
+        let headers = message
+            .headers
+            .as_ref()
+            .map(|headers| headers.as_map().clone());
 
         Self::empty(
             timestamp,
@@ -146,6 +152,24 @@ impl Message {
         )
     }
 
+    /// Returns the time elapsed since the message was appended on the server to the current time of the clock.
+    pub fn age_since(&self, clock: &dyn Clock) -> std::time::Duration {
+        IggyTimestamp::from(self.timestamp).age_since(clock)
+    }
+
+    /// Returns the position of the message within the sent batch, if it was assigned using the `iggy.seq` header.
+    pub fn sequence(&self) -> Option<u32> {
+        self.headers
+            .as_ref()
+            .and_then(|headers| headers.get(SEQUENCE_HEADER))
+            .and_then(|value| value.as_uint32().ok())
+    }
+
+    /// Decodes the payload as the text in the provided encoding, e.g. to consume the legacy data stored in Latin-1 or UTF-16.
+    pub fn decode_text(&self, encoding: TextEncoding) -> Result<String, IggyError> {
+        encoding.decode(&self.payload)
+    }
+
     /// Creates a new message without a specified offset.
     pub fn empty(
         timestamp: u64,
@@ -1,7 +1,11 @@
 use crate::error::IggyError;
 use crate::identifier::Identifier;
-use crate::messages::send_messages::{Message, Partitioning};
+use crate::messages::send_messages::{Message, Partitioning, PartitioningKind};
+use crate::utils::key_hash::HashAlgo;
+use crate::utils::{consistent_hash, key_hash};
+use std::collections::HashMap;
 use std::fmt::Debug;
+use std::sync::atomic::{AtomicU32, Ordering};
 
 /// The trait represent the logic responsible for calculating the partition ID and is used by the `IggyClient`.
 /// This might be especially useful when the partition ID is not constant and might be calculated based on the stream ID, topic ID and other parameters.
@@ -14,3 +18,450 @@ pub trait Partitioner: Send + Sync + Debug {
         messages: &[Message],
     ) -> Result<u32, IggyError>;
 }
+
+/// The client-side round-robin balancer, which cycles through the partition IDs based on the known partitions count.
+/// The partitions count should be refreshed from the topic metadata, and whenever it changes, the counter is reset.
+/// If the partitions count is unknown (equal to 0), there is no partition ID to pick: `next_partition_id` returns `None`,
+/// `next_partitioning` falls back to the balancing done by the server, and the `Partitioner` implementation,
+/// which has to return the concrete partition ID, fails with `InvalidCommand`.
+#[derive(Debug, Default)]
+pub struct ClientBalancer {
+    partitions_count: AtomicU32,
+    counter: AtomicU32,
+}
+
+impl ClientBalancer {
+    /// Create a new balancer for the provided partitions count.
+    pub fn new(partitions_count: u32) -> Self {
+        Self {
+            partitions_count: AtomicU32::new(partitions_count),
+            counter: AtomicU32::new(0),
+        }
+    }
+
+    /// Get the currently known partitions count.
+    pub fn partitions_count(&self) -> u32 {
+        self.partitions_count.load(Ordering::Relaxed)
+    }
+
+    /// Update the known partitions count, and reset the counter if it has changed.
+    pub fn set_partitions_count(&self, partitions_count: u32) {
+        let previous_count = self
+            .partitions_count
+            .swap(partitions_count, Ordering::Relaxed);
+        if previous_count != partitions_count {
+            self.counter.store(0, Ordering::Relaxed);
+        }
+    }
+
+    /// Get the next partition ID, or `None` if the partitions count is unknown.
+    pub fn next_partition_id(&self) -> Option<u32> {
+        let partitions_count = self.partitions_count();
+        if partitions_count == 0 {
+            return None;
+        }
+
+        let counter = self.counter.fetch_add(1, Ordering::Relaxed);
+        Some(counter % partitions_count + 1)
+    }
+
+    /// Get the partitioning for the next partition ID, or the balanced one if the partitions count is unknown.
+    pub fn next_partitioning(&self) -> Partitioning {
+        match self.next_partition_id() {
+            Some(partition_id) => Partitioning::partition_id(partition_id),
+            None => Partitioning::balanced(),
+        }
+    }
+}
+
+/// The producer-side cache of the partitions resolved for the messages keys, which converts the `MessagesKey` partitioning
+/// into the cheaper `PartitionId` one, so the key isn't sent and hashed by the server again for every batch.
+/// The partition is resolved with the same hash as the server, see `Partitioning::resolve_partition`, so the routing is identical.
+/// The cache is keyed by the key bytes, and invalidated whenever the partitions count changes.
+/// It holds at most `max_keys` keys (`DEFAULT_MAX_CACHED_KEYS` by default), and once it's full, it's cleared before
+/// caching the next key, since resolving the partition again is just the hash of the key.
+/// If the partitions count is unknown (equal to 0), the partitioning is deferred to the server.
+#[derive(Debug)]
+pub struct PartitioningCache {
+    partitions_count: u32,
+    max_keys: usize,
+    partition_ids: HashMap<Vec<u8>, u32>,
+}
+
+/// The default maximum number of the keys held by `PartitioningCache`.
+pub const DEFAULT_MAX_CACHED_KEYS: usize = 10_000;
+
+impl Default for PartitioningCache {
+    fn default() -> Self {
+        Self::with_max_keys(DEFAULT_MAX_CACHED_KEYS)
+    }
+}
+
+impl PartitioningCache {
+    /// Create a new empty cache holding at most `DEFAULT_MAX_CACHED_KEYS` keys.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new empty cache holding at most the provided number of keys (at least 1).
+    pub fn with_max_keys(max_keys: usize) -> Self {
+        Self {
+            partitions_count: 0,
+            max_keys: max_keys.max(1),
+            partition_ids: HashMap::new(),
+        }
+    }
+
+    /// Get the partitioning for the messages key, i.e. `PartitionId` of the partition to which the key is routed
+    /// for the provided partitions count, or `MessagesKey` if the partitions count is unknown.
+    /// Fails with `InvalidCommand` for the empty or longer than 255 bytes key, like `Partitioning::messages_key`.
+    pub fn partitioning_for(
+        &mut self,
+        key: &[u8],
+        partitions_count: u32,
+    ) -> Result<Partitioning, IggyError> {
+        if key.is_empty() || key.len() > 255 {
+            return Err(IggyError::InvalidCommand);
+        }
+
+        if partitions_count == 0 {
+            return Partitioning::messages_key(key);
+        }
+
+        if self.partitions_count != partitions_count {
+            self.partition_ids.clear();
+            self.partitions_count = partitions_count;
+        }
+
+        let partition_id = match self.partition_ids.get(key) {
+            Some(partition_id) => *partition_id,
+            None => {
+                let partition_id = resolve_partition_id(
+                    &Partitioning::messages_key(key)?,
+                    partitions_count,
+                    &AtomicU32::default(),
+                )?;
+                if self.partition_ids.len() >= self.max_keys {
+                    self.partition_ids.clear();
+                }
+                self.partition_ids.insert(key.to_vec(), partition_id);
+                partition_id
+            }
+        };
+        Ok(Partitioning::partition_id(partition_id))
+    }
+
+    /// Get the number of the cached keys.
+    pub fn len(&self) -> usize {
+        self.partition_ids.len()
+    }
+
+    /// Check if there are no cached keys.
+    pub fn is_empty(&self) -> bool {
+        self.partition_ids.is_empty()
+    }
+}
+
+/// Get the next partition ID (starting from 1) using the same round-robin algorithm as the server does for the `Balanced` partitioning.
+/// The counter holds the next partition ID to be used, the server initializes it to 1, and 0 is treated the same way.
+/// Once the counter exceeds the partitions count, it wraps around to the first partition. Returns 0 if there are no partitions.
+pub fn round_robin_partition_id(counter: &AtomicU32, partitions_count: u32) -> u32 {
+    if partitions_count == 0 {
+        return 0;
+    }
+
+    let partition_id = counter.fetch_add(1, Ordering::SeqCst);
+    if partition_id == 0 || partition_id > partitions_count {
+        counter.store(2, Ordering::SeqCst);
+        return 1;
+    }
+
+    partition_id
+}
+
+/// Resolve the partition ID (starting from 1) to which the messages with the provided partitioning are appended.
+/// This is the only implementation of the routing, shared by the server, `Partitioning::resolve_partition`,
+/// `SendMessagesRetry`, `PartitioningCache` and the command resolving the partitions of the keys in the CLI:
+/// - `Balanced` - the round robin driven by the counter, see `round_robin_partition_id`.
+/// - `PartitionId` - the provided partition ID as is.
+/// - `MessagesKey` - the 32-bit xxHash (seed 0) of the key modulo the partitions count, with 0 mapped to the last partition, see `key_hash`.
+/// - `ConsistentKey` - the jump consistent hash of the 64-bit FNV-1a hash of the key, see `consistent_hash`.
+/// - `HashedKey` - the hash of the key with the encoded algorithm modulo the partitions count, see `key_hash::calculate_partition_id_with`.
+///
+/// The counter is used (and advanced) only by the `Balanced` partitioning. Returns 0 if there is no partition,
+/// i.e. the partition ID is provided as 0 or there are no partitions, and the callers decide how to report it.
+/// Fails with `InvalidCommand` if the partition ID value is malformed and `UnsupportedHashAlgorithm` if the hash algorithm is unknown.
+pub fn resolve_partition_id(
+    partitioning: &Partitioning,
+    partitions_count: u32,
+    round_robin_counter: &AtomicU32,
+) -> Result<u32, IggyError> {
+    let partition_id = match partitioning.kind {
+        PartitioningKind::Balanced => {
+            round_robin_partition_id(round_robin_counter, partitions_count)
+        }
+        PartitioningKind::PartitionId => partitioning
+            .value
+            .as_slice()
+            .try_into()
+            .map(u32::from_le_bytes)
+            .map_err(|_| IggyError::InvalidCommand)?,
+        PartitioningKind::MessagesKey => {
+            key_hash::calculate_partition_id(&partitioning.value, partitions_count)
+        }
+        PartitioningKind::ConsistentKey => {
+            consistent_hash::calculate_partition_id(&partitioning.value, partitions_count)
+        }
+        PartitioningKind::HashedKey => {
+            let algo = HashAlgo::from_code(
+                *partitioning
+                    .value
+                    .first()
+                    .ok_or(IggyError::InvalidCommand)?,
+            )?;
+            key_hash::calculate_partition_id_with(algo, &partitioning.value[1..], partitions_count)
+        }
+    };
+    Ok(partition_id)
+}
+
+/// Count the keys routed to each partition with the `MessagesKey` partitioning, using the same hash as the server,
+/// see `Partitioning::resolve_partition`. The count of the partition with ID `n` is at index `n - 1`.
+/// Available only with the `testing` feature.
+#[cfg(any(test, feature = "testing"))]
+pub fn partition_distribution<K: AsRef<[u8]>>(
+    keys: impl IntoIterator<Item = K>,
+    partitions_count: u32,
+) -> Vec<u64> {
+    let mut distribution = vec![0; partitions_count as usize];
+    for key in keys {
+        let partition_id = key_hash::calculate_partition_id(key.as_ref(), partitions_count);
+        if partition_id > 0 {
+            distribution[partition_id as usize - 1] += 1;
+        }
+    }
+    distribution
+}
+
+/// Assert that the keys are spread evenly across the partitions, to catch the hot keys in the tests of the partitioning.
+/// Panics if there are no keys or partitions, or if the count of keys routed to any partition deviates from the mean
+/// by more than `max_skew`, which is the fraction of the mean, e.g. `0.1` allows the deviation of 10%.
+/// Available only with the `testing` feature.
+#[cfg(any(test, feature = "testing"))]
+pub fn assert_distribution_within<K: AsRef<[u8]>>(
+    keys: impl IntoIterator<Item = K>,
+    partitions_count: u32,
+    max_skew: f64,
+) {
+    let distribution = partition_distribution(keys, partitions_count);
+    let keys_count = distribution.iter().sum::<u64>();
+    assert!(
+        keys_count > 0,
+        "There are no keys to distribute across {partitions_count} partitions"
+    );
+
+    let mean = keys_count as f64 / f64::from(partitions_count);
+    for (index, count) in distribution.iter().enumerate() {
+        let skew = (*count as f64 - mean).abs() / mean;
+        assert!(
+            skew <= max_skew,
+            "Partition {} has {count} keys, which deviates from the mean of {mean:.2} by {skew:.3}, more than {max_skew}, distribution: {distribution:?}",
+            index + 1
+        );
+    }
+}
+
+impl Partitioner for ClientBalancer {
+    fn calculate_partition_id(
+        &self,
+        _stream_id: &Identifier,
+        _topic_id: &Identifier,
+        _partitioning: &Partitioning,
+        _messages: &[Message],
+    ) -> Result<u32, IggyError> {
+        self.next_partition_id().ok_or(IggyError::InvalidCommand)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::send_messages::PartitioningKind;
+
+    #[test]
+    fn balancer_should_cycle_through_partitions() {
+        let balancer = ClientBalancer::new(3);
+        let partition_ids = (0..7)
+            .map(|_| balancer.next_partition_id().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(partition_ids, vec![1, 2, 3, 1, 2, 3, 1]);
+        assert_eq!(balancer.next_partitioning(), Partitioning::partition_id(2));
+    }
+
+    #[test]
+    fn round_robin_should_cycle_through_partitions_starting_from_counter() {
+        let counter = AtomicU32::new(1);
+        let partition_ids = (0..7)
+            .map(|_| round_robin_partition_id(&counter, 3))
+            .collect::<Vec<_>>();
+        assert_eq!(partition_ids, vec![1, 2, 3, 1, 2, 3, 1]);
+
+        let counter = AtomicU32::new(0);
+        assert_eq!(round_robin_partition_id(&counter, 3), 1);
+        assert_eq!(round_robin_partition_id(&counter, 3), 2);
+        assert_eq!(round_robin_partition_id(&counter, 0), 0);
+    }
+
+    #[test]
+    fn partition_id_should_be_resolved_as_zero_without_partitions() {
+        let counter = AtomicU32::new(0);
+        for partitioning in [
+            Partitioning::balanced(),
+            Partitioning::messages_key_str("orders").unwrap(),
+            Partitioning::consistent_key(b"orders").unwrap(),
+            Partitioning::messages_key_with(b"orders", HashAlgo::Xxh64).unwrap(),
+        ] {
+            assert_eq!(resolve_partition_id(&partitioning, 0, &counter).unwrap(), 0);
+            assert_ne!(resolve_partition_id(&partitioning, 3, &counter).unwrap(), 0);
+        }
+    }
+
+    #[test]
+    fn balancer_as_partitioner_should_fail_when_partitions_count_is_unknown() {
+        let balancer = ClientBalancer::default();
+        let result = balancer.calculate_partition_id(
+            &Identifier::numeric(1).unwrap(),
+            &Identifier::numeric(1).unwrap(),
+            &Partitioning::balanced(),
+            &[],
+        );
+        assert!(matches!(result, Err(IggyError::InvalidCommand)));
+    }
+
+    #[test]
+    fn balancer_should_reset_counter_when_partitions_count_changes() {
+        let balancer = ClientBalancer::new(3);
+        balancer.next_partition_id();
+        balancer.next_partition_id();
+
+        balancer.set_partitions_count(2);
+        assert_eq!(balancer.partitions_count(), 2);
+        let partition_ids = (0..3)
+            .map(|_| balancer.next_partition_id().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(partition_ids, vec![1, 2, 1]);
+    }
+
+    #[test]
+    fn uniform_keys_should_be_distributed_within_skew() {
+        let keys = (0..10_000).map(|key| format!("key-{key}"));
+
+        assert_distribution_within(keys, 8, 0.1);
+    }
+
+    #[test]
+    #[should_panic(expected = "deviates from the mean")]
+    fn skewed_keys_should_not_be_distributed_within_skew() {
+        let keys = (0..1_000).map(|key| match key % 10 {
+            0 => format!("key-{key}"),
+            _ => String::from("hot-key"),
+        });
+
+        assert_distribution_within(keys, 8, 0.1);
+    }
+
+    #[test]
+    fn distribution_should_count_keys_by_resolved_partition() {
+        let keys = ["orders", "orders", "customer-42"];
+
+        let distribution = partition_distribution(keys, 10);
+
+        assert_eq!(distribution.iter().sum::<u64>(), 3);
+        assert_eq!(distribution[6], 2);
+        assert_eq!(distribution[1], 1);
+    }
+
+    #[test]
+    fn cached_partitioning_should_route_like_messages_key() {
+        let mut cache = PartitioningCache::new();
+        let counter = AtomicU32::new(0);
+        let keys = (0..100).map(|key| format!("key-{key}")).collect::<Vec<_>>();
+
+        for partitions_count in [1, 3, 10, 16] {
+            for _ in 0..2 {
+                for key in &keys {
+                    let partition_id = Partitioning::messages_key(key.as_bytes())
+                        .unwrap()
+                        .resolve_partition(partitions_count, &counter)
+                        .unwrap();
+
+                    assert_eq!(
+                        cache
+                            .partitioning_for(key.as_bytes(), partitions_count)
+                            .unwrap(),
+                        Partitioning::partition_id(partition_id)
+                    );
+                }
+            }
+            assert_eq!(cache.len(), keys.len());
+        }
+    }
+
+    #[test]
+    fn partitioning_cache_should_be_invalidated_when_partitions_count_changes() {
+        let mut cache = PartitioningCache::new();
+
+        assert_eq!(
+            cache.partitioning_for(b"customer-42", 10).unwrap(),
+            Partitioning::partition_id(2)
+        );
+        cache.partitioning_for(b"orders", 10).unwrap();
+        assert_eq!(cache.len(), 2);
+
+        assert_eq!(
+            cache.partitioning_for(b"customer-42", 7).unwrap(),
+            Partitioning::partition_id(4)
+        );
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn partitioning_cache_should_not_exceed_max_keys() {
+        let mut cache = PartitioningCache::with_max_keys(2);
+        cache.partitioning_for(b"orders", 10).unwrap();
+        cache.partitioning_for(b"payments", 10).unwrap();
+        assert_eq!(cache.len(), 2);
+
+        assert_eq!(
+            cache.partitioning_for(b"customer-42", 10).unwrap(),
+            Partitioning::partition_id(2)
+        );
+        assert_eq!(cache.len(), 1);
+        assert_eq!(PartitioningCache::with_max_keys(0).max_keys, 1);
+    }
+
+    #[test]
+    fn partitioning_cache_should_defer_to_server_when_partitions_count_is_unknown() {
+        let mut cache = PartitioningCache::new();
+
+        assert_eq!(
+            cache.partitioning_for(b"orders", 0).unwrap(),
+            Partitioning::messages_key(b"orders").unwrap()
+        );
+        assert!(cache.is_empty());
+        assert!(matches!(
+            cache.partitioning_for(b"", 10),
+            Err(IggyError::InvalidCommand)
+        ));
+    }
+
+    #[test]
+    fn balancer_should_defer_to_server_when_partitions_count_is_unknown() {
+        let balancer = ClientBalancer::default();
+        assert!(balancer.next_partition_id().is_none());
+        assert_eq!(
+            balancer.next_partitioning().kind,
+            PartitioningKind::Balanced
+        );
+    }
+}
@@ -202,6 +202,10 @@ impl Client for TcpClient {
         info!("{} client has disconnected from server.", NAME);
         Ok(())
     }
+
+    async fn is_authenticated(&self) -> bool {
+        self.get_state().await == ClientState::Authenticated
+    }
 }
 
 #[async_trait]
@@ -1,3 +1,11 @@
 pub fn calculate(data: &[u8]) -> u32 {
     crc32fast::hash(data)
 }
+
+pub fn calculate_parts(parts: &[&[u8]]) -> u32 {
+    let mut hasher = crc32fast::Hasher::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize()
+}
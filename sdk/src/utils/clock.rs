@@ -0,0 +1,91 @@
+use crate::utils::timestamp::IggyTimestamp;
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// The source of the current time, so that the time-dependent logic (e.g. the age or the expiry of the messages)
+/// can be tested deterministically by providing the `MockClock` instead of the default `SystemClock`.
+pub trait Clock: Debug + Send + Sync {
+    /// Returns the current timestamp.
+    fn now(&self) -> IggyTimestamp;
+}
+
+/// The clock returning the current system time.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> IggyTimestamp {
+        IggyTimestamp::now()
+    }
+}
+
+/// The clock returning the manually set time, which only changes when it's set or advanced.
+#[derive(Debug, Default)]
+pub struct MockClock {
+    micros: AtomicU64,
+}
+
+impl MockClock {
+    /// Create the clock set to the provided timestamp.
+    pub fn new(timestamp: IggyTimestamp) -> Self {
+        Self {
+            micros: AtomicU64::new(timestamp.to_micros()),
+        }
+    }
+
+    /// Set the clock to the provided timestamp.
+    pub fn set(&self, timestamp: IggyTimestamp) {
+        self.micros.store(timestamp.to_micros(), Ordering::SeqCst);
+    }
+
+    /// Move the clock forward by the provided duration, saturating at the maximum value.
+    pub fn advance(&self, duration: Duration) {
+        let micros = u64::try_from(duration.as_micros()).unwrap_or(u64::MAX);
+        let _ = self
+            .micros
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |current| {
+                Some(current.saturating_add(micros))
+            });
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> IggyTimestamp {
+        IggyTimestamp::from(self.micros.load(Ordering::SeqCst))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mock_clock_should_only_change_when_set_or_advanced() {
+        let clock = MockClock::new(IggyTimestamp::from(1_000_000));
+        assert_eq!(clock.now().to_micros(), 1_000_000);
+        assert_eq!(clock.now().to_micros(), 1_000_000);
+
+        clock.advance(Duration::from_millis(1500));
+        assert_eq!(clock.now().to_micros(), 2_500_000);
+
+        clock.set(IggyTimestamp::from(42));
+        assert_eq!(clock.now().to_micros(), 42);
+
+        clock.advance(Duration::MAX);
+        assert_eq!(clock.now().to_micros(), u64::MAX);
+    }
+
+    #[test]
+    fn age_should_be_measured_against_the_provided_clock() {
+        let clock = MockClock::new(IggyTimestamp::from(10_000_000));
+        let timestamp = IggyTimestamp::from(7_500_000);
+        assert_eq!(timestamp.age_since(&clock), Duration::from_millis(2500));
+
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(timestamp.age_since(&clock), Duration::from_millis(3500));
+
+        clock.set(IggyTimestamp::from(5_000_000));
+        assert_eq!(timestamp.age_since(&clock), Duration::ZERO);
+    }
+}
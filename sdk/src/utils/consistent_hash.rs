@@ -0,0 +1,74 @@
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+const JUMP_MULTIPLIER: u64 = 2_862_933_555_777_941_757;
+
+/// Calculates the partition ID (starting from 1) for the provided key using the jump consistent hash,
+/// so that when the partitions count changes from N to N + 1, only about 1/(N + 1) of the keys are remapped.
+/// The key is first hashed with the 64-bit FNV-1a, which is stable across the platforms and versions.
+/// Returns 0 if there are no partitions.
+pub fn calculate_partition_id(key: &[u8], partitions_count: u32) -> u32 {
+    if partitions_count == 0 {
+        return 0;
+    }
+
+    jump_consistent_hash(fnv1a_64(key), partitions_count) + 1
+}
+
+/// Calculates the 64-bit FNV-1a hash of the provided data.
+pub fn fnv1a_64(data: &[u8]) -> u64 {
+    data.iter().fold(FNV_OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(FNV_PRIME)
+    })
+}
+
+/// Maps the key to one of the buckets in range `[0, buckets)` using the jump consistent hash algorithm
+/// described in "A Fast, Minimal Memory, Consistent Hash Algorithm" by John Lamping and Eric Veach.
+pub fn jump_consistent_hash(mut key: u64, buckets: u32) -> u32 {
+    let mut bucket: i64 = -1;
+    let mut next_bucket: i64 = 0;
+    while next_bucket < i64::from(buckets) {
+        bucket = next_bucket;
+        key = key.wrapping_mul(JUMP_MULTIPLIER).wrapping_add(1);
+        next_bucket =
+            ((bucket + 1) as f64 * ((1u64 << 31) as f64 / ((key >> 33) + 1) as f64)) as i64;
+    }
+    bucket as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partition_id_should_be_in_range() {
+        for key in 0..1000u32 {
+            let partition_id = calculate_partition_id(&key.to_le_bytes(), 7);
+            assert!((1..=7).contains(&partition_id));
+        }
+    }
+
+    #[test]
+    fn partition_id_should_be_0_when_there_are_no_partitions() {
+        assert_eq!(calculate_partition_id(b"key", 0), 0);
+    }
+
+    #[test]
+    fn adding_partition_should_remap_only_fraction_of_keys() {
+        let partitions_count = 10;
+        let keys_count = 10_000u32;
+        let mut remapped_keys = 0;
+        for key in 0..keys_count {
+            let key = format!("key-{key}");
+            let partition_id = calculate_partition_id(key.as_bytes(), partitions_count);
+            let new_partition_id = calculate_partition_id(key.as_bytes(), partitions_count + 1);
+            if partition_id != new_partition_id {
+                assert_eq!(new_partition_id, partitions_count + 1);
+                remapped_keys += 1;
+            }
+        }
+
+        let remapped_ratio = f64::from(remapped_keys) / f64::from(keys_count);
+        let expected_ratio = 1.0 / f64::from(partitions_count + 1);
+        assert!((remapped_ratio - expected_ratio).abs() < 0.02);
+    }
+}
@@ -3,6 +3,7 @@ use crate::utils::text;
 use aes_gcm::aead::generic_array::GenericArray;
 use aes_gcm::aead::{Aead, OsRng};
 use aes_gcm::{AeadCore, Aes256Gcm, KeyInit};
+use bytes::Bytes;
 use std::fmt::Debug;
 
 pub trait Encryptor: Send + Sync + Debug {
@@ -10,6 +11,12 @@ pub trait Encryptor: Send + Sync + Debug {
     fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>, IggyError>;
 }
 
+/// The cipher used to transparently encrypt and decrypt the payload of a single message.
+pub trait PayloadCipher: Send + Sync + Debug {
+    fn encrypt(&self, payload: Bytes) -> Result<Bytes, IggyError>;
+    fn decrypt(&self, payload: Bytes) -> Result<Bytes, IggyError>;
+}
+
 pub struct Aes256GcmEncryptor {
     cipher: Aes256Gcm,
 }
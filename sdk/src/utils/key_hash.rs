@@ -0,0 +1,186 @@
+use crate::error::IggyError;
+use crate::utils::consistent_hash;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use xxhash_rust::xxh32::xxh32;
+use xxhash_rust::xxh64::xxh64;
+
+/// The algorithm used to hash the messages key of the `HashedKey` partitioning, encoded as the first byte of its value,
+/// so the server applies the same algorithm as the producer:
+/// - `Xxh32` - the 32-bit xxHash (seed 0), the same as for the `MessagesKey` partitioning.
+/// - `Fnv1a64` - the 64-bit FNV-1a hash.
+/// - `Xxh64` - the 64-bit xxHash (seed 0).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HashAlgo {
+    /// The 32-bit xxHash (seed 0).
+    #[default]
+    Xxh32,
+    /// The 64-bit FNV-1a hash.
+    Fnv1a64,
+    /// The 64-bit xxHash (seed 0).
+    Xxh64,
+}
+
+impl HashAlgo {
+    /// Get the code of the hash algorithm.
+    pub fn as_code(&self) -> u8 {
+        match self {
+            HashAlgo::Xxh32 => 1,
+            HashAlgo::Fnv1a64 => 2,
+            HashAlgo::Xxh64 => 3,
+        }
+    }
+
+    /// Get the hash algorithm from the provided code.
+    pub fn from_code(code: u8) -> Result<Self, IggyError> {
+        match code {
+            1 => Ok(HashAlgo::Xxh32),
+            2 => Ok(HashAlgo::Fnv1a64),
+            3 => Ok(HashAlgo::Xxh64),
+            _ => Err(IggyError::UnsupportedHashAlgorithm(code)),
+        }
+    }
+
+    /// Get the stable lowercase name of the hash algorithm, matching the `Display` output.
+    pub fn name(&self) -> &'static str {
+        match self {
+            HashAlgo::Xxh32 => "xxh32",
+            HashAlgo::Fnv1a64 => "fnv1a64",
+            HashAlgo::Xxh64 => "xxh64",
+        }
+    }
+
+    /// Calculates the hash of the provided data, widened to 64 bits for the 32-bit algorithms.
+    pub fn hash(&self, data: &[u8]) -> u64 {
+        match self {
+            HashAlgo::Xxh32 => u64::from(calculate_32(data)),
+            HashAlgo::Fnv1a64 => consistent_hash::fnv1a_64(data),
+            HashAlgo::Xxh64 => xxh64(data, 0),
+        }
+    }
+}
+
+impl Display for HashAlgo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Calculates the partition ID (starting from 1) for the provided messages key, the same way as the server does
+/// for the `MessagesKey` partitioning, so the client can resolve the target partition upfront.
+/// The key is hashed with the 32-bit xxHash, and the hash modulo the partitions count is used, with 0 mapped to the last partition.
+/// Returns 0 if there are no partitions.
+pub fn calculate_partition_id(key: &[u8], partitions_count: u32) -> u32 {
+    if partitions_count == 0 {
+        return 0;
+    }
+
+    let partition_id = calculate_32(key) % partitions_count;
+    if partition_id == 0 {
+        return partitions_count;
+    }
+
+    partition_id
+}
+
+/// Calculates the partition ID (starting from 1) for the provided messages key hashed with the provided algorithm,
+/// the same way as the server does for the `HashedKey` partitioning. The hash modulo the partitions count is used,
+/// with 0 mapped to the last partition, so `HashAlgo::Xxh32` resolves the same partition as `calculate_partition_id`.
+/// Returns 0 if there are no partitions.
+pub fn calculate_partition_id_with(algo: HashAlgo, key: &[u8], partitions_count: u32) -> u32 {
+    if partitions_count == 0 {
+        return 0;
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    let partition_id = (algo.hash(key) % u64::from(partitions_count)) as u32;
+    if partition_id == 0 {
+        return partitions_count;
+    }
+
+    partition_id
+}
+
+/// Calculates the 32-bit xxHash of the provided data.
+pub fn calculate_32(data: &[u8]) -> u32 {
+    xxh32(data, 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partition_id_should_be_in_range() {
+        for key in 0..1000u32 {
+            let partition_id = calculate_partition_id(&key.to_le_bytes(), 7);
+            assert!((1..=7).contains(&partition_id));
+        }
+    }
+
+    #[test]
+    fn partition_id_should_be_0_when_there_are_no_partitions() {
+        assert_eq!(calculate_partition_id(b"key", 0), 0);
+    }
+
+    #[test]
+    fn zero_hash_remainder_should_map_to_last_partition() {
+        let key = (0..1000u32)
+            .map(|key| key.to_le_bytes())
+            .find(|key| calculate_32(key).is_multiple_of(5))
+            .unwrap();
+
+        assert_eq!(calculate_partition_id(&key, 5), 5);
+    }
+
+    #[test]
+    fn partition_id_should_be_pinned_for_each_hash_algorithm() {
+        assert_eq!(
+            calculate_partition_id_with(HashAlgo::Xxh32, b"orders", 10),
+            7
+        );
+        assert_eq!(
+            calculate_partition_id_with(HashAlgo::Fnv1a64, b"orders", 10),
+            2
+        );
+        assert_eq!(
+            calculate_partition_id_with(HashAlgo::Xxh64, b"orders", 10),
+            5
+        );
+        assert_eq!(
+            calculate_partition_id_with(HashAlgo::Xxh32, b"customer-42", 7),
+            4
+        );
+        assert_eq!(
+            calculate_partition_id_with(HashAlgo::Fnv1a64, b"customer-42", 7),
+            7
+        );
+        assert_eq!(
+            calculate_partition_id_with(HashAlgo::Xxh64, b"customer-42", 7),
+            1
+        );
+    }
+
+    #[test]
+    fn xxh32_algorithm_should_match_legacy_partition_id() {
+        for key in 0..1000u32 {
+            let key = key.to_le_bytes();
+            assert_eq!(
+                calculate_partition_id_with(HashAlgo::Xxh32, &key, 7),
+                calculate_partition_id(&key, 7)
+            );
+        }
+    }
+
+    #[test]
+    fn hash_algorithm_should_be_mapped_from_its_code() {
+        for algo in [HashAlgo::Xxh32, HashAlgo::Fnv1a64, HashAlgo::Xxh64] {
+            assert_eq!(HashAlgo::from_code(algo.as_code()).unwrap(), algo);
+        }
+        assert!(matches!(
+            HashAlgo::from_code(0),
+            Err(IggyError::UnsupportedHashAlgorithm(0))
+        ));
+    }
+}
@@ -1,6 +1,9 @@
 pub mod byte_size;
 pub mod checksum;
+pub mod clock;
+pub mod consistent_hash;
 pub mod crypto;
 pub mod duration;
+pub mod key_hash;
 pub mod text;
 pub mod timestamp;
@@ -3,6 +3,7 @@ use base64::engine::general_purpose;
 use base64::Engine;
 use lazy_static::lazy_static;
 use regex::Regex;
+use std::fmt::Display;
 
 lazy_static! {
     static ref RESOURCE_NAME_REGEX: Regex = Regex::new(r"^[\w\.\-\s]+$").unwrap();
@@ -32,3 +33,101 @@ pub fn from_base64_as_bytes(value: &str) -> Result<Vec<u8>, IggyError> {
 pub fn as_base64(value: &[u8]) -> String {
     general_purpose::STANDARD.encode(value)
 }
+
+/// The encoding of the text stored in the binary payload.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TextEncoding {
+    /// The UTF-8 encoding.
+    Utf8,
+    /// The ISO-8859-1 encoding, each byte maps directly to the Unicode code point.
+    Latin1,
+    /// The UTF-16 little-endian encoding without the byte order mark.
+    Utf16Le,
+}
+
+impl TextEncoding {
+    /// Decode the provided bytes into the string, returning `IggyError::InvalidTextEncoding` if they are not valid in this encoding.
+    pub fn decode(&self, bytes: &[u8]) -> Result<String, IggyError> {
+        match self {
+            TextEncoding::Utf8 => std::str::from_utf8(bytes)
+                .map(|text| text.to_string())
+                .map_err(|_| IggyError::InvalidTextEncoding(self.to_string())),
+            TextEncoding::Latin1 => Ok(bytes.iter().map(|byte| *byte as char).collect()),
+            TextEncoding::Utf16Le => {
+                if !bytes.len().is_multiple_of(2) {
+                    return Err(IggyError::InvalidTextEncoding(self.to_string()));
+                }
+
+                let units = bytes
+                    .chunks_exact(2)
+                    .map(|unit| u16::from_le_bytes([unit[0], unit[1]]));
+                char::decode_utf16(units)
+                    .collect::<Result<String, _>>()
+                    .map_err(|_| IggyError::InvalidTextEncoding(self.to_string()))
+            }
+        }
+    }
+}
+
+impl Display for TextEncoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TextEncoding::Utf8 => write!(f, "utf8"),
+            TextEncoding::Latin1 => write!(f, "latin1"),
+            TextEncoding::Utf16Le => write!(f, "utf16le"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn utf8_text_should_be_decoded() {
+        let text = TextEncoding::Utf8
+            .decode(&[0x63, 0x61, 0x66, 0xC3, 0xA9])
+            .unwrap();
+
+        assert_eq!(text, "café");
+    }
+
+    #[test]
+    fn invalid_utf8_text_should_not_be_decoded() {
+        let result = TextEncoding::Utf8.decode(&[0x63, 0x61, 0x66, 0xE9]);
+
+        assert!(matches!(result, Err(IggyError::InvalidTextEncoding(_))));
+    }
+
+    #[test]
+    fn latin1_text_should_be_decoded() {
+        let text = TextEncoding::Latin1
+            .decode(&[0x63, 0x61, 0x66, 0xE9, 0xA3])
+            .unwrap();
+
+        assert_eq!(text, "café£");
+    }
+
+    #[test]
+    fn utf16le_text_should_be_decoded() {
+        let text = TextEncoding::Utf16Le
+            .decode(&[0x63, 0x00, 0xE9, 0x00, 0xAC, 0x20, 0x3D, 0xD8, 0x00, 0xDE])
+            .unwrap();
+
+        assert_eq!(text, "cé€😀");
+    }
+
+    #[test]
+    fn utf16le_text_with_odd_length_should_not_be_decoded() {
+        let result = TextEncoding::Utf16Le.decode(&[0x63, 0x00, 0x61]);
+
+        assert!(matches!(result, Err(IggyError::InvalidTextEncoding(_))));
+    }
+
+    #[test]
+    fn utf16le_text_with_unpaired_surrogate_should_not_be_decoded() {
+        let result = TextEncoding::Utf16Le.decode(&[0x3D, 0xD8, 0x63, 0x00]);
+
+        assert!(matches!(result, Err(IggyError::InvalidTextEncoding(_))));
+    }
+}
@@ -1,4 +1,7 @@
+use crate::error::IggyError;
+use crate::utils::clock::Clock;
 use chrono::{DateTime, Local, Utc};
+use std::ops::{Add, Sub};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 /// A struct that represents a timestamp.
@@ -28,6 +31,16 @@ impl IggyTimestamp {
         IggyTimestamp::default()
     }
 
+    /// Parses the timestamp from the RFC 3339 string, e.g. `2023-09-17T16:34:06Z` or `2023-09-17T18:34:06+02:00`.
+    /// The timestamps before the Unix epoch are rejected.
+    pub fn from_rfc3339(value: &str) -> Result<Self, IggyError> {
+        let timestamp = DateTime::parse_from_rfc3339(value)
+            .map_err(|_| IggyError::CannotParseTimestamp(value.to_string()))?;
+        let micros = u64::try_from(timestamp.timestamp_micros())
+            .map_err(|_| IggyError::CannotParseTimestamp(value.to_string()))?;
+        Ok(IggyTimestamp::from(micros))
+    }
+
     pub fn to_secs(&self) -> u64 {
         self.0.duration_since(UNIX_EPOCH).unwrap().as_secs()
     }
@@ -43,6 +56,36 @@ impl IggyTimestamp {
     pub fn to_local(&self, format: &str) -> String {
         DateTime::<Local>::from(self.0).format(format).to_string()
     }
+
+    /// Returns the duration elapsed from the other timestamp, or zero if the other timestamp is later.
+    pub fn duration_since(&self, other: &IggyTimestamp) -> Duration {
+        Duration::from_micros(self.to_micros().saturating_sub(other.to_micros()))
+    }
+
+    /// Returns the duration elapsed from the timestamp to the current time of the clock, or zero if the timestamp is later.
+    pub fn age_since(&self, clock: &dyn Clock) -> Duration {
+        clock.now().duration_since(self)
+    }
+}
+
+impl Add<Duration> for IggyTimestamp {
+    type Output = IggyTimestamp;
+
+    /// Adds the duration to the timestamp, saturating at the maximum value.
+    fn add(self, duration: Duration) -> Self::Output {
+        let micros = u64::try_from(duration.as_micros()).unwrap_or(u64::MAX);
+        IggyTimestamp::from(self.to_micros().saturating_add(micros))
+    }
+}
+
+impl Sub<Duration> for IggyTimestamp {
+    type Output = IggyTimestamp;
+
+    /// Subtracts the duration from the timestamp, saturating at the Unix epoch.
+    fn sub(self, duration: Duration) -> Self::Output {
+        let micros = u64::try_from(duration.as_micros()).unwrap_or(u64::MAX);
+        IggyTimestamp::from(self.to_micros().saturating_sub(micros))
+    }
 }
 
 impl From<u64> for IggyTimestamp {
@@ -81,4 +124,51 @@ mod tests {
         let timestamp = IggyTimestamp::from(1663472051111);
         assert_eq!(timestamp.to_micros(), 1663472051111);
     }
+
+    #[test]
+    fn test_timestamp_from_rfc3339() {
+        let timestamp = IggyTimestamp::from_rfc3339("2023-09-17T16:34:06.13168Z").unwrap();
+        assert_eq!(timestamp.to_micros(), 1694968446131680);
+
+        let timestamp = IggyTimestamp::from_rfc3339("2023-09-17T18:34:06+02:00").unwrap();
+        assert_eq!(timestamp.to_secs(), 1694968446);
+    }
+
+    #[test]
+    fn test_timestamp_from_invalid_rfc3339_should_fail() {
+        assert!(matches!(
+            IggyTimestamp::from_rfc3339("2023-09-17 16:34"),
+            Err(IggyError::CannotParseTimestamp(_))
+        ));
+        assert!(matches!(
+            IggyTimestamp::from_rfc3339("1969-12-31T23:59:59Z"),
+            Err(IggyError::CannotParseTimestamp(_))
+        ));
+    }
+
+    #[test]
+    fn test_timestamp_add_duration_across_second_boundary() {
+        let timestamp = IggyTimestamp::from(1_999_999);
+        let timestamp = timestamp + Duration::from_micros(2);
+        assert_eq!(timestamp.to_micros(), 2_000_001);
+        assert_eq!(timestamp.to_secs(), 2);
+    }
+
+    #[test]
+    fn test_timestamp_sub_duration_should_saturate_to_epoch() {
+        let timestamp = IggyTimestamp::from(1_000);
+        let timestamp = timestamp - Duration::from_secs(1);
+        assert_eq!(timestamp.to_micros(), 0);
+    }
+
+    #[test]
+    fn test_timestamp_duration_since() {
+        let earlier = IggyTimestamp::from(1_000_000);
+        let later = IggyTimestamp::from(3_500_000);
+        assert_eq!(
+            later.duration_since(&earlier),
+            Duration::from_micros(2_500_000)
+        );
+        assert_eq!(earlier.duration_since(&later), Duration::ZERO);
+    }
 }
@@ -1,6 +1,7 @@
 use crate::binary::handlers::consumer_groups::{
     create_consumer_group_handler, delete_consumer_group_handler, get_consumer_group_handler,
     get_consumer_groups_handler, join_consumer_group_handler, leave_consumer_group_handler,
+    reset_consumer_group_offset_handler,
 };
 use crate::binary::handlers::consumer_offsets::*;
 use crate::binary::handlers::messages::*;
@@ -103,6 +104,9 @@ async fn try_handle(
         Command::SendMessages(command) => {
             send_messages_handler::handle(command, sender, session, system).await
         }
+        Command::SendMessagesConditional(command) => {
+            send_messages_conditional_handler::handle(command, sender, session, system).await
+        }
         Command::PollMessages(command) => {
             poll_messages_handler::handle(command, sender, session, system).await
         }
@@ -172,5 +176,8 @@ async fn try_handle(
         Command::LeaveConsumerGroup(command) => {
             leave_consumer_group_handler::handle(command, sender, session, system).await
         }
+        Command::ResetConsumerGroupOffset(command) => {
+            reset_consumer_group_offset_handler::handle(command, sender, session, system).await
+        }
     }
 }
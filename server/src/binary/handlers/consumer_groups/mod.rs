@@ -4,3 +4,4 @@ pub mod get_consumer_group_handler;
 pub mod get_consumer_groups_handler;
 pub mod join_consumer_group_handler;
 pub mod leave_consumer_group_handler;
+pub mod reset_consumer_group_offset_handler;
@@ -0,0 +1,28 @@
+use crate::binary::sender::Sender;
+use crate::streaming::session::Session;
+use crate::streaming::systems::system::SharedSystem;
+use anyhow::Result;
+use iggy::consumer_groups::reset_consumer_group_offset::ResetConsumerGroupOffset;
+use iggy::error::IggyError;
+use tracing::debug;
+
+pub async fn handle(
+    command: &ResetConsumerGroupOffset,
+    sender: &mut dyn Sender,
+    session: &Session,
+    system: &SharedSystem,
+) -> Result<(), IggyError> {
+    debug!("session: {session}, command: {command}");
+    let system = system.read();
+    system
+        .reset_consumer_group_offset(
+            session,
+            &command.stream_id,
+            &command.topic_id,
+            &command.consumer_group_id,
+            &command.to,
+        )
+        .await?;
+    sender.send_empty_ok_response().await?;
+    Ok(())
+}
@@ -1,2 +1,3 @@
 pub mod poll_messages_handler;
+pub mod send_messages_conditional_handler;
 pub mod send_messages_handler;
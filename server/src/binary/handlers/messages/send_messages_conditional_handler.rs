@@ -0,0 +1,29 @@
+use crate::binary::sender::Sender;
+use crate::streaming::session::Session;
+use crate::streaming::systems::system::SharedSystem;
+use anyhow::Result;
+use iggy::error::IggyError;
+use iggy::messages::send_messages_conditional::SendMessagesConditional;
+use tracing::debug;
+
+pub async fn handle(
+    command: &SendMessagesConditional,
+    sender: &mut dyn Sender,
+    session: &Session,
+    system: &SharedSystem,
+) -> Result<(), IggyError> {
+    debug!("session: {session}, command: {command}");
+    let system = system.read();
+    system
+        .append_messages_conditional(
+            session,
+            &command.send_messages.stream_id,
+            &command.send_messages.topic_id,
+            &command.send_messages.partitioning,
+            command.expected_last_id,
+            command.send_messages.messages(),
+        )
+        .await?;
+    sender.send_empty_ok_response().await?;
+    Ok(())
+}
@@ -20,7 +20,8 @@ pub async fn handle(
             &command.stream_id,
             &command.topic_id,
             &command.partitioning,
-            &command.messages,
+            command.idempotency_key,
+            command.messages(),
         )
         .await?;
     sender.send_empty_ok_response().await?;
@@ -7,9 +7,9 @@ use crate::configs::server::{
     PersonalAccessTokenConfig, ServerConfig,
 };
 use crate::configs::system::{
-    CacheConfig, CompressionConfig, DatabaseConfig, EncryptionConfig, LoggingConfig,
-    MessageDeduplicationConfig, PartitionConfig, RetentionPolicyConfig, RuntimeConfig,
-    SegmentConfig, StreamConfig, SystemConfig, TopicConfig,
+    BatchDeduplicationConfig, CacheConfig, CompressionConfig, DatabaseConfig, EncryptionConfig,
+    LoggingConfig, MessageDeduplicationConfig, PartitionConfig, RetentionPolicyConfig,
+    RuntimeConfig, SegmentConfig, StreamConfig, SystemConfig, TopicConfig,
 };
 use crate::configs::tcp::{TcpConfig, TcpTlsConfig};
 use std::sync::Arc;
@@ -150,6 +150,7 @@ impl Default for SystemConfig {
             segment: SegmentConfig::default(),
             compression: CompressionConfig::default(),
             message_deduplication: MessageDeduplicationConfig::default(),
+            batch_deduplication: BatchDeduplicationConfig::default(),
         }
     }
 }
@@ -254,3 +255,12 @@ impl Default for MessageDeduplicationConfig {
         }
     }
 }
+
+impl Default for BatchDeduplicationConfig {
+    fn default() -> BatchDeduplicationConfig {
+        BatchDeduplicationConfig {
+            max_entries: 10000,
+            expiry: "1m".parse().unwrap(),
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use crate::configs::quic::{QuicCertificateConfig, QuicConfig};
-use crate::configs::system::MessageDeduplicationConfig;
+use crate::configs::system::{BatchDeduplicationConfig, MessageDeduplicationConfig};
 use crate::configs::{
     http::{HttpConfig, HttpCorsConfig, HttpJwtConfig, HttpMetricsConfig, HttpTlsConfig},
     resource_quota::MemoryResourceQuota,
@@ -205,6 +205,16 @@ impl Display for MessageDeduplicationConfig {
     }
 }
 
+impl Display for BatchDeduplicationConfig {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{{ max_entries: {:?}, expiry: {:?} }}",
+            self.max_entries, self.expiry
+        )
+    }
+}
+
 impl Display for SegmentConfig {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
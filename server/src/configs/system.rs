@@ -22,6 +22,7 @@ pub struct SystemConfig {
     pub encryption: EncryptionConfig,
     pub compression: CompressionConfig,
     pub message_deduplication: MessageDeduplicationConfig,
+    pub batch_deduplication: BatchDeduplicationConfig,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -97,6 +98,14 @@ pub struct MessageDeduplicationConfig {
     pub expiry: IggyDuration,
 }
 
+#[serde_as]
+#[derive(Debug, Deserialize, Serialize)]
+pub struct BatchDeduplicationConfig {
+    pub max_entries: u64,
+    #[serde_as(as = "DisplayFromStr")]
+    pub expiry: IggyDuration,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct SegmentConfig {
     pub size: IggyByteSize,
@@ -5,9 +5,10 @@ use crate::http::shared::AppState;
 use crate::streaming::session::Session;
 use axum::extract::{Path, State};
 use axum::http::StatusCode;
-use axum::routing::get;
+use axum::routing::{get, put};
 use axum::{Extension, Json, Router};
 use iggy::consumer_groups::create_consumer_group::CreateConsumerGroup;
+use iggy::consumer_groups::reset_consumer_group_offset::ResetConsumerGroupOffset;
 use iggy::identifier::Identifier;
 use iggy::models::consumer_group::{ConsumerGroup, ConsumerGroupDetails};
 use iggy::validatable::Validatable;
@@ -23,6 +24,10 @@ pub fn router(state: Arc<AppState>) -> Router {
             "/streams/:stream_id/topics/:topic_id/consumer-groups/:consumer_group_id",
             get(get_consumer_group).delete(delete_consumer_group),
         )
+        .route(
+            "/streams/:stream_id/topics/:topic_id/consumer-groups/:consumer_group_id/offsets",
+            put(reset_consumer_group_offset),
+        )
         .with_state(state)
 }
 
@@ -104,3 +109,26 @@ async fn delete_consumer_group(
         .await?;
     Ok(StatusCode::NO_CONTENT)
 }
+
+async fn reset_consumer_group_offset(
+    State(state): State<Arc<AppState>>,
+    Extension(identity): Extension<Identity>,
+    Path((stream_id, topic_id, consumer_group_id)): Path<(String, String, String)>,
+    Json(mut command): Json<ResetConsumerGroupOffset>,
+) -> Result<StatusCode, CustomError> {
+    command.stream_id = Identifier::from_str_value(&stream_id)?;
+    command.topic_id = Identifier::from_str_value(&topic_id)?;
+    command.consumer_group_id = Identifier::from_str_value(&consumer_group_id)?;
+    command.validate()?;
+    let system = state.system.read();
+    system
+        .reset_consumer_group_offset(
+            &Session::stateless(identity.user_id, identity.ip_address),
+            &command.stream_id,
+            &command.topic_id,
+            &command.consumer_group_id,
+            &command.to,
+        )
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
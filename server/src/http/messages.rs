@@ -7,11 +7,12 @@ use crate::streaming::session::Session;
 use crate::streaming::systems::messages::PollingArgs;
 use axum::extract::{Path, Query, State};
 use axum::http::StatusCode;
-use axum::routing::get;
+use axum::routing::{get, post};
 use axum::{Extension, Json, Router};
 use iggy::identifier::Identifier;
 use iggy::messages::poll_messages::PollMessages;
 use iggy::messages::send_messages::SendMessages;
+use iggy::messages::send_messages_conditional::SendMessagesConditional;
 use iggy::validatable::Validatable;
 use std::sync::Arc;
 
@@ -21,6 +22,10 @@ pub fn router(state: Arc<AppState>) -> Router {
             "/streams/:stream_id/topics/:topic_id/messages",
             get(poll_messages).post(send_messages),
         )
+        .route(
+            "/streams/:stream_id/topics/:topic_id/messages/conditional",
+            post(send_messages_conditional),
+        )
         .with_state(state)
 }
 
@@ -68,7 +73,34 @@ async fn send_messages(
             &command.stream_id,
             &command.topic_id,
             &command.partitioning,
-            &command.messages,
+            command.idempotency_key,
+            command.messages(),
+        )
+        .await?;
+    Ok(StatusCode::CREATED)
+}
+
+async fn send_messages_conditional(
+    State(state): State<Arc<AppState>>,
+    Extension(identity): Extension<Identity>,
+    Path((stream_id, topic_id)): Path<(String, String)>,
+    Json(mut command): Json<SendMessagesConditional>,
+) -> Result<StatusCode, CustomError> {
+    command.send_messages.stream_id = Identifier::from_str_value(&stream_id)?;
+    command.send_messages.topic_id = Identifier::from_str_value(&topic_id)?;
+    command.send_messages.partitioning.length =
+        command.send_messages.partitioning.value.len() as u8;
+    command.validate()?;
+
+    let system = state.system.read();
+    system
+        .append_messages_conditional(
+            &Session::stateless(identity.user_id, identity.ip_address),
+            &command.send_messages.stream_id,
+            &command.send_messages.topic_id,
+            &command.send_messages.partitioning,
+            command.expected_last_id,
+            command.send_messages.messages(),
         )
         .await?;
     Ok(StatusCode::CREATED)
@@ -33,13 +33,14 @@ impl MessageDeduplicator {
     }
 
     /// Tries to insert the given ID, returns false if it already exists.
+    /// The check and the insertion are atomic, so only one of the concurrent callers inserts the same ID.
     pub async fn try_insert(&self, id: &u128) -> bool {
-        if self.exists(id) {
-            false
-        } else {
-            self.insert(*id).await;
-            true
-        }
+        self.cache.entry(*id).or_insert(true).await.is_fresh()
+    }
+
+    /// Removes the given ID, so it can be inserted again.
+    pub async fn remove(&self, id: &u128) {
+        self.cache.invalidate(id).await;
     }
 }
 
@@ -61,6 +62,34 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn message_deduplicator_should_insert_identifier_once_for_concurrent_callers() {
+        let deduplicator = std::sync::Arc::new(MessageDeduplicator::new(Some(1000), None));
+        let tasks = (0..10)
+            .map(|_| {
+                let deduplicator = deduplicator.clone();
+                tokio::spawn(async move { deduplicator.try_insert(&1).await })
+            })
+            .collect::<Vec<_>>();
+
+        let mut inserted = 0;
+        for task in tasks {
+            if task.await.unwrap() {
+                inserted += 1;
+            }
+        }
+        assert_eq!(inserted, 1);
+    }
+
+    #[tokio::test]
+    async fn message_deduplicator_should_insert_removed_identifier_again() {
+        let deduplicator = MessageDeduplicator::new(Some(1000), None);
+        assert!(deduplicator.try_insert(&1).await);
+        deduplicator.remove(&1).await;
+        assert!(!deduplicator.exists(&1));
+        assert!(deduplicator.try_insert(&1).await);
+    }
+
     #[tokio::test]
     async fn message_deduplicator_should_evict_identifiers_after_given_time_to_live() {
         let max_entries = 3;
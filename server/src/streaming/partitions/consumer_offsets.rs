@@ -2,6 +2,7 @@ use crate::streaming::partitions::partition::{ConsumerOffset, Partition};
 use crate::streaming::polling_consumer::PollingConsumer;
 use dashmap::DashMap;
 use iggy::consumer::ConsumerKind;
+use iggy::consumer_groups::reset_consumer_group_offset::{ResetOffsetKind, ResetOffsetTarget};
 use iggy::error::IggyError;
 use tracing::trace;
 
@@ -62,6 +63,64 @@ impl Partition {
         Ok(())
     }
 
+    /// Resets the stored offset of the consumer group, so the next poll returns the message pointed by the target.
+    /// The offset is removed if the target points to the first message, or there are no messages in the partition yet.
+    pub async fn reset_consumer_group_offset(
+        &self,
+        consumer_group_id: u32,
+        to: &ResetOffsetTarget,
+    ) -> Result<(), IggyError> {
+        trace!(
+            "Resetting offset for consumer group: {} to: {}, partition: {}, current: {}...",
+            consumer_group_id,
+            to,
+            self.partition_id,
+            self.current_offset
+        );
+        let latest_offset = self.should_increment_offset.then_some(self.current_offset);
+        let offset = match to.kind {
+            ResetOffsetKind::Earliest => None,
+            ResetOffsetKind::Latest => latest_offset,
+            ResetOffsetKind::Timestamp => match self.get_offset_by_timestamp(to.value) {
+                Some(offset) => offset.checked_sub(1),
+                None => latest_offset,
+            },
+        };
+
+        match offset {
+            Some(offset) => {
+                self.store_offset(ConsumerKind::ConsumerGroup, consumer_group_id, offset)
+                    .await
+            }
+            None => {
+                self.delete_offset(ConsumerKind::ConsumerGroup, consumer_group_id)
+                    .await
+            }
+        }
+    }
+
+    fn get_offset_by_timestamp(&self, timestamp: u64) -> Option<u64> {
+        self.segments.iter().find_map(|segment| {
+            segment
+                .time_indexes
+                .as_ref()?
+                .iter()
+                .find(|time_index| time_index.timestamp >= timestamp)
+                .map(|time_index| segment.start_offset + time_index.relative_offset as u64)
+        })
+    }
+
+    async fn delete_offset(&self, kind: ConsumerKind, consumer_id: u32) -> Result<(), IggyError> {
+        let consumer_offsets = self.get_consumer_offsets(kind);
+        if let Some((_, consumer_offset)) = consumer_offsets.remove(&consumer_id) {
+            self.storage
+                .partition
+                .delete_consumer_offset(&consumer_offset)
+                .await?;
+        }
+        Ok(())
+    }
+
     async fn store_offset(
         &self,
         kind: ConsumerKind,
@@ -147,3 +206,116 @@ impl Partition {
             );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::system::SystemConfig;
+    use crate::streaming::partitions::create_messages;
+    use crate::streaming::storage::tests::get_test_system_storage;
+    use std::sync::atomic::AtomicU64;
+    use std::sync::Arc;
+
+    const CONSUMER_GROUP_ID: u32 = 1;
+
+    #[tokio::test]
+    async fn consumer_group_offset_should_be_removed_when_reset_to_earliest() {
+        let partition = create_partition_with_messages().await;
+        store_consumer_group_offset(&partition, 1).await;
+
+        partition
+            .reset_consumer_group_offset(CONSUMER_GROUP_ID, &ResetOffsetTarget::earliest())
+            .await
+            .unwrap();
+
+        assert!(partition
+            .consumer_group_offsets
+            .get(&CONSUMER_GROUP_ID)
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn consumer_group_offset_should_be_set_to_current_offset_when_reset_to_latest() {
+        let partition = create_partition_with_messages().await;
+
+        partition
+            .reset_consumer_group_offset(CONSUMER_GROUP_ID, &ResetOffsetTarget::latest())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            get_consumer_group_offset(&partition),
+            Some(partition.current_offset)
+        );
+    }
+
+    #[tokio::test]
+    async fn consumer_group_offset_should_be_removed_when_reset_to_latest_without_messages() {
+        let partition = create_partition();
+        store_consumer_group_offset(&partition, 0).await;
+
+        partition
+            .reset_consumer_group_offset(CONSUMER_GROUP_ID, &ResetOffsetTarget::latest())
+            .await
+            .unwrap();
+
+        assert_eq!(get_consumer_group_offset(&partition), None);
+    }
+
+    #[tokio::test]
+    async fn consumer_group_offset_should_be_reset_to_first_message_after_timestamp() {
+        let partition = create_partition_with_messages().await;
+        store_consumer_group_offset(&partition, 1).await;
+
+        partition
+            .reset_consumer_group_offset(CONSUMER_GROUP_ID, &ResetOffsetTarget::timestamp(0))
+            .await
+            .unwrap();
+        assert_eq!(get_consumer_group_offset(&partition), None);
+
+        partition
+            .reset_consumer_group_offset(CONSUMER_GROUP_ID, &ResetOffsetTarget::timestamp(u64::MAX))
+            .await
+            .unwrap();
+        assert_eq!(
+            get_consumer_group_offset(&partition),
+            Some(partition.current_offset)
+        );
+    }
+
+    async fn store_consumer_group_offset(partition: &Partition, offset: u64) {
+        partition
+            .store_consumer_offset(PollingConsumer::ConsumerGroup(CONSUMER_GROUP_ID, 1), offset)
+            .await
+            .unwrap();
+    }
+
+    fn get_consumer_group_offset(partition: &Partition) -> Option<u64> {
+        partition
+            .consumer_group_offsets
+            .get(&CONSUMER_GROUP_ID)
+            .map(|consumer_offset| consumer_offset.offset)
+    }
+
+    async fn create_partition_with_messages() -> Partition {
+        let mut partition = create_partition();
+        partition.append_messages(create_messages()).await.unwrap();
+        partition
+    }
+
+    fn create_partition() -> Partition {
+        Partition::create(
+            1,
+            2,
+            3,
+            true,
+            Arc::new(SystemConfig::default()),
+            Arc::new(get_test_system_storage()),
+            None,
+            Arc::new(AtomicU64::new(0)),
+            Arc::new(AtomicU64::new(0)),
+            Arc::new(AtomicU64::new(0)),
+            Arc::new(AtomicU64::new(0)),
+        )
+    }
+}
@@ -96,6 +96,24 @@ impl PartitionStorage for FilePartitionStorage {
         Ok(consumer_offsets)
     }
 
+    async fn delete_consumer_offset(&self, offset: &ConsumerOffset) -> Result<(), IggyError> {
+        if let Err(err) = self
+            .db
+            .remove(&offset.key)
+            .with_context(|| format!("Failed to delete consumer offset, key: {}", offset.key))
+        {
+            return Err(IggyError::CannotDeleteResource(err));
+        }
+
+        trace!(
+            "Deleted consumer offset value: {} for {} with ID: {}",
+            offset.offset,
+            offset.kind,
+            offset.consumer_id
+        );
+        Ok(())
+    }
+
     async fn delete_consumer_offsets(
         &self,
         kind: ConsumerKind,
@@ -83,6 +83,7 @@ pub trait PartitionStorage: Storage<Partition> {
         topic_id: u32,
         partition_id: u32,
     ) -> Result<Vec<ConsumerOffset>, IggyError>;
+    async fn delete_consumer_offset(&self, offset: &ConsumerOffset) -> Result<(), IggyError>;
     async fn delete_consumer_offsets(
         &self,
         kind: ConsumerKind,
@@ -414,6 +415,10 @@ pub(crate) mod tests {
             Ok(vec![])
         }
 
+        async fn delete_consumer_offset(&self, _offset: &ConsumerOffset) -> Result<(), IggyError> {
+            Ok(())
+        }
+
         async fn delete_consumer_offsets(
             &self,
             _kind: ConsumerKind,
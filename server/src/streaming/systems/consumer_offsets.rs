@@ -1,6 +1,7 @@
 use crate::streaming::polling_consumer::PollingConsumer;
 use crate::streaming::session::Session;
 use crate::streaming::systems::system::System;
+use iggy::consumer_groups::reset_consumer_group_offset::ResetOffsetTarget;
 use iggy::error::IggyError;
 use iggy::identifier::Identifier;
 use iggy::models::consumer_offset_info::ConsumerOffsetInfo;
@@ -44,4 +45,26 @@ impl System {
 
         topic.get_consumer_offset(consumer).await
     }
+
+    pub async fn reset_consumer_group_offset(
+        &self,
+        session: &Session,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        consumer_group_id: &Identifier,
+        to: &ResetOffsetTarget,
+    ) -> Result<(), IggyError> {
+        self.ensure_authenticated(session)?;
+        let stream = self.get_stream(stream_id)?;
+        let topic = stream.get_topic(topic_id)?;
+        self.permissioner.reset_consumer_group_offset(
+            session.get_user_id(),
+            stream.stream_id,
+            topic.topic_id,
+        )?;
+
+        topic
+            .reset_consumer_group_offset(consumer_group_id, to)
+            .await
+    }
 }
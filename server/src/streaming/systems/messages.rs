@@ -3,12 +3,15 @@ use crate::streaming::models::messages::PolledMessages;
 use crate::streaming::polling_consumer::PollingConsumer;
 use crate::streaming::session::Session;
 use crate::streaming::systems::system::System;
+use crate::streaming::topics::topic::Topic;
 use bytes::Bytes;
+use iggy::compression::compression_algorithm::CompressionAlgorithm;
 use iggy::error::IggyError;
 use iggy::identifier::Identifier;
 use iggy::messages::poll_messages::PollingStrategy;
 use iggy::messages::send_messages;
 use iggy::messages::send_messages::Partitioning;
+use iggy::messages::MAX_PAYLOAD_SIZE;
 use iggy::models::messages::Message;
 use std::sync::Arc;
 use tracing::{error, trace};
@@ -94,13 +97,15 @@ impl System {
         Ok(polled_messages)
     }
 
+    /// Append the messages to the topic, the batch with the idempotency key of the already appended one is ignored.
     pub async fn append_messages(
         &self,
         session: &Session,
         stream_id: &Identifier,
         topic_id: &Identifier,
         partitioning: &Partitioning,
-        messages: &Vec<send_messages::Message>,
+        idempotency_key: Option<u128>,
+        messages: &[send_messages::Message],
     ) -> Result<(), IggyError> {
         self.ensure_authenticated(session)?;
         let stream = self.get_stream(stream_id)?;
@@ -111,26 +116,121 @@ impl System {
             topic.topic_id,
         )?;
 
+        // The key is claimed atomically before the batch is appended, so the concurrent retries can't both append it,
+        // and it's released if the append fails, so the failed batch can still be retried.
+        if let Some(idempotency_key) = idempotency_key {
+            if !self.batch_deduplicator.try_insert(&idempotency_key).await {
+                trace!(
+                    "Ignoring the already appended batch with idempotency key: {idempotency_key}"
+                );
+                return Ok(());
+            }
+        }
+
+        let result = self
+            .append_prepared_messages(topic, partitioning, messages)
+            .await;
+        if let Some(idempotency_key) = idempotency_key {
+            if result.is_err() {
+                self.batch_deduplicator.remove(&idempotency_key).await;
+            }
+        }
+        result?;
+        self.metrics.increment_messages(messages.len() as u64);
+        Ok(())
+    }
+
+    async fn append_prepared_messages(
+        &self,
+        topic: &Topic,
+        partitioning: &Partitioning,
+        messages: &[send_messages::Message],
+    ) -> Result<(), IggyError> {
+        let received_messages = self.prepare_messages_to_append(messages).await?;
+        if messages
+            .iter()
+            .any(|message| message.partition_id.is_some())
+        {
+            let received_messages = messages
+                .iter()
+                .map(|message| message.partition_id)
+                .zip(received_messages)
+                .collect();
+            topic
+                .append_messages_with_partition_ids(partitioning, received_messages)
+                .await
+        } else {
+            topic.append_messages(partitioning, received_messages).await
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn append_messages_conditional(
+        &self,
+        session: &Session,
+        stream_id: &Identifier,
+        topic_id: &Identifier,
+        partitioning: &Partitioning,
+        expected_last_id: u128,
+        messages: &[send_messages::Message],
+    ) -> Result<(), IggyError> {
+        self.ensure_authenticated(session)?;
+        let stream = self.get_stream(stream_id)?;
+        let topic = stream.get_topic(topic_id)?;
+        self.permissioner.append_messages(
+            session.get_user_id(),
+            stream.stream_id,
+            topic.topic_id,
+        )?;
+
+        let received_messages = self.prepare_messages_to_append(messages).await?;
+        topic
+            .append_messages_conditional(partitioning, expected_last_id, received_messages)
+            .await?;
+        self.metrics.increment_messages(messages.len() as u64);
+        Ok(())
+    }
+
+    async fn prepare_messages_to_append(
+        &self,
+        messages: &[send_messages::Message],
+    ) -> Result<Vec<Message>, IggyError> {
         let mut received_messages = Vec::with_capacity(messages.len());
         let mut batch_size_bytes = 0u64;
+        let mut decompression_budget = MAX_PAYLOAD_SIZE as usize;
 
         // For large batches it would be better to use par_iter() from rayon.
         for message in messages {
+            // The messages sent over HTTP keep their compressed payloads, which are decompressed within the budget
+            // of the whole batch, so the stored and polled messages don't depend on the compression.
+            let decompressed_message;
+            let message = if message.compression == CompressionAlgorithm::None {
+                message
+            } else {
+                let mut message = message.clone();
+                message
+                    .decompress(decompression_budget)
+                    .map_err(|error| match error {
+                        IggyError::DecompressionLimitExceeded => IggyError::TooBigMessagePayload,
+                        error => error,
+                    })?;
+                decompressed_message = message;
+                &decompressed_message
+            };
+            decompression_budget = decompression_budget.saturating_sub(message.payload.len());
+
             let encrypted_message;
             let message = match self.encryptor {
                 Some(ref encryptor) => {
                     let payload = encryptor.encrypt(message.payload.as_ref())?;
-                    encrypted_message = send_messages::Message {
-                        id: message.id,
-                        length: payload.len() as u32,
-                        payload: Bytes::from(payload),
-                        headers: message.headers.clone(),
-                    };
+                    let mut message = message.clone();
+                    message.map_payload_cow(|_| Some(Bytes::from(payload)));
+                    encrypted_message = message;
                     &encrypted_message
                 }
                 None => message,
             };
-            batch_size_bytes += message.get_size_bytes() as u64;
+            batch_size_bytes += message.get_size_bytes();
             received_messages.push(Message::from_message(message));
         }
 
@@ -141,11 +241,8 @@ impl System {
                 self.clean_cache(batch_size_bytes).await;
             }
         }
-        topic
-            .append_messages(partitioning, received_messages)
-            .await?;
-        self.metrics.increment_messages(messages.len() as u64);
-        Ok(())
+
+        Ok(received_messages)
     }
 }
 
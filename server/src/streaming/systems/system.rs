@@ -2,6 +2,7 @@ use crate::configs::server::PersonalAccessTokenConfig;
 use crate::configs::system::SystemConfig;
 use crate::streaming::cache::memory_tracker::CacheMemoryTracker;
 use crate::streaming::clients::client_manager::ClientManager;
+use crate::streaming::deduplication::message_deduplicator::MessageDeduplicator;
 use crate::streaming::diagnostics::metrics::Metrics;
 use crate::streaming::persistence::persister::*;
 use crate::streaming::session::Session;
@@ -61,6 +62,7 @@ pub struct System {
     pub(crate) encryptor: Option<Box<dyn Encryptor>>,
     pub(crate) metrics: Metrics,
     pub(crate) db: Option<Arc<Db>>,
+    pub(crate) batch_deduplicator: MessageDeduplicator,
     pub personal_access_token: PersonalAccessTokenConfig,
 }
 
@@ -106,6 +108,20 @@ impl System {
             "Server-side encryption is {}.",
             Self::map_toggle_str(config.encryption.enabled)
         );
+        // The idempotency keys of the appended batches are shared by all the topics and always deduplicated,
+        // as only the batches sent with the key are affected, independently of the message IDs deduplication.
+        let batch_deduplicator = MessageDeduplicator::new(
+            if config.batch_deduplication.max_entries > 0 {
+                Some(config.batch_deduplication.max_entries)
+            } else {
+                None
+            },
+            if config.batch_deduplication.expiry.is_zero() {
+                None
+            } else {
+                Some(config.batch_deduplication.expiry)
+            },
+        );
         System {
             encryptor: match config.encryption.enabled {
                 true => Some(Box::new(
@@ -121,6 +137,7 @@ impl System {
             permissioner: Permissioner::default(),
             metrics: Metrics::init(),
             db,
+            batch_deduplicator,
             personal_access_token: pat_config,
         }
     }
@@ -1,9 +1,12 @@
 use crate::streaming::partitions::partition::Partition;
 use crate::streaming::polling_consumer::PollingConsumer;
 use crate::streaming::topics::topic::Topic;
+use iggy::consumer_groups::reset_consumer_group_offset::ResetOffsetTarget;
 use iggy::error::IggyError;
+use iggy::identifier::Identifier;
 use iggy::models::consumer_offset_info::ConsumerOffsetInfo;
 use tokio::sync::RwLock;
+use tracing::info;
 
 impl Topic {
     pub async fn store_consumer_offset(
@@ -30,6 +33,30 @@ impl Topic {
         })
     }
 
+    pub async fn reset_consumer_group_offset(
+        &self,
+        consumer_group_id: &Identifier,
+        to: &ResetOffsetTarget,
+    ) -> Result<(), IggyError> {
+        let consumer_group_id = self
+            .get_consumer_group(consumer_group_id)?
+            .read()
+            .await
+            .consumer_group_id;
+        for partition in self.partitions.values() {
+            let partition = partition.read().await;
+            partition
+                .reset_consumer_group_offset(consumer_group_id, to)
+                .await?;
+        }
+
+        info!(
+            "Reset offsets to: {} for consumer group with ID: {} for topic with ID: {} and stream with ID: {}.",
+            to.kind, consumer_group_id, self.topic_id, self.stream_id
+        );
+        Ok(())
+    }
+
     async fn resolve_partition(
         &self,
         consumer: PollingConsumer,
@@ -2,11 +2,11 @@ use crate::streaming::models::messages::PolledMessages;
 use crate::streaming::polling_consumer::PollingConsumer;
 use crate::streaming::topics::topic::Topic;
 use crate::streaming::utils::file::folder_size;
-use crate::streaming::utils::hash;
 use iggy::error::IggyError;
 use iggy::messages::poll_messages::{PollingKind, PollingStrategy};
 use iggy::messages::send_messages::{Partitioning, PartitioningKind};
 use iggy::models::messages::Message;
+use iggy::partitioner;
 use std::collections::HashMap;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
@@ -68,25 +68,100 @@ impl Topic {
             return Ok(());
         }
 
-        let partition_id = match partitioning.kind {
-            PartitioningKind::Balanced => self.get_next_partition_id(),
-            PartitioningKind::PartitionId => {
-                u32::from_le_bytes(partitioning.value[..partitioning.length as usize].try_into()?)
+        let partition_id = self.resolve_partition_id(partitioning)?;
+        self.append_messages_to_partition(partition_id, messages)
+            .await
+    }
+
+    /// Appends the messages with the partition ID set to the chosen partitions, and the rest using the partitioning.
+    /// The partition ID of the message can't conflict with the batch `PartitionId` partitioning, and all the partitions
+    /// are resolved and verified before any message is appended, so the invalid batch is rejected as a whole.
+    /// The order of the messages is preserved within each partition, but the batch isn't appended atomically across the partitions.
+    pub async fn append_messages_with_partition_ids(
+        &self,
+        partitioning: &Partitioning,
+        messages: Vec<(Option<u32>, Message)>,
+    ) -> Result<(), IggyError> {
+        if !self.has_partitions() {
+            return Err(IggyError::NoPartitions(self.topic_id, self.stream_id));
+        }
+
+        let batch_partition_id = match partitioning.kind {
+            PartitioningKind::PartitionId => Some(self.resolve_partition_id(partitioning)?),
+            _ => None,
+        };
+        let mut partitioned_messages: Vec<(u32, Vec<Message>)> = Vec::new();
+        let mut remaining_messages = Vec::new();
+        for (index, (partition_id, message)) in messages.into_iter().enumerate() {
+            let Some(partition_id) = partition_id else {
+                remaining_messages.push(message);
+                continue;
+            };
+
+            if let Some(batch_partition_id) = batch_partition_id {
+                if partition_id != batch_partition_id {
+                    return Err(IggyError::InvalidMessageAtIndex(
+                        index,
+                        Box::new(IggyError::ConflictingMessagePartitionId(
+                            partition_id,
+                            batch_partition_id,
+                        )),
+                    ));
+                }
             }
-            PartitioningKind::MessagesKey => {
-                self.calculate_partition_id_by_messages_key_hash(&partitioning.value)
+
+            match partitioned_messages
+                .iter_mut()
+                .find(|(id, _)| *id == partition_id)
+            {
+                Some((_, messages)) => messages.push(message),
+                None => partitioned_messages.push((partition_id, vec![message])),
             }
+        }
+
+        let remaining_partition_id = match batch_partition_id {
+            _ if remaining_messages.is_empty() => None,
+            Some(partition_id) => Some(partition_id),
+            None => Some(self.resolve_partition_id(partitioning)?),
         };
+        for partition_id in remaining_partition_id
+            .iter()
+            .chain(partitioned_messages.iter().map(|(id, _)| id))
+        {
+            if !self.partitions.contains_key(partition_id) {
+                return Err(IggyError::PartitionNotFound(
+                    *partition_id,
+                    self.topic_id,
+                    self.stream_id,
+                ));
+            }
+        }
 
-        self.append_messages_to_partition(partition_id, messages)
-            .await
+        if let Some(partition_id) = remaining_partition_id {
+            self.append_messages_to_partition(partition_id, remaining_messages)
+                .await?;
+        }
+        for (partition_id, messages) in partitioned_messages {
+            self.append_messages_to_partition(partition_id, messages)
+                .await?;
+        }
+
+        Ok(())
     }
 
-    async fn append_messages_to_partition(
+    /// Appends the messages only if the ID of the last message in the target partition is equal to the expected one (or the partition is empty and the expected ID is 0).
+    /// The check and the append are performed while holding the partition write lock.
+    pub async fn append_messages_conditional(
         &self,
-        partition_id: u32,
+        partitioning: &Partitioning,
+        expected_last_id: u128,
         messages: Vec<Message>,
     ) -> Result<(), IggyError> {
+        if !self.has_partitions() {
+            return Err(IggyError::NoPartitions(self.topic_id, self.stream_id));
+        }
+
+        let partition_id = self.resolve_partition_id(partitioning)?;
         let partition = self.partitions.get(&partition_id);
         if partition.is_none() {
             return Err(IggyError::PartitionNotFound(
@@ -98,36 +173,66 @@ impl Topic {
 
         let partition = partition.unwrap();
         let mut partition = partition.write().await;
-        partition.append_messages(messages).await?;
-        Ok(())
-    }
+        let last_id = if partition.get_messages_count() == 0 {
+            0
+        } else {
+            partition
+                .get_last_messages(1)
+                .await?
+                .last()
+                .map_or(0, |message| message.id)
+        };
 
-    fn get_next_partition_id(&self) -> u32 {
-        let mut partition_id = self.current_partition_id.fetch_add(1, Ordering::SeqCst);
-        let partitions_count = self.partitions.len() as u32;
-        if partition_id > partitions_count {
-            partition_id = 1;
-            self.current_partition_id
-                .swap(partition_id + 1, Ordering::SeqCst);
+        if last_id != expected_last_id {
+            trace!(
+                "Last message ID: {} doesn't match the expected one: {} for partition: {}",
+                last_id,
+                expected_last_id,
+                partition_id
+            );
+            return Err(IggyError::ConditionFailed);
         }
-        trace!("Next partition ID: {}", partition_id);
-        partition_id
-    }
 
-    fn calculate_partition_id_by_messages_key_hash(&self, messages_key: &[u8]) -> u32 {
-        let messages_key_hash = hash::calculate_32(messages_key);
-        let partitions_count = self.get_partitions_count();
-        let mut partition_id = messages_key_hash % partitions_count;
-        if partition_id == 0 {
-            partition_id = partitions_count;
+        if messages.is_empty() {
+            return Ok(());
         }
+
+        partition.append_messages(messages).await?;
+        Ok(())
+    }
+
+    fn resolve_partition_id(&self, partitioning: &Partitioning) -> Result<u32, IggyError> {
+        let partition_id = partitioner::resolve_partition_id(
+            partitioning,
+            self.get_partitions_count(),
+            &self.current_partition_id,
+        )?;
         trace!(
-            "Calculated partition ID: {} for messages key: {:?}, hash: {}",
+            "Resolved partition ID: {} for partitioning: {}",
             partition_id,
-            messages_key,
-            messages_key_hash
+            partitioning
         );
-        partition_id
+        Ok(partition_id)
+    }
+
+    async fn append_messages_to_partition(
+        &self,
+        partition_id: u32,
+        messages: Vec<Message>,
+    ) -> Result<(), IggyError> {
+        let partition = self.partitions.get(&partition_id);
+        if partition.is_none() {
+            return Err(IggyError::PartitionNotFound(
+                partition_id,
+                self.topic_id,
+                self.stream_id,
+            ));
+        }
+
+        let partition = partition.unwrap();
+        let mut partition = partition.write().await;
+        partition.append_messages(messages).await?;
+        Ok(())
     }
 
     pub(crate) async fn load_messages_from_disk_to_cache(&mut self) -> Result<(), IggyError> {
@@ -255,6 +360,7 @@ mod tests {
     use super::*;
     use crate::configs::system::SystemConfig;
     use crate::streaming::storage::tests::get_test_system_storage;
+    use crate::streaming::utils::hash;
     use bytes::Bytes;
     use iggy::models::messages::MessageState;
     use std::sync::atomic::AtomicU64;
@@ -342,7 +448,9 @@ mod tests {
 
         let mut expected_partition_id = 0;
         for _ in 1..=messages_count {
-            let partition_id = topic.get_next_partition_id();
+            let partition_id = topic
+                .resolve_partition_id(&Partitioning::balanced())
+                .unwrap();
             expected_partition_id += 1;
             if expected_partition_id > partitions_count {
                 expected_partition_id = 1;
@@ -360,7 +468,7 @@ mod tests {
 
         for entity_id in 1..=messages_count {
             let key = Partitioning::messages_key_u32(entity_id);
-            let partition_id = topic.calculate_partition_id_by_messages_key_hash(&key.value);
+            let partition_id = topic.resolve_partition_id(&key).unwrap();
             let entity_id_hash = hash::calculate_32(&key.value);
             let mut expected_partition_id = entity_id_hash % partitions_count;
             if expected_partition_id == 0 {
@@ -371,6 +479,98 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn given_partition_ids_messages_should_be_appended_to_the_chosen_partitions() {
+        let partitioning = Partitioning::balanced();
+        let partitions_count = 3;
+        let topic = init_topic(partitions_count);
+        let messages = create_messages_with_partition_ids(&[None, Some(3), None, Some(2), Some(3)]);
+
+        topic
+            .append_messages_with_partition_ids(&partitioning, messages)
+            .await
+            .unwrap();
+
+        for partition in topic.get_partitions() {
+            let partition = partition.read().await;
+            let ids = partition
+                .cache
+                .as_ref()
+                .unwrap()
+                .to_vec()
+                .iter()
+                .map(|message| message.id)
+                .collect::<Vec<_>>();
+            let expected_ids = match partition.partition_id {
+                1 => vec![1, 3],
+                2 => vec![4],
+                _ => vec![2, 5],
+            };
+            assert_eq!(ids, expected_ids);
+        }
+    }
+
+    #[tokio::test]
+    async fn given_partition_id_conflicting_with_batch_partition_id_no_messages_should_be_appended()
+    {
+        let partitioning = Partitioning::partition_id(1);
+        let topic = init_topic(3);
+        let messages = create_messages_with_partition_ids(&[None, Some(1), Some(2)]);
+
+        let error = topic
+            .append_messages_with_partition_ids(&partitioning, messages)
+            .await
+            .unwrap_err();
+
+        assert!(
+            matches!(error, IggyError::InvalidMessageAtIndex(2, ref inner) if matches!(**inner, IggyError::ConflictingMessagePartitionId(2, 1)))
+        );
+        assert_eq!(get_appended_messages_count(&topic).await, 0);
+    }
+
+    #[tokio::test]
+    async fn given_not_existing_partition_id_no_messages_should_be_appended() {
+        let partitioning = Partitioning::balanced();
+        let topic = init_topic(3);
+        let messages = create_messages_with_partition_ids(&[None, Some(2), Some(4)]);
+
+        let error = topic
+            .append_messages_with_partition_ids(&partitioning, messages)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(error, IggyError::PartitionNotFound(4, 2, 1)));
+        assert_eq!(get_appended_messages_count(&topic).await, 0);
+    }
+
+    fn create_messages_with_partition_ids(
+        partition_ids: &[Option<u32>],
+    ) -> Vec<(Option<u32>, Message)> {
+        partition_ids
+            .iter()
+            .enumerate()
+            .map(|(index, partition_id)| {
+                let message = Message::empty(
+                    1,
+                    MessageState::Available,
+                    index as u128 + 1,
+                    Bytes::from("test"),
+                    1,
+                    None,
+                );
+                (*partition_id, message)
+            })
+            .collect()
+    }
+
+    async fn get_appended_messages_count(topic: &Topic) -> u64 {
+        let mut count = 0;
+        for partition in topic.get_partitions() {
+            count += partition.read().await.get_messages_count();
+        }
+        count
+    }
+
     fn init_topic(partitions_count: u32) -> Topic {
         let storage = Arc::new(get_test_system_storage());
         let stream_id = 1;
@@ -3,12 +3,11 @@ use iggy::clients::client::IggyClient;
 use iggy::error::IggyError;
 use iggy::identifier::Identifier;
 use iggy::messages::send_messages::{Message, Partitioning, SendMessages};
-use iggy::models::header::{HeaderKey, HeaderValue};
+use iggy::models::header::{HeaderKey, HeaderValue, Headers};
 use iggy::streams::create_stream::CreateStream;
 use iggy::topics::create_topic::CreateTopic;
 use iggy::topics::get_topics::GetTopics;
 use rand::Rng;
-use std::collections::HashMap;
 use std::str::FromStr;
 
 const PROD_STREAM_ID: u32 = 1;
@@ -131,14 +130,17 @@ async fn send_messages(client: &IggyClient) -> Result<(), IggyError> {
                     let headers = match rng.gen_bool(0.5) {
                         false => None,
                         true => {
-                            let mut headers = HashMap::new();
+                            let mut headers = Headers::new();
+                            headers.insert(
+                                HeaderKey::new("key 1")?,
+                                HeaderValue::from_str("value1")?,
+                            )?;
                             headers
-                                .insert(HeaderKey::new("key 1")?, HeaderValue::from_str("value1")?);
-                            headers.insert(HeaderKey::new("key-2")?, HeaderValue::from_bool(true)?);
+                                .insert(HeaderKey::new("key-2")?, HeaderValue::from_bool(true)?)?;
                             headers.insert(
                                 HeaderKey::new("key_3")?,
                                 HeaderValue::from_uint64(123456)?,
-                            );
+                            )?;
                             Some(headers)
                         }
                     };
@@ -148,12 +150,12 @@ async fn send_messages(client: &IggyClient) -> Result<(), IggyError> {
                     message_id += 1;
                 }
                 client
-                    .send_messages(&mut SendMessages {
-                        stream_id: Identifier::numeric(stream_id)?,
-                        topic_id: Identifier::numeric(topic.id)?,
-                        partitioning: Partitioning::balanced(),
+                    .send_messages(&mut SendMessages::new(
+                        Identifier::numeric(stream_id)?.into(),
+                        Identifier::numeric(topic.id)?.into(),
+                        Partitioning::balanced(),
                         messages,
-                    })
+                    ))
                     .await?;
                 messages = Vec::new();
             }